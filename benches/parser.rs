@@ -243,9 +243,76 @@ fn bench_then(c: &mut Criterion) {
     });
 }
 
+fn bench_map(c: &mut Criterion) {
+    let digit = one_of::<_, &str, extra::Default>('0'..='9');
+
+    let mapped = digit
+        .clone()
+        .map(|c: char| c.to_digit(10).unwrap())
+        .map(|n| n * 2)
+        .repeated()
+        .collect::<Vec<_>>();
+    let mapped2 = digit
+        .map2(|c: char| c.to_digit(10).unwrap(), |n| n * 2)
+        .repeated()
+        .collect::<Vec<_>>();
+
+    let input = "0123456789".repeat(1000);
+
+    let mut group = c.benchmark_group("map");
+
+    group.bench_function(BenchmarkId::new("map(f).map(g)", "digits"), |b| {
+        b.iter(|| {
+            black_box(mapped.parse(black_box(input.as_str())))
+                .into_result()
+                .unwrap();
+        })
+    });
+
+    group.bench_function(BenchmarkId::new("map2(f, g)", "digits"), |b| {
+        b.iter(|| {
+            black_box(mapped2.parse(black_box(input.as_str())))
+                .into_result()
+                .unwrap();
+        })
+    });
+}
+
+fn bench_just_seq(c: &mut Criterion) {
+    let chained = just::<_, &str, extra::Default>("<<")
+        .then(just("="))
+        .ignored()
+        .repeated()
+        .collect::<Vec<_>>();
+    let seq = chumsky::primitive::just_seq::<_, _, &str, extra::Default>(["<<", "="])
+        .ignored()
+        .repeated()
+        .collect::<Vec<_>>();
+
+    let input = "<<=".repeat(1000);
+
+    let mut group = c.benchmark_group("just_seq");
+
+    group.bench_function(BenchmarkId::new("just.then(just)", "<<="), |b| {
+        b.iter(|| {
+            black_box(chained.parse(black_box(input.as_str())))
+                .into_result()
+                .unwrap();
+        })
+    });
+
+    group.bench_function(BenchmarkId::new("just_seq", "<<="), |b| {
+        b.iter(|| {
+            black_box(seq.parse(black_box(input.as_str())))
+                .into_result()
+                .unwrap();
+        })
+    });
+}
+
 criterion_group!(
     name = benches;
     config = utils::make_criterion();
-    targets = bench_choice, bench_or, bench_group, bench_then,
+    targets = bench_choice, bench_or, bench_group, bench_then, bench_map, bench_just_seq,
 );
 criterion_main!(benches);
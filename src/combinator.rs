@@ -108,6 +108,11 @@ where
     ) -> IPResult<M, O> {
         self.parser.next_cfg(inp, &mut state.0, &state.1)
     }
+
+    #[inline(always)]
+    fn may_stop<M: Mode>(&self, state: &Self::IterState<M>) -> bool {
+        self.parser.may_stop_cfg(&state.0, &state.1)
+    }
 }
 
 /// See [`ConfigIterParser::try_configure`]
@@ -183,6 +188,11 @@ where
     ) -> IPResult<M, O> {
         self.parser.next_cfg(inp, &mut state.0, &state.1)
     }
+
+    #[inline(always)]
+    fn may_stop<M: Mode>(&self, state: &Self::IterState<M>) -> bool {
+        self.parser.may_stop_cfg(&state.0, &state.1)
+    }
 }
 
 /// See [`Parser::map_slice`].
@@ -280,6 +290,248 @@ where
     go_extra!(I::Slice);
 }
 
+/// See [`Parser::slice_and_span`]
+pub struct SliceAndSpan<A, O> {
+    pub(crate) parser: A,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<O>,
+}
+
+impl<A: Copy, O> Copy for SliceAndSpan<A, O> {}
+impl<A: Clone, O> Clone for SliceAndSpan<A, O> {
+    fn clone(&self) -> Self {
+        SliceAndSpan {
+            parser: self.parser.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, A, I, O, E> ParserSealed<'a, I, (I::Slice, I::Span), E> for SliceAndSpan<A, O>
+where
+    A: Parser<'a, I, O, E>,
+    I: SliceInput<'a>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, (I::Slice, I::Span)>
+    where
+        Self: Sized,
+    {
+        let before = inp.offset();
+        self.parser.go::<Check>(inp)?;
+        let after = inp.offset();
+
+        Ok(M::bind(|| {
+            (
+                inp.slice_inner(before.offset..after.offset),
+                inp.span_since(before),
+            )
+        }))
+    }
+
+    go_extra!((I::Slice, I::Span));
+}
+
+/// See [`Parser::to_token`].
+pub struct ToToken<A, O, F> {
+    pub(crate) parser: A,
+    pub(crate) mapper: F,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<O>,
+}
+
+impl<A: Copy, O, F: Copy> Copy for ToToken<A, O, F> {}
+impl<A: Clone, O, F: Clone> Clone for ToToken<A, O, F> {
+    fn clone(&self) -> Self {
+        ToToken {
+            parser: self.parser.clone(),
+            mapper: self.mapper.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, A, I, O, E, F, U> ParserSealed<'a, I, U, E> for ToToken<A, O, F>
+where
+    A: Parser<'a, I, O, E>,
+    I: SliceInput<'a>,
+    E: ParserExtra<'a, I>,
+    F: Fn(I::Slice, I::Span) -> U,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, U>
+    where
+        Self: Sized,
+    {
+        let before = inp.offset();
+        self.parser.go::<Check>(inp)?;
+        let after = inp.offset();
+
+        Ok(M::bind(|| {
+            (self.mapper)(
+                inp.slice_inner(before.offset..after.offset),
+                inp.span_since(before),
+            )
+        }))
+    }
+
+    go_extra!(U);
+}
+
+/// See [`Parser::map_str`].
+pub struct MapStr<A, O> {
+    pub(crate) parser: A,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<O>,
+}
+
+impl<A: Copy, O> Copy for MapStr<A, O> {}
+impl<A: Clone, O> Clone for MapStr<A, O> {
+    fn clone(&self) -> Self {
+        MapStr {
+            parser: self.parser.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, A, I, O, E> ParserSealed<'a, I, &'a str, E> for MapStr<A, O>
+where
+    A: Parser<'a, I, O, E>,
+    I: StrInput<'a, u8>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, &'a str> {
+        let before = inp.offset().offset;
+        // Keep the errors of the inner parser apart, so that only they are discarded if the slice is invalid
+        let (res, inner_alt) = inp.with_own_alt(|inp| self.parser.go::<Check>(inp));
+        if res.is_err() {
+            inp.add_located_alt(inner_alt);
+            return Err(());
+        }
+        let after = inp.offset().offset;
+
+        let bytes = inp.slice_inner(before..after);
+        match core::str::from_utf8(bytes) {
+            Ok(s) => {
+                inp.add_located_alt(inner_alt);
+                Ok(M::bind(|| s))
+            }
+            Err(err) => {
+                let start = before + err.valid_up_to();
+                let end = err.error_len().map_or(after, |len| start + len);
+                // SAFETY: `start` and `end` both lie within the slice that was just parsed
+                let span = unsafe { inp.input.span(start..end) };
+                let found = bytes.get(err.valid_up_to()).map(MaybeRef::Ref);
+                // The invalid bytes are more relevant than whatever the inner parser expected after its last token
+                inp.add_alt(end, None, found, span);
+                Err(())
+            }
+        }
+    }
+
+    go_extra!(&'a str);
+}
+
+/// See [`Parser::reparse_slice`].
+pub struct ReparseSlice<A, B, OA> {
+    pub(crate) parser: A,
+    pub(crate) inner: B,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<OA>,
+}
+
+impl<A: Copy, B: Copy, OA> Copy for ReparseSlice<A, B, OA> {}
+impl<A: Clone, B: Clone, OA> Clone for ReparseSlice<A, B, OA> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            inner: self.inner.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, A, B, I, OA, O, E> ParserSealed<'a, I, O, E> for ReparseSlice<A, B, OA>
+where
+    A: Parser<'a, I, OA, E>,
+    B: Parser<'a, input::SliceView<'a, I>, O, extra::Full<E::Error, E::State, E::Context>>,
+    I: SliceInput<'a> + Clone,
+    E: ParserExtra<'a, I>,
+    E::Error: Error<'a, input::SliceView<'a, I>>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let before = inp.offset().offset;
+        self.parser.go::<Check>(inp)?;
+        let view = input::SliceView {
+            input: inp.input.clone(),
+            start: before,
+            end: inp.offset,
+        };
+        inp.offset = before;
+
+        #[cfg(feature = "memoization")]
+        let mut memos = HashMap::default();
+        inp.with_view(
+            &view,
+            |inp| (&self.inner).then_ignore(end()).go::<M>(inp),
+            #[cfg(feature = "memoization")]
+            &mut memos,
+        )
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::fixed_size`].
+pub struct FixedSize<A, const N: usize> {
+    pub(crate) parser: A,
+    #[cfg(debug_assertions)]
+    pub(crate) location: Location<'static>,
+}
+
+impl<A: Copy, const N: usize> Copy for FixedSize<A, N> {}
+impl<A: Clone, const N: usize> Clone for FixedSize<A, N> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            #[cfg(debug_assertions)]
+            location: self.location,
+        }
+    }
+}
+
+impl<'a, A, I, O, E, const N: usize> ParserSealed<'a, I, O, E> for FixedSize<A, N>
+where
+    A: Parser<'a, I, O, E>,
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        #[cfg(debug_assertions)]
+        let before = inp.offset().offset.into();
+        let out = self.parser.go::<M>(inp)?;
+        #[cfg(debug_assertions)]
+        {
+            let consumed = inp.offset().offset.into() - before;
+            debug_assert!(
+                consumed == N,
+                "found FixedSize combinator declared as {} units consuming {} units at {}",
+                N,
+                consumed,
+                self.location,
+            );
+        }
+        Ok(out)
+    }
+
+    go_extra!(O);
+}
+
 /// See [`Parser::filter`].
 pub struct Filter<A, F> {
     pub(crate) parser: A,
@@ -320,6 +572,118 @@ where
     go_extra!(O);
 }
 
+/// See [`Parser::followed_by`].
+#[derive(Copy, Clone)]
+pub struct FollowedBy<A, F> {
+    pub(crate) parser: A,
+    pub(crate) pred: F,
+}
+
+impl<'a, A, I, O, E, F> ParserSealed<'a, I, O, E> for FollowedBy<A, F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+    F: Fn(Option<&I::Token>) -> bool,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let out = self.parser.go::<M>(inp)?;
+        let before = inp.save();
+        let (at, found) = inp.next_maybe_inner();
+        if (self.pred)(found.as_ref().map(Borrow::borrow)) {
+            inp.rewind(before);
+            Ok(out)
+        } else {
+            let err_span = inp.span_since(before.offset());
+            inp.rewind(before);
+            inp.add_alt(at, None, found.map(|f| f.into()), err_span);
+            Err(())
+        }
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::filter_labelled`].
+#[derive(Copy, Clone)]
+pub struct FilterLabelled<A, F, L> {
+    pub(crate) parser: A,
+    pub(crate) filter: F,
+    #[cfg_attr(not(feature = "label"), allow(dead_code))]
+    pub(crate) label: L,
+}
+
+impl<A, F, L> FilterLabelled<A, F, L> {
+    /// Run the parser and filter its output, using `label` to attach the label to the error if it gets rejected.
+    #[inline(always)]
+    fn go_labelled<'a, M, I, O, E>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+        label: impl FnOnce(&mut E::Error),
+    ) -> PResult<M, O>
+    where
+        M: Mode,
+        I: Input<'a>,
+        E: ParserExtra<'a, I>,
+        A: Parser<'a, I, O, E>,
+        F: Fn(&O) -> bool,
+    {
+        let before = inp.save();
+        self.parser.go::<Emit>(inp).and_then(|out| {
+            if (self.filter)(&out) {
+                Ok(M::bind(|| out))
+            } else {
+                // Report the first token of the rejected pattern as the one that was found
+                let after = inp.save();
+                inp.rewind(before);
+                let found = inp.peek_maybe();
+                inp.rewind(after);
+
+                let mut err =
+                    E::Error::expected_found(None, found, inp.span_since(before.offset()));
+                label(&mut err);
+                inp.add_alt_err(inp.offset().offset, err);
+                Err(())
+            }
+        })
+    }
+}
+
+#[cfg(feature = "label")]
+impl<'a, A, I, O, E, F, L> ParserSealed<'a, I, O, E> for FilterLabelled<A, F, L>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+    F: Fn(&O) -> bool,
+    L: Clone,
+    E::Error: LabelError<'a, I, L>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        self.go_labelled::<M, _, _, _>(inp, |err| err.label_with(self.label.clone()))
+    }
+
+    go_extra!(O);
+}
+
+#[cfg(not(feature = "label"))]
+impl<'a, A, I, O, E, F, L> ParserSealed<'a, I, O, E> for FilterLabelled<A, F, L>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+    F: Fn(&O) -> bool,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        self.go_labelled::<M, _, _, _>(inp, |_| {})
+    }
+
+    go_extra!(O);
+}
+
 /// See [`Parser::map`].
 pub struct Map<A, OA, F> {
     pub(crate) parser: A,
@@ -386,58 +750,137 @@ where
             Err(()) => Err(()),
         }
     }
+
+    #[inline(always)]
+    fn may_stop<M: Mode>(&self, state: &Self::IterState<M>) -> bool {
+        self.parser.may_stop(state)
+    }
 }
 
-/// See [`Parser::map_group`].
-#[cfg(feature = "nightly")]
-pub struct MapGroup<A, OA, F> {
+/// See [`Parser::map2`].
+pub struct Map2<A, OA, F, OF, G> {
     pub(crate) parser: A,
-    pub(crate) mapper: F,
+    pub(crate) first: F,
+    pub(crate) second: G,
     #[allow(dead_code)]
-    pub(crate) phantom: EmptyPhantom<OA>,
+    pub(crate) phantom: EmptyPhantom<(OA, OF)>,
 }
 
-#[cfg(feature = "nightly")]
-impl<A: Copy, OA, F: Copy> Copy for MapGroup<A, OA, F> {}
-#[cfg(feature = "nightly")]
-impl<A: Clone, OA, F: Clone> Clone for MapGroup<A, OA, F> {
+impl<A: Copy, OA, F: Copy, OF, G: Copy> Copy for Map2<A, OA, F, OF, G> {}
+impl<A: Clone, OA, F: Clone, OF, G: Clone> Clone for Map2<A, OA, F, OF, G> {
     fn clone(&self) -> Self {
         Self {
             parser: self.parser.clone(),
-            mapper: self.mapper.clone(),
+            first: self.first.clone(),
+            second: self.second.clone(),
             phantom: EmptyPhantom::new(),
         }
     }
 }
 
-#[cfg(feature = "nightly")]
-impl<'a, I, O, E, A, OA, F> ParserSealed<'a, I, O, E> for MapGroup<A, OA, F>
+impl<'a, I, O, E, A, OA, F, OF, G> ParserSealed<'a, I, O, E> for Map2<A, OA, F, OF, G>
 where
     I: Input<'a>,
     E: ParserExtra<'a, I>,
     A: Parser<'a, I, OA, E>,
-    F: Fn<OA, Output = O>,
-    OA: Tuple,
+    F: Fn(OA) -> OF,
+    G: Fn(OF) -> O,
 {
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
         let out = self.parser.go::<M>(inp)?;
-        Ok(M::map(out, |out| self.mapper.call(out)))
+        Ok(M::map(out, |out| (self.second)((self.first)(out))))
     }
 
     go_extra!(O);
 }
 
-#[cfg(feature = "nightly")]
-impl<'a, I, O, E, A, OA, F> IterParserSealed<'a, I, O, E> for MapGroup<A, OA, F>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-    A: IterParser<'a, I, OA, E>,
-    F: Fn<OA, Output = O>,
-    OA: Tuple,
-{
-    type IterState<M: Mode> = A::IterState<M>
+/// A function that can be called with the elements of a tuple as its arguments. See [`Parser::map_group`].
+///
+/// This trait is implemented for functions of up to 12 arguments.
+pub trait GroupFn<Args, O> {
+    #[doc(hidden)]
+    fn call_group(&self, args: Args) -> O;
+}
+
+macro_rules! impl_group_fn_for_tuple {
+    () => {};
+    ($head:ident $($X:ident)*) => {
+        impl_group_fn_for_tuple!($($X)*);
+        impl_group_fn_for_tuple!(~ $head $($X)*);
+    };
+    (~ $($X:ident)*) => {
+        #[allow(non_snake_case)]
+        impl<Func, Out, $($X),*> GroupFn<($($X,)*), Out> for Func
+        where
+            Func: Fn($($X),*) -> Out,
+        {
+            #[inline(always)]
+            fn call_group(&self, ($($X,)*): ($($X,)*)) -> Out {
+                self($($X),*)
+            }
+        }
+    };
+}
+
+impl_group_fn_for_tuple! {
+    A_
+    B_
+    C_
+    D_
+    E_
+    F_
+    G_
+    H_
+    I_
+    J_
+    K_
+    L_
+}
+
+/// See [`Parser::map_group`].
+pub struct MapGroup<A, OA, F> {
+    pub(crate) parser: A,
+    pub(crate) mapper: F,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<OA>,
+}
+
+impl<A: Copy, OA, F: Copy> Copy for MapGroup<A, OA, F> {}
+impl<A: Clone, OA, F: Clone> Clone for MapGroup<A, OA, F> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            mapper: self.mapper.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, O, E, A, OA, F> ParserSealed<'a, I, O, E> for MapGroup<A, OA, F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+    F: GroupFn<OA, O>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let out = self.parser.go::<M>(inp)?;
+        Ok(M::map(out, |args| self.mapper.call_group(args)))
+    }
+
+    go_extra!(O);
+}
+
+impl<'a, I, O, E, A, OA, F> IterParserSealed<'a, I, O, E> for MapGroup<A, OA, F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: IterParser<'a, I, OA, E>,
+    F: GroupFn<OA, O>,
+{
+    type IterState<M: Mode> = A::IterState<M>
     where
         I: 'a;
 
@@ -456,11 +899,55 @@ where
         state: &mut Self::IterState<M>,
     ) -> IPResult<M, O> {
         match self.parser.next::<M>(inp, state) {
-            Ok(Some(o)) => Ok(Some(M::map(o, |o| self.mapper.call(o)))),
+            Ok(Some(o)) => Ok(Some(M::map(o, |args| self.mapper.call_group(args)))),
             Ok(None) => Ok(None),
             Err(()) => Err(()),
         }
     }
+
+    #[inline(always)]
+    fn may_stop<M: Mode>(&self, state: &Self::IterState<M>) -> bool {
+        self.parser.may_stop(state)
+    }
+}
+
+/// See [`Parser::lazy_map`].
+pub struct LazyMap<A, OA, F> {
+    pub(crate) parser: A,
+    pub(crate) mapper: F,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<OA>,
+}
+
+impl<A: Copy, OA, F: Copy> Copy for LazyMap<A, OA, F> {}
+impl<A: Clone, OA, F: Clone> Clone for LazyMap<A, OA, F> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            mapper: self.mapper.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, O, E, A, OA, F> ParserSealed<'a, I, Deferred<'a, O>, E> for LazyMap<A, OA, F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+    OA: 'a,
+    F: Fn(OA) -> O + Clone + 'a,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, Deferred<'a, O>> {
+        let out = self.parser.go::<M>(inp)?;
+        Ok(M::map(out, |out| {
+            let mapper = self.mapper.clone();
+            Deferred::new(move || mapper(out))
+        }))
+    }
+
+    go_extra!(Deferred<'a, O>);
 }
 
 /// See [`Parser::map_with_span`].
@@ -618,6 +1105,69 @@ where
     go_extra!(O);
 }
 
+/// See [`Parser::with_events`].
+#[derive(Copy, Clone)]
+pub struct WithEvents<A, K> {
+    pub(crate) parser: A,
+    pub(crate) kind: K,
+}
+
+impl<'a, I, O, E, A, K> ParserSealed<'a, I, O, E> for WithEvents<A, K>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: EventSink<K, I::Span>,
+    I::Span: Clone,
+    A: Parser<'a, I, O, E>,
+    K: Clone + 'a,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let events = inp.events.len;
+        inp.record_event(Event::Enter(self.kind.clone()));
+        let before = inp.offset();
+        match self.parser.go::<M>(inp) {
+            Ok(out) => {
+                let span = inp.span_since(before);
+                inp.record_event(Event::Exit(self.kind.clone(), span));
+                Ok(out)
+            }
+            Err(()) => {
+                inp.forget_events(events);
+                Err(())
+            }
+        }
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::with_node_id`].
+#[derive(Copy, Clone)]
+pub struct WithNodeId<A> {
+    pub(crate) parser: A,
+    pub(crate) kind: u64,
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for WithNodeId<A>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: NodeIdSource,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let offset = inp.offset().offset.into();
+        inp.state().node_ids().enter(self.kind, offset);
+        let res = self.parser.go::<M>(inp);
+        inp.state().node_ids().exit();
+        res
+    }
+
+    go_extra!(O);
+}
+
 /// See [`Parser::try_map_with_state`].
 pub struct TryMapWithState<A, OA, F> {
     pub(crate) parser: A,
@@ -930,6 +1480,94 @@ where
     go_extra!((OA, OB));
 }
 
+/// See [`Parser::then_map_with_span`].
+pub struct ThenMapWithSpan<A, B, OA, OB, F, E> {
+    pub(crate) parser_a: A,
+    pub(crate) parser_b: B,
+    pub(crate) mapper: F,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(OA, OB, E)>,
+}
+
+impl<A: Copy, B: Copy, OA, OB, F: Copy, E> Copy for ThenMapWithSpan<A, B, OA, OB, F, E> {}
+impl<A: Clone, B: Clone, OA, OB, F: Clone, E> Clone for ThenMapWithSpan<A, B, OA, OB, F, E> {
+    fn clone(&self) -> Self {
+        Self {
+            parser_a: self.parser_a.clone(),
+            parser_b: self.parser_b.clone(),
+            mapper: self.mapper.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, O, E, A, B, OA, OB, F> ParserSealed<'a, I, O, E> for ThenMapWithSpan<A, B, OA, OB, F, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+    B: Parser<'a, I, OB, E>,
+    F: Fn(OA, OB, I::Span) -> O,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let before = inp.offset();
+        let a = self.parser_a.go::<M>(inp)?;
+        let b = self.parser_b.go::<M>(inp)?;
+        Ok(M::combine(a, b, |a: OA, b: OB| {
+            let span = inp.span_since(before);
+            (self.mapper)(a, b, span)
+        }))
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::then_with_gap`].
+pub struct ThenWithGap<A, G, B, OA, OG, OB, E> {
+    pub(crate) parser_a: A,
+    pub(crate) gap: G,
+    pub(crate) parser_b: B,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(OA, OG, OB, E)>,
+}
+
+impl<A: Copy, G: Copy, B: Copy, OA, OG, OB, E> Copy for ThenWithGap<A, G, B, OA, OG, OB, E> {}
+impl<A: Clone, G: Clone, B: Clone, OA, OG, OB, E> Clone for ThenWithGap<A, G, B, OA, OG, OB, E> {
+    fn clone(&self) -> Self {
+        Self {
+            parser_a: self.parser_a.clone(),
+            gap: self.gap.clone(),
+            parser_b: self.parser_b.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, E, A, G, B, OA, OG, OB> ParserSealed<'a, I, (OA, OG, OB), E>
+    for ThenWithGap<A, G, B, OA, OG, OB, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+    G: Parser<'a, I, OG, E>,
+    B: Parser<'a, I, OB, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, (OA, OG, OB)> {
+        let a = self.parser_a.go::<M>(inp)?;
+        let gap = self.gap.go::<M>(inp)?;
+        let b = self.parser_b.go::<M>(inp)?;
+        Ok(M::combine(
+            M::combine(a, gap, |a, gap| (a, gap)),
+            b,
+            |(a, gap), b| (a, gap, b),
+        ))
+    }
+
+    go_extra!((OA, OG, OB));
+}
+
 /// See [`Parser::ignore_then`].
 pub struct IgnoreThen<A, B, OA, E> {
     pub(crate) parser_a: A,
@@ -1032,20 +1670,18 @@ where
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
         let inp2 = self.parser_b.go::<Emit>(inp)?;
 
-        let alt = inp.errors.alt.take();
-
         #[cfg(feature = "memoization")]
         let mut memos = HashMap::default();
-        let res = inp.with_input(
-            &inp2,
-            |inp| (&self.parser_a).then_ignore(end()).go::<M>(inp),
-            #[cfg(feature = "memoization")]
-            &mut memos,
-        );
+        let (res, new_alt) = inp.with_own_alt(|inp| {
+            inp.with_input(
+                &inp2,
+                |inp| (&self.parser_a).then_ignore(end()).go::<M>(inp),
+                #[cfg(feature = "memoization")]
+                &mut memos,
+            )
+        });
 
         // TODO: Translate secondary error offsets too
-        let new_alt = inp.errors.alt.take();
-        inp.errors.alt = alt;
         if let Some(new_alt) = new_alt {
             inp.add_alt_err(inp.offset().offset, new_alt.err);
         }
@@ -1126,6 +1762,11 @@ where
 
         inp.with_ctx(ctx, |inp| self.then.next(inp, inner_state))
     }
+
+    #[inline(always)]
+    fn may_stop<M: Mode>(&self, state: &Self::IterState<M>) -> bool {
+        self.then.may_stop(&state.1)
+    }
 }
 
 /// See [`Parser::then_with_ctx`].
@@ -1199,71 +1840,208 @@ where
 
         inp.with_ctx(ctx, |inp| self.then.next(inp, inner_state))
     }
+
+    #[inline(always)]
+    fn may_stop<M: Mode>(&self, state: &Self::IterState<M>) -> bool {
+        self.then.may_stop(&state.1)
+    }
 }
 
-/// See [`Parser::with_ctx`].
-pub struct WithCtx<A, Ctx> {
+/// See [`Parser::then_with_cached`].
+pub struct ThenWithCached<A, B, OA, F> {
     pub(crate) parser: A,
-    pub(crate) ctx: Ctx,
+    pub(crate) then: F,
+    pub(crate) capacity: usize,
+    pub(crate) cache: RefC<RefLock<DerivedParsers<OA, B>>>,
 }
 
-impl<A: Copy, Ctx: Copy> Copy for WithCtx<A, Ctx> {}
-impl<A: Clone, Ctx: Clone> Clone for WithCtx<A, Ctx> {
+/// The derived parsers cached by [`ThenWithCached`], along with the parse that they were built during.
+pub(crate) struct DerivedParsers<OA, B> {
+    pub(crate) parse: Option<RefW<()>>,
+    pub(crate) parsers: HashMap<OA, RefC<B>>,
+}
+
+impl<OA, B> Default for DerivedParsers<OA, B> {
+    fn default() -> Self {
+        Self {
+            parse: None,
+            parsers: HashMap::default(),
+        }
+    }
+}
+
+impl<A: Clone, B, OA, F: Clone> Clone for ThenWithCached<A, B, OA, F> {
     fn clone(&self) -> Self {
-        WithCtx {
+        Self {
             parser: self.parser.clone(),
-            ctx: self.ctx.clone(),
+            then: self.then.clone(),
+            capacity: self.capacity,
+            cache: self.cache.clone(),
         }
     }
 }
 
-impl<'a, I, O, E, A, Ctx> ParserSealed<'a, I, O, E> for WithCtx<A, Ctx>
+impl<A, B, OA, F> ThenWithCached<A, B, OA, F> {
+    /// Set the maximum number of derived parsers that are cached at once. Once the cache is full, parsers for
+    /// outputs that are not already cached are built each time they are needed, as with [`Parser::then_with`].
+    ///
+    /// The default capacity is 32.
+    pub fn capacity(self, capacity: usize) -> Self {
+        Self { capacity, ..self }
+    }
+}
+
+impl<'a, I, E, A, B, OA, OB, F> ParserSealed<'a, I, (OA, OB), E> for ThenWithCached<A, B, OA, F>
 where
     I: Input<'a>,
     E: ParserExtra<'a, I>,
-    A: Parser<'a, I, O, extra::Full<E::Error, E::State, Ctx>>,
-    Ctx: 'a,
+    A: Parser<'a, I, OA, E>,
+    B: Parser<'a, I, OB, E>,
+    OA: Hash + Eq + Clone,
+    F: Fn(&OA) -> B,
 {
     #[inline(always)]
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
-        inp.with_ctx(&self.ctx, |inp| self.parser.go::<M>(inp))
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, (OA, OB)> {
+        let out = self.parser.go::<Emit>(inp)?;
+        let parse = RefC::downgrade(inp.parse_token());
+        // The cache must not be locked while the derived parser runs, since it may (recursively) contain this parser
+        let cached = self.cache.with(|cache| {
+            // Parsers built during an earlier parse are never reused
+            if !matches!(&cache.parse, Some(p) if p.ptr_eq(&parse)) {
+                cache.parse = Some(parse);
+                cache.parsers.clear();
+            }
+            cache.parsers.get(&out).cloned()
+        });
+        let then = match cached {
+            Some(then) => then,
+            None => {
+                let then = RefC::new((self.then)(&out));
+                self.cache.with(|cache| {
+                    if cache.parsers.len() < self.capacity {
+                        cache.parsers.insert(out.clone(), then.clone());
+                    }
+                });
+                then
+            }
+        };
+        let then_out = then.go::<M>(inp)?;
+        Ok(M::map(then_out, |then_out| (out, then_out)))
     }
 
-    go_extra!(O);
+    go_extra!((OA, OB));
 }
 
-/// See [`Parser::with_state`].
-pub struct WithState<A, State> {
+/// See [`Parser::cached_at_position`].
+#[derive(Copy, Clone)]
+pub struct CachedAtPosition<A> {
     pub(crate) parser: A,
-    pub(crate) state: State,
-}
-
-impl<A: Copy, Ctx: Copy> Copy for WithState<A, Ctx> {}
-impl<A: Clone, Ctx: Clone> Clone for WithState<A, Ctx> {
-    fn clone(&self) -> Self {
-        WithState {
-            parser: self.parser.clone(),
-            state: self.state.clone(),
-        }
-    }
+    pub(crate) key: u64,
 }
 
-impl<'a, I, O, E, A, State> ParserSealed<'a, I, O, E> for WithState<A, State>
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for CachedAtPosition<A>
 where
     I: Input<'a>,
     E: ParserExtra<'a, I>,
-    A: Parser<'a, I, O, extra::Full<E::Error, State, E::Context>>,
-    State: 'a + Clone,
+    E::State: PositionCacheSource<I::Offset, O>,
+    A: Parser<'a, I, O, E>,
+    O: Clone,
 {
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
-        inp.with_state(&mut self.state.clone(), |inp| self.parser.go::<M>(inp))
+        let before = inp.save();
+        if let Some((end, out)) = inp.state().position_cache().get(self.key, before.offset) {
+            let out = M::bind(|| out.clone());
+            inp.offset = end;
+            inp.replay_cached(self.key);
+            return Ok(out);
+        }
+
+        inp.begin_cached();
+        let res = self.parser.go::<Emit>(inp);
+        let cache = res.is_ok()
+            && inp
+                .errors
+                .secondary_errors_since(before.err_count)
+                .is_empty();
+        inp.end_cached(before, cache.then_some(self.key));
+
+        let out = res?;
+        if cache {
+            let end = inp.offset;
+            inp.state()
+                .position_cache()
+                .insert(self.key, before.offset, end, out.clone());
+        }
+        Ok(M::bind(|| out))
     }
 
     go_extra!(O);
 }
 
-/// See [`Parser::delimited_by`].
+/// See [`Parser::with_ctx`].
+pub struct WithCtx<A, Ctx> {
+    pub(crate) parser: A,
+    pub(crate) ctx: Ctx,
+}
+
+impl<A: Copy, Ctx: Copy> Copy for WithCtx<A, Ctx> {}
+impl<A: Clone, Ctx: Clone> Clone for WithCtx<A, Ctx> {
+    fn clone(&self) -> Self {
+        WithCtx {
+            parser: self.parser.clone(),
+            ctx: self.ctx.clone(),
+        }
+    }
+}
+
+impl<'a, I, O, E, A, Ctx> ParserSealed<'a, I, O, E> for WithCtx<A, Ctx>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, extra::Full<E::Error, E::State, Ctx>>,
+    Ctx: 'a,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        inp.with_ctx(&self.ctx, |inp| self.parser.go::<M>(inp))
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::with_state`].
+pub struct WithState<A, State> {
+    pub(crate) parser: A,
+    pub(crate) state: State,
+}
+
+impl<A: Copy, Ctx: Copy> Copy for WithState<A, Ctx> {}
+impl<A: Clone, Ctx: Clone> Clone for WithState<A, Ctx> {
+    fn clone(&self) -> Self {
+        WithState {
+            parser: self.parser.clone(),
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<'a, I, O, E, A, State> ParserSealed<'a, I, O, E> for WithState<A, State>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, extra::Full<E::Error, State, E::Context>>,
+    State: 'a + Clone,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        inp.with_state(&mut self.state.clone(), |inp| self.parser.go::<M>(inp))
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::delimited_by`].
 pub struct DelimitedBy<A, B, C, OB, OC> {
     pub(crate) parser: A,
     pub(crate) start: B,
@@ -1340,10 +2118,106 @@ where
     go_extra!(OA);
 }
 
+/// See [`Parser::collect_trivia_into_state`].
+#[cfg(feature = "trivia")]
+#[derive(Copy, Clone)]
+pub struct CollectTrivia<A> {
+    pub(crate) parser: A,
+}
+
+#[cfg(feature = "trivia")]
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for CollectTrivia<A>
+where
+    I: SliceInput<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: TriviaSink<I::Span, I::Slice>,
+    I::Span: Clone,
+    I::Slice: Clone,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let before = inp.offset();
+        let out = self.parser.go::<M>(inp)?;
+        inp.record_trivia(before);
+        Ok(out)
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::profiled`].
+#[cfg(feature = "profile")]
+#[derive(Copy, Clone)]
+pub struct Profiled<A> {
+    pub(crate) parser: A,
+    pub(crate) name: &'static str,
+}
+
+#[cfg(feature = "profile")]
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for Profiled<A>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        if inp.profile.is_none() {
+            return self.parser.go::<M>(inp);
+        }
+        // Every token pulled from the input spends a step of the budget, so the difference is the number pulled
+        let remaining = inp.budget.remaining;
+        let res = self.parser.go::<M>(inp);
+        if let Some(profile) = inp.profile.as_mut() {
+            let entry = profile.entries.entry(self.name).or_default();
+            entry.invocations += 1;
+            entry.successes += res.is_ok() as usize;
+            entry.tokens += remaining - inp.budget.remaining;
+        }
+        res
+    }
+
+    go_extra!(O);
+}
+
 /// See [`Parser::or`].
 #[derive(Copy, Clone)]
 pub struct Or<A, B> {
     pub(crate) choice: crate::primitive::Choice<(A, B)>,
+    pub(crate) union_expected: bool,
+}
+
+impl<A, B> Or<A, B> {
+    /// When both parsers fail at the same position, report everything that either of them expected there.
+    ///
+    /// This is useful for lexer-like choices where either branch could validly begin at the current position. If one
+    /// parser got further into the input than the other before failing, only its error is kept, since the other
+    /// parser's expectations were never tried at that position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let kw = just::<_, _, extra::Err<Rich<char>>>("let")
+    ///     .or(just("if"))
+    ///     .union_expected();
+    ///
+    /// let err = kw.parse("x").into_errors().remove(0);
+    /// assert_eq!(err.span(), &SimpleSpan::new(0, 1));
+    /// assert_eq!(err.expected().len(), 2);
+    ///
+    /// // `let` got further than `if`, so only its expectations are reported
+    /// let err = kw.parse("lo").into_errors().remove(0);
+    /// assert_eq!(err.span(), &SimpleSpan::new(1, 2));
+    /// assert_eq!(err.expected().len(), 1);
+    /// ```
+    pub fn union_expected(self) -> Self {
+        Self {
+            union_expected: true,
+            ..self
+        }
+    }
 }
 
 impl<'a, I, O, E, A, B> ParserSealed<'a, I, O, E> for Or<A, B>
@@ -1355,7 +2229,28 @@ where
 {
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
-        self.choice.go::<M>(inp)
+        if !self.union_expected {
+            return self.choice.go::<M>(inp);
+        }
+
+        let (a, b) = &self.choice.parsers;
+        let before = inp.save();
+
+        let (res, alt) = inp.with_own_alt(|inp| {
+            a.go::<M>(inp).or_else(|()| {
+                inp.rewind(before);
+                let (res, b_alt) = inp.with_own_alt(|inp| b.go::<M>(inp));
+                if res.is_err() {
+                    inp.rewind(before);
+                }
+                // Errors at the same position are merged, otherwise only the furthest is kept
+                inp.add_located_alt(b_alt);
+                res
+            })
+        });
+
+        inp.add_located_alt(alt);
+        res
     }
 
     go_extra!(O);
@@ -1389,12 +2284,63 @@ impl RepeatedCfg {
     }
 }
 
+/// See [`Parser::same_repeated`].
+pub struct SameRepeated<A, O> {
+    pub(crate) parser: A,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<O>,
+}
+
+impl<A: Copy, O> Copy for SameRepeated<A, O> {}
+impl<A: Clone, O> Clone for SameRepeated<A, O> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, E, A, O> ParserSealed<'a, I, (I::Slice, usize), E> for SameRepeated<A, O>
+where
+    I: SliceInput<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+    O: PartialEq,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, (I::Slice, usize)> {
+        let start = inp.offset().offset;
+        // Equality checks need real outputs, so the inner parser always runs in emitting mode
+        let first = self.parser.go::<Emit>(inp)?;
+        let mut count = 1;
+        loop {
+            let before = inp.save();
+            match self.parser.go::<Emit>(inp) {
+                // An item that consumes no input would be found again forever, so it ends the run
+                Ok(out) if out == first && inp.offset != before.offset => count += 1,
+                _ => {
+                    inp.rewind(before);
+                    break;
+                }
+            }
+        }
+        let end = inp.offset().offset;
+
+        Ok(M::bind(|| (inp.slice_inner(start..end), count)))
+    }
+
+    go_extra!((I::Slice, usize));
+}
+
 /// See [`Parser::repeated`].
 pub struct Repeated<A, OA, I, E> {
     pub(crate) parser: A,
     pub(crate) at_least: usize,
     // Slightly evil: Should be `Option<usize>`, but we encode `!0` as 'no cap' because it's so large
     pub(crate) at_most: u64,
+    pub(crate) capacity_hint: usize,
+    pub(crate) require_progress: bool,
     #[cfg(debug_assertions)]
     pub(crate) location: Location<'static>,
     #[allow(dead_code)]
@@ -1408,6 +2354,8 @@ impl<A: Clone, OA, I, E> Clone for Repeated<A, OA, I, E> {
             parser: self.parser.clone(),
             at_least: self.at_least,
             at_most: self.at_most,
+            capacity_hint: self.capacity_hint,
+            require_progress: self.require_progress,
             #[cfg(debug_assertions)]
             location: self.location,
             phantom: EmptyPhantom::new(),
@@ -1480,89 +2428,327 @@ where
             ..self
         }
     }
-}
 
-impl<'a, I, E, A, OA> ParserSealed<'a, I, (), E> for Repeated<A, OA, I, E>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-    A: Parser<'a, I, OA, E>,
-{
-    #[inline(always)]
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, ()> {
-        if self.at_most == !0 && self.at_least == 0 {
-            loop {
-                let before = inp.save();
-                match self.parser.go::<Check>(inp) {
-                    Ok(()) => {}
-                    Err(()) => {
-                        // TODO: Helper for this? Rewind does this? (seconds one may be bad for other cases)
-                        inp.errors.alt = None;
-                        inp.rewind(before);
-                        break Ok(M::bind(|| ()));
-                    }
-                }
-                #[cfg(debug_assertions)]
-                debug_assert!(
-                    before.offset() != inp.offset(),
-                    "found Repeated combinator making no progress at {}",
-                    self.location,
-                );
-            }
-        } else {
-            let mut state = self.make_iter::<Check>(inp)?;
-            loop {
-                #[cfg(debug_assertions)]
-                let before = inp.offset();
-                match self.next::<Check>(inp, &mut state) {
-                    Ok(Some(())) => {}
-                    Ok(None) => break Ok(M::bind(|| ())),
-                    // TODO: Technically we should be rewinding here: as-is, this is invalid since errorring parsers
-                    // are permitted to leave input state unspecified. Really, unwinding should occur *here* and not in
-                    // `next`.
-                    Err(()) => break Err(()),
-                }
-                #[cfg(debug_assertions)]
-                debug_assert!(
-                    before != inp.offset(),
-                    "found Repeated combinator making no progress at {}",
-                    self.location,
-                );
-            }
+    /// Provide a hint about how many times the pattern is likely to appear.
+    ///
+    /// When collected (see [`IterParser::collect`]), the container will be created with
+    /// [`Container::with_capacity`] using this hint, avoiding reallocations. The hint has no effect on which inputs
+    /// are accepted.
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let digits = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(|c: &char| c.is_ascii_digit())
+    ///     .repeated()
+    ///     .with_capacity_hint(16)
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let digits = digits.parse("123").into_result().unwrap();
+    /// assert_eq!(digits, vec!['1', '2', '3']);
+    /// assert!(digits.capacity() >= 16);
+    /// ```
+    pub fn with_capacity_hint(self, capacity_hint: usize) -> Self {
+        Self {
+            capacity_hint,
+            ..self
         }
     }
 
-    go_extra!(());
-}
-
-impl<'a, A, O, I, E> IterParserSealed<'a, I, O, E> for Repeated<A, O, I, E>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-    A: Parser<'a, I, O, E>,
-{
-    type IterState<M: Mode> = usize;
+    /// Fail with an error if the pattern ever matches without consuming any input.
+    ///
+    /// A pattern that can match zero-width would otherwise repeat forever (or, in debug builds, panic), which usually
+    /// indicates a bug in the grammar. With this enabled, the repetition instead fails with the error message
+    /// "parser made no progress" (see [`Error::message`]).
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let items = just::<_, _, extra::Err<Rich<char>>>('a')
+    ///     .or_not()
+    ///     .repeated()
+    ///     .require_progress()
+    ///     .collect::<Vec<_>>();
+    ///
+    /// // After the last `a`, `or_not` matches without consuming any input
+    /// let errs = items.parse("aa").into_errors();
+    /// assert_eq!(errs[0].to_string(), "parser made no progress");
+    /// ```
+    pub fn require_progress(self) -> Self {
+        Self {
+            require_progress: true,
+            ..self
+        }
+    }
 
-    #[inline(always)]
-    fn make_iter<M: Mode>(
-        &self,
-        _inp: &mut InputRef<'a, '_, I, E>,
-    ) -> PResult<Emit, Self::IterState<M>> {
-        Ok(0)
+    /// Collect the repeated outputs into a [`Vec`], dropping any item that is equal to the item immediately before it.
+    ///
+    /// This is useful for normalising a sequence, such as collapsing runs of whitespace tokens into one.
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let letters = one_of::<_, _, extra::Err<Simple<char>>>("ab").padded().repeated().collect_dedup();
+    ///
+    /// assert_eq!(letters.parse("a a b b b a").into_result(), Ok(vec!['a', 'b', 'a']));
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    pub fn collect_dedup(self) -> CollectDedup<Self, OA>
+    where
+        OA: PartialEq,
+    {
+        CollectDedup {
+            parser: self,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
     }
 
-    #[inline(always)]
-    fn next<M: Mode>(
-        &self,
-        inp: &mut InputRef<'a, '_, I, E>,
-        count: &mut Self::IterState<M>,
-    ) -> IPResult<M, O> {
+    /// Collect the repeated outputs into a [`Vec`], merging runs of consecutive items that share the same key.
+    ///
+    /// Each new item is compared (by `key`) with the last item collected so far. If the keys are equal, the two are
+    /// combined with `merge`, otherwise the new item starts a new group.
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// enum Run { Text(String), Space }
+    ///
+    /// let run = choice((
+    ///     any::<_, extra::Err<Simple<char>>>().filter(|c: &char| !c.is_whitespace()).map(|c| Run::Text(c.to_string())),
+    ///     any().filter(|c: &char| c.is_whitespace()).to(Run::Space),
+    /// ));
+    ///
+    /// let runs = run
+    ///     .repeated()
+    ///     .group_by(core::mem::discriminant, |a, b| match (a, b) {
+    ///         (Run::Text(a), Run::Text(b)) => Run::Text(a + &b),
+    ///         (a, _) => a,
+    ///     });
+    ///
+    /// assert_eq!(
+    ///     runs.parse("ab  c").into_result(),
+    ///     Ok(vec![Run::Text("ab".to_string()), Run::Space, Run::Text("c".to_string())]),
+    /// );
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    pub fn group_by<K, KF, MF>(self, key: KF, merge: MF) -> GroupBy<Self, OA, KF, MF>
+    where
+        K: PartialEq,
+        KF: Fn(&OA) -> K,
+        MF: Fn(OA, OA) -> OA,
+    {
+        GroupBy {
+            parser: self,
+            key,
+            merge,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Only attempt each repetition of the pattern while a guard parser matches.
+    ///
+    /// Before each item, `guard` is checked without consuming any input. The repetition stops as soon as the guard
+    /// fails, leaving the remaining input (including whatever caused the guard to fail) unconsumed. Limits such as
+    /// [`Repeated::at_least`] still apply.
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let stmt = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .then_ignore(just(';'))
+    ///     .padded();
+    ///
+    /// let block = stmt
+    ///     .repeated()
+    ///     .while_(any().filter(|c: &char| *c != '}'))
+    ///     .collect::<Vec<_>>()
+    ///     .delimited_by(just('{'), just('}'));
+    ///
+    /// assert_eq!(block.parse("{a; b;}").into_result(), Ok(vec!["a", "b"]));
+    /// ```
+    pub fn while_<G, OG>(self, guard: G) -> Repeated<IgnoreThen<Rewind<G>, A, OG, E>, OA, I, E>
+    where
+        G: Parser<'a, I, OG, E>,
+    {
+        Repeated {
+            parser: IgnoreThen {
+                parser_a: Rewind { parser: guard },
+                parser_b: self.parser,
+                phantom: EmptyPhantom::new(),
+            },
+            at_least: self.at_least,
+            at_most: self.at_most,
+            capacity_hint: self.capacity_hint,
+            require_progress: self.require_progress,
+            #[cfg(debug_assertions)]
+            location: self.location,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Collect the outputs of the pattern into a [`Vec`], along with the span of each individual item.
+    ///
+    /// Unlike using [`Parser::map_with_span`] on the whole collection, which produces a single span covering every
+    /// item, this produces one span per item, which is useful for tooling that needs to point at specific elements.
+    ///
+    /// The output type of this parser is `Vec<(OA, I::Span)>`.
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let words = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .padded()
+    ///     .repeated()
+    ///     .collect_with_spans();
+    ///
+    /// assert_eq!(
+    ///     words.parse("foo bar").into_result(),
+    ///     Ok(vec![("foo", SimpleSpan::new(0, 4)), ("bar", SimpleSpan::new(4, 7))]),
+    /// );
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    pub fn collect_with_spans(
+        self,
+    ) -> Collect<
+        Repeated<MapWithSpan<A, OA, fn(OA, I::Span) -> (OA, I::Span)>, (OA, I::Span), I, E>,
+        (OA, I::Span),
+        Vec<(OA, I::Span)>,
+    > {
+        Repeated {
+            parser: MapWithSpan {
+                parser: self.parser,
+                mapper: (|out, span| (out, span)) as fn(_, _) -> _,
+                phantom: EmptyPhantom::new(),
+            },
+            at_least: self.at_least,
+            at_most: self.at_most,
+            capacity_hint: self.capacity_hint,
+            require_progress: self.require_progress,
+            #[cfg(debug_assertions)]
+            location: self.location,
+            phantom: EmptyPhantom::new(),
+        }
+        .collect()
+    }
+
+    /// Apply a function to each output of the pattern, collecting only those for which it produces [`Some`].
+    ///
+    /// This is useful for discarding items that are parsed but not needed, such as comments. Note that limits such as
+    /// [`Repeated::at_least`] apply to the number of items parsed, not the number collected.
+    ///
+    /// The output type of this parser is `C`, the type being collected into.
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let digits = one_of::<_, _, extra::Err<Simple<char>>>("0123456789_")
+    ///     .repeated()
+    ///     .filter_map_collect::<_, String, _>(|c| Some(c).filter(|c| *c != '_'));
+    ///
+    /// assert_eq!(digits.parse("1_000_000").into_result(), Ok("1000000".to_string()));
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    pub fn filter_map_collect<U, C, F>(self, f: F) -> Collect<IterFilterMap<Self, OA, F>, U, C>
+    where
+        F: Fn(OA) -> Option<U>,
+        C: Container<U>,
+    {
+        IterFilterMap {
+            parser: self,
+            filter_map: f,
+            phantom: EmptyPhantom::new(),
+        }
+        .collect()
+    }
+}
+
+impl<'a, I, E, A, OA> ParserSealed<'a, I, (), E> for Repeated<A, OA, I, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, ()> {
+        if self.at_most == !0 && self.at_least == 0 {
+            loop {
+                let before = inp.save();
+                match self.parser.go::<Check>(inp) {
+                    Ok(()) if self.require_progress && before.offset() == inp.offset() => {
+                        inp.add_no_progress_err(before.offset());
+                        break Err(());
+                    }
+                    Ok(()) => {}
+                    Err(()) => {
+                        // TODO: Helper for this? Rewind does this? (seconds one may be bad for other cases)
+                        inp.errors.alt = None;
+                        inp.rewind(before);
+                        break Ok(M::bind(|| ()));
+                    }
+                }
+                #[cfg(debug_assertions)]
+                debug_assert!(
+                    before.offset() != inp.offset(),
+                    "found Repeated combinator making no progress at {}",
+                    self.location,
+                );
+            }
+        } else {
+            let mut state = self.make_iter::<Check>(inp)?;
+            loop {
+                #[cfg(debug_assertions)]
+                let before = inp.offset();
+                match self.next::<Check>(inp, &mut state) {
+                    Ok(Some(())) => {}
+                    Ok(None) => break Ok(M::bind(|| ())),
+                    // TODO: Technically we should be rewinding here: as-is, this is invalid since errorring parsers
+                    // are permitted to leave input state unspecified. Really, unwinding should occur *here* and not in
+                    // `next`.
+                    Err(()) => break Err(()),
+                }
+                #[cfg(debug_assertions)]
+                debug_assert!(
+                    before != inp.offset(),
+                    "found Repeated combinator making no progress at {}",
+                    self.location,
+                );
+            }
+        }
+    }
+
+    go_extra!(());
+}
+
+impl<'a, A, O, I, E> IterParserSealed<'a, I, O, E> for Repeated<A, O, I, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    type IterState<M: Mode> = usize;
+
+    #[inline(always)]
+    fn make_iter<M: Mode>(
+        &self,
+        _inp: &mut InputRef<'a, '_, I, E>,
+    ) -> PResult<Emit, Self::IterState<M>> {
+        Ok(0)
+    }
+
+    #[inline(always)]
+    fn next<M: Mode>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+        count: &mut Self::IterState<M>,
+    ) -> IPResult<M, O> {
         if *count as u64 >= self.at_most {
             return Ok(None);
         }
 
         let before = inp.save();
         match self.parser.go::<M>(inp) {
+            Ok(_) if self.require_progress && before.offset() == inp.offset() => {
+                inp.add_no_progress_err(before.offset());
+                Err(())
+            }
             Ok(item) => {
                 *count += 1;
                 Ok(Some(item))
@@ -1577,6 +2763,16 @@ where
             }
         }
     }
+
+    #[inline(always)]
+    fn capacity_hint(&self) -> usize {
+        self.capacity_hint
+    }
+
+    #[inline(always)]
+    fn may_stop<M: Mode>(&self, count: &Self::IterState<M>) -> bool {
+        *count >= self.at_least
+    }
 }
 
 impl<'a, A, O, I, E> ConfigIterParserSealed<'a, I, O, E> for Repeated<A, O, I, E>
@@ -1603,6 +2799,10 @@ where
 
         let before = inp.save();
         match self.parser.go::<M>(inp) {
+            Ok(_) if self.require_progress && before.offset() == inp.offset() => {
+                inp.add_no_progress_err(before.offset());
+                Err(())
+            }
             Ok(item) => {
                 *count += 1;
                 Ok(Some(item))
@@ -1617,6 +2817,11 @@ where
             }
         }
     }
+
+    #[inline(always)]
+    fn may_stop_cfg<M: Mode>(&self, count: &Self::IterState<M>, cfg: &Self::Config) -> bool {
+        *count >= cfg.at_least.unwrap_or(self.at_least)
+    }
 }
 
 /// See [`Parser::separated_by`].
@@ -1628,6 +2833,8 @@ pub struct SeparatedBy<A, B, OA, OB, I, E> {
     pub(crate) at_most: u64,
     pub(crate) allow_leading: bool,
     pub(crate) allow_trailing: bool,
+    pub(crate) forbid_trailing: bool,
+    pub(crate) require_progress: bool,
     #[cfg(debug_assertions)]
     pub(crate) location: Location<'static>,
     #[allow(dead_code)]
@@ -1644,6 +2851,8 @@ impl<A: Clone, B: Clone, OA, OB, I, E> Clone for SeparatedBy<A, B, OA, OB, I, E>
             at_most: self.at_most,
             allow_leading: self.allow_leading,
             allow_trailing: self.allow_trailing,
+            forbid_trailing: self.forbid_trailing,
+            require_progress: self.require_progress,
             #[cfg(debug_assertions)]
             location: self.location,
             phantom: EmptyPhantom::new(),
@@ -1786,41 +2995,258 @@ where
             ..self
         }
     }
-}
 
-impl<'a, I, E, A, B, OA, OB> IterParserSealed<'a, I, OA, E> for SeparatedBy<A, B, OA, OB, I, E>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-    A: Parser<'a, I, OA, E>,
-    B: Parser<'a, I, OB, E>,
-{
-    type IterState<M: Mode> = usize
+    /// Treat a trailing separator after the last item as an error, rather than leaving it unconsumed.
+    ///
+    /// By default, a separator that is not followed by an item is left for whatever parser comes next, which often
+    /// results in a confusing error far from the real problem. With this, the list instead fails with an error that
+    /// points at the trailing separator (see [`Error::trailing_separator`]). If an item follows the separator but fails
+    /// partway through, the item's own error is reported instead. This has no effect if [`SeparatedBy::allow_trailing`] is also used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let numbers = text::int::<_, _, extra::Err<Rich<char>>>(10)
+    ///     .separated_by(just(','))
+    ///     .forbid_trailing_explicit()
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(numbers.parse("1,2").into_result(), Ok(vec!["1", "2"]));
+    /// let errs = numbers.parse("1,2,").into_errors();
+    /// assert_eq!(errs[0].span(), &SimpleSpan::new(3, 4));
+    /// assert_eq!(errs[0].to_string(), "unexpected trailing separator");
+    /// ```
+    pub fn forbid_trailing_explicit(self) -> Self {
+        Self {
+            forbid_trailing: true,
+            ..self
+        }
+    }
+
+    /// Require that items be separated by whitespace, in addition to the separator.
+    ///
+    /// Whitespace is permitted on either side of the separator, but at least one whitespace character must appear
+    /// between two items. This is most useful in combination with [`empty`] as the separator, for whitespace-separated
+    /// lists without any explicit separator token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let letters = one_of::<_, _, extra::Err<Simple<char>>>("abc")
+    ///     .separated_by(empty())
+    ///     .require_whitespace()
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(letters.parse("a b  c").into_result(), Ok(vec!['a', 'b', 'c']));
+    /// // Adjacent items are not permitted
+    /// assert!(letters.parse("ab").has_errors());
+    /// ```
+    pub fn require_whitespace(self) -> SeparatedBy<A, RequireWhitespace<B>, OA, OB, I, E>
     where
-        I: 'a;
+        I: ValueInput<'a>,
+        I::Token: Char,
+    {
+        SeparatedBy {
+            parser: self.parser,
+            separator: RequireWhitespace {
+                separator: self.separator,
+            },
+            at_least: self.at_least,
+            at_most: self.at_most,
+            allow_leading: self.allow_leading,
+            allow_trailing: self.allow_trailing,
+            forbid_trailing: self.forbid_trailing,
+            require_progress: self.require_progress,
+            #[cfg(debug_assertions)]
+            location: self.location,
+            phantom: EmptyPhantom::new(),
+        }
+    }
 
-    #[inline(always)]
-    fn make_iter<M: Mode>(
-        &self,
-        _inp: &mut InputRef<'a, '_, I, E>,
-    ) -> PResult<Emit, Self::IterState<M>> {
-        Ok(0)
+    /// Apply a recovery strategy to each item of the list, should it fail.
+    ///
+    /// Without this, a malformed item ends the list (or fails it entirely, if too few items have been parsed). With
+    /// it, a malformed item is recovered via the given [`Strategy`] in the same way as [`Parser::recover_with`], its
+    /// error is emitted, and parsing of the list continues with the next separator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let numbers = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .padded()
+    ///     .separated_by(just(','))
+    ///     .recover_item_with(via_parser(none_of(",]").repeated().at_least(1).to("?")))
+    ///     .collect::<Vec<_>>()
+    ///     .delimited_by(just('['), just(']'));
+    ///
+    /// let (output, errs) = numbers.parse("[1, oops, 3]").into_output_errors();
+    /// assert_eq!(output, Some(vec!["1", "?", "3"]));
+    /// assert_eq!(errs.len(), 1);
+    /// ```
+    pub fn recover_item_with<S: Strategy<'a, I, OA, E>>(
+        self,
+        strategy: S,
+    ) -> SeparatedBy<RecoverWith<A, S>, B, OA, OB, I, E> {
+        SeparatedBy {
+            parser: RecoverWith {
+                parser: self.parser,
+                strategy,
+            },
+            separator: self.separator,
+            at_least: self.at_least,
+            at_most: self.at_most,
+            allow_leading: self.allow_leading,
+            allow_trailing: self.allow_trailing,
+            forbid_trailing: self.forbid_trailing,
+            require_progress: self.require_progress,
+            #[cfg(debug_assertions)]
+            location: self.location,
+            phantom: EmptyPhantom::new(),
+        }
     }
 
-    #[inline(always)]
-    fn next<M: Mode>(
-        &self,
-        inp: &mut InputRef<'a, '_, I, E>,
-        state: &mut Self::IterState<M>,
-    ) -> IPResult<M, OA> {
-        if *state as u64 >= self.at_most {
-            return Ok(None);
+    /// Fail with an error if an item and its preceding separator ever match without consuming any input.
+    ///
+    /// The first item has no separator before it, so it may be empty. See [`Repeated::require_progress`].
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let items = just::<_, _, extra::Err<Rich<char>>>('a')
+    ///     .or_not()
+    ///     .separated_by(just(','))
+    ///     .require_progress()
+    ///     .collect::<Vec<_>>();
+    ///
+    /// // Empty items are fine, so long as the separator before them consumed input
+    /// assert_eq!(items.parse("a,,a").into_result(), Ok(vec![Some('a'), None, Some('a')]));
+    /// assert_eq!(items.parse(",a").into_result(), Ok(vec![None, Some('a')]));
+    ///
+    /// // After the `a`, both the optional separator and the optional item match without consuming any input
+    /// let items = just::<_, _, extra::Err<Rich<char>>>('a')
+    ///     .or_not()
+    ///     .separated_by(just(',').or_not())
+    ///     .require_progress()
+    ///     .collect::<Vec<_>>();
+    /// let errs = items.parse("a").into_errors();
+    /// assert_eq!(errs[0].to_string(), "parser made no progress");
+    /// ```
+    pub fn require_progress(self) -> Self {
+        Self {
+            require_progress: true,
+            ..self
         }
+    }
+}
 
-        let before_separator = inp.save();
-        if *state == 0 && self.allow_leading {
-            if self.separator.go::<Check>(inp).is_err() {
-                inp.rewind(before_separator);
+/// See [`SeparatedBy::require_whitespace`].
+#[derive(Copy, Clone)]
+pub struct RequireWhitespace<B> {
+    pub(crate) separator: B,
+}
+
+impl<'a, I, O, E, B> ParserSealed<'a, I, O, E> for RequireWhitespace<B>
+where
+    I: ValueInput<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: Char,
+    B: Parser<'a, I, O, E>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let before = inp.offset();
+        inp.skip_while(|c| c.is_whitespace());
+        let mut found_whitespace = before != inp.offset();
+
+        let out = self.separator.go::<M>(inp)?;
+
+        let after_separator = inp.offset();
+        inp.skip_while(|c| c.is_whitespace());
+        found_whitespace |= after_separator != inp.offset();
+
+        if found_whitespace {
+            Ok(out)
+        } else {
+            let span = inp.span_since(after_separator);
+            inp.add_alt_err(
+                inp.offset,
+                E::Error::message(span, "expected whitespace between items".to_string()),
+            );
+            Err(())
+        }
+    }
+
+    go_extra!(O);
+}
+
+/// Note on the errors produced by a list element which element of the list it was: both the alternative error, if the
+/// element failed, and any errors emitted since `err_count` while it recovered. See [`Error::in_list_element`].
+fn note_list_element<'a, I, E>(inp: &mut InputRef<'a, '_, I, E>, err_count: usize, index: usize)
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    if let Some(mut alt) = inp.errors.alt.take() {
+        alt.err = alt.err.in_list_element(index);
+        inp.errors.alt = Some(alt);
+    }
+    let recovered = inp.errors.secondary.split_off(err_count);
+    inp.errors.secondary.extend(
+        recovered
+            .into_iter()
+            .map(|err| Located::at(err.pos, err.err.in_list_element(index))),
+    );
+}
+
+impl<'a, I, E, A, B, OA, OB> IterParserSealed<'a, I, OA, E> for SeparatedBy<A, B, OA, OB, I, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+    B: Parser<'a, I, OB, E>,
+{
+    type IterState<M: Mode> = usize
+    where
+        I: 'a;
+
+    #[inline(always)]
+    fn make_iter<M: Mode>(
+        &self,
+        _inp: &mut InputRef<'a, '_, I, E>,
+    ) -> PResult<Emit, Self::IterState<M>> {
+        Ok(0)
+    }
+
+    #[inline(always)]
+    fn next<M: Mode>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+        state: &mut Self::IterState<M>,
+    ) -> IPResult<M, OA> {
+        Ok(self.next_with_start::<M>(inp, state)?.map(|(_, item)| item))
+    }
+
+    #[inline(always)]
+    fn may_stop<M: Mode>(&self, state: &Self::IterState<M>) -> bool {
+        *state >= self.at_least
+    }
+
+    #[inline(always)]
+    fn next_with_start<'parse, M: Mode>(
+        &self,
+        inp: &mut InputRef<'a, 'parse, I, E>,
+        state: &mut Self::IterState<M>,
+    ) -> Result<Option<(input::Offset<'a, 'parse, I>, M::Output<OA>)>, ()> {
+        if *state as u64 >= self.at_most {
+            return Ok(None);
+        }
+
+        let before_separator = inp.save();
+        if *state == 0 && self.allow_leading {
+            if self.separator.go::<Check>(inp).is_err() {
+                inp.rewind(before_separator);
             }
         } else if *state > 0 {
             match self.separator.go::<Check>(inp) {
@@ -1839,10 +3265,24 @@ where
         }
 
         let before_item = inp.save();
-        match self.parser.go::<M>(inp) {
+        let res = self.parser.go::<M>(inp);
+        // The first item isn't mid-list, so only later items are noted
+        if *state > 0 && (res.is_err() || inp.errors.secondary.len() > before_item.err_count) {
+            note_list_element(inp, before_item.err_count, *state);
+        }
+        match res {
+            // The first item has no separator before it, so only a later item can repeat without making progress
+            Ok(_)
+                if self.require_progress
+                    && *state > 0
+                    && before_separator.offset() == inp.offset() =>
+            {
+                inp.add_no_progress_err(before_separator.offset());
+                Err(())
+            }
             Ok(item) => {
                 *state += 1;
-                Ok(Some(item))
+                Ok(Some((before_item.offset(), item)))
             }
             Err(()) if *state < self.at_least => {
                 // We have errored before we have reached the count,
@@ -1859,6 +3299,26 @@ where
                 // consumed the separator, so we need to rewind it.
                 if self.allow_trailing {
                     inp.rewind(before_item);
+                } else if self.forbid_trailing && *state > 0 {
+                    // An item that got past its first token before failing is the real problem, so only blame the
+                    // separator if the item failed on whatever came straight after it
+                    inp.rewind(before_item);
+                    let first_token_end = inp.next_maybe_inner().0.into();
+                    let item_progressed =
+                        matches!(&inp.errors.alt, Some(alt) if alt.pos.into() > first_token_end);
+                    if !item_progressed {
+                        inp.rewind(before_separator);
+                        let found = inp.next_maybe_inner().1;
+                        inp.rewind(before_item);
+                        let span = inp.span_since(before_separator.offset());
+                        // The separator is the real problem, so it replaces whatever the missing item expected
+                        inp.errors.alt = None;
+                        inp.add_alt_err(
+                            before_item.offset,
+                            E::Error::trailing_separator(found.map(|f| f.into()), span),
+                        );
+                    }
+                    return Err(());
                 } else {
                     inp.rewind(before_separator);
                 }
@@ -1949,6 +3409,89 @@ where
         state.0 += 1;
         Ok(out)
     }
+
+    #[inline(always)]
+    fn capacity_hint(&self) -> usize {
+        self.parser.capacity_hint()
+    }
+
+    #[inline(always)]
+    fn may_stop<M: Mode>(&self, state: &Self::IterState<M>) -> bool {
+        self.parser.may_stop(&state.1)
+    }
+}
+
+/// See [`IterParser::stop_on`].
+pub struct StopOn<A, B, OB> {
+    pub(crate) parser: A,
+    pub(crate) marker: B,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<OB>,
+}
+
+impl<A: Copy, B: Copy, OB> Copy for StopOn<A, B, OB> {}
+impl<A: Clone, B: Clone, OB> Clone for StopOn<A, B, OB> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            marker: self.marker.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, O, E, A, B, OB> IterParserSealed<'a, I, O, E> for StopOn<A, B, OB>
+where
+    A: IterParser<'a, I, O, E>,
+    B: Parser<'a, I, OB, E>,
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    type IterState<M: Mode> = (bool, A::IterState<M>)
+    where
+        I: 'a;
+
+    #[inline(always)]
+    fn make_iter<M: Mode>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+    ) -> PResult<Emit, Self::IterState<M>> {
+        Ok((false, A::make_iter(&self.parser, inp)?))
+    }
+
+    #[inline(always)]
+    fn next<M: Mode>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+        state: &mut Self::IterState<M>,
+    ) -> IPResult<M, O> {
+        if state.0 {
+            return Ok(None);
+        }
+
+        // Until the inner parser has produced as many items as it needs, the marker is not a way out: the inner parser
+        // gets to look at it instead, and fails there just as it would at any other missing item
+        if self.parser.may_stop(&state.1) {
+            let before = inp.save();
+            if self.marker.go::<Check>(inp).is_ok() {
+                state.0 = true;
+                return Ok(None);
+            }
+            inp.rewind(before);
+        }
+
+        self.parser.next(inp, &mut state.1)
+    }
+
+    #[inline(always)]
+    fn capacity_hint(&self) -> usize {
+        self.parser.capacity_hint()
+    }
+
+    #[inline(always)]
+    fn may_stop<M: Mode>(&self, state: &Self::IterState<M>) -> bool {
+        state.0 || self.parser.may_stop(&state.1)
+    }
 }
 
 /// See [`IterParser::collect`].
@@ -1972,6 +3515,35 @@ impl<A: Clone, O, C> Clone for Collect<A, O, C> {
     }
 }
 
+impl<A, O, C> Collect<A, O, C> {
+    /// Collect into a different [`Container`] type instead, without changing how the items are parsed.
+    ///
+    /// The items are pushed directly into the new container as they are parsed, so this doesn't require building the
+    /// original container first and converting it afterwards.
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let letters = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(char::is_ascii_alphabetic)
+    ///     .repeated()
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(letters.parse("abc").into_result(), Ok(vec!['a', 'b', 'c']));
+    /// assert_eq!(letters.collect_as::<String>().parse("abc").into_result(), Ok("abc".to_string()));
+    /// ```
+    pub fn collect_as<D>(self) -> Collect<A, O, D>
+    where
+        D: Container<O>,
+    {
+        Collect {
+            parser: self.parser,
+            #[cfg(debug_assertions)]
+            location: self.location,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
 impl<'a, I, O, E, A, C> ParserSealed<'a, I, C, E> for Collect<A, O, C>
 where
     I: Input<'a>,
@@ -1981,7 +3553,7 @@ where
 {
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, C> {
-        let mut output = M::bind::<C, _>(|| C::default());
+        let mut output = M::bind::<C, _>(|| C::with_capacity(self.parser.capacity_hint()));
         let mut iter_state = self.parser.make_iter::<M>(inp)?;
         #[cfg(debug_assertions)]
         let mut i = 0;
@@ -2014,216 +3586,172 @@ where
     go_extra!(C);
 }
 
-/// See [`IterParser::collect_exactly`]
-pub struct CollectExactly<A, O, C> {
+/// See [`IterParser::collect_flat`].
+pub struct CollectFlat<A, O, C> {
     pub(crate) parser: A,
+    #[cfg(debug_assertions)]
+    pub(crate) location: Location<'static>,
     #[allow(dead_code)]
     pub(crate) phantom: EmptyPhantom<(O, C)>,
 }
 
-impl<A: Copy, O, C> Copy for CollectExactly<A, O, C> {}
-impl<A: Clone, O, C> Clone for CollectExactly<A, O, C> {
+impl<A: Copy, O, C> Copy for CollectFlat<A, O, C> {}
+impl<A: Clone, O, C> Clone for CollectFlat<A, O, C> {
     fn clone(&self) -> Self {
         Self {
             parser: self.parser.clone(),
+            #[cfg(debug_assertions)]
+            location: self.location,
             phantom: EmptyPhantom::new(),
         }
     }
 }
 
-impl<'a, I, O, E, A, C> ParserSealed<'a, I, C, E> for CollectExactly<A, O, C>
+impl<'a, I, O, E, A, C> ParserSealed<'a, I, C, E> for CollectFlat<A, O, C>
 where
     I: Input<'a>,
     E: ParserExtra<'a, I>,
     A: IterParser<'a, I, O, E>,
-    C: ContainerExactly<O>,
+    O: IntoIterator,
+    C: Container<O::Item>,
 {
-    #[inline]
+    #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, C> {
-        let before = inp.offset();
-        let mut output = M::bind(|| C::uninit());
-        let mut iter_state = self.parser.make_iter::<M>(inp)?;
-        for idx in 0..C::LEN {
-            match self.parser.next::<M>(inp, &mut iter_state) {
-                Ok(Some(out)) => {
-                    M::combine_mut(&mut output, out, |c, out| C::write(c, idx, out));
-                }
-                Ok(None) => {
-                    inp.add_alt(inp.offset, None, None, inp.span_since(before));
-                    // SAFETY: We're guaranteed to have initialized up to `idx` values
-                    M::map(output, |mut output| unsafe {
-                        C::drop_before(&mut output, idx)
-                    });
-                    return Err(());
-                }
-                Err(()) => {
-                    // SAFETY: We're guaranteed to have initialized up to `idx` values
-                    M::map(output, |mut output| unsafe {
-                        C::drop_before(&mut output, idx)
-                    });
-                    return Err(());
-                }
-            }
-        }
-        // SAFETY: If we reach this point, we guarantee to have initialized C::LEN values
-        Ok(M::map(output, |output| unsafe { C::take(output) }))
+        collect_runs::<M, _, _, _, _, _>(
+            &self.parser,
+            inp,
+            C::with_capacity,
+            |output, items| items.into_iter().for_each(|item| output.push(item)),
+            #[cfg(debug_assertions)]
+            "CollectFlat",
+            #[cfg(debug_assertions)]
+            self.location,
+        )
     }
 
     go_extra!(C);
 }
 
-/// See [`Parser::or_not`].
-#[derive(Copy, Clone)]
-pub struct OrNot<A> {
-    pub(crate) parser: A,
-}
-
-impl<'a, I, O, E, A> ParserSealed<'a, I, Option<O>, E> for OrNot<A>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-    A: Parser<'a, I, O, E>,
-{
-    #[inline(always)]
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, Option<O>> {
-        let before = inp.save();
-        Ok(match self.parser.go::<M>(inp) {
-            Ok(out) => M::map::<O, _, _>(out, Some),
-            Err(()) => {
-                inp.rewind(before);
-                M::bind::<Option<O>, _>(|| None)
-            }
-        })
-    }
-
-    go_extra!(Option<O>);
-}
-
-/// See [`Parser::not`].
-pub struct Not<A, OA> {
+/// See [`Repeated::group_by`].
+pub struct GroupBy<A, O, KF, MF> {
     pub(crate) parser: A,
+    pub(crate) key: KF,
+    pub(crate) merge: MF,
+    #[cfg(debug_assertions)]
+    pub(crate) location: Location<'static>,
     #[allow(dead_code)]
-    pub(crate) phantom: EmptyPhantom<OA>,
+    pub(crate) phantom: EmptyPhantom<O>,
 }
 
-impl<A: Copy, OA> Copy for Not<A, OA> {}
-impl<A: Clone, OA> Clone for Not<A, OA> {
+impl<A: Copy, O, KF: Copy, MF: Copy> Copy for GroupBy<A, O, KF, MF> {}
+impl<A: Clone, O, KF: Clone, MF: Clone> Clone for GroupBy<A, O, KF, MF> {
     fn clone(&self) -> Self {
         Self {
             parser: self.parser.clone(),
+            key: self.key.clone(),
+            merge: self.merge.clone(),
+            #[cfg(debug_assertions)]
+            location: self.location,
             phantom: EmptyPhantom::new(),
         }
     }
 }
 
-impl<'a, I, E, A, OA> ParserSealed<'a, I, (), E> for Not<A, OA>
+/// Collect the items of `parser` into the output created by `with_capacity`, using `push` to add each item to those
+/// collected so far. This is the loop shared by [`CollectFlat`], [`GroupBy`] and [`CollectDedup`].
+#[inline(always)]
+fn collect_runs<'a, M, I, O, E, A, C>(
+    parser: &A,
+    inp: &mut InputRef<'a, '_, I, E>,
+    with_capacity: impl FnOnce(usize) -> C,
+    mut push: impl FnMut(&mut C, O),
+    #[cfg(debug_assertions)] name: &str,
+    #[cfg(debug_assertions)] location: Location<'static>,
+) -> PResult<M, C>
 where
-    I: ValueInput<'a>,
+    M: Mode,
+    I: Input<'a>,
     E: ParserExtra<'a, I>,
-    A: Parser<'a, I, OA, E>,
+    A: IterParser<'a, I, O, E>,
 {
-    #[inline(always)]
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, ()> {
-        let before = inp.save();
+    let mut output = M::bind::<C, _>(|| with_capacity(parser.capacity_hint()));
+    let mut iter_state = parser.make_iter::<M>(inp)?;
+    #[cfg(debug_assertions)]
+    let mut i = 0;
+    loop {
+        #[cfg(debug_assertions)]
+        let before = inp.offset();
+        match parser.next::<M>(inp, &mut iter_state) {
+            Ok(Some(out)) => M::combine_mut(&mut output, out, &mut push),
+            Ok(None) => break Ok(output),
+            Err(()) => break Err(()),
+        }
+        // Only the first item may match without consuming any input: after that, the same pattern would match
+        // nothing again and again forever.
+        #[cfg(debug_assertions)]
+        {
+            if i >= 1 {
+                debug_assert!(
+                    before != inp.offset(),
+                    "found {} combinator making no progress at {}",
+                    name,
+                    location,
+                );
+            }
+            i += 1;
+        }
+    }
+}
 
-        let alt = inp.errors.alt.take();
-
-        let result = self.parser.go::<Check>(inp);
-        let result_span = inp.span_since(before.offset());
-        inp.rewind(before);
-
-        inp.errors.alt = alt;
-
-        match result {
-            Ok(()) => {
-                let (at, found) = inp.next_inner();
-                inp.add_alt(at, None, found.map(|f| f.into()), result_span);
-                Err(())
-            }
-            Err(()) => Ok(M::bind(|| ())),
-        }
-    }
-
-    go_extra!(());
-}
-
-/// See [`Parser::and_is`].
-pub struct AndIs<A, B, OB> {
-    pub(crate) parser_a: A,
-    pub(crate) parser_b: B,
-    #[allow(dead_code)]
-    pub(crate) phantom: EmptyPhantom<OB>,
-}
-
-impl<A: Copy, B: Copy, OB> Copy for AndIs<A, B, OB> {}
-impl<A: Clone, B: Clone, OB> Clone for AndIs<A, B, OB> {
-    fn clone(&self) -> Self {
-        Self {
-            parser_a: self.parser_a.clone(),
-            parser_b: self.parser_b.clone(),
-            phantom: EmptyPhantom::new(),
-        }
-    }
-}
-
-impl<'a, I, E, A, B, OA, OB> ParserSealed<'a, I, OA, E> for AndIs<A, B, OB>
+impl<'a, I, O, E, A, K, KF, MF> ParserSealed<'a, I, Vec<O>, E> for GroupBy<A, O, KF, MF>
 where
     I: Input<'a>,
     E: ParserExtra<'a, I>,
-    A: Parser<'a, I, OA, E>,
-    B: Parser<'a, I, OB, E>,
+    A: IterParser<'a, I, O, E>,
+    K: PartialEq,
+    KF: Fn(&O) -> K,
+    MF: Fn(O, O) -> O,
 {
     #[inline(always)]
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, OA> {
-        let before = inp.save();
-        match self.parser_a.go::<M>(inp) {
-            Ok(out) => {
-                // A succeeded -- go back to the beginning and try B
-                let after = inp.save();
-                inp.rewind(before);
-
-                match self.parser_b.go::<Check>(inp) {
-                    Ok(()) => {
-                        // B succeeded -- go to the end of A and return its output
-                        inp.rewind(after);
-                        Ok(out)
-                    }
-                    Err(()) => {
-                        // B failed -- go back to the beginning and fail
-                        inp.rewind(before);
-                        Err(())
-                    }
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, Vec<O>> {
+        collect_runs::<M, _, _, _, _, _>(
+            &self.parser,
+            inp,
+            Vec::with_capacity,
+            |output, item| match output.pop() {
+                Some(last) if (self.key)(&last) == (self.key)(&item) => {
+                    output.push((self.merge)(last, item))
                 }
-            }
-            Err(()) => {
-                // A failed -- go back to the beginning and fail
-                inp.rewind(before);
-                Err(())
-            }
-        }
+                Some(last) => {
+                    output.push(last);
+                    output.push(item);
+                }
+                None => output.push(item),
+            },
+            #[cfg(debug_assertions)]
+            "GroupBy",
+            #[cfg(debug_assertions)]
+            self.location,
+        )
     }
 
-    go_extra!(OA);
+    go_extra!(Vec<O>);
 }
 
-/// See [`IterParser::foldr`].
-pub struct Foldr<F, A, B, OA, E> {
-    pub(crate) parser_a: A,
-    pub(crate) parser_b: B,
-    pub(crate) folder: F,
+/// See [`Repeated::collect_dedup`].
+pub struct CollectDedup<A, O> {
+    pub(crate) parser: A,
     #[cfg(debug_assertions)]
     pub(crate) location: Location<'static>,
     #[allow(dead_code)]
-    pub(crate) phantom: EmptyPhantom<(OA, E)>,
+    pub(crate) phantom: EmptyPhantom<O>,
 }
 
-impl<F: Copy, A: Copy, B: Copy, OA, E> Copy for Foldr<F, A, B, OA, E> {}
-impl<F: Clone, A: Clone, B: Clone, OA, E> Clone for Foldr<F, A, B, OA, E> {
+impl<A: Copy, O> Copy for CollectDedup<A, O> {}
+impl<A: Clone, O> Clone for CollectDedup<A, O> {
     fn clone(&self) -> Self {
         Self {
-            parser_a: self.parser_a.clone(),
-            parser_b: self.parser_b.clone(),
-            folder: self.folder.clone(),
+            parser: self.parser.clone(),
             #[cfg(debug_assertions)]
             location: self.location,
             phantom: EmptyPhantom::new(),
@@ -2231,541 +3759,2685 @@ impl<F: Clone, A: Clone, B: Clone, OA, E> Clone for Foldr<F, A, B, OA, E> {
     }
 }
 
-impl<'a, I, F, A, B, O, OA, E> ParserSealed<'a, I, O, E> for Foldr<F, A, B, OA, E>
+impl<'a, I, O, E, A> ParserSealed<'a, I, Vec<O>, E> for CollectDedup<A, O>
 where
     I: Input<'a>,
-    A: IterParser<'a, I, OA, E>,
-    B: Parser<'a, I, O, E>,
     E: ParserExtra<'a, I>,
-    F: Fn(OA, O) -> O,
+    A: IterParser<'a, I, O, E>,
+    O: PartialEq,
 {
     #[inline(always)]
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
-    where
-        Self: Sized,
-    {
-        let mut a_out = M::bind(|| Vec::new());
-        let mut iter_state = self.parser_a.make_iter::<M>(inp)?;
-        loop {
-            #[cfg(debug_assertions)]
-            let before = inp.offset();
-            match self.parser_a.next::<M>(inp, &mut iter_state) {
-                Ok(Some(out)) => {
-                    M::combine_mut(&mut a_out, out, |a_out, item| a_out.push(item));
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, Vec<O>> {
+        collect_runs::<M, _, _, _, _, _>(
+            &self.parser,
+            inp,
+            Vec::with_capacity,
+            |output, item| {
+                if output.last() != Some(&item) {
+                    output.push(item);
                 }
-                Ok(None) => break,
-                Err(()) => return Err(()),
-            }
+            },
             #[cfg(debug_assertions)]
-            debug_assert!(
-                before != inp.offset(),
-                "found Foldr combinator making no progress at {}",
-                self.location,
-            );
-        }
-
-        let b_out = self.parser_b.go::<M>(inp)?;
-
-        Ok(M::combine(a_out, b_out, |a_out, b_out| {
-            a_out.into_iter().rfold(b_out, |b, a| (self.folder)(a, b))
-        }))
+            "CollectDedup",
+            #[cfg(debug_assertions)]
+            self.location,
+        )
     }
 
-    go_extra!(O);
+    go_extra!(Vec<O>);
 }
 
-/// See [`IterParser::foldr_with_state`].
-pub struct FoldrWithState<F, A, B, OA, E> {
-    pub(crate) parser_a: A,
-    pub(crate) parser_b: B,
+/// See [`IterParser::fold`].
+pub struct Fold<A, O, T, F> {
+    pub(crate) parser: A,
+    pub(crate) init: T,
     pub(crate) folder: F,
-    #[cfg(debug_assertions)]
-    pub(crate) location: Location<'static>,
     #[allow(dead_code)]
-    pub(crate) phantom: EmptyPhantom<(OA, E)>,
+    pub(crate) phantom: EmptyPhantom<O>,
 }
 
-impl<F: Copy, A: Copy, B: Copy, OA, E> Copy for FoldrWithState<F, A, B, OA, E> {}
-impl<F: Clone, A: Clone, B: Clone, OA, E> Clone for FoldrWithState<F, A, B, OA, E> {
+impl<A: Copy, O, T: Copy, F: Copy> Copy for Fold<A, O, T, F> {}
+impl<A: Clone, O, T: Clone, F: Clone> Clone for Fold<A, O, T, F> {
     fn clone(&self) -> Self {
         Self {
-            parser_a: self.parser_a.clone(),
-            parser_b: self.parser_b.clone(),
+            parser: self.parser.clone(),
+            init: self.init.clone(),
             folder: self.folder.clone(),
-            #[cfg(debug_assertions)]
-            location: self.location,
             phantom: EmptyPhantom::new(),
         }
     }
 }
 
-impl<'a, I, F, A, B, O, OA, E> ParserSealed<'a, I, O, E> for FoldrWithState<F, A, B, OA, E>
+impl<'a, I, O, E, A, T, F> ParserSealed<'a, I, T, E> for Fold<A, O, T, F>
 where
     I: Input<'a>,
-    A: IterParser<'a, I, OA, E>,
-    B: Parser<'a, I, O, E>,
     E: ParserExtra<'a, I>,
-    F: Fn(OA, O, &mut E::State) -> O,
+    A: IterParser<'a, I, O, E>,
+    T: Clone,
+    F: Fn(T, O) -> T,
 {
     #[inline(always)]
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
-    where
-        Self: Sized,
-    {
-        let mut a_out = M::bind(|| Vec::new());
-        let mut iter_state = self.parser_a.make_iter::<M>(inp)?;
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, T> {
+        let mut acc = M::bind(|| self.init.clone());
+        let mut iter_state = self.parser.make_iter::<M>(inp)?;
         loop {
-            #[cfg(debug_assertions)]
-            let before = inp.offset();
-            match self.parser_a.next::<M>(inp, &mut iter_state) {
-                Ok(Some(out)) => {
-                    M::combine_mut(&mut a_out, out, |a_out, item| a_out.push(item));
-                }
-                Ok(None) => break,
-                Err(()) => return Err(()),
+            match self.parser.next::<M>(inp, &mut iter_state) {
+                Ok(Some(out)) => acc = M::combine(acc, out, &self.folder),
+                Ok(None) => break Ok(acc),
+                Err(()) => break Err(()),
             }
-            #[cfg(debug_assertions)]
-            debug_assert!(
-                before != inp.offset(),
-                "found FoldrWithState combinator making no progress at {}",
-                self.location,
-            );
         }
-
-        let b_out = self.parser_b.go::<M>(inp)?;
-
-        Ok(M::combine(a_out, b_out, |a_out, b_out| {
-            let state = inp.state();
-            a_out
-                .into_iter()
-                .rfold(b_out, |b, a| (self.folder)(a, b, state))
-        }))
     }
 
-    go_extra!(O);
+    go_extra!(T);
 }
 
-/// See [`Parser::foldl`].
-pub struct Foldl<F, A, B, OB, E> {
-    pub(crate) parser_a: A,
-    pub(crate) parser_b: B,
-    pub(crate) folder: F,
-    #[cfg(debug_assertions)]
-    pub(crate) location: Location<'static>,
+/// See [`Repeated::filter_map_collect`].
+pub struct IterFilterMap<A, OA, F> {
+    pub(crate) parser: A,
+    pub(crate) filter_map: F,
     #[allow(dead_code)]
-    pub(crate) phantom: EmptyPhantom<(OB, E)>,
+    pub(crate) phantom: EmptyPhantom<OA>,
 }
 
-impl<F: Copy, A: Copy, B: Copy, OB, E> Copy for Foldl<F, A, B, OB, E> {}
-impl<F: Clone, A: Clone, B: Clone, OB, E> Clone for Foldl<F, A, B, OB, E> {
+impl<A: Copy, OA, F: Copy> Copy for IterFilterMap<A, OA, F> {}
+impl<A: Clone, OA, F: Clone> Clone for IterFilterMap<A, OA, F> {
     fn clone(&self) -> Self {
         Self {
-            parser_a: self.parser_a.clone(),
-            parser_b: self.parser_b.clone(),
-            folder: self.folder.clone(),
-            #[cfg(debug_assertions)]
-            location: self.location,
+            parser: self.parser.clone(),
+            filter_map: self.filter_map.clone(),
             phantom: EmptyPhantom::new(),
         }
     }
 }
 
-impl<'a, I, F, A, B, O, OB, E> ParserSealed<'a, I, O, E> for Foldl<F, A, B, OB, E>
+impl<'a, I, O, E, A, OA, F> IterParserSealed<'a, I, O, E> for IterFilterMap<A, OA, F>
 where
     I: Input<'a>,
-    A: Parser<'a, I, O, E>,
-    B: IterParser<'a, I, OB, E>,
     E: ParserExtra<'a, I>,
-    F: Fn(O, OB) -> O,
+    A: IterParser<'a, I, OA, E>,
+    F: Fn(OA) -> Option<O>,
 {
-    #[inline(always)]
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    // The inner parser must always produce outputs so that they can be filtered
+    type IterState<M: Mode> = A::IterState<Emit>
     where
-        Self: Sized,
-    {
-        let mut out = self.parser_a.go::<M>(inp)?;
-        let mut iter_state = self.parser_b.make_iter::<M>(inp)?;
+        I: 'a;
+
+    #[inline(always)]
+    fn make_iter<M: Mode>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+    ) -> PResult<Emit, Self::IterState<M>> {
+        self.parser.make_iter::<Emit>(inp)
+    }
+
+    #[inline(always)]
+    fn next<M: Mode>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+        state: &mut Self::IterState<M>,
+    ) -> IPResult<M, O> {
         loop {
-            #[cfg(debug_assertions)]
-            let before = inp.offset();
-            match self.parser_b.next::<M>(inp, &mut iter_state) {
-                Ok(Some(b_out)) => {
-                    out = M::combine(out, b_out, |out, b_out| (self.folder)(out, b_out));
+            match self.parser.next::<Emit>(inp, state)? {
+                Some(out) => {
+                    if let Some(out) = (self.filter_map)(out) {
+                        break Ok(Some(M::bind(|| out)));
+                    }
                 }
-                Ok(None) => break Ok(out),
-                Err(()) => break Err(()),
+                None => break Ok(None),
             }
-            #[cfg(debug_assertions)]
-            debug_assert!(
-                before != inp.offset(),
-                "found Foldl combinator making no progress at {}",
-                self.location,
-            );
         }
     }
 
-    go_extra!(O);
+    #[inline(always)]
+    fn may_stop<M: Mode>(&self, state: &Self::IterState<M>) -> bool {
+        self.parser.may_stop::<Emit>(state)
+    }
 }
 
-/// See [`Parser::foldl_with_state`].
-pub struct FoldlWithState<F, A, B, OB, E> {
-    pub(crate) parser_a: A,
-    pub(crate) parser_b: B,
-    pub(crate) folder: F,
-    #[cfg(debug_assertions)]
-    pub(crate) location: Location<'static>,
+/// See [`IterParser::collect_exactly`]
+pub struct CollectExactly<A, O, C> {
+    pub(crate) parser: A,
     #[allow(dead_code)]
-    pub(crate) phantom: EmptyPhantom<(OB, E)>,
+    pub(crate) phantom: EmptyPhantom<(O, C)>,
 }
 
-impl<F: Copy, A: Copy, B: Copy, OB, E> Copy for FoldlWithState<F, A, B, OB, E> {}
-impl<F: Clone, A: Clone, B: Clone, OB, E> Clone for FoldlWithState<F, A, B, OB, E> {
+impl<A: Copy, O, C> Copy for CollectExactly<A, O, C> {}
+impl<A: Clone, O, C> Clone for CollectExactly<A, O, C> {
     fn clone(&self) -> Self {
         Self {
-            parser_a: self.parser_a.clone(),
-            parser_b: self.parser_b.clone(),
-            folder: self.folder.clone(),
-            #[cfg(debug_assertions)]
-            location: self.location,
+            parser: self.parser.clone(),
             phantom: EmptyPhantom::new(),
         }
     }
 }
 
-impl<'a, I, F, A, B, O, OB, E> ParserSealed<'a, I, O, E> for FoldlWithState<F, A, B, OB, E>
+impl<'a, I, O, E, A, C> ParserSealed<'a, I, C, E> for CollectExactly<A, O, C>
 where
     I: Input<'a>,
-    A: Parser<'a, I, O, E>,
-    B: IterParser<'a, I, OB, E>,
     E: ParserExtra<'a, I>,
-    F: Fn(O, OB, &mut E::State) -> O,
+    A: IterParser<'a, I, O, E>,
+    C: ContainerExactly<O>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, C> {
+        let before = inp.offset();
+        let mut output = M::bind(|| C::uninit());
+        let mut iter_state = self.parser.make_iter::<M>(inp)?;
+        for idx in 0..C::LEN {
+            match self.parser.next::<M>(inp, &mut iter_state) {
+                Ok(Some(out)) => {
+                    M::combine_mut(&mut output, out, |c, out| C::write(c, idx, out));
+                }
+                Ok(None) => {
+                    inp.add_alt(inp.offset, None, None, inp.span_since(before));
+                    // SAFETY: We're guaranteed to have initialized up to `idx` values
+                    M::map(output, |mut output| unsafe {
+                        C::drop_before(&mut output, idx)
+                    });
+                    return Err(());
+                }
+                Err(()) => {
+                    // SAFETY: We're guaranteed to have initialized up to `idx` values
+                    M::map(output, |mut output| unsafe {
+                        C::drop_before(&mut output, idx)
+                    });
+                    return Err(());
+                }
+            }
+        }
+        // SAFETY: If we reach this point, we guarantee to have initialized C::LEN values
+        Ok(M::map(output, |output| unsafe { C::take(output) }))
+    }
+
+    go_extra!(C);
+}
+
+/// See [`IterParser::collect_unique`].
+pub struct CollectUnique<A, F, G, O, C> {
+    pub(crate) parser: A,
+    pub(crate) key: F,
+    pub(crate) duplicate: G,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(O, C)>,
+}
+
+impl<A: Copy, F: Copy, G: Copy, O, C> Copy for CollectUnique<A, F, G, O, C> {}
+impl<A: Clone, F: Clone, G: Clone, O, C> Clone for CollectUnique<A, F, G, O, C> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            key: self.key.clone(),
+            duplicate: self.duplicate.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, O, E, A, F, G, K, C> ParserSealed<'a, I, C, E> for CollectUnique<A, F, G, O, C>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: IterParser<'a, I, O, E>,
+    F: Fn(&O) -> K,
+    G: Fn(K, I::Span) -> E::Error,
+    K: Hash + Eq,
+    C: Container<O>,
 {
     #[inline(always)]
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
-    where
-        Self: Sized,
-    {
-        let mut out = self.parser_a.go::<M>(inp)?;
-        let mut iter_state = self.parser_b.make_iter::<M>(inp)?;
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, C> {
+        let mut output = M::bind::<C, _>(|| C::with_capacity(self.parser.capacity_hint()));
+        let mut seen = HashSet::new();
+        // Keys must be inspected, so the inner parser always runs in emit mode
+        let mut iter_state = self.parser.make_iter::<Emit>(inp)?;
         loop {
-            #[cfg(debug_assertions)]
-            let before = inp.offset();
-            match self.parser_b.next::<M>(inp, &mut iter_state) {
-                Ok(Some(b_out)) => {
-                    let state = inp.state();
-                    out = M::combine(out, b_out, |out, b_out| (self.folder)(out, b_out, state));
+            match self.parser.next_with_start::<Emit>(inp, &mut iter_state) {
+                Ok(Some((start, out))) => {
+                    let key = (self.key)(&out);
+                    if seen.contains(&key) {
+                        let err = (self.duplicate)(key, inp.span_since(start));
+                        inp.emit(inp.offset, err);
+                    } else {
+                        seen.insert(key);
+                        M::combine_mut(&mut output, M::bind(|| out), |output: &mut C, item| {
+                            output.push(item)
+                        });
+                    }
                 }
-                Ok(None) => break Ok(out),
+                Ok(None) => break Ok(output),
                 Err(()) => break Err(()),
             }
-            #[cfg(debug_assertions)]
-            debug_assert!(
-                before != inp.offset(),
-                "found FoldlWithState combinator making no progress at {}",
-                self.location,
-            );
         }
     }
 
-    go_extra!(O);
+    go_extra!(C);
 }
 
-/// See [`Parser::rewind`].
-#[must_use]
-#[derive(Copy, Clone)]
-pub struct Rewind<A> {
+/// See [`IterParser::collect_sorted`].
+pub struct CollectSorted<A, F, G, O, C> {
     pub(crate) parser: A,
+    pub(crate) in_order: F,
+    pub(crate) out_of_order: G,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(O, C)>,
 }
 
-impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for Rewind<A>
+impl<A: Copy, F: Copy, G: Copy, O, C> Copy for CollectSorted<A, F, G, O, C> {}
+impl<A: Clone, F: Clone, G: Clone, O, C> Clone for CollectSorted<A, F, G, O, C> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            in_order: self.in_order.clone(),
+            out_of_order: self.out_of_order.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, O, E, A, F, G, C> ParserSealed<'a, I, C, E> for CollectSorted<A, F, G, O, C>
 where
     I: Input<'a>,
     E: ParserExtra<'a, I>,
-    A: Parser<'a, I, O, E>,
+    A: IterParser<'a, I, O, E>,
+    F: Fn(&O, &O) -> bool,
+    G: Fn(&O, I::Span) -> E::Error,
+    O: Clone,
+    C: Container<O>,
 {
     #[inline(always)]
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
-        let before = inp.save();
-        match self.parser.go::<M>(inp) {
-            Ok(out) => {
-                inp.rewind(before);
-                Ok(out)
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, C> {
+        let mut output = M::bind::<C, _>(|| C::with_capacity(self.parser.capacity_hint()));
+        let mut prev = None;
+        // Items must be compared, so the inner parser always runs in emit mode
+        let mut iter_state = self.parser.make_iter::<Emit>(inp)?;
+        loop {
+            match self.parser.next_with_start::<Emit>(inp, &mut iter_state) {
+                Ok(Some((start, out))) => {
+                    if let Some(prev) = &prev {
+                        if !(self.in_order)(prev, &out) {
+                            let err = (self.out_of_order)(&out, inp.span_since(start));
+                            inp.emit(inp.offset, err);
+                        }
+                    }
+                    prev = Some(out.clone());
+                    M::combine_mut(&mut output, M::bind(|| out), |output: &mut C, item| {
+                        output.push(item)
+                    });
+                }
+                Ok(None) => break Ok(output),
+                Err(()) => break Err(()),
             }
-            Err(()) => Err(()),
         }
     }
 
-    go_extra!(O);
+    go_extra!(C);
 }
 
-/// See [`Parser::map_err`].
+/// See [`Parser::or_tagged`].
+#[cfg(feature = "either")]
 #[derive(Copy, Clone)]
-pub struct MapErr<A, F> {
-    pub(crate) parser: A,
-    pub(crate) mapper: F,
+pub struct OrTagged<A, B> {
+    pub(crate) parser_a: A,
+    pub(crate) parser_b: B,
 }
 
-impl<'a, I, O, E, A, F> ParserSealed<'a, I, O, E> for MapErr<A, F>
+#[cfg(feature = "either")]
+impl<'a, I, E, A, B, OA, OB> ParserSealed<'a, I, ::either::Either<OA, OB>, E> for OrTagged<A, B>
 where
     I: Input<'a>,
     E: ParserExtra<'a, I>,
-    A: Parser<'a, I, O, E>,
-    F: Fn(E::Error) -> E::Error,
+    A: Parser<'a, I, OA, E>,
+    B: Parser<'a, I, OB, E>,
 {
     #[inline(always)]
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
-    where
-        Self: Sized,
-    {
-        let res = self.parser.go::<M>(inp);
-
-        if res.is_err() {
-            let mut e = inp.errors.alt.take().expect("error but no alt?");
-            e.err = (self.mapper)(e.err);
-            inp.errors.alt = Some(e);
+    fn go<M: Mode>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+    ) -> PResult<M, ::either::Either<OA, OB>> {
+        let before = inp.save();
+        match self.parser_a.go::<M>(inp) {
+            Ok(out) => return Ok(M::map(out, ::either::Either::Left)),
+            Err(()) => inp.rewind(before),
+        }
+        match self.parser_b.go::<M>(inp) {
+            Ok(out) => Ok(M::map(out, ::either::Either::Right)),
+            Err(()) => {
+                inp.rewind(before);
+                Err(())
+            }
         }
-
-        res
     }
 
-    go_extra!(O);
+    go_extra!(::either::Either<OA, OB>);
 }
 
-// /// See [`Parser::map_err_with_span`].
-// #[derive(Copy, Clone)]
-// pub struct MapErrWithSpan<A, F> {
-//     pub(crate) parser: A,
-//     pub(crate) mapper: F,
-// }
-
-// impl<'a, I, O, E, A, F> ParserSealed<'a, I, O, E> for MapErrWithSpan<A, F>
-// where
-//     I: Input<'a>,
-//     E: ParserExtra<'a, I>,
-//     A: Parser<'a, I, O, E>,
-//     F: Fn(E::Error, I::Span) -> E::Error,
-// {
-//     #[inline(always)]
-//     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
-//     where
-//         Self: Sized,
-//     {
-//         let start = inp.offset();
-//         let res = self.parser.go::<M>(inp);
-
-//         if res.is_err() {
-//             let mut e = inp.errors.alt.take().expect("error but no alt?");
-//             let span = inp.span_since(start);
-//             e.err = (self.mapper)(e.err, span);
-//             inp.errors.alt = Some(e);
-//         }
+/// See [`Parser::or_not`].
+#[derive(Copy, Clone)]
+pub struct OrNot<A> {
+    pub(crate) parser: A,
+}
 
-//         res
-//     }
+impl<'a, I, O, E, A> ParserSealed<'a, I, Option<O>, E> for OrNot<A>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, Option<O>> {
+        let before = inp.save();
+        Ok(match self.parser.go::<M>(inp) {
+            Ok(out) => M::map::<O, _, _>(out, Some),
+            Err(()) => {
+                inp.rewind(before);
+                M::bind::<Option<O>, _>(|| None)
+            }
+        })
+    }
 
-//     go_extra!(O);
-// }
+    go_extra!(Option<O>);
+}
 
-/// See [`Parser::map_err_with_state`].
+/// See [`Parser::or_at_end`].
 #[derive(Copy, Clone)]
-pub struct MapErrWithState<A, F> {
+pub struct OrAtEnd<A, O> {
     pub(crate) parser: A,
-    pub(crate) mapper: F,
+    pub(crate) default: O,
 }
 
-impl<'a, I, O, E, A, F> ParserSealed<'a, I, O, E> for MapErrWithState<A, F>
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for OrAtEnd<A, O>
 where
     I: Input<'a>,
     E: ParserExtra<'a, I>,
     A: Parser<'a, I, O, E>,
-    F: Fn(E::Error, I::Span, &mut E::State) -> E::Error,
+    O: Clone,
 {
     #[inline(always)]
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
-    where
-        Self: Sized,
-    {
-        let start = inp.offset();
-        let res = self.parser.go::<M>(inp);
-
-        if res.is_err() {
-            let mut e = inp.errors.alt.take().expect("error but no alt?");
-            let span = inp.span_since(start);
-            e.err = (self.mapper)(e.err, span, inp.state());
-            inp.errors.alt = Some(e);
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let before = inp.save();
+        match self.parser.go::<M>(inp) {
+            Ok(out) => Ok(out),
+            Err(()) => {
+                inp.rewind(before);
+                if inp.peek_maybe().is_none() {
+                    Ok(M::bind(|| self.default.clone()))
+                } else {
+                    Err(())
+                }
+            }
         }
-
-        res
     }
 
     go_extra!(O);
 }
 
-/// See [`Parser::validate`]
-pub struct Validate<A, OA, F> {
+/// See [`Parser::not`].
+pub struct Not<A, OA> {
     pub(crate) parser: A,
-    pub(crate) validator: F,
     #[allow(dead_code)]
     pub(crate) phantom: EmptyPhantom<OA>,
 }
 
-impl<A: Copy, OA, F: Copy> Copy for Validate<A, OA, F> {}
-impl<A: Clone, OA, F: Clone> Clone for Validate<A, OA, F> {
+impl<A: Copy, OA> Copy for Not<A, OA> {}
+impl<A: Clone, OA> Clone for Not<A, OA> {
     fn clone(&self) -> Self {
-        Validate {
+        Self {
             parser: self.parser.clone(),
-            validator: self.validator.clone(),
             phantom: EmptyPhantom::new(),
         }
     }
 }
 
-impl<'a, I, OA, U, E, A, F> ParserSealed<'a, I, U, E> for Validate<A, OA, F>
+impl<'a, I, E, A, OA> ParserSealed<'a, I, (), E> for Not<A, OA>
 where
-    I: Input<'a>,
+    I: ValueInput<'a>,
     E: ParserExtra<'a, I>,
     A: Parser<'a, I, OA, E>,
-    F: Fn(OA, I::Span, &mut Emitter<E::Error>) -> U,
 {
     #[inline(always)]
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, U>
-    where
-        Self: Sized,
-    {
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, ()> {
+        let before = inp.save();
+
+        let alt = inp.errors.alt.take();
+
+        let result = self.parser.go::<Check>(inp);
+        let result_span = inp.span_since(before.offset());
+        inp.rewind(before);
+
+        inp.errors.alt = alt;
+
+        match result {
+            Ok(()) => {
+                let (at, found) = inp.next_inner();
+                inp.add_alt(at, None, found.map(|f| f.into()), result_span);
+                Err(())
+            }
+            Err(()) => Ok(M::bind(|| ())),
+        }
+    }
+
+    go_extra!(());
+}
+
+/// See [`Parser::and_is`].
+pub struct AndIs<A, B, OB> {
+    pub(crate) parser_a: A,
+    pub(crate) parser_b: B,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<OB>,
+}
+
+impl<A: Copy, B: Copy, OB> Copy for AndIs<A, B, OB> {}
+impl<A: Clone, B: Clone, OB> Clone for AndIs<A, B, OB> {
+    fn clone(&self) -> Self {
+        Self {
+            parser_a: self.parser_a.clone(),
+            parser_b: self.parser_b.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, E, A, B, OA, OB> ParserSealed<'a, I, OA, E> for AndIs<A, B, OB>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+    B: Parser<'a, I, OB, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, OA> {
+        let before = inp.save();
+        match self.parser_a.go::<M>(inp) {
+            Ok(out) => {
+                // A succeeded -- go back to the beginning and try B
+                let after = inp.save();
+                inp.rewind(before);
+
+                match self.parser_b.go::<Check>(inp) {
+                    Ok(()) => {
+                        // B succeeded -- go to the end of A and return its output
+                        inp.rewind(after);
+                        Ok(out)
+                    }
+                    Err(()) => {
+                        // B failed -- go back to the beginning and fail
+                        inp.rewind(before);
+                        Err(())
+                    }
+                }
+            }
+            Err(()) => {
+                // A failed -- go back to the beginning and fail
+                inp.rewind(before);
+                Err(())
+            }
+        }
+    }
+
+    go_extra!(OA);
+}
+
+/// See [`IterParser::foldr`].
+pub struct Foldr<F, A, B, OA, E> {
+    pub(crate) parser_a: A,
+    pub(crate) parser_b: B,
+    pub(crate) folder: F,
+    #[cfg(debug_assertions)]
+    pub(crate) location: Location<'static>,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(OA, E)>,
+}
+
+impl<F: Copy, A: Copy, B: Copy, OA, E> Copy for Foldr<F, A, B, OA, E> {}
+impl<F: Clone, A: Clone, B: Clone, OA, E> Clone for Foldr<F, A, B, OA, E> {
+    fn clone(&self) -> Self {
+        Self {
+            parser_a: self.parser_a.clone(),
+            parser_b: self.parser_b.clone(),
+            folder: self.folder.clone(),
+            #[cfg(debug_assertions)]
+            location: self.location,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, F, A, B, O, OA, E> ParserSealed<'a, I, O, E> for Foldr<F, A, B, OA, E>
+where
+    I: Input<'a>,
+    A: IterParser<'a, I, OA, E>,
+    B: Parser<'a, I, O, E>,
+    E: ParserExtra<'a, I>,
+    F: Fn(OA, O) -> O,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    where
+        Self: Sized,
+    {
+        let mut a_out = M::bind(|| Vec::new());
+        let mut iter_state = self.parser_a.make_iter::<M>(inp)?;
+        loop {
+            #[cfg(debug_assertions)]
+            let before = inp.offset();
+            match self.parser_a.next::<M>(inp, &mut iter_state) {
+                Ok(Some(out)) => {
+                    M::combine_mut(&mut a_out, out, |a_out, item| a_out.push(item));
+                }
+                Ok(None) => break,
+                Err(()) => return Err(()),
+            }
+            #[cfg(debug_assertions)]
+            debug_assert!(
+                before != inp.offset(),
+                "found Foldr combinator making no progress at {}",
+                self.location,
+            );
+        }
+
+        let b_out = self.parser_b.go::<M>(inp)?;
+
+        Ok(M::combine(a_out, b_out, |a_out, b_out| {
+            a_out.into_iter().rfold(b_out, |b, a| (self.folder)(a, b))
+        }))
+    }
+
+    go_extra!(O);
+}
+
+/// See [`IterParser::foldr_with_state`].
+pub struct FoldrWithState<F, A, B, OA, E> {
+    pub(crate) parser_a: A,
+    pub(crate) parser_b: B,
+    pub(crate) folder: F,
+    #[cfg(debug_assertions)]
+    pub(crate) location: Location<'static>,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(OA, E)>,
+}
+
+impl<F: Copy, A: Copy, B: Copy, OA, E> Copy for FoldrWithState<F, A, B, OA, E> {}
+impl<F: Clone, A: Clone, B: Clone, OA, E> Clone for FoldrWithState<F, A, B, OA, E> {
+    fn clone(&self) -> Self {
+        Self {
+            parser_a: self.parser_a.clone(),
+            parser_b: self.parser_b.clone(),
+            folder: self.folder.clone(),
+            #[cfg(debug_assertions)]
+            location: self.location,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, F, A, B, O, OA, E> ParserSealed<'a, I, O, E> for FoldrWithState<F, A, B, OA, E>
+where
+    I: Input<'a>,
+    A: IterParser<'a, I, OA, E>,
+    B: Parser<'a, I, O, E>,
+    E: ParserExtra<'a, I>,
+    F: Fn(OA, O, &mut E::State) -> O,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    where
+        Self: Sized,
+    {
+        let mut a_out = M::bind(|| Vec::new());
+        let mut iter_state = self.parser_a.make_iter::<M>(inp)?;
+        loop {
+            #[cfg(debug_assertions)]
+            let before = inp.offset();
+            match self.parser_a.next::<M>(inp, &mut iter_state) {
+                Ok(Some(out)) => {
+                    M::combine_mut(&mut a_out, out, |a_out, item| a_out.push(item));
+                }
+                Ok(None) => break,
+                Err(()) => return Err(()),
+            }
+            #[cfg(debug_assertions)]
+            debug_assert!(
+                before != inp.offset(),
+                "found FoldrWithState combinator making no progress at {}",
+                self.location,
+            );
+        }
+
+        let b_out = self.parser_b.go::<M>(inp)?;
+
+        Ok(M::combine(a_out, b_out, |a_out, b_out| {
+            let state = inp.state();
+            a_out
+                .into_iter()
+                .rfold(b_out, |b, a| (self.folder)(a, b, state))
+        }))
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::foldl`].
+pub struct Foldl<F, A, B, OB, E> {
+    pub(crate) parser_a: A,
+    pub(crate) parser_b: B,
+    pub(crate) folder: F,
+    #[cfg(debug_assertions)]
+    pub(crate) location: Location<'static>,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(OB, E)>,
+}
+
+impl<F: Copy, A: Copy, B: Copy, OB, E> Copy for Foldl<F, A, B, OB, E> {}
+impl<F: Clone, A: Clone, B: Clone, OB, E> Clone for Foldl<F, A, B, OB, E> {
+    fn clone(&self) -> Self {
+        Self {
+            parser_a: self.parser_a.clone(),
+            parser_b: self.parser_b.clone(),
+            folder: self.folder.clone(),
+            #[cfg(debug_assertions)]
+            location: self.location,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, F, A, B, O, OB, E> ParserSealed<'a, I, O, E> for Foldl<F, A, B, OB, E>
+where
+    I: Input<'a>,
+    A: Parser<'a, I, O, E>,
+    B: IterParser<'a, I, OB, E>,
+    E: ParserExtra<'a, I>,
+    F: Fn(O, OB) -> O,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    where
+        Self: Sized,
+    {
+        let mut out = self.parser_a.go::<M>(inp)?;
+        let mut iter_state = self.parser_b.make_iter::<M>(inp)?;
+        loop {
+            #[cfg(debug_assertions)]
+            let before = inp.offset();
+            match self.parser_b.next::<M>(inp, &mut iter_state) {
+                Ok(Some(b_out)) => {
+                    out = M::combine(out, b_out, |out, b_out| (self.folder)(out, b_out));
+                }
+                Ok(None) => break Ok(out),
+                Err(()) => break Err(()),
+            }
+            #[cfg(debug_assertions)]
+            debug_assert!(
+                before != inp.offset(),
+                "found Foldl combinator making no progress at {}",
+                self.location,
+            );
+        }
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::foldl_with_state`].
+pub struct FoldlWithState<F, A, B, OB, E> {
+    pub(crate) parser_a: A,
+    pub(crate) parser_b: B,
+    pub(crate) folder: F,
+    #[cfg(debug_assertions)]
+    pub(crate) location: Location<'static>,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(OB, E)>,
+}
+
+impl<F: Copy, A: Copy, B: Copy, OB, E> Copy for FoldlWithState<F, A, B, OB, E> {}
+impl<F: Clone, A: Clone, B: Clone, OB, E> Clone for FoldlWithState<F, A, B, OB, E> {
+    fn clone(&self) -> Self {
+        Self {
+            parser_a: self.parser_a.clone(),
+            parser_b: self.parser_b.clone(),
+            folder: self.folder.clone(),
+            #[cfg(debug_assertions)]
+            location: self.location,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, F, A, B, O, OB, E> ParserSealed<'a, I, O, E> for FoldlWithState<F, A, B, OB, E>
+where
+    I: Input<'a>,
+    A: Parser<'a, I, O, E>,
+    B: IterParser<'a, I, OB, E>,
+    E: ParserExtra<'a, I>,
+    F: Fn(O, OB, &mut E::State) -> O,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    where
+        Self: Sized,
+    {
+        let mut out = self.parser_a.go::<M>(inp)?;
+        let mut iter_state = self.parser_b.make_iter::<M>(inp)?;
+        loop {
+            #[cfg(debug_assertions)]
+            let before = inp.offset();
+            match self.parser_b.next::<M>(inp, &mut iter_state) {
+                Ok(Some(b_out)) => {
+                    let state = inp.state();
+                    out = M::combine(out, b_out, |out, b_out| (self.folder)(out, b_out, state));
+                }
+                Ok(None) => break Ok(out),
+                Err(()) => break Err(()),
+            }
+            #[cfg(debug_assertions)]
+            debug_assert!(
+                before != inp.offset(),
+                "found FoldlWithState combinator making no progress at {}",
+                self.location,
+            );
+        }
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::rewind`].
+#[must_use]
+#[derive(Copy, Clone)]
+pub struct Rewind<A> {
+    pub(crate) parser: A,
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for Rewind<A>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let before = inp.save();
+        match self.parser.go::<M>(inp) {
+            Ok(out) => {
+                inp.rewind(before);
+                Ok(out)
+            }
+            Err(()) => Err(()),
+        }
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::map_err`].
+#[derive(Copy, Clone)]
+pub struct MapErr<A, F> {
+    pub(crate) parser: A,
+    pub(crate) mapper: F,
+}
+
+impl<'a, I, O, E, A, F> ParserSealed<'a, I, O, E> for MapErr<A, F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+    F: Fn(E::Error) -> E::Error,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    where
+        Self: Sized,
+    {
+        let (res, mut alt) = inp.with_own_alt(|inp| self.parser.go::<M>(inp));
+
+        if res.is_err() {
+            let e = alt.take().expect("error but no alt?");
+            alt = Some(Located::at(e.pos, (self.mapper)(e.err)));
+        }
+
+        inp.add_located_alt(alt);
+        res
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::suggest`].
+#[derive(Copy, Clone)]
+pub struct Suggest<A, F> {
+    pub(crate) parser: A,
+    pub(crate) suggester: F,
+}
+
+impl<'a, I, O, E, A, F> ParserSealed<'a, I, O, E> for Suggest<A, F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+    F: Fn(&E::Error) -> Option<String>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    where
+        Self: Sized,
+    {
+        let (res, mut alt) = inp.with_own_alt(|inp| self.parser.go::<M>(inp));
+
+        if res.is_err() {
+            let mut e = alt.take().expect("error but no alt?");
+            if let Some(suggestion) = (self.suggester)(&e.err) {
+                e.err = e.err.with_suggestion(suggestion);
+            }
+            alt = Some(e);
+        }
+
+        inp.add_located_alt(alt);
+        res
+    }
+
+    go_extra!(O);
+}
+
+/// Configuration for [`Parser::with_related_span`], used in [`ConfigParser::configure`]
+pub struct WithRelatedSpanCfg<S> {
+    span: Option<S>,
+}
+
+impl<S> WithRelatedSpanCfg<S> {
+    /// Set the related span to be attached to the error
+    #[inline]
+    pub fn span(mut self, new_span: S) -> Self {
+        self.span = Some(new_span);
+        self
+    }
+}
+
+impl<S> Default for WithRelatedSpanCfg<S> {
+    #[inline]
+    fn default() -> Self {
+        WithRelatedSpanCfg { span: None }
+    }
+}
+
+/// See [`Parser::with_related_span`].
+#[derive(Copy, Clone)]
+pub struct WithRelatedSpan<A, T> {
+    pub(crate) parser: A,
+    pub(crate) message: T,
+}
+
+impl<'a, I, O, E, A, T> ParserSealed<'a, I, O, E> for WithRelatedSpan<A, T>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+    T: ToString,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    where
+        Self: Sized,
+    {
+        Self::go_cfg::<M>(self, inp, WithRelatedSpanCfg::default())
+    }
+
+    go_extra!(O);
+}
+
+impl<'a, I, O, E, A, T> ConfigParserSealed<'a, I, O, E> for WithRelatedSpan<A, T>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+    T: ToString,
+{
+    type Config = WithRelatedSpanCfg<I::Span>;
+
+    #[inline]
+    fn go_cfg<M: Mode>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+        cfg: Self::Config,
+    ) -> PResult<M, O> {
+        let (res, mut alt) = inp.with_own_alt(|inp| self.parser.go::<M>(inp));
+
+        if let (Err(()), Some(span)) = (&res, cfg.span) {
+            let e = alt.take().expect("error but no alt?");
+            let err = e.err.with_related_span(span, || self.message.to_string());
+            alt = Some(Located::at(e.pos, err));
+        }
+
+        inp.add_located_alt(alt);
+        res
+    }
+
+    go_cfg_extra!(O);
+}
+
+// /// See [`Parser::map_err_with_span`].
+// #[derive(Copy, Clone)]
+// pub struct MapErrWithSpan<A, F> {
+//     pub(crate) parser: A,
+//     pub(crate) mapper: F,
+// }
+
+// impl<'a, I, O, E, A, F> ParserSealed<'a, I, O, E> for MapErrWithSpan<A, F>
+// where
+//     I: Input<'a>,
+//     E: ParserExtra<'a, I>,
+//     A: Parser<'a, I, O, E>,
+//     F: Fn(E::Error, I::Span) -> E::Error,
+// {
+//     #[inline(always)]
+//     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+//     where
+//         Self: Sized,
+//     {
+//         let start = inp.offset();
+//         let res = self.parser.go::<M>(inp);
+
+//         if res.is_err() {
+//             let mut e = inp.errors.alt.take().expect("error but no alt?");
+//             let span = inp.span_since(start);
+//             e.err = (self.mapper)(e.err, span);
+//             inp.errors.alt = Some(e);
+//         }
+
+//         res
+//     }
+
+//     go_extra!(O);
+// }
+
+/// See [`Parser::map_err_with_state`].
+#[derive(Copy, Clone)]
+pub struct MapErrWithState<A, F> {
+    pub(crate) parser: A,
+    pub(crate) mapper: F,
+}
+
+impl<'a, I, O, E, A, F> ParserSealed<'a, I, O, E> for MapErrWithState<A, F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+    F: Fn(E::Error, I::Span, &mut E::State) -> E::Error,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    where
+        Self: Sized,
+    {
+        let start = inp.offset();
+        let (res, mut alt) = inp.with_own_alt(|inp| self.parser.go::<M>(inp));
+
+        if res.is_err() {
+            let e = alt.take().expect("error but no alt?");
+            let span = inp.span_since(start);
+            alt = Some(Located::at(e.pos, (self.mapper)(e.err, span, inp.state())));
+        }
+
+        inp.add_located_alt(alt);
+        res
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::validate`]
+pub struct Validate<A, OA, F> {
+    pub(crate) parser: A,
+    pub(crate) validator: F,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<OA>,
+}
+
+impl<A: Copy, OA, F: Copy> Copy for Validate<A, OA, F> {}
+impl<A: Clone, OA, F: Clone> Clone for Validate<A, OA, F> {
+    fn clone(&self) -> Self {
+        Validate {
+            parser: self.parser.clone(),
+            validator: self.validator.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, OA, U, E, A, F> ParserSealed<'a, I, U, E> for Validate<A, OA, F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+    F: Fn(OA, I::Span, &mut Emitter<E::Error>) -> U,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, U>
+    where
+        Self: Sized,
+    {
+        let before = inp.offset();
+        let out = self.parser.go::<Emit>(inp)?;
+
+        let span = inp.span_since(before);
+        let mut emitter = Emitter::new();
+        let out = (self.validator)(out, span, &mut emitter);
+        for err in emitter.errors() {
+            inp.emit(inp.offset, err);
+        }
+        Ok(M::bind(|| out))
+    }
+
+    go_extra!(U);
+}
+
+/// See [`Parser::validate_with_state`].
+pub struct ValidateWithState<A, OA, F> {
+    pub(crate) parser: A,
+    pub(crate) validator: F,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<OA>,
+}
+
+impl<A: Copy, OA, F: Copy> Copy for ValidateWithState<A, OA, F> {}
+impl<A: Clone, OA, F: Clone> Clone for ValidateWithState<A, OA, F> {
+    fn clone(&self) -> Self {
+        ValidateWithState {
+            parser: self.parser.clone(),
+            validator: self.validator.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, OA, U, E, A, F> ParserSealed<'a, I, U, E> for ValidateWithState<A, OA, F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+    F: Fn(OA, I::Span, &mut E::State, &mut Emitter<E::Error>) -> U,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, U>
+    where
+        Self: Sized,
+    {
         let before = inp.offset();
         let out = self.parser.go::<Emit>(inp)?;
 
-        let span = inp.span_since(before);
-        let mut emitter = Emitter::new();
-        let out = (self.validator)(out, span, &mut emitter);
-        for err in emitter.errors() {
-            inp.emit(inp.offset, err);
+        let span = inp.span_since(before);
+        let mut emitter = Emitter::new();
+        let out = (self.validator)(out, span, inp.state(), &mut emitter);
+        for err in emitter.errors() {
+            inp.emit(inp.offset, err);
+        }
+        Ok(M::bind(|| out))
+    }
+
+    go_extra!(U);
+}
+
+// /// See [`Parser::or_else`].
+// #[derive(Copy, Clone)]
+// pub struct OrElse<A, F> {
+//     pub(crate) parser: A,
+//     pub(crate) or_else: F,
+// }
+
+// impl<'a, I, O, E, A, F> ParserSealed<'a, I, O, E> for OrElse<A, F>
+// where
+//     I: Input<'a>,
+//     E: ParserExtra<'a, I>,
+//     A: Parser<'a, I, O, E>,
+//     F: Fn(E::Error) -> Result<O, E::Error>,
+// {
+//     #[inline(always)]
+//     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+//     where
+//         Self: Sized,
+//     {
+//         let before = inp.save();
+//         match self.parser.go::<M>(inp) {
+//             Ok(out) => Ok(out),
+//             Err(()) => {
+//                 let err = inp.errors.alt.take().expect("error but no alt?");
+//                 match (self.or_else)(err.err) {
+//                     Ok(out) => {
+//                         inp.rewind(before);
+//                         Ok(M::bind(|| out))
+//                     }
+//                     Err(new_err) => {
+//                         inp.errors.alt = Some(Located {
+//                             pos: err.pos,
+//                             err: new_err,
+//                         });
+//                         Err(())
+//                     }
+//                 }
+//             }
+//         }
+//     }
+
+//     go_extra!(O);
+// }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn separated_by_at_least() {
+        let parser = just::<_, _, extra::Default>('-')
+            .separated_by(just(','))
+            .at_least(3)
+            .collect();
+
+        assert_eq!(parser.parse("-,-,-").into_result(), Ok(vec!['-', '-', '-']));
+    }
+
+    #[test]
+    fn separated_by_at_least_without_leading() {
+        let parser = just::<_, _, extra::Default>('-')
+            .separated_by(just(','))
+            .at_least(3)
+            .collect::<Vec<_>>();
+
+        // Is empty means no errors
+        assert!(parser.parse(",-,-,-").has_errors());
+    }
+
+    #[test]
+    fn separated_by_at_least_without_trailing() {
+        let parser = just::<_, _, extra::Default>('-')
+            .separated_by(just(','))
+            .at_least(3)
+            .collect::<Vec<_>>();
+
+        // Is empty means no errors
+        assert!(parser.parse("-,-,-,").has_errors());
+    }
+
+    #[test]
+    fn separated_by_at_least_with_leading() {
+        let parser = just::<_, _, extra::Default>('-')
+            .separated_by(just(','))
+            .allow_leading()
+            .at_least(3)
+            .collect();
+
+        assert_eq!(
+            parser.parse(",-,-,-").into_result(),
+            Ok(vec!['-', '-', '-'])
+        );
+        assert!(parser.parse(",-,-").has_errors());
+    }
+
+    #[test]
+    fn separated_by_at_least_with_trailing() {
+        let parser = just::<_, _, extra::Default>('-')
+            .separated_by(just(','))
+            .allow_trailing()
+            .at_least(3)
+            .collect();
+
+        assert_eq!(
+            parser.parse("-,-,-,").into_result(),
+            Ok(vec!['-', '-', '-'])
+        );
+        assert!(parser.parse("-,-,").has_errors());
+    }
+
+    #[test]
+    fn separated_by_leaves_last_separator() {
+        let parser = just::<_, _, extra::Default>('-')
+            .separated_by(just(','))
+            .collect::<Vec<_>>()
+            .then(just(','));
+        assert_eq!(
+            parser.parse("-,-,-,").into_result(),
+            Ok((vec!['-', '-', '-'], ',')),
+        )
+    }
+
+    #[test]
+    fn separated_by_forbid_trailing_separator() {
+        let parser = text::int::<_, _, extra::Err<Rich<char>>>(10)
+            .separated_by(just(','))
+            .forbid_trailing_explicit()
+            .collect::<Vec<_>>();
+
+        assert_eq!(parser.parse("1,2").into_result(), Ok(vec!["1", "2"]));
+
+        let errs = parser.parse("1,2,").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(3, 4));
+        assert_eq!(errs[0].to_string(), "unexpected trailing separator");
+
+        // The separator is also blamed when something other than the end of the input follows it
+        let list = parser.delimited_by(just('['), just(']'));
+        let errs = list.parse("[1,2,]").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(4, 5));
+        assert_eq!(errs[0].to_string(), "unexpected trailing separator");
+
+        // An item that fails partway through is blamed instead of the separator
+        let pairs = just::<_, _, extra::Err<Rich<char>>>("ab")
+            .separated_by(just(','))
+            .forbid_trailing_explicit();
+        let errs = pairs.parse("ab,ab,ac").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(7, 8));
+        assert_eq!(errs[0].found(), Some(&'c'));
+
+        // The span covers everything the separator matched
+        let padded = just::<_, _, extra::Err<Rich<char>>>('-')
+            .separated_by(just(';').padded())
+            .forbid_trailing_explicit();
+        let errs = padded.parse("- ; - ; ").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(5, 8));
+    }
+
+    #[test]
+    fn collect_unique_emits_duplicates() {
+        let parser = any::<_, extra::Err<Rich<char>>>()
+            .filter(|c: &char| c.is_alphabetic())
+            .separated_by(just(','))
+            .collect_unique::<Vec<_>, _, _, _>(
+                |c: &char| *c,
+                |c, span| Rich::custom(span, format!("duplicate `{}`", c)),
+            );
+
+        let (output, errs) = parser.parse("a,b,a").into_output_errors();
+        assert_eq!(output, Some(vec!['a', 'b']));
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].to_string(), "duplicate `a`");
+        // The span covers only the duplicate item, not the separator before it
+        assert_eq!(errs[0].span(), &SimpleSpan::new(4, 5));
+    }
+
+    #[test]
+    fn or_union_expected() {
+        let parser = just::<_, _, extra::Err<Rich<char>>>("ab")
+            .or(just("cd"))
+            .union_expected();
+
+        // Both branches fail immediately
+        let err = parser.parse("").into_errors().remove(0);
+        assert_eq!(err.span(), &SimpleSpan::new(0, 0));
+        let mut expected = err.expected().map(|e| e.to_string()).collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(expected, vec!["'a'", "'c'"]);
+
+        // The first branch consumes input before failing, so the second branch's expectations don't apply there
+        let err = parser.parse("ax").into_errors().remove(0);
+        assert_eq!(err.span(), &SimpleSpan::new(1, 2));
+        let expected = err.expected().map(|e| e.to_string()).collect::<Vec<_>>();
+        assert_eq!(expected, vec!["'b'"]);
+
+        // The second branch consumes input before failing
+        let err = parser.parse("cx").into_errors().remove(0);
+        assert_eq!(err.span(), &SimpleSpan::new(1, 2));
+        let expected = err.expected().map(|e| e.to_string()).collect::<Vec<_>>();
+        assert_eq!(expected, vec!["'d'"]);
+    }
+
+    #[test]
+    fn separated_by_require_whitespace() {
+        let parser = text::ascii::ident::<_, _, extra::Default>()
+            .separated_by(empty())
+            .require_whitespace()
+            .collect::<Vec<_>>();
+
+        assert_eq!(parser.parse("a b c").into_result(), Ok(vec!["a", "b", "c"]));
+        assert_eq!(parser.parse("ab").into_result(), Ok(vec!["ab"]));
+
+        let parser = one_of::<_, _, extra::Default>("abc")
+            .separated_by(empty())
+            .require_whitespace()
+            .collect::<Vec<_>>();
+
+        assert_eq!(parser.parse("a b c").into_result(), Ok(vec!['a', 'b', 'c']));
+        assert!(parser.parse("ab").has_errors());
+
+        let parser = one_of::<_, _, extra::Err<Rich<char>>>("abc")
+            .separated_by(empty())
+            .require_whitespace()
+            .at_least(2)
+            .collect::<Vec<_>>();
+        let errs = parser.parse("ab").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].to_string(), "expected whitespace between items");
+        assert_eq!(errs[0].span(), &SimpleSpan::new(1, 1));
+    }
+
+    #[test]
+    fn map_with_state_skipped_in_check() {
+        let parser = any::<_, extra::Full<EmptyErr, usize, ()>>()
+            .map_with_state(|c, _, count: &mut usize| {
+                *count += 1;
+                c
+            })
+            .repeated()
+            .collect::<String>();
+
+        let mut count = 0;
+        assert!(!parser.check_with_state("abc", &mut count).has_errors());
+        assert_eq!(count, 0);
+
+        assert_eq!(
+            parser.parse_with_state("abc", &mut count).into_result(),
+            Ok("abc".to_string()),
+        );
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn repeated_with_capacity_hint() {
+        let parser = any::<_, extra::Default>()
+            .repeated()
+            .with_capacity_hint(64)
+            .collect::<Vec<_>>();
+
+        let output = parser.parse("abc").into_result().unwrap();
+        assert_eq!(output, vec!['a', 'b', 'c']);
+        assert!(output.capacity() >= 64);
+    }
+
+    #[test]
+    fn or_at_end() {
+        let parser = text::ascii::ident::<_, _, extra::Default>()
+            .then(just(':').ignore_then(text::ascii::ident()).or_at_end("any"));
+
+        assert_eq!(parser.parse("x:int").into_result(), Ok(("x", "int")));
+        assert_eq!(parser.parse("x").into_result(), Ok(("x", "any")));
+        assert!(parser.parse("x!").has_errors());
+        assert!(parser.parse("x:").has_errors());
+    }
+
+    #[test]
+    fn slice_and_span() {
+        let parser = text::ascii::ident::<_, _, extra::Default>()
+            .slice_and_span()
+            .padded();
+
+        assert_eq!(
+            parser.parse("  hello ").into_result(),
+            Ok(("hello", SimpleSpan::new(2, 7))),
+        );
+    }
+
+    #[test]
+    fn then_padded() {
+        let operand = text::ascii::ident::<_, _, extra::Default>();
+        let op = one_of("+-");
+        let parser = operand
+            .then_padded(op)
+            .then_padded(operand)
+            .then_ignore_padded(end());
+
+        assert_eq!(
+            parser.parse("a   +   b").into_result(),
+            Ok((("a", '+'), "b")),
+        );
+        assert_eq!(parser.parse("a-b \n").into_result(), Ok((("a", '-'), "b")));
+        assert!(parser.parse("a + ").has_errors());
+    }
+
+    #[test]
+    fn map2() {
+        let parser = text::int::<_, _, extra::Default>(10)
+            .map2(|s: &str| s.parse::<u32>().unwrap(), |n| n * 2);
+
+        assert_eq!(parser.parse("21").into_result(), Ok(42));
+        assert_eq!(
+            parser.parse("21").into_result(),
+            text::int::<_, _, extra::Default>(10)
+                .map(|s: &str| s.parse::<u32>().unwrap())
+                .map(|n| n * 2)
+                .parse("21")
+                .into_result(),
+        );
+    }
+
+    #[test]
+    fn with_events() {
+        use crate::extra::Event;
+
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        enum Kind {
+            Num,
+            Add,
+            Mul,
+        }
+
+        type Events = Vec<Event<Kind, SimpleSpan>>;
+
+        let num = text::int::<_, _, extra::State<Events>>(10).with_events(Kind::Num);
+        let mul = num.then_ignore(just('*')).then(num).with_events(Kind::Mul);
+        let add = num.then_ignore(just('+')).then(num).with_events(Kind::Add);
+        // `mul` fails after its first operand, so its events must be discarded
+        let expr = mul.or(add);
+
+        let mut events = Events::new();
+        assert_eq!(
+            expr.parse_with_state("12+3", &mut events).into_result(),
+            Ok(("12", "3")),
+        );
+        assert_eq!(
+            events,
+            vec![
+                Event::Enter(Kind::Add),
+                Event::Enter(Kind::Num),
+                Event::Exit(Kind::Num, SimpleSpan::new(0, 2)),
+                Event::Enter(Kind::Num),
+                Event::Exit(Kind::Num, SimpleSpan::new(3, 4)),
+                Event::Exit(Kind::Add, SimpleSpan::new(0, 4)),
+            ],
+        );
+    }
+
+    #[test]
+    fn separated_by_recover_item_with() {
+        let numbers = text::int::<_, _, extra::Err<Rich<char>>>(10)
+            .padded()
+            .map(|s: &str| s.parse::<i32>().unwrap())
+            .separated_by(just(','))
+            .recover_item_with(via_parser(none_of(',').repeated().at_least(1).to(-1)))
+            .collect::<Vec<_>>();
+
+        let (output, errs) = numbers.parse("1, bad, 3").into_output_errors();
+        assert_eq!(output, Some(vec![1, -1, 3]));
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(3, 4));
+    }
+
+    #[test]
+    fn chain_left() {
+        #[derive(Debug, PartialEq)]
+        enum Expr {
+            Num(u32),
+            Sub(Box<Expr>, Box<Expr>),
+        }
+
+        let num = text::int::<_, _, extra::Default>(10)
+            .padded()
+            .map(|s: &str| Expr::Num(s.parse().unwrap()));
+        let expr = num.chain_left(just('-').ignore_then(num), |lhs, rhs| {
+            Expr::Sub(Box::new(lhs), Box::new(rhs))
+        });
+
+        assert_eq!(
+            expr.parse("1 - 2 - 3").into_result(),
+            Ok(Expr::Sub(
+                Box::new(Expr::Sub(Box::new(Expr::Num(1)), Box::new(Expr::Num(2)))),
+                Box::new(Expr::Num(3)),
+            )),
+        );
+    }
+
+    #[test]
+    fn filter_labelled() {
+        let vowel = any::<_, extra::Err<Rich<char>>>()
+            .filter_labelled(|c: &char| "aeiou".contains(*c), "vowel");
+
+        assert_eq!(vowel.parse("e").into_result(), Ok('e'));
+
+        let errs = vowel.parse("x").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(0, 1));
+        #[cfg(feature = "label")]
+        assert_eq!(errs[0].to_string(), "found 'x' expected vowel");
+        #[cfg(not(feature = "label"))]
+        assert_eq!(errs[0].to_string(), "found 'x' expected something else");
+    }
+
+    #[test]
+    fn map_str() {
+        let parser = any::<&[u8], extra::Err<Rich<u8>>>()
+            .filter(|b| *b != b';')
+            .repeated()
+            .map_str()
+            .then_ignore(just(b';'));
+
+        assert_eq!(
+            parser.parse("h\u{e9}llo;".as_bytes()).into_result(),
+            Ok("h\u{e9}llo"),
+        );
+
+        let errs = parser.parse(b"ab\xffcd;").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(2, 3));
+        assert_eq!(errs[0].found(), Some(&0xff));
+
+        // A truncated multi-byte sequence at the end of the slice
+        let errs = parser.parse(b"ab\xe2\x82;").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(2, 4));
+
+        // Errors from other branches that got further are kept
+        let either = just(b"ab\xffcd!").to("literal").or(parser);
+        let errs = either.parse(b"ab\xffcd;").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(5, 6));
+    }
+
+    #[test]
+    fn then_with_gap() {
+        let word = text::ascii::ident::<_, _, extra::Default>();
+        let parser = word.then_with_gap(text::whitespace().slice(), word);
+
+        let (a, gap, b) = parser.parse("a   b").into_result().unwrap();
+        assert_eq!((a, gap, b), ("a", "   ", "b"));
+        assert_eq!([a, gap, b].concat(), "a   b");
+
+        assert_eq!(parser.parse("a\tb").into_result(), Ok(("a", "\t", "b")));
+    }
+
+    #[test]
+    fn repeated_collect_with_spans() {
+        let items = text::int::<_, _, extra::Default>(10)
+            .then_ignore(just(';'))
+            .repeated()
+            .at_least(1)
+            .collect_with_spans();
+
+        assert_eq!(
+            items.parse("1;23;456;").into_result(),
+            Ok(vec![
+                ("1", SimpleSpan::new(0, 2)),
+                ("23", SimpleSpan::new(2, 5)),
+                ("456", SimpleSpan::new(5, 9)),
+            ]),
+        );
+        assert!(items.parse("").has_errors());
+    }
+
+    #[test]
+    fn validate_with_state() {
+        use alloc::string::ToString;
+        use hashbrown::HashSet;
+
+        type Extra = extra::Full<Rich<'static, char>, HashSet<String>, ()>;
+
+        let decl = text::ascii::keyword::<_, _, _, Extra>("let")
+            .ignore_then(text::ascii::ident().padded())
+            .then_ignore(just(';').padded())
+            .validate_with_state(|name: &str, span, declared: &mut HashSet<_>, emitter| {
+                if !declared.insert(name.to_string()) {
+                    let msg = format!("`{}` shadows an earlier declaration", name);
+                    emitter.emit(Rich::custom(span, msg));
+                }
+                name
+            })
+            .repeated()
+            .collect::<Vec<_>>();
+
+        let mut declared = HashSet::new();
+        let (output, errs) = decl
+            .parse_with_state("let a; let b; let a;", &mut declared)
+            .into_output_errors();
+
+        assert_eq!(output, Some(vec!["a", "b", "a"]));
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(14, 20));
+        assert_eq!(errs[0].to_string(), "`a` shadows an earlier declaration");
+        assert_eq!(declared.len(), 2);
+    }
+
+    #[test]
+    fn repeated_filter_map_collect() {
+        #[derive(Clone, Debug, PartialEq)]
+        enum Token<'a> {
+            Ident(&'a str),
+            Comment,
+        }
+
+        let comment = just("//").then(none_of('\n').repeated()).to(Token::Comment);
+        let ident = text::ascii::ident::<_, _, extra::Default>().map(Token::Ident);
+        let tokens = comment
+            .or(ident)
+            .padded()
+            .repeated()
+            .filter_map_collect::<_, Vec<_>, _>(|tok| match tok {
+                Token::Comment => None,
+                tok => Some(tok),
+            });
+
+        assert_eq!(
+            tokens.parse("foo // one\nbar\n// two\nbaz").into_result(),
+            Ok(["foo", "bar", "baz"].map(Token::Ident).to_vec()),
+        );
+    }
+
+    #[test]
+    fn separated_by_choice_separator() {
+        let parser = text::ascii::ident::<_, _, extra::Default>()
+            .separated_by(one_of(",\n"))
+            .at_least(3)
+            .allow_trailing()
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            parser.parse("a,b\nc").into_result(),
+            Ok(vec!["a", "b", "c"])
+        );
+        assert_eq!(
+            parser.parse("a\nb,c\n").into_result(),
+            Ok(vec!["a", "b", "c"])
+        );
+        // Each separator may be either kind, but only one separator is permitted between items
+        assert!(parser.parse("a,\nb,c").has_errors());
+        assert!(parser.parse("a,b").has_errors());
+
+        let parser = text::ascii::ident::<_, _, extra::Default>()
+            .separated_by(choice((just(",").ignored(), text::newline())))
+            .at_least(3)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            parser.parse("a\r\nb,c").into_result(),
+            Ok(vec!["a", "b", "c"])
+        );
+        assert!(parser.parse("a,b,c\n").has_errors());
+    }
+
+    #[test]
+    fn suggest() {
+        use crate::error::RichPattern;
+        use alloc::string::ToString;
+
+        let eq = text::ascii::ident::<_, _, extra::Err<Rich<char>>>()
+            .padded()
+            .then_ignore(just("==").suggest(|e: &Rich<char>| {
+                e.expected()
+                    .any(|p| matches!(p, RichPattern::Token(t) if **t == '='))
+                    .then(|| "did you mean `==`?".to_string())
+            }))
+            .then(text::ascii::ident().padded());
+
+        assert_eq!(eq.parse("a == b").into_result(), Ok(("a", "b")));
+
+        let errs = eq.parse("a = b").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].suggestion(), Some("did you mean `==`?"));
+        assert_eq!(errs[0].span(), &SimpleSpan::new(3, 4));
+    }
+
+    #[test]
+    fn then_ignore_check_builds_no_output() {
+        use core::cell::Cell;
+
+        struct Counted<'b>(&'b Cell<usize>);
+
+        impl Drop for Counted<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let built = Cell::new(0);
+        let item = any::<_, extra::Err<Simple<char>>>().map(|_| Counted(&built));
+        let parser = item
+            .then(item)
+            .then_ignore(end())
+            .map(|(a, _)| a)
+            .then_ignore(empty());
+
+        assert!(!parser.check("ab").has_errors());
+        assert_eq!(built.get(), 0);
+
+        drop(parser.parse("ab").into_result());
+        assert_eq!(built.get(), 2);
+    }
+
+    #[test]
+    fn to_token() {
+        #[derive(Debug, PartialEq)]
+        struct Token<'a> {
+            text: &'a str,
+            span: SimpleSpan,
+        }
+
+        let keyword = text::ascii::keyword::<_, _, _, extra::Err<Simple<char>>>("let")
+            .to_token(|text, span| Token { text, span })
+            .padded();
+
+        assert_eq!(
+            keyword.parse("  let ").into_result(),
+            Ok(Token {
+                text: "let",
+                span: SimpleSpan::new(2, 5)
+            }),
+        );
+        assert!(keyword.parse("letter").has_errors());
+    }
+
+    #[test]
+    #[cfg(feature = "trivia")]
+    fn collect_trivia_into_state() {
+        type Extra<'a> = extra::State<Vec<(SimpleSpan, &'a str)>>;
+
+        let line_comment = just::<_, _, Extra>("//")
+            .then(any().and_is(just('\n').not()).repeated())
+            .ignored()
+            .collect_trivia_into_state();
+        let block_comment = just("/*")
+            .then(any().and_is(just("*/").not()).repeated())
+            .then(just("*/"))
+            .ignored()
+            .collect_trivia_into_state();
+        let trivia = choice((line_comment, block_comment)).padded().repeated();
+
+        let ident = text::ascii::ident().padded().padded_by(trivia);
+        // The first alternative backtracks after skipping trivia, which must not be recorded twice
+        let stmt = choice((
+            ident
+                .then_ignore(just('='))
+                .then(ident)
+                .map(|(a, b)| (a, Some(b))),
+            ident.map(|a| (a, None)),
+        ))
+        .then_ignore(just(';'));
+
+        let src = "/* header */ x = y; // assign\nz /* lone */;";
+        let mut trivia = Vec::new();
+        assert_eq!(
+            stmt.repeated()
+                .collect::<Vec<_>>()
+                .parse_with_state(src, &mut trivia)
+                .into_result(),
+            Ok(vec![("x", Some("y")), ("z", None)]),
+        );
+        assert_eq!(
+            trivia,
+            vec![
+                (SimpleSpan::new(0, 12), "/* header */"),
+                (SimpleSpan::new(20, 29), "// assign"),
+                (SimpleSpan::new(32, 42), "/* lone */"),
+            ],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "trivia")]
+    fn padded_with_trivia_forgets_backtracked_trivia() {
+        let comment = just::<_, _, extra::State<Vec<(SimpleSpan, &str)>>>("//")
+            .then(any().and_is(just('\n').not()).repeated());
+        let assign = text::ascii::ident()
+            .padded_with_trivia(comment)
+            .then_ignore(just('='))
+            .to(false);
+        // The second alternative consumes the comment without recording it, so nothing may be left behind by the first
+        let raw = any().and_is(just(';').not()).repeated().to(true);
+        let stmt = assign.or(raw).then_ignore(just(';'));
+
+        let mut trivia = Vec::new();
+        assert_eq!(
+            stmt.parse_with_state("x // c\n;", &mut trivia)
+                .into_result(),
+            Ok(true),
+        );
+        assert_eq!(trivia, vec![]);
+
+        let mut trivia = Vec::new();
+        assert_eq!(
+            stmt.parse_with_state("x // c\n=;", &mut trivia)
+                .into_result(),
+            Ok(false),
+        );
+        assert_eq!(trivia, vec![(SimpleSpan::new(2, 6), "// c")]);
+    }
+
+    #[test]
+    fn separated_by_error_names_list_element() {
+        use alloc::string::ToString;
+
+        let list = |at_least| {
+            text::int::<_, _, extra::Err<Rich<char>>>(10)
+                .padded()
+                .separated_by(just(','))
+                .at_least(at_least)
+                .collect::<Vec<_>>()
+                .delimited_by(just('['), just(']'))
+        };
+
+        assert_eq!(
+            list(3).parse("[1, 2, 3]").into_result(),
+            Ok(vec!["1", "2", "3"])
+        );
+
+        let errs = list(3).parse("[1, 2, x]").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(7, 8));
+        assert_eq!(errs[0].list_element(), Some(2));
+        assert!(errs[0].to_string().ends_with(" (3rd element)"));
+
+        // Failing on the first element isn't mid-list, so no element is recorded
+        let errs = list(3).parse("[x]").into_errors();
+        assert_eq!(errs[0].list_element(), None);
+        assert!(!errs[0].to_string().contains("element"));
+
+        // The list ends early when the element fails, but the element is still the one that failed
+        let errs = list(0).parse("[1, 2, x]").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(7, 8));
+        assert_eq!(errs[0].list_element(), Some(2));
+
+        // Errors emitted while an element recovers are noted too
+        let recovered = text::int::<_, _, extra::Err<Rich<char>>>(10)
+            .recover_with(via_parser(any().filter(char::is_ascii_alphabetic).to("0")))
+            .padded()
+            .separated_by(just(','))
+            .collect::<Vec<_>>()
+            .delimited_by(just('['), just(']'));
+        let (out, errs) = recovered.parse("[1, x, 3]").into_output_errors();
+        assert_eq!(out, Some(vec!["1", "0", "3"]));
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(4, 5));
+        assert_eq!(errs[0].list_element(), Some(1));
+
+        // The innermost list's element is the one recorded
+        let nested = list(3)
+            .padded()
+            .separated_by(just(','))
+            .at_least(2)
+            .collect::<Vec<_>>()
+            .delimited_by(just('['), just(']'));
+        let errs = nested.parse("[[1, 2, 3], [4, 5, x]]").into_errors();
+        assert_eq!(errs[0].span(), &SimpleSpan::new(19, 20));
+        assert_eq!(errs[0].list_element(), Some(2));
+    }
+
+    #[test]
+    fn try_map_emit() {
+        use alloc::string::ToString;
+
+        #[derive(Debug, PartialEq)]
+        enum Literal {
+            Int(i64),
         }
-        Ok(M::bind(|| out))
+
+        let lit = just::<_, _, extra::Err<Rich<char>>>('0')
+            .or_not()
+            .then(text::int(10))
+            .try_map_emit(
+                |(leading_zero, digits): (Option<char>, &str), span, emitter| {
+                    if leading_zero.is_some() {
+                        emitter.emit(Rich::custom(span, "warning: leading zero"));
+                    }
+                    Literal::Int(digits.parse().unwrap())
+                },
+            );
+
+        assert_eq!(lit.parse("17").into_result(), Ok(Literal::Int(17)));
+
+        let (out, errs) = lit.parse("017").into_output_errors();
+        assert_eq!(out, Some(Literal::Int(17)));
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].to_string(), "warning: leading zero");
+        assert_eq!(errs[0].span(), &SimpleSpan::new(0, 3));
     }
 
-    go_extra!(U);
-}
+    #[test]
+    fn separated_by_fold() {
+        let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
+            .from_str::<u64>()
+            .unwrapped();
+        // The accumulator is the only thing built: no intermediate `Vec` is involved
+        let sum = int
+            .separated_by(just('+'))
+            .at_least(1)
+            .fold(0, |acc, x| acc + x);
+
+        assert_eq!(sum.parse("1+2+3").into_result(), Ok(6));
+        assert_eq!(sum.parse("42").into_result(), Ok(42));
+        assert!(sum.parse("1+2+").has_errors());
+
+        // Folding works for other iterable parsers too
+        let digits = one_of::<_, _, extra::Err<Simple<char>>>('0'..='9')
+            .repeated()
+            .fold(String::new(), |mut acc, c| {
+                acc.insert(0, c);
+                acc
+            });
+        assert_eq!(digits.parse("123").into_result(), Ok("321".to_string()));
+    }
 
-// /// See [`Parser::or_else`].
-// #[derive(Copy, Clone)]
-// pub struct OrElse<A, F> {
-//     pub(crate) parser: A,
-//     pub(crate) or_else: F,
-// }
+    #[test]
+    fn then_map_with_span_assignment() {
+        #[derive(Debug, PartialEq)]
+        enum Expr<'a> {
+            Var(&'a str, SimpleSpan<usize>),
+            Num(u64, SimpleSpan<usize>),
+            Assign(Box<Expr<'a>>, Box<Expr<'a>>, SimpleSpan<usize>),
+        }
 
-// impl<'a, I, O, E, A, F> ParserSealed<'a, I, O, E> for OrElse<A, F>
-// where
-//     I: Input<'a>,
-//     E: ParserExtra<'a, I>,
-//     A: Parser<'a, I, O, E>,
-//     F: Fn(E::Error) -> Result<O, E::Error>,
-// {
-//     #[inline(always)]
-//     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
-//     where
-//         Self: Sized,
-//     {
-//         let before = inp.save();
-//         match self.parser.go::<M>(inp) {
-//             Ok(out) => Ok(out),
-//             Err(()) => {
-//                 let err = inp.errors.alt.take().expect("error but no alt?");
-//                 match (self.or_else)(err.err) {
-//                     Ok(out) => {
-//                         inp.rewind(before);
-//                         Ok(M::bind(|| out))
-//                     }
-//                     Err(new_err) => {
-//                         inp.errors.alt = Some(Located {
-//                             pos: err.pos,
-//                             err: new_err,
-//                         });
-//                         Err(())
-//                     }
-//                 }
-//             }
-//         }
-//     }
+        let var = text::ascii::ident::<_, _, extra::Err<Simple<char>>>().map_with_span(Expr::Var);
+        let num = text::int(10)
+            .from_str()
+            .unwrapped()
+            .map_with_span(Expr::Num);
+        let assign = var
+            .then_ignore(just('=').padded())
+            .then_map_with_span(num, |lhs, rhs, span| {
+                Expr::Assign(Box::new(lhs), Box::new(rhs), span)
+            })
+            .padded();
 
-//     go_extra!(O);
-// }
+        assert_eq!(
+            assign.parse("  foo = 12 ").into_result(),
+            Ok(Expr::Assign(
+                Box::new(Expr::Var("foo", SimpleSpan::new(2, 5))),
+                Box::new(Expr::Num(12, SimpleSpan::new(8, 10))),
+                SimpleSpan::new(2, 10),
+            )),
+        );
+        assert_eq!(
+            assign.parse("foo = 12").into_result(),
+            var.then_ignore(just('=').padded())
+                .then(num)
+                .map_with_span(|(lhs, rhs), span| Expr::Assign(Box::new(lhs), Box::new(rhs), span))
+                .parse("foo = 12")
+                .into_result(),
+        );
+    }
 
-#[cfg(test)]
-mod tests {
-    use crate::prelude::*;
+    #[test]
+    fn same_repeated_run() {
+        let run = any::<_, extra::Err<Simple<char>>>().same_repeated();
+
+        assert_eq!(
+            run.then(any()).parse("aaab").into_result(),
+            Ok((("aaa", 3), 'b')),
+        );
+        assert_eq!(run.lazy().parse("b").into_result(), Ok(("b", 1)));
+        assert!(run.parse("").has_errors());
+
+        // Runs of multi-character tokens compare outputs, not input
+        let word = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+            .padded()
+            .same_repeated();
+        assert_eq!(
+            word.lazy().parse("go go go stop").into_result(),
+            Ok(("go go go ", 3)),
+        );
+
+        // Items that consume no input end the run rather than repeating forever
+        let maybe_a = just::<_, _, extra::Err<Simple<char>>>('a')
+            .or_not()
+            .same_repeated();
+        assert_eq!(maybe_a.lazy().parse("b").into_result(), Ok(("", 1)));
+        assert_eq!(maybe_a.lazy().parse("aab").into_result(), Ok(("aa", 2)));
+    }
 
     #[test]
-    fn separated_by_at_least() {
-        let parser = just::<_, _, extra::Default>('-')
-            .separated_by(just(','))
-            .at_least(3)
-            .collect();
+    fn then_with_cached_builds_once_per_output() {
+        use core::cell::Cell;
 
-        assert_eq!(parser.parse("-,-,-").into_result(), Ok(vec!['-', '-', '-']));
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        enum Base {
+            Bin,
+            Hex,
+        }
+
+        let input = "b101 xff b1 x0 xa0 b0 ".repeat(20);
+        let built = Cell::new(0);
+        let number = just::<_, _, extra::Err<Simple<char>>>('b')
+            .to(Base::Bin)
+            .or(just('x').to(Base::Hex))
+            .then_with_cached(|base| {
+                built.set(built.get() + 1);
+                text::int(match base {
+                    Base::Bin => 2,
+                    Base::Hex => 16,
+                })
+                .slice()
+            });
+        let numbers = number.clone().padded().repeated().collect::<Vec<_>>();
+
+        let out = numbers.parse(input.as_str()).into_result().unwrap();
+        assert_eq!(out.len(), 120);
+        assert_eq!(out[0], (Base::Bin, "101"));
+        assert_eq!(out[1], (Base::Hex, "ff"));
+        // Two distinct bases means two derived parsers, no matter how many numbers were parsed
+        assert_eq!(built.get(), 2);
+
+        // Derived parsers are not reused by later parses
+        assert_eq!(numbers.parse("b1 b10").into_result().unwrap().len(), 2);
+        assert_eq!(built.get(), 3);
+
+        // Once the cache is full, parsers for other outputs are built every time
+        built.set(0);
+        let numbers = number.capacity(1).padded().repeated().collect::<Vec<_>>();
+        assert_eq!(numbers.parse("b1 xf b0 xa").into_result().unwrap().len(), 4);
+        assert_eq!(built.get(), 3);
+
+        // With the `sync` feature, the cache doesn't stop the parser from being shared between threads
+        #[cfg(feature = "sync")]
+        {
+            fn assert_send_sync<T: Send + Sync>(_: &T) {}
+            assert_send_sync(
+                &any::<&str, extra::Err<Simple<char>>>().then_with_cached(|c: &char| just(*c)),
+            );
+        }
     }
 
     #[test]
-    fn separated_by_at_least_without_leading() {
-        let parser = just::<_, _, extra::Default>('-')
-            .separated_by(just(','))
-            .at_least(3)
-            .collect::<Vec<_>>();
+    fn collect_sorted_out_of_order() {
+        let int = text::int::<_, _, extra::Err<Rich<char>>>(10)
+            .from_str::<u32>()
+            .unwrapped();
+        let sorted = int.separated_by(just(',')).collect_sorted::<Vec<_>, _, _>(
+            |a, b| a < b,
+            |_, span| Rich::custom(span, "out of order"),
+        );
 
-        // Is empty means no errors
-        assert!(parser.parse(",-,-,-").has_errors());
+        let (output, errs) = sorted.parse("1,3,2").into_output_errors();
+        assert_eq!(output, Some(vec![1, 3, 2]));
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].to_string(), "out of order");
+        // The span covers only the out-of-order item, not the separator before it
+        assert_eq!(errs[0].span(), &SimpleSpan::new(4, 5));
+
+        assert_eq!(sorted.parse("1,2,3").into_result(), Ok(vec![1, 2, 3]));
+        assert_eq!(sorted.parse("3,2,1").into_errors().len(), 2);
     }
 
     #[test]
-    fn separated_by_at_least_without_trailing() {
-        let parser = just::<_, _, extra::Default>('-')
+    fn fixed_size_correct() {
+        let header = just::<_, &[u8], extra::Err<Simple<u8>>>(b"HDR")
+            .ignore_then(any().repeated().exactly(5).collect::<Vec<_>>())
+            .fixed_size::<8>();
+
+        assert_eq!(
+            header.parse(b"HDR12345").into_result(),
+            Ok(b"12345".to_vec()),
+        );
+        // Failed parses are not checked
+        assert!(header.parse(b"HDR1234").has_errors());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "declared as 8 units consuming 7 units")]
+    fn fixed_size_mismatch() {
+        // A layout mistake: the header is 8 bytes, but the parser only reads 4 after the magic
+        let header = just::<_, &[u8], extra::Err<Simple<u8>>>(b"HDR")
+            .ignore_then(any().repeated().exactly(4).collect::<Vec<_>>())
+            .fixed_size::<8>();
+
+        let _ = header.lazy().parse(b"HDR12345");
+    }
+
+    #[test]
+    fn then_optional_spans() {
+        let ty = just::<_, _, extra::Err<Simple<char>>>(':')
+            .padded()
+            .ignore_then(text::ascii::ident());
+        let binding = text::ascii::ident()
+            .then_optional(ty)
+            .map_with_span(|(name, ty), span: SimpleSpan<usize>| (name, ty, span));
+        let bindings = binding.padded().separated_by(just(',')).collect::<Vec<_>>();
+
+        assert_eq!(
+            bindings.parse("a: int, b, c :bool").into_result(),
+            Ok(vec![
+                ("a", Some("int"), SimpleSpan::new(0, 6)),
+                ("b", None, SimpleSpan::new(8, 9)),
+                ("c", Some("bool"), SimpleSpan::new(11, 18)),
+            ]),
+        );
+
+        // A partially-present second part is not consumed, and so doesn't extend the span
+        assert_eq!(
+            binding.lazy().parse("x :").into_result(),
+            Ok(("x", None, SimpleSpan::new(0, 1))),
+        );
+    }
+
+    #[test]
+    fn stop_on_end_marker() {
+        use core::cell::Cell;
+
+        let parsed = Cell::new(0);
+        let item = text::int::<_, _, extra::Err<Simple<char>>>(10)
+            .from_str::<u32>()
+            .unwrapped()
+            .map(|n| {
+                parsed.set(parsed.get() + 1);
+                n
+            })
+            .then_ignore(just(';').padded());
+        let items = item.repeated().stop_on(just("__END__")).collect::<Vec<_>>();
+        let file = items.then(any().repeated().slice());
+
+        assert_eq!(
+            file.parse("1; 2; 3;\n__END__\n4; 5; garbage").into_result(),
+            Ok((vec![1, 2, 3], "\n4; 5; garbage")),
+        );
+        assert_eq!(parsed.get(), 3);
+
+        // Without a marker, everything is parsed
+        assert_eq!(items.parse("1; 2;").into_result(), Ok(vec![1, 2]));
+        assert!(items.parse("1; x").has_errors());
+    }
+
+    #[test]
+    fn stop_on_respects_at_least() {
+        let items = just::<_, _, extra::Err<Rich<char>>>('a')
+            .repeated()
+            .at_least(2)
+            .stop_on(just('m'))
+            .collect::<Vec<_>>();
+
+        // Stopping before enough items have been parsed is an error, reported where the missing item was expected
+        let errs = items.parse("m").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(0, 1));
+        assert_eq!(errs[0].found(), Some(&'m'));
+        let errs = items.parse("am").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(1, 2));
+
+        assert_eq!(items.parse("aam").into_result(), Ok(vec!['a', 'a']));
+        assert_eq!(items.lazy().parse("aaamaa").into_result(), Ok(vec!['a'; 3]));
+
+        let items = just::<_, _, extra::Err<Rich<char>>>('a')
+            .repeated()
+            .exactly(2)
+            .stop_on(just('m'))
+            .collect::<Vec<_>>();
+        assert!(items.parse("am").has_errors());
+        assert_eq!(items.parse("aam").into_result(), Ok(vec!['a', 'a']));
+
+        let items = just::<_, _, extra::Err<Rich<char>>>('a')
             .separated_by(just(','))
-            .at_least(3)
+            .at_least(2)
+            .stop_on(just(';'))
             .collect::<Vec<_>>();
+        assert!(items.parse("a;").has_errors());
+        assert_eq!(items.parse("a,a;").into_result(), Ok(vec!['a', 'a']));
+    }
 
-        // Is empty means no errors
-        assert!(parser.parse("-,-,-,").has_errors());
+    #[test]
+    fn reparse_slice_string_contents() {
+        let int = text::int::<_, _, extra::Err<Rich<char>>>(10)
+            .from_str::<u32>()
+            .unwrapped();
+        let sum = int.foldl(just('+').ignore_then(int).repeated(), |a, b| a + b);
+        let literal = none_of::<_, _, extra::Err<Rich<char>>>('"')
+            .repeated()
+            .reparse_slice(sum)
+            .delimited_by(just('"'), just('"'))
+            .padded();
+
+        assert_eq!(literal.parse(r#""1+2""#).into_result(), Ok(3));
+        assert_eq!(literal.parse(r#" "10+20+3" "#).into_result(), Ok(33));
+
+        // Errors in the contents are reported at their position in the whole input
+        let errs = literal.parse(r#""1+?""#).into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(3, 4));
+        assert_eq!(errs[0].found(), Some(&'?'));
+
+        // The contents must be parsed completely
+        assert!(literal.parse(r#""1+2 ""#).has_errors());
     }
 
     #[test]
-    fn separated_by_at_least_with_leading() {
-        let parser = just::<_, _, extra::Default>('-')
-            .separated_by(just(','))
-            .allow_leading()
-            .at_least(3)
-            .collect();
+    fn repeated_while_guard() {
+        let stmt = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+            .then_ignore(just(';'))
+            .padded();
+
+        let stmts = stmt.repeated().while_(none_of('e')).collect::<Vec<_>>();
 
+        // Without the guard, every statement gets parsed
         assert_eq!(
-            parser.parse(",-,-,-").into_result(),
-            Ok(vec!['-', '-', '-'])
+            stmt.repeated()
+                .collect::<Vec<_>>()
+                .parse("a; b; end; c;")
+                .into_result(),
+            Ok(vec!["a", "b", "end", "c"]),
         );
-        assert!(parser.parse(",-,-").has_errors());
+        // The guard stops the repetition before `end`, even though it's a valid statement
+        assert_eq!(
+            stmts
+                .then(any().repeated().slice())
+                .parse("a; b; end; c;")
+                .into_result(),
+            Ok((vec!["a", "b"], "end; c;")),
+        );
+        assert_eq!(stmts.lazy().parse("a; end;").into_result(), Ok(vec!["a"]));
     }
 
     #[test]
-    fn separated_by_at_least_with_trailing() {
-        let parser = just::<_, _, extra::Default>('-')
+    fn map_group_spreads_tuple() {
+        #[derive(Debug, PartialEq)]
+        struct Date {
+            year: u16,
+            month: u8,
+            day: u8,
+        }
+
+        let date = group((
+            text::int::<_, _, extra::Err<Simple<char>>>(10)
+                .from_str()
+                .unwrapped()
+                .then_ignore(just('-')),
+            text::int(10).from_str().unwrapped().then_ignore(just('-')),
+            text::int(10).from_str().unwrapped(),
+        ))
+        .map_group(|year, month, day| Date { year, month, day });
+
+        assert_eq!(
+            date.parse("2023-10-31").into_result(),
+            Ok(Date {
+                year: 2023,
+                month: 10,
+                day: 31,
+            }),
+        );
+    }
+
+    #[test]
+    fn cached_at_position_runs_once() {
+        use crate::extra::PositionCache;
+        use std::{cell::Cell, rc::Rc};
+
+        type Extra = extra::State<PositionCache<usize, String>>;
+
+        let calls = Rc::new(Cell::new(0));
+        let counted = {
+            let calls = calls.clone();
+            text::ascii::ident::<_, _, Extra>().map(move |s: &str| {
+                calls.set(calls.get() + 1);
+                s.to_string()
+            })
+        };
+        let ident = counted.cached_at_position("ident");
+        let item = choice((
+            ident.clone().then_ignore(just('(')),
+            ident.clone().then_ignore(just('[')),
+        ));
+
+        assert_eq!(item.parse("foo[").into_result(), Ok("foo".to_string()));
+        assert_eq!(calls.get(), 1);
+
+        // A new parse must not reuse the output of a previous one
+        assert_eq!(item.parse("bar(").into_result(), Ok("bar".to_string()));
+        assert_eq!(calls.get(), 2);
+
+        // Parsers with different keys don't share outputs, even at the same position
+        let upper = text::ascii::ident::<_, _, Extra>()
+            .map(|s: &str| s.to_uppercase())
+            .cached_at_position("upper");
+        let item = ident
+            .then_ignore(just('('))
+            .or(upper.then_ignore(just('[')));
+        assert_eq!(item.parse("foo[").into_result(), Ok("FOO".to_string()));
+    }
+
+    #[test]
+    fn cached_at_position_replays_events() {
+        use crate::extra::{Event, EventSink, PositionCache, PositionCacheSource};
+
+        #[derive(Default)]
+        struct State {
+            cache: PositionCache<usize, ()>,
+            events: Vec<Event<&'static str, SimpleSpan>>,
+        }
+
+        impl PositionCacheSource<usize, ()> for State {
+            fn position_cache(&mut self) -> &mut PositionCache<usize, ()> {
+                &mut self.cache
+            }
+        }
+
+        impl EventSink<&'static str, SimpleSpan> for State {
+            fn event(&mut self, event: Event<&'static str, SimpleSpan>) {
+                self.events.event(event);
+            }
+
+            fn forget(&mut self, count: usize) {
+                self.events.forget(count);
+            }
+        }
+
+        let ident = text::ascii::ident::<_, _, extra::State<State>>()
+            .ignored()
+            .with_events("ident")
+            .cached_at_position("ident");
+        // The first alternative's events are forgotten when it fails, so the reused output must record them again
+        let item = ident
+            .then_ignore(just('('))
+            .or(ident.then_ignore(just('[')))
+            .with_events("item");
+
+        let mut state = State::default();
+        assert_eq!(
+            item.parse_with_state("foo[", &mut state).into_result(),
+            Ok(())
+        );
+        assert_eq!(
+            state.events,
+            vec![
+                Event::Enter("item"),
+                Event::Enter("ident"),
+                Event::Exit("ident", SimpleSpan::new(0, 3)),
+                Event::Exit("item", SimpleSpan::new(0, 4)),
+            ],
+        );
+    }
+
+    #[test]
+    fn lazy_map_skips_discarded_branch() {
+        use std::{cell::Cell, rc::Rc};
+
+        let runs = Rc::new(Cell::new(0));
+        let expensive = {
+            let runs = runs.clone();
+            move |s: &str| {
+                runs.set(runs.get() + 1);
+                s.repeat(1000)
+            }
+        };
+
+        let word = text::ascii::ident::<_, _, extra::Err<Simple<char>>>().lazy_map(expensive);
+        // The first branch matches the word but is discarded because `!` doesn't follow it
+        let parser = word
+            .then_ignore(just('!'))
+            .map(Some)
+            .or(text::ascii::ident().then_ignore(just('?')).map(|_| None));
+
+        let out = parser.parse("hi?").into_result().unwrap();
+        assert!(out.is_none());
+        assert_eq!(runs.get(), 0);
+
+        let out = parser.parse("hi!").into_result().unwrap().unwrap();
+        assert_eq!(runs.get(), 0);
+        assert_eq!(out.force().len(), 2000);
+        assert_eq!(runs.get(), 1);
+        out.force();
+        assert_eq!(runs.get(), 1);
+    }
+
+    #[test]
+    fn lazy_map_poisoned_by_panic() {
+        use crate::util::Deferred;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let deferred = Deferred::new(|| -> u32 { panic!("construction failed") });
+        assert!(catch_unwind(AssertUnwindSafe(|| *deferred.force())).is_err());
+        assert!(!deferred.is_forced());
+
+        let err = catch_unwind(AssertUnwindSafe(|| *deferred.force())).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<&str>(),
+            Some(&"deferred value was poisoned by a panic during its construction"),
+        );
+        assert!(catch_unwind(AssertUnwindSafe(|| deferred.into_inner())).is_err());
+    }
+
+    #[test]
+    fn collect_flat_flattens_tokens() {
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        enum Token {
+            Gt,
+            Eq,
+            Ident,
+        }
+
+        let token = choice((
+            // In this context, `>>=` is lexed as three separate tokens
+            just(">>=").map(|_| vec![Token::Gt, Token::Gt, Token::Eq]),
+            just('>').map(|_| vec![Token::Gt]),
+            text::ascii::ident::<_, _, extra::Err<Simple<char>>>().map(|_| vec![Token::Ident]),
+        ))
+        .padded();
+        let tokens = token.repeated().collect_flat::<Vec<_>>();
+
+        assert_eq!(
+            tokens.parse("a >>= b > c").into_result(),
+            Ok(vec![
+                Token::Ident,
+                Token::Gt,
+                Token::Gt,
+                Token::Eq,
+                Token::Ident,
+                Token::Gt,
+                Token::Ident,
+            ]),
+        );
+    }
+
+    #[test]
+    fn repeated_group_by_merges_text_runs() {
+        #[derive(Clone, Debug, PartialEq)]
+        enum Tok {
+            Text(String),
+            Space,
+        }
+
+        let tok = choice((
+            text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+                .map(|s: &str| Tok::Text(s.to_string())),
+            just('.').map(|c: char| Tok::Text(c.to_string())),
+            text::whitespace().at_least(1).to(Tok::Space),
+        ));
+
+        let runs = tok.repeated().group_by(
+            |tok| matches!(tok, Tok::Text(_)),
+            |a, b| match (a, b) {
+                (Tok::Text(a), Tok::Text(b)) => Tok::Text(a + &b),
+                (a, _) => a,
+            },
+        );
+
+        assert_eq!(
+            runs.parse("foo.bar  baz").into_result(),
+            Ok(vec![
+                Tok::Text("foo.bar".to_string()),
+                Tok::Space,
+                Tok::Text("baz".to_string()),
+            ]),
+        );
+        assert_eq!(runs.parse("").into_result(), Ok(vec![]));
+    }
+
+    #[test]
+    fn collect_as_changes_container() {
+        let items = any::<_, extra::Err<Simple<char>>>()
+            .filter(|c: &char| *c != ',')
             .separated_by(just(','))
-            .allow_trailing()
-            .at_least(3)
-            .collect();
+            .collect::<Vec<char>>();
 
         assert_eq!(
-            parser.parse("-,-,-,").into_result(),
-            Ok(vec!['-', '-', '-'])
+            items.collect_as::<String>().parse("a,b,c").into_result(),
+            Ok("abc".to_string()),
+        );
+        assert_eq!(
+            items
+                .collect_as::<std::collections::BTreeSet<_>>()
+                .parse("c,a,c")
+                .into_result(),
+            Ok(['a', 'c'].into_iter().collect()),
         );
-        assert!(parser.parse("-,-,").has_errors());
     }
 
     #[test]
-    fn separated_by_leaves_last_separator() {
-        let parser = just::<_, _, extra::Default>('-')
+    fn followed_by_lets_float_take_dot() {
+        #[derive(Clone, Debug, PartialEq)]
+        enum Token<'a> {
+            Ident(&'a str),
+            Dot,
+            Float(&'a str),
+        }
+
+        let is_digit = |c: Option<&char>| matches!(c, Some(c) if c.is_ascii_digit());
+        let member = just::<_, _, extra::Err<Simple<char>>>('.')
+            .followed_by(move |c| !is_digit(c))
+            .to(Token::Dot);
+        let float = text::digits(10)
+            .or_not()
+            .then(just('.'))
+            .then(text::digits(10))
+            .map_slice(Token::Float);
+        let ident = text::ascii::ident().map(Token::Ident);
+        let token = choice((member, float, ident));
+        let tokens = token.repeated().collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens.parse("a.b.5").into_result(),
+            Ok(vec![
+                Token::Ident("a"),
+                Token::Dot,
+                Token::Ident("b"),
+                Token::Float(".5"),
+            ]),
+        );
+        assert_eq!(tokens.parse(".").into_result(), Ok(vec![Token::Dot]));
+    }
+
+    #[test]
+    fn repeated_collect_dedup() {
+        #[derive(Clone, Debug, PartialEq)]
+        enum Tok {
+            A,
+            B,
+        }
+
+        let tok = just::<_, _, extra::Err<Simple<char>>>('a')
+            .to(Tok::A)
+            .or(just('b').to(Tok::B))
+            .padded();
+        let toks = tok.repeated().collect_dedup();
+
+        assert_eq!(
+            toks.parse("a a b b b a").into_result(),
+            Ok(vec![Tok::A, Tok::B, Tok::A]),
+        );
+        assert_eq!(toks.parse("").into_result(), Ok(vec![]));
+    }
+
+    #[test]
+    fn with_related_span_points_at_open_brace() {
+        let item = text::ascii::ident().padded();
+        let block = just::<_, _, extra::Err<Rich<char>>>('{')
+            .map_with_span(|_, span| span)
+            .ignore_with_ctx(
+                item.repeated()
+                    .ignore_then(
+                        just('}')
+                            .with_related_span("unclosed `{` opened here")
+                            .configure(|cfg, open| cfg.span(*open)),
+                    )
+                    .ignored(),
+            );
+        let blocks = just(' ').repeated().ignore_then(block);
+
+        assert_eq!(blocks.parse("  { a b }").into_result(), Ok(()));
+
+        let errs = blocks.parse("  { a b").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(7, 7));
+        assert_eq!(
+            errs[0].related_spans().collect::<Vec<_>>(),
+            vec![(&SimpleSpan::new(2, 3), "unclosed `{` opened here")],
+        );
+
+        // Without a configured span, there's nothing to point at
+        let close = just::<_, _, extra::Err<Rich<char>>>('}').with_related_span("opened here");
+        let errs = close.parse("a").into_errors();
+        assert_eq!(errs[0].related_spans().count(), 0);
+    }
+
+    #[test]
+    fn with_node_id_is_deterministic() {
+        use crate::extra::{NodeId, NodeIds};
+
+        #[derive(Debug, PartialEq)]
+        enum Ast {
+            Num(NodeId),
+            Add(NodeId, Box<Ast>, Box<Ast>),
+        }
+
+        let num = text::int::<_, _, extra::State<NodeIds>>(10)
+            .map_with_state(|_, _, ids: &mut NodeIds| Ast::Num(ids.current().unwrap()))
+            .with_node_id("num");
+        let add = num
+            .then_ignore(just('+'))
+            .then(num)
+            .map_with_state(|(a, b), _, ids: &mut NodeIds| {
+                Ast::Add(ids.current().unwrap(), Box::new(a), Box::new(b))
+            })
+            .with_node_id("add");
+        // `add` fails after its first operand when there is no `+`, leaving the path balanced for `num`
+        let expr = add.or(num);
+
+        let parse = |src| {
+            expr.parse_with_state(src, &mut NodeIds::new())
+                .into_result()
+                .unwrap()
+        };
+
+        let first = parse("1+2");
+        assert_eq!(first, parse("1+2"));
+
+        let (add_id, a_id, b_id) = match first {
+            Ast::Add(add_id, a, b) => match (*a, *b) {
+                (Ast::Num(a_id), Ast::Num(b_id)) => (add_id, a_id, b_id),
+                _ => panic!("expected numbers"),
+            },
+            _ => panic!("expected an addition"),
+        };
+        assert_ne!(add_id, a_id);
+        assert_ne!(a_id, b_id);
+
+        // A lone number has no `add` ancestor, so it is a different node to the first operand above
+        assert_ne!(parse("1"), Ast::Num(a_id));
+        assert_eq!(parse("1"), parse("1"));
+    }
+
+    #[test]
+    fn require_progress_reports_zero_width_item() {
+        let item = just::<_, _, extra::Err<Rich<char>>>('a').or_not();
+
+        // Both the collecting and non-collecting paths must report the error rather than looping
+        let collected = item.repeated().require_progress().collect::<Vec<_>>();
+        let errs = collected.parse("aa").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].to_string(), "parser made no progress");
+        assert_eq!(errs[0].span(), &SimpleSpan::new(2, 2));
+
+        let ignored = item.repeated().require_progress();
+        let errs = ignored.parse("aa").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].to_string(), "parser made no progress");
+
+        let separated = item
             .separated_by(just(','))
-            .collect::<Vec<_>>()
-            .then(just(','));
+            .require_progress()
+            .collect::<Vec<_>>();
         assert_eq!(
-            parser.parse("-,-,-,").into_result(),
-            Ok((vec!['-', '-', '-'], ',')),
-        )
+            separated.parse("a,,a").into_result(),
+            Ok(vec![Some('a'), None, Some('a')]),
+        );
+        // The first item has no separator before it, so it may be empty
+        assert_eq!(separated.parse("").into_result(), Ok(vec![None]));
+        assert_eq!(
+            separated.parse(",a").into_result(),
+            Ok(vec![None, Some('a')])
+        );
+
+        let separated = item
+            .separated_by(just(',').or_not())
+            .require_progress()
+            .collect::<Vec<_>>();
+        let errs = separated.parse("a").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].to_string(), "parser made no progress");
+        assert_eq!(errs[0].span(), &SimpleSpan::new(1, 1));
     }
 }
@@ -5,6 +5,10 @@ use alloc::collections::LinkedList;
 use hashbrown::HashSet;
 
 /// A utility trait for types that can be constructed from a series of items.
+///
+/// This trait is not sealed: implement it for your own types (or for third-party collections, such as those in
+/// `imbl` or `indexmap`) to use them as targets of [`IterParser::collect`]. The [`impl_container!`] macro can write
+/// the implementation for you.
 pub trait Container<T>: Default {
     /// Create a container, attempting to pre-allocate enough space for `n` items.
     ///
@@ -142,8 +146,52 @@ impl<T: Ord> Container<T> for alloc::collections::BTreeSet<T> {
     }
 }
 
+/// Implement [`Container`] for a type by describing how to push an item into it.
+///
+/// The type must already implement [`Default`], which is used to create an empty container. Generic parameters are
+/// given in square brackets, followed by the container type, the item type, and a closure-like body that pushes an
+/// item.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// #[derive(Debug, PartialEq)]
+/// struct Stack<T>(Vec<T>);
+///
+/// impl<T> Default for Stack<T> {
+///     fn default() -> Self { Stack(Vec::new()) }
+/// }
+///
+/// chumsky::impl_container!([T] Stack<T> => T, |stack, item| stack.0.push(item));
+///
+/// let digits = any::<_, extra::Err<Simple<char>>>()
+///     .filter(char::is_ascii_digit)
+///     .repeated()
+///     .collect::<Stack<_>>();
+///
+/// assert_eq!(digits.parse("123").into_result(), Ok(Stack(vec!['1', '2', '3'])));
+/// ```
+#[macro_export]
+macro_rules! impl_container {
+    ([$($gen:tt)*] $ty:ty => $item:ty, |$this:pat_param, $arg:pat_param| $push:expr) => {
+        impl<$($gen)*> $crate::container::Container<$item> for $ty {
+            fn push(&mut self, item: $item) {
+                let $this = self;
+                let $arg = item;
+                $push;
+            }
+        }
+    };
+    ($ty:ty => $item:ty, |$this:pat_param, $arg:pat_param| $push:expr) => {
+        $crate::impl_container!([] $ty => $item, |$this, $arg| $push);
+    };
+}
+
 /// A utility trait for types that hold a specific constant number of output values.
 ///
+/// Like [`Container`], this trait may be implemented for your own types, but note the safety requirements below.
+///
 /// # Safety
 ///
 /// This trait requires that [`Uninit`](ContainerExactly::Uninit) be sound to reinterpret as `Self`
@@ -603,6 +651,100 @@ impl<'p, T: Clone + Ord> Seq<'p, T> for alloc::collections::BTreeSet<T> {
     }
 }
 
+impl<'p, T: Eq + Hash> Seq<'p, T> for &'p HashSet<T> {
+    type Item<'a> = &'p T
+    where
+        Self: 'a;
+
+    type Iter<'a> = hashbrown::hash_set::Iter<'p, T>
+    where
+        Self: 'a;
+
+    #[inline(always)]
+    fn seq_iter(&self) -> Self::Iter<'_> {
+        self.iter()
+    }
+
+    #[inline(always)]
+    fn contains(&self, val: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        HashSet::contains(self, val)
+    }
+
+    #[inline]
+    fn to_maybe_ref<'b>(item: Self::Item<'b>) -> MaybeRef<'p, T>
+    where
+        'p: 'b,
+    {
+        MaybeRef::Ref(item)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'p, T: Eq + Hash> Seq<'p, T> for &'p std::collections::HashSet<T> {
+    type Item<'a> = &'p T
+    where
+        Self: 'a;
+
+    type Iter<'a> = std::collections::hash_set::Iter<'p, T>
+    where
+        Self: 'a;
+
+    #[inline(always)]
+    fn seq_iter(&self) -> Self::Iter<'_> {
+        self.iter()
+    }
+
+    #[inline(always)]
+    fn contains(&self, val: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        std::collections::HashSet::contains(self, val)
+    }
+
+    #[inline]
+    fn to_maybe_ref<'b>(item: Self::Item<'b>) -> MaybeRef<'p, T>
+    where
+        'p: 'b,
+    {
+        MaybeRef::Ref(item)
+    }
+}
+
+impl<'p, T: Ord> Seq<'p, T> for &'p alloc::collections::BTreeSet<T> {
+    type Item<'a> = &'p T
+    where
+        Self: 'a;
+
+    type Iter<'a> = alloc::collections::btree_set::Iter<'p, T>
+    where
+        Self: 'a;
+
+    #[inline(always)]
+    fn seq_iter(&self) -> Self::Iter<'_> {
+        self.iter()
+    }
+
+    #[inline(always)]
+    fn contains(&self, val: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        alloc::collections::BTreeSet::contains(self, val)
+    }
+
+    #[inline]
+    fn to_maybe_ref<'b>(item: Self::Item<'b>) -> MaybeRef<'p, T>
+    where
+        'p: 'b,
+    {
+        MaybeRef::Ref(item)
+    }
+}
+
 impl<'p, T> Seq<'p, T> for Range<T>
 where
     T: Clone + PartialOrd, // Explicit declaration of an implied truth - `Step` requires these
@@ -849,4 +991,68 @@ mod test {
         assert_eq!(&**c, &[0, 1, 2, 3]);
         drop_container::<Box<Rc<[usize; 4]>>>();
     }
+
+    #[test]
+    fn impl_container_macro() {
+        use crate::prelude::*;
+        use alloc::collections::VecDeque;
+
+        #[derive(Debug, PartialEq)]
+        struct Reversed<T>(VecDeque<T>);
+
+        impl<T> Default for Reversed<T> {
+            fn default() -> Self {
+                Reversed(VecDeque::new())
+            }
+        }
+
+        crate::impl_container!([T] Reversed<T> => T, |this, item| this.0.push_front(item));
+
+        #[derive(Default, Debug, PartialEq)]
+        struct Sum(u32);
+
+        crate::impl_container!(Sum => u32, |this, n| this.0 += n);
+
+        let digit = any::<_, extra::Err<Simple<char>>>().filter(char::is_ascii_digit);
+
+        assert_eq!(
+            digit
+                .repeated()
+                .collect::<Reversed<_>>()
+                .parse("123")
+                .into_result(),
+            Ok(Reversed(VecDeque::from(['3', '2', '1']))),
+        );
+        assert_eq!(
+            digit
+                .map(|c| c.to_digit(10).unwrap())
+                .repeated()
+                .collect::<Sum>()
+                .parse("123")
+                .into_result(),
+            Ok(Sum(6)),
+        );
+    }
+
+    #[test]
+    fn one_of_borrowed_sets() {
+        use crate::prelude::*;
+        use alloc::collections::BTreeSet;
+
+        // A set that outlives the parser, such as one loaded from configuration at startup
+        let operators = ['+', '-', '*', '/'].into_iter().collect::<HashSet<_>>();
+        let op = one_of::<_, _, extra::Err<Simple<char>>>(&operators);
+        assert_eq!(op.parse("*").into_result(), Ok('*'));
+        assert!(op.parse("%").has_errors());
+
+        let vowels = "aeiou".chars().collect::<BTreeSet<_>>();
+        let consonants = none_of::<_, _, extra::Err<Simple<char>>>(&vowels)
+            .repeated()
+            .collect::<String>();
+        assert_eq!(
+            consonants.parse("rhythm").into_result(),
+            Ok("rhythm".into())
+        );
+        assert!(consonants.parse("vowel").has_errors());
+    }
 }
@@ -0,0 +1,70 @@
+//! Lossless concrete syntax trees, built with the [`rowan`](https://docs.rs/rowan) crate.
+//!
+//! Mark the parsers that correspond to syntax nodes with [`Parser::node`] and parse with a
+//! `Vec<Event<K, S>>` as the parser state. The recorded events can then be assembled into a
+//! [`GreenNode`] with [`build_green_tree`]. Any input not covered by a node (whitespace, comments,
+//! punctuation, etc.) is kept as tokens, so the text of the tree is always identical to the input.
+
+use super::*;
+use crate::extra::Event;
+use rowan::{GreenNode, GreenNodeBuilder, SyntaxKind};
+
+/// Assemble a lossless [`GreenNode`] from the source text and the [`Event`]s produced by parsers
+/// marked with [`Parser::node`].
+///
+/// The whole input is wrapped in a node of kind `root`. Text that lies between nodes, or between a
+/// node's boundary and its children, becomes a token of kind `token`. Events for nodes that never
+/// finished (for example, because parsing failed) are ignored.
+pub fn build_green_tree<K, S>(src: &str, events: &[Event<K, S>], root: K, token: K) -> GreenNode
+where
+    K: Clone + Into<SyntaxKind>,
+    S: Span<Offset = usize>,
+{
+    // Find the start offset of every node by pairing each `Enter` with its `Exit`
+    let mut starts = vec![None; events.len()];
+    let mut open = Vec::new();
+    for (i, event) in events.iter().enumerate() {
+        match event {
+            Event::Enter(_) => open.push(i),
+            Event::Exit(_, span) => {
+                if let Some(enter) = open.pop() {
+                    starts[enter] = Some(span.start());
+                }
+            }
+        }
+    }
+
+    let token = token.into();
+    let mut builder = GreenNodeBuilder::new();
+    let mut cursor = 0;
+    let mut push_text = |builder: &mut GreenNodeBuilder, to: usize| {
+        if to > cursor {
+            builder.token(token, &src[cursor..to]);
+            cursor = to;
+        }
+    };
+
+    builder.start_node(root.into());
+    let mut depth = 0usize;
+    for (event, start) in events.iter().zip(starts) {
+        match (event, start) {
+            (Event::Enter(kind), Some(start)) => {
+                push_text(&mut builder, start);
+                builder.start_node(kind.clone().into());
+                depth += 1;
+            }
+            (Event::Enter(_), None) => {}
+            (Event::Exit(_, span), _) => {
+                if depth > 0 {
+                    push_text(&mut builder, span.end());
+                    builder.finish_node();
+                    depth -= 1;
+                }
+            }
+        }
+    }
+    push_text(&mut builder, src.len());
+    builder.finish_node();
+
+    builder.finish()
+}
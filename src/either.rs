@@ -34,7 +34,7 @@ where
 #[cfg(test)]
 mod tests {
     use crate::{
-        prelude::{any, just},
+        prelude::{any, choice, just},
         IterParser, Parser,
     };
     use either::Either;
@@ -75,4 +75,39 @@ mod tests {
             assert!(parser.parse("[3, 4, 5, 67 89,]").has_errors());
         }
     }
+
+    #[test]
+    fn or_tagged() {
+        use crate::{error::Simple, extra, text};
+
+        #[derive(Debug, PartialEq)]
+        enum Token<'a> {
+            Keyword(&'a str),
+            Ident(&'a str),
+        }
+
+        let keyword = choice((
+            text::ascii::keyword::<_, _, _, extra::Err<Simple<char>>>("let"),
+            text::ascii::keyword("fn"),
+        ));
+        let token = keyword
+            .or_tagged(text::ascii::ident())
+            .map(|tagged| match tagged {
+                Either::Left(kw) => Token::Keyword(kw),
+                Either::Right(ident) => Token::Ident(ident),
+            })
+            .padded()
+            .repeated()
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            token.parse("let fn letter").into_result(),
+            Ok(vec![
+                Token::Keyword("let"),
+                Token::Keyword("fn"),
+                Token::Ident("letter"),
+            ]),
+        );
+        assert!(token.parse("let 1").has_errors());
+    }
 }
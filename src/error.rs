@@ -7,7 +7,7 @@
 //! like [`Cheap`], [`Simple`] or [`Rich`].
 
 use super::*;
-use alloc::string::ToString;
+use alloc::{format, string::ToString};
 
 /// A trait that describes parser error types.
 ///
@@ -96,6 +96,67 @@ pub trait Error<'a, I: Input<'a>>: Sized {
         self.merge(Self::expected_found(expected, found, span))
     }
 
+    /// Create an error with a custom message, for problems that are not a conflict between expected and found inputs
+    /// (such as an integer literal that does not fit within its type).
+    ///
+    /// Error types that have nowhere to store a message may ignore it, in which case the default behaviour is to produce
+    /// an error that expected and found nothing at the given span.
+    #[inline(always)]
+    fn message(span: I::Span, message: String) -> Self {
+        #![allow(unused_variables)]
+        Self::expected_found(None, None, span)
+    }
+
+    /// Attach a suggestion (a fix-it, such as "did you mean `==`?") to this error.
+    ///
+    /// Error types that have nowhere to store a suggestion may ignore it, which is the default behaviour. Of the
+    /// built-in error types, only [`Rich`] stores suggestions.
+    #[inline(always)]
+    fn with_suggestion(self, suggestion: String) -> Self {
+        #![allow(unused_variables)]
+        self
+    }
+
+    /// Get the suggestion attached to this error, if any.
+    #[inline(always)]
+    fn suggestion(&self) -> Option<&str> {
+        None
+    }
+
+    /// Attach a related span to this error, along with a message describing how it relates (such as "the `{` was
+    /// opened here"). This allows diagnostics to point at more than one location.
+    ///
+    /// The message is produced by calling `message`, so error types that have nowhere to store related spans can
+    /// ignore them without building it, which is the default behaviour.
+    #[inline(always)]
+    fn with_related_span<F: FnOnce() -> String>(self, span: I::Span, message: F) -> Self {
+        #![allow(unused_variables)]
+        self
+    }
+
+    /// Record that this error was produced by the element at the given (zero-based) index of a list, such as one
+    /// parsed by [`Parser::separated_by`]. This happens whenever an element after the first fails, even if the list
+    /// then ends early rather than failing, and for any errors emitted while such an element recovers.
+    ///
+    /// When lists are nested, this is called for the innermost list first, and that index should be the one kept.
+    ///
+    /// Error types that have nowhere to store this information may ignore it, which is the default behaviour.
+    #[inline(always)]
+    fn in_list_element(self, index: usize) -> Self {
+        #![allow(unused_variables)]
+        self
+    }
+
+    /// Create an error indicating that a list parsed with [`SeparatedBy::forbid_trailing_explicit`] ended with a
+    /// separator that was not followed by an item. `found` is the first token of the separator, and the span covers
+    /// all of it.
+    ///
+    /// By default, this is an error that expected nothing and found the separator at the given span.
+    #[inline(always)]
+    fn trailing_separator(found: Option<MaybeRef<'a, I::Token>>, span: I::Span) -> Self {
+        Self::expected_found(None, found, span)
+    }
+
     /// Fast path for `a = Error::expected_found(...)` that may incur less overhead by, for example, reusing allocations.
     #[inline(always)]
     fn replace_expected_found<E: IntoIterator<Item = Option<MaybeRef<'a, I::Token>>>>(
@@ -108,6 +169,15 @@ pub trait Error<'a, I: Input<'a>>: Sized {
     }
 }
 
+/// A trait implemented by error types that can summarise a list of errors as a single error. See
+/// [`ParseResult::summarize_errors`].
+pub trait FromErrors: Sized {
+    /// Create a single error summarising the given errors.
+    ///
+    /// `errors` is never empty.
+    fn from_errors(errors: Vec<Self>) -> Self;
+}
+
 /// A ZST error type that tracks only whether a parse error occurred at all. This type is for when
 /// you want maximum parse speed, at the cost of all error reporting.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Copy, Clone, Default)]
@@ -124,12 +194,31 @@ impl<'a, I: Input<'a>> Error<'a, I> for EmptyErr {
     }
 }
 
+impl FromErrors for EmptyErr {
+    #[inline]
+    fn from_errors(_: Vec<Self>) -> Self {
+        EmptyErr(())
+    }
+}
+
 impl fmt::Display for EmptyErr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "error")
     }
 }
 
+/// An error indicating that a parser did more work than it was permitted to. See [`Parser::parse_with_budget`].
+#[cfg(feature = "budget")]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct BudgetExceeded(pub(crate) ());
+
+#[cfg(feature = "budget")]
+impl fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "budget exceeded")
+    }
+}
+
 /// A very cheap error type that tracks only the error span. This type is most useful when you want fast parsing but do
 /// not particularly care about the quality of error messages.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -148,6 +237,14 @@ impl<'a, I: Input<'a>> Error<'a, I> for Cheap<I::Span> {
     }
 }
 
+/// [`Cheap`] has nowhere to store a count of errors, so the summary is simply the first error, unchanged.
+impl<S> FromErrors for Cheap<S> {
+    #[inline]
+    fn from_errors(errors: Vec<Self>) -> Self {
+        errors.into_iter().next().expect("no errors to summarize")
+    }
+}
+
 impl<S> fmt::Debug for Cheap<S>
 where
     S: fmt::Debug,
@@ -169,6 +266,9 @@ where
 
 /// A simple error type that tracks the error span and found token. This type is most useful when you want fast parsing
 /// but do not particularly care about the quality of error messages.
+///
+/// To remain cheap (and [`Copy`]), this type does not store suggestions attached with [`Parser::suggest`]: use
+/// [`Rich`] if you need them.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Simple<'a, T, S = SimpleSpan<usize>> {
     span: S,
@@ -202,6 +302,14 @@ impl<'a, I: Input<'a>> Error<'a, I> for Simple<'a, I::Token, I::Span> {
     }
 }
 
+/// [`Simple`] has nowhere to store a count of errors, so the summary is simply the first error, unchanged.
+impl<'a, T, S> FromErrors for Simple<'a, T, S> {
+    #[inline]
+    fn from_errors(errors: Vec<Self>) -> Self {
+        errors.into_iter().next().expect("no errors to summarize")
+    }
+}
+
 impl<'a, T, S> fmt::Debug for Simple<'a, T, S>
 where
     T: fmt::Debug,
@@ -514,6 +622,9 @@ where
 pub struct Rich<'a, T, S = SimpleSpan<usize>, L = &'static str> {
     span: S,
     reason: Box<RichReason<'a, T, L>>,
+    suggestion: Option<String>,
+    list_element: Option<usize>,
+    related: Vec<(S, String)>,
     #[cfg(feature = "label")]
     context: Vec<(L, S)>,
 }
@@ -533,7 +644,13 @@ impl<'a, T, S, L> Rich<'a, T, S, L> {
             fmt_span,
             fmt_label,
             if with_spans { Some(&self.span) } else { None },
-        )
+        )?;
+        if let Some(index) = self.list_element {
+            write!(f, " (")?;
+            write_ordinal(f, index + 1)?;
+            write!(f, " element)")?;
+        }
+        Ok(())
     }
 }
 
@@ -544,6 +661,9 @@ impl<'a, T, S, L> Rich<'a, T, S, L> {
         Rich {
             span,
             reason: Box::new(RichReason::Custom(msg.to_string())),
+            suggestion: None,
+            list_element: None,
+            related: Vec::new(),
             #[cfg(feature = "label")]
             context: Vec::new(),
         }
@@ -569,6 +689,25 @@ impl<'a, T, S, L> Rich<'a, T, S, L> {
         self.reason.found()
     }
 
+    /// Get the suggestion attached to this error, if any. See [`Parser::suggest`].
+    pub fn suggestion(&self) -> Option<&str> {
+        self.suggestion.as_deref()
+    }
+
+    /// Get the (zero-based) index of the list element that produced this error, if any. For nested lists, this is the
+    /// index within the innermost list. See [`Error::in_list_element`].
+    ///
+    /// When set, the element is also mentioned when the error is displayed, as in "(3rd element)".
+    pub fn list_element(&self) -> Option<usize> {
+        self.list_element
+    }
+
+    /// Return an iterator over the related spans attached to this error, along with their messages. See
+    /// [`Parser::with_related_span`].
+    pub fn related_spans(&self) -> impl Iterator<Item = (&S, &str)> {
+        self.related.iter().map(|(span, msg)| (span, msg.as_str()))
+    }
+
     /// Return an iterator over the labelled contexts of this error, from least general to most.
     ///
     /// 'Context' here means parser patterns that the parser was in the process of parsing when the error occurred. To
@@ -617,6 +756,9 @@ impl<'a, T, S, L> Rich<'a, T, S, L> {
         Rich {
             span: self.span,
             reason: Box::new(self.reason.map_token(f)),
+            suggestion: self.suggestion,
+            list_element: self.list_element,
+            related: self.related,
             #[cfg(feature = "label")]
             context: self.context,
         }
@@ -646,17 +788,24 @@ where
                     .collect(),
                 found,
             }),
+            suggestion: None,
+            list_element: None,
+            related: Vec::new(),
             #[cfg(feature = "label")]
             context: Vec::new(),
         }
     }
 
     #[inline]
-    fn merge(self, other: Self) -> Self {
+    fn merge(mut self, other: Self) -> Self {
         let new_reason = self.reason.flat_merge(*other.reason);
+        self.related.extend(other.related);
         Self {
             span: self.span,
             reason: Box::new(new_reason),
+            suggestion: self.suggestion.or(other.suggestion),
+            list_element: self.list_element.or(other.list_element),
+            related: self.related,
             #[cfg(feature = "label")]
             context: self.context, // TOOD: Merge contexts
         }
@@ -741,10 +890,67 @@ where
                 });
             }
         }
+        self.suggestion = None;
+        self.list_element = None;
+        self.related.clear();
         #[cfg(feature = "label")]
         self.context.clear();
         self
     }
+
+    #[inline]
+    fn message(span: I::Span, message: String) -> Self {
+        Self::custom(span, message)
+    }
+
+    #[inline]
+    fn with_suggestion(self, suggestion: String) -> Self {
+        Self {
+            suggestion: Some(suggestion),
+            ..self
+        }
+    }
+
+    #[inline]
+    fn suggestion(&self) -> Option<&str> {
+        self.suggestion.as_deref()
+    }
+
+    #[inline]
+    fn with_related_span<F: FnOnce() -> String>(mut self, span: I::Span, message: F) -> Self {
+        self.related.push((span, message()));
+        self
+    }
+
+    #[inline]
+    fn in_list_element(self, index: usize) -> Self {
+        Self {
+            list_element: self.list_element.or(Some(index)),
+            ..self
+        }
+    }
+
+    #[inline]
+    fn trailing_separator(_found: Option<MaybeRef<'a, I::Token>>, span: I::Span) -> Self {
+        Self::custom(span, "unexpected trailing separator")
+    }
+}
+
+impl<'a, T, S, L> FromErrors for Rich<'a, T, S, L>
+where
+    S: fmt::Debug + Clone,
+{
+    /// The summary is a custom error of the form "3 errors, first at 2..5", pointing to the span of the first error.
+    fn from_errors(errors: Vec<Self>) -> Self {
+        let first = errors.first().expect("no errors to summarize");
+        let msg = format!(
+            "{} error{}, first at {:?}",
+            errors.len(),
+            if errors.len() == 1 { "" } else { "s" },
+            first.span,
+        );
+        Rich::custom(first.span.clone(), msg)
+    }
 }
 
 #[cfg(feature = "label")]
@@ -800,6 +1006,17 @@ where
     }
 }
 
+fn write_ordinal(f: &mut fmt::Formatter, n: usize) -> fmt::Result {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    write!(f, "{n}{suffix}")
+}
+
 fn write_token<T>(
     f: &mut fmt::Formatter,
     mut fmt_token: impl FnMut(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
@@ -2,6 +2,7 @@
 //! Useful for custom allocation, error handling, context-specific parsers, and more.
 
 use super::*;
+use core::hash::Hasher;
 
 type DefaultErr = EmptyErr;
 type DefaultState = ();
@@ -67,3 +68,193 @@ where
     type State = S;
     type Context = C;
 }
+
+/// An event produced by a parser marked with [`Parser::with_events`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Event<K, S> {
+    /// A marked parser of the given kind has started parsing.
+    Enter(K),
+    /// A marked parser of the given kind has successfully finished parsing the given span.
+    Exit(K, S),
+}
+
+/// A parser state type that can receive [`Event`]s from parsers marked with [`Parser::with_events`].
+///
+/// Events are recorded in order. When a marked parser fails, or a parser that produced events backtracks, those events
+/// are discarded via [`EventSink::forget`].
+pub trait EventSink<K, S> {
+    /// Record a new event.
+    fn event(&mut self, event: Event<K, S>);
+
+    /// Forget the `count` most recently recorded events, because the parser that produced them backtracked.
+    fn forget(&mut self, count: usize);
+}
+
+impl<K, S> EventSink<K, S> for Vec<Event<K, S>> {
+    fn event(&mut self, event: Event<K, S>) {
+        self.push(event);
+    }
+
+    fn forget(&mut self, count: usize) {
+        self.truncate(self.len().saturating_sub(count));
+    }
+}
+
+/// A parser state type that can receive trivia (comments, etc.) from parsers marked with
+/// [`Parser::collect_trivia_into_state`] or padded with [`Parser::padded_with_trivia`].
+#[cfg(feature = "trivia")]
+pub trait TriviaSink<S, T> {
+    /// Record a piece of trivia that was matched at the given span.
+    fn trivia(&mut self, span: S, text: T);
+
+    /// Forget the `count` most recently recorded pieces of trivia, because the parser that matched them backtracked.
+    fn forget(&mut self, count: usize);
+}
+
+#[cfg(feature = "trivia")]
+impl<S, T> TriviaSink<S, T> for Vec<(S, T)> {
+    fn trivia(&mut self, span: S, text: T) {
+        self.push((span, text));
+    }
+
+    fn forget(&mut self, count: usize) {
+        self.truncate(self.len().saturating_sub(count));
+    }
+}
+
+/// A stable identifier for a node marked with [`Parser::with_node_id`].
+///
+/// Ids are derived deterministically from the kinds and start positions of the node and all of its marked ancestors,
+/// so the same input always produces the same ids, and nodes whose position and ancestry are unaffected by an edit
+/// keep their ids across it. This makes them suitable as keys when diffing or incrementally updating syntax trees.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(u64);
+
+impl NodeId {
+    /// Get the raw value of this id.
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+/// A parser state type that assigns [`NodeId`]s to parsers marked with [`Parser::with_node_id`].
+///
+/// While a marked parser is running, its id is available from [`NodeIds::current`], most commonly from within
+/// [`Parser::map_with_state`].
+#[derive(Clone, Debug, Default)]
+pub struct NodeIds {
+    path: Vec<NodeId>,
+}
+
+impl NodeIds {
+    /// Create a new, empty set of node ids.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the id of the innermost marked node currently being parsed, if any.
+    pub fn current(&self) -> Option<NodeId> {
+        self.path.last().copied()
+    }
+
+    pub(crate) fn enter(&mut self, kind: u64, offset: usize) -> NodeId {
+        let mut hasher = Fnv(self.current().map_or(Fnv::BASIS, NodeId::as_u64));
+        hasher.write(&kind.to_le_bytes());
+        hasher.write(&(offset as u64).to_le_bytes());
+        let id = NodeId(hasher.finish());
+        self.path.push(id);
+        id
+    }
+
+    pub(crate) fn exit(&mut self) {
+        self.path.pop();
+    }
+}
+
+/// A parser state type that contains [`NodeIds`], allowing it to be used with [`Parser::with_node_id`].
+pub trait NodeIdSource {
+    /// Get the node ids contained within this state.
+    fn node_ids(&mut self) -> &mut NodeIds;
+}
+
+impl NodeIdSource for NodeIds {
+    fn node_ids(&mut self) -> &mut NodeIds {
+        self
+    }
+}
+
+/// A parser state type that remembers the outputs of parsers marked with [`Parser::cached_at_position`].
+///
+/// Each marked parser keeps only its most recent output, along with the span of input that produced it. Outputs are
+/// only valid for the input that they were produced from, so use a new cache for each parse.
+#[derive(Clone, Debug)]
+pub struct PositionCache<T, O> {
+    // The start offset, end offset, and output of each marked parser, by key
+    entries: HashMap<u64, (T, T, O)>,
+}
+
+impl<T, O> PositionCache<T, O> {
+    /// Create a new, empty cache.
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::default(),
+        }
+    }
+
+    pub(crate) fn get(&self, key: u64, start: T) -> Option<(T, &O)>
+    where
+        T: Copy + PartialEq,
+    {
+        match self.entries.get(&key) {
+            Some((entry_start, end, out)) if *entry_start == start => Some((*end, out)),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn insert(&mut self, key: u64, start: T, end: T, out: O) {
+        self.entries.insert(key, (start, end, out));
+    }
+}
+
+impl<T, O> core::default::Default for PositionCache<T, O> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A parser state type that contains a [`PositionCache`], allowing it to be used with [`Parser::cached_at_position`].
+pub trait PositionCacheSource<T, O> {
+    /// Get the position cache contained within this state.
+    fn position_cache(&mut self) -> &mut PositionCache<T, O>;
+}
+
+impl<T, O> PositionCacheSource<T, O> for PositionCache<T, O> {
+    fn position_cache(&mut self) -> &mut PositionCache<T, O> {
+        self
+    }
+}
+
+// FNV-1a, used because (unlike the standard library's hashers) its output is fixed and does not vary between runs
+pub(crate) struct Fnv(u64);
+
+impl Fnv {
+    pub(crate) const BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+    pub(crate) fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+        let mut hasher = Fnv(Self::BASIS);
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
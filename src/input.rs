@@ -106,6 +106,9 @@ pub trait Input<'a>: Sealed + 'a {
     /// an identifier that corresponds to the file the spans originated from.
     ///
     /// Returns spans containing your provided context as the Span::Context
+    ///
+    /// When parsing several files with the same parser, give each input its own context (e.g: a file id) and every
+    /// span seen by combinators such as [`Parser::map_with_span`] will carry it.
     fn with_context<S: Span>(self, context: S::Context) -> WithContext<S, Self>
     where
         Self: Sized,
@@ -162,6 +165,13 @@ pub trait SliceInput<'a>: ExactSizeInput<'a> {
 pub trait StrInput<'a, C: Char>:
     ValueInput<'a, Offset = usize, Token = C> + SliceInput<'a, Slice = &'a C::Str>
 {
+    /// The text that this input reads from, the offset of its first byte, and how much indentation this input already
+    /// strips from the start of each line. Used by [`primitive::dedented`] to build a view that shares its offsets.
+    #[doc(hidden)]
+    fn indented_source(&self) -> (&'a C::Str, usize, usize) {
+        let start = self.start();
+        (self.slice_from(start..), start, 0)
+    }
 }
 
 /// Implemented by inputs that can have tokens borrowed from them.
@@ -658,6 +668,10 @@ where
     S::Offset: From<<I::Span as Span>::Offset>,
     C: Char,
 {
+    #[inline(always)]
+    fn indented_source(&self) -> (&'a C::Str, usize, usize) {
+        self.input.indented_source()
+    }
 }
 
 /// An input wrapper that maps the span type of your input
@@ -775,6 +789,397 @@ where
     F: Fn(I::Span) -> S,
     C: Char,
 {
+    #[inline(always)]
+    fn indented_source(&self) -> (&'a C::Str, usize, usize) {
+        self.input.indented_source()
+    }
+}
+
+/// A view of an input that covers only the part of it matched by another parser. See [`Parser::reparse_slice`].
+///
+/// Offsets, spans and slices are those of the original input, so spans produced while parsing the view can be used
+/// directly alongside spans from the rest of the input.
+pub struct SliceView<'a, I: Input<'a>> {
+    pub(crate) input: I,
+    pub(crate) start: I::Offset,
+    pub(crate) end: I::Offset,
+}
+
+impl<'a, I: Input<'a> + Copy> Copy for SliceView<'a, I> {}
+impl<'a, I: Input<'a> + Clone> Clone for SliceView<'a, I> {
+    fn clone(&self) -> Self {
+        Self {
+            input: self.input.clone(),
+            start: self.start,
+            end: self.end,
+        }
+    }
+}
+
+impl<'a, I: Input<'a>> Sealed for SliceView<'a, I> {}
+impl<'a, I: Input<'a>> Input<'a> for SliceView<'a, I> {
+    type Offset = I::Offset;
+    type Token = I::Token;
+    type Span = I::Span;
+
+    #[inline(always)]
+    fn start(&self) -> Self::Offset {
+        self.start
+    }
+
+    type TokenMaybe = I::TokenMaybe;
+
+    #[inline(always)]
+    unsafe fn next_maybe(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::TokenMaybe>) {
+        if offset < self.end {
+            self.input.next_maybe(offset)
+        } else {
+            (offset, None)
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn span(&self, range: Range<Self::Offset>) -> Self::Span {
+        self.input.span(range)
+    }
+
+    #[inline(always)]
+    fn prev(offs: Self::Offset) -> Self::Offset {
+        I::prev(offs)
+    }
+}
+
+impl<'a, I: Input<'a>> ExactSizeInput<'a> for SliceView<'a, I> {
+    #[inline(always)]
+    unsafe fn span_from(&self, range: RangeFrom<Self::Offset>) -> Self::Span {
+        self.input.span(range.start..self.end)
+    }
+}
+
+impl<'a, I: ValueInput<'a>> ValueInput<'a> for SliceView<'a, I> {
+    #[inline(always)]
+    unsafe fn next(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::Token>) {
+        if offset < self.end {
+            self.input.next(offset)
+        } else {
+            (offset, None)
+        }
+    }
+}
+
+impl<'a, I: BorrowInput<'a>> BorrowInput<'a> for SliceView<'a, I> {
+    #[inline(always)]
+    unsafe fn next_ref(&self, offset: Self::Offset) -> (Self::Offset, Option<&'a Self::Token>) {
+        if offset < self.end {
+            self.input.next_ref(offset)
+        } else {
+            (offset, None)
+        }
+    }
+}
+
+impl<'a, I: SliceInput<'a>> SliceInput<'a> for SliceView<'a, I> {
+    type Slice = I::Slice;
+
+    #[inline(always)]
+    fn slice(&self, range: Range<Self::Offset>) -> Self::Slice {
+        self.input.slice(range)
+    }
+
+    #[inline(always)]
+    fn slice_from(&self, from: RangeFrom<Self::Offset>) -> Self::Slice {
+        self.input.slice(from.start..self.end)
+    }
+}
+
+impl<'a, C: Char, I: StrInput<'a, C>> StrInput<'a, C> for SliceView<'a, I> {
+    #[inline(always)]
+    fn indented_source(&self) -> (&'a C::Str, usize, usize) {
+        let (_, base, stripped) = self.input.indented_source();
+        (self.input.slice(base..self.end), base, stripped)
+    }
+}
+
+/// A view of a string input that strips a fixed amount of leading indentation from every line. See
+/// [`primitive::dedented`].
+///
+/// The view covers a block of lines: it ends just before the first non-blank line that is indented by fewer than
+/// `indent` spaces. Blank lines are always part of the block, even if they are indented by less.
+///
+/// Offsets, spans and slices are those of the original input. This means that spans can be used directly alongside
+/// spans from the rest of the input, but also that a slice spanning several lines will include their indentation.
+#[derive(Copy, Clone)]
+pub struct DedentedStr<'a> {
+    pub(crate) src: &'a str,
+    /// The offset of the first byte of `src`.
+    pub(crate) base: usize,
+    /// The indentation stripped from each line, including what the original input already stripped.
+    pub(crate) indent: usize,
+    /// The indentation that the original input already stripped from each line.
+    pub(crate) stripped: usize,
+}
+
+impl<'a> DedentedStr<'a> {
+    /// If `offset` is at the start of a line, skip that line's indentation (if the line is part of the block).
+    pub(crate) fn skip_indent(&self, offset: usize) -> usize {
+        self.line_start(offset)
+            .and_then(|start| self.enter_line(start))
+            .unwrap_or(offset)
+    }
+
+    /// If `offset` is at the start of a line, or just after the indentation that the original input already stripped
+    /// from it, find the offset of the start of that line.
+    fn line_start(&self, offset: usize) -> Option<usize> {
+        let before = &self.src.as_bytes()[..offset - self.base];
+        [0, self.stripped].into_iter().find_map(|stripped| {
+            let start = before.len().checked_sub(stripped)?;
+            let is_start = (start == 0 || before[start - 1] == b'\n')
+                && before[start..].iter().all(|b| *b == b' ');
+            is_start.then_some(self.base + start)
+        })
+    }
+
+    /// Given the offset of the start of a line, find the offset after its indentation, or `None` if the line is not
+    /// part of the block.
+    fn enter_line(&self, offset: usize) -> Option<usize> {
+        let rest = &self.src[offset - self.base..];
+        let spaces = rest.bytes().take_while(|b| *b == b' ').count();
+        if spaces >= self.indent {
+            Some(offset + self.indent)
+        } else if rest[spaces..].is_empty() || rest[spaces..].starts_with(['\r', '\n']) {
+            Some(offset + spaces)
+        } else {
+            None
+        }
+    }
+
+    fn end(&self, offset: usize) -> usize {
+        let mut offset = offset;
+        // SAFETY: `next` only ever returns offsets at character boundaries of the original string
+        while let (next, Some(_)) = unsafe { self.next(offset) } {
+            offset = next;
+        }
+        offset
+    }
+}
+
+impl<'a> Sealed for DedentedStr<'a> {}
+impl<'a> Input<'a> for DedentedStr<'a> {
+    type Offset = usize;
+    type Token = char;
+    type Span = SimpleSpan<usize>;
+
+    #[inline]
+    fn start(&self) -> Self::Offset {
+        self.skip_indent(self.base)
+    }
+
+    type TokenMaybe = char;
+
+    #[inline(always)]
+    unsafe fn next_maybe(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::TokenMaybe>) {
+        self.next(offset)
+    }
+
+    #[inline(always)]
+    unsafe fn span(&self, range: Range<Self::Offset>) -> Self::Span {
+        range.into()
+    }
+
+    #[inline(always)]
+    fn prev(offs: Self::Offset) -> Self::Offset {
+        offs.saturating_sub(1)
+    }
+}
+
+impl<'a> ExactSizeInput<'a> for DedentedStr<'a> {
+    #[inline(always)]
+    unsafe fn span_from(&self, range: RangeFrom<Self::Offset>) -> Self::Span {
+        (range.start..self.end(range.start)).into()
+    }
+}
+
+impl<'a> ValueInput<'a> for DedentedStr<'a> {
+    #[inline(always)]
+    unsafe fn next(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::Token>) {
+        let offset = match self.line_start(offset) {
+            Some(start) => match self.enter_line(start) {
+                Some(offset) => offset,
+                None => return (offset, None),
+            },
+            None => offset,
+        };
+        // SAFETY: Offsets produced by this input are always at character boundaries of the original string
+        match unsafe { self.src.next(offset - self.base) } {
+            (next, Some('\n')) => {
+                let next = self.base + next;
+                (self.enter_line(next).unwrap_or(next), Some('\n'))
+            }
+            (next, c) => (self.base + next, c),
+        }
+    }
+}
+
+impl<'a> StrInput<'a, char> for DedentedStr<'a> {
+    #[inline(always)]
+    fn indented_source(&self) -> (&'a str, usize, usize) {
+        (self.src, self.base, self.indent)
+    }
+}
+
+impl<'a> SliceInput<'a> for DedentedStr<'a> {
+    type Slice = &'a str;
+
+    #[inline(always)]
+    fn slice(&self, range: Range<Self::Offset>) -> Self::Slice {
+        &self.src[range.start - self.base..range.end - self.base]
+    }
+
+    #[inline(always)]
+    fn slice_from(&self, from: RangeFrom<Self::Offset>) -> Self::Slice {
+        &self.src[from.start - self.base..self.end(from.start) - self.base]
+    }
+}
+
+/// An input that reads from a ring buffer, wrapping around from the end of the buffer to its start.
+///
+/// The input starts at index `start` of the buffer and is `len` tokens long, so a record that straddles the end of the
+/// buffer can be parsed as if it were contiguous. Offsets and spans are relative to the start of the input, not to the
+/// start of the buffer.
+///
+/// Because the tokens of a slice of this input may wrap around the end of the buffer, slices are represented as a pair
+/// of buffer slices (in the same way as [`VecDeque::as_slices`](alloc::collections::VecDeque::as_slices)): the second
+/// is empty unless the slice wraps.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, input::WrappingInput};
+/// let buffer = *b"lo!..hel";
+/// let word = any::<_, extra::Err<Simple<u8>>>()
+///     .filter(u8::is_ascii_alphabetic)
+///     .repeated()
+///     .map_slice(|(head, tail): (&[u8], &[u8])| [head, tail].concat());
+///
+/// assert_eq!(
+///     word.then_ignore(just(b'!')).parse(WrappingInput::new(&buffer, 5, 6)).into_result(),
+///     Ok(b"hello".to_vec()),
+/// );
+/// ```
+pub struct WrappingInput<'a, T> {
+    buffer: &'a [T],
+    start: usize,
+    len: usize,
+}
+
+impl<'a, T> Copy for WrappingInput<'a, T> {}
+impl<'a, T> Clone for WrappingInput<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> WrappingInput<'a, T> {
+    /// Create a new input that reads `len` tokens from `buffer`, starting at index `start` and wrapping around to the
+    /// start of the buffer if necessary.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` is not an index within the buffer (unless the buffer and `len` are both empty), or if `len` is
+    /// larger than the buffer.
+    pub fn new(buffer: &'a [T], start: usize, len: usize) -> Self {
+        assert!(
+            start < buffer.len() || (start == 0 && len == 0),
+            "start index {} is out of bounds for a buffer of length {}",
+            start,
+            buffer.len(),
+        );
+        assert!(
+            len <= buffer.len(),
+            "input length {} is larger than the buffer length {}",
+            len,
+            buffer.len(),
+        );
+        Self { buffer, start, len }
+    }
+}
+
+impl<'a, T> Sealed for WrappingInput<'a, T> {}
+impl<'a, T> Input<'a> for WrappingInput<'a, T> {
+    type Offset = usize;
+    type Token = T;
+    type Span = SimpleSpan<usize>;
+
+    #[inline(always)]
+    fn start(&self) -> Self::Offset {
+        0
+    }
+
+    type TokenMaybe = &'a T;
+
+    #[inline(always)]
+    unsafe fn next_maybe(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::TokenMaybe>) {
+        self.next_ref(offset)
+    }
+
+    #[inline(always)]
+    unsafe fn span(&self, range: Range<Self::Offset>) -> Self::Span {
+        range.into()
+    }
+
+    #[inline(always)]
+    fn prev(offs: Self::Offset) -> Self::Offset {
+        offs.saturating_sub(1)
+    }
+}
+
+impl<'a, T> ExactSizeInput<'a> for WrappingInput<'a, T> {
+    #[inline(always)]
+    unsafe fn span_from(&self, range: RangeFrom<Self::Offset>) -> Self::Span {
+        (range.start..self.len).into()
+    }
+}
+
+impl<'a, T> SliceInput<'a> for WrappingInput<'a, T> {
+    type Slice = (&'a [T], &'a [T]);
+
+    #[inline(always)]
+    fn slice(&self, range: Range<Self::Offset>) -> Self::Slice {
+        let cap = self.buffer.len();
+        let (start, end) = (self.start + range.start, self.start + range.end);
+        if end <= cap {
+            (&self.buffer[start..end], &[])
+        } else if start >= cap {
+            (&self.buffer[start - cap..end - cap], &[])
+        } else {
+            (&self.buffer[start..], &self.buffer[..end - cap])
+        }
+    }
+
+    #[inline(always)]
+    fn slice_from(&self, from: RangeFrom<Self::Offset>) -> Self::Slice {
+        self.slice(from.start..self.len)
+    }
+}
+
+impl<'a, T: Clone> ValueInput<'a> for WrappingInput<'a, T> {
+    #[inline(always)]
+    unsafe fn next(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::Token>) {
+        let (offset, tok) = self.next_ref(offset);
+        (offset, tok.cloned())
+    }
+}
+
+impl<'a, T> BorrowInput<'a> for WrappingInput<'a, T> {
+    #[inline(always)]
+    unsafe fn next_ref(&self, offset: Self::Offset) -> (Self::Offset, Option<&'a Self::Token>) {
+        if offset < self.len {
+            let tok = &self.buffer[(self.start + offset) % self.buffer.len()];
+            (offset + 1, Some(tok))
+        } else {
+            (offset, None)
+        }
+    }
 }
 
 /// Represents a location in an input that can be rewound to.
@@ -783,6 +1188,9 @@ where
 pub struct Marker<'a, 'parse, I: Input<'a>> {
     pub(crate) offset: I::Offset,
     pub(crate) err_count: usize,
+    #[cfg(feature = "trivia")]
+    pub(crate) trivia_len: usize,
+    pub(crate) events_len: usize,
     phantom: PhantomData<fn(&'parse ()) -> &'parse ()>, // Invariance
 }
 
@@ -829,6 +1237,10 @@ impl<'a, 'parse, I: Input<'a>> PartialEq for Offset<'a, 'parse, I> {
 pub(crate) struct Errors<T, E> {
     pub(crate) alt: Option<Located<T, E>>,
     pub(crate) secondary: Vec<Located<T, E>>,
+    /// The furthest alternative error seen so far. See [`Parser::parse_with_furthest_error`].
+    pub(crate) furthest: Option<Located<T, E>>,
+    /// Used to copy the alternative error into `furthest`. `None` if the furthest error is not being tracked.
+    pub(crate) clone_furthest: Option<fn(&E) -> E>,
 }
 
 impl<T, E> Errors<T, E> {
@@ -837,6 +1249,35 @@ impl<T, E> Errors<T, E> {
     pub(crate) fn secondary_errors_since(&mut self, err_count: usize) -> &mut [Located<T, E>] {
         self.secondary.get_mut(err_count..).unwrap_or(&mut [])
     }
+
+    /// Update the furthest error, if it's being tracked and the current alternative error is at least as far along.
+    #[inline(always)]
+    fn update_furthest(&mut self)
+    where
+        T: Copy + Into<usize>,
+    {
+        // Tracking is rare, so keep the check small enough that it doesn't hinder inlining of the error paths
+        if self.clone_furthest.is_some() {
+            self.update_furthest_slow();
+        }
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn update_furthest_slow(&mut self)
+    where
+        T: Copy + Into<usize>,
+    {
+        if let (Some(clone), Some(alt)) = (self.clone_furthest, &self.alt) {
+            let is_further = match &self.furthest {
+                Some(furthest) => furthest.pos.into() <= alt.pos.into(),
+                None => true,
+            };
+            if is_further {
+                self.furthest = Some(Located::at(alt.pos, clone(&alt.err)));
+            }
+        }
+    }
 }
 
 impl<T, E> Default for Errors<T, E> {
@@ -844,17 +1285,171 @@ impl<T, E> Default for Errors<T, E> {
         Self {
             alt: None,
             secondary: Vec::new(),
+            furthest: None,
+            clone_furthest: None,
+        }
+    }
+}
+
+/// The number of things of one sort, such as trivia or events, recorded into the parser state so far. See
+/// [`Parser::collect_trivia_into_state`] and [`Parser::with_events`].
+pub(crate) struct StateLog<'a, S> {
+    pub(crate) len: usize,
+    /// Used to make the state forget things that were recorded by parsers that later backtracked. `None` if nothing
+    /// has been recorded.
+    forget: Option<fn(&mut S, usize)>,
+    /// The number of parsers marked with [`Parser::cached_at_position`] that are currently running. While there are
+    /// any, everything recorded is also kept in `recorded`, starting from the `recorded_start`th thing.
+    caching: usize,
+    recorded_start: usize,
+    recorded: Vec<Replay<'a, S>>,
+    /// What each cached parser recorded when its output was cached, by key, so that it can be recorded again when the
+    /// output is reused.
+    cached: HashMap<u64, Vec<Replay<'a, S>>>,
+}
+
+impl<S> Default for StateLog<'_, S> {
+    fn default() -> Self {
+        Self {
+            len: 0,
+            forget: None,
+            caching: 0,
+            recorded_start: 0,
+            recorded: Vec::new(),
+            cached: HashMap::default(),
+        }
+    }
+}
+
+impl<'a, S> StateLog<'a, S> {
+    /// Note that something was recorded into the state. `replay` is only called if a cached parser is running.
+    #[inline(always)]
+    fn push(
+        &mut self,
+        forget: fn(&mut S, usize),
+        replay: impl FnOnce() -> RefC<dyn Fn(&mut S) + 'a>,
+    ) {
+        if self.caching > 0 {
+            self.recorded.push(Replay {
+                record: replay(),
+                forget,
+            });
+        }
+        self.len += 1;
+        self.forget = Some(forget);
+    }
+
+    /// Make the state forget the things recorded since there were `len` of them.
+    fn forget(&mut self, state: &mut S, len: usize) {
+        if let Some(forget) = self.forget {
+            forget(state, self.len - len);
+        }
+        self.len = len;
+        if self.caching > 0 {
+            self.recorded
+                .truncate(len.saturating_sub(self.recorded_start));
+        }
+    }
+
+    fn begin_cached(&mut self) {
+        if self.caching == 0 {
+            self.recorded_start = self.len;
+        }
+        self.caching += 1;
+    }
+
+    /// Stop keeping what a cached parser that started when there were `start` things records, remembering it under
+    /// `key` if its output was cached.
+    fn end_cached(&mut self, start: usize, key: Option<u64>) {
+        if let Some(key) = key {
+            let recorded = self.recorded[start - self.recorded_start..].to_vec();
+            self.cached.insert(key, recorded);
+        }
+        self.caching -= 1;
+        if self.caching == 0 {
+            self.recorded.clear();
+        }
+    }
+
+    /// Record again whatever the cached parser with the given key recorded when its output was cached.
+    fn replay_cached(&mut self, state: &mut S, key: u64) {
+        if let Some(recorded) = self.cached.get(&key).cloned() {
+            for replay in recorded {
+                (replay.record)(state);
+                self.push(replay.forget, || replay.record.clone());
+            }
+        }
+    }
+}
+
+/// Something recorded into the parser state, kept so that it can be recorded again. See [`StateLog::replay_cached`].
+struct Replay<'a, S> {
+    record: RefC<dyn Fn(&mut S) + 'a>,
+    forget: fn(&mut S, usize),
+}
+
+impl<S> Clone for Replay<'_, S> {
+    fn clone(&self) -> Self {
+        Self {
+            record: self.record.clone(),
+            forget: self.forget,
         }
     }
 }
 
+/// The number of tokens that a parser may still pull from its input. See [`Parser::parse_with_budget`].
+#[cfg(feature = "budget")]
+pub(crate) struct Budget {
+    pub(crate) remaining: usize,
+    pub(crate) exceeded: bool,
+}
+
+#[cfg(feature = "budget")]
+impl Budget {
+    pub(crate) fn new(remaining: usize) -> Self {
+        Self {
+            remaining,
+            exceeded: false,
+        }
+    }
+
+    /// Attempt to spend a step of the budget, returning `false` if it has been exhausted.
+    #[inline(always)]
+    fn spend(&mut self) -> bool {
+        if self.remaining == 0 {
+            self.exceeded = true;
+            false
+        } else {
+            self.remaining -= 1;
+            true
+        }
+    }
+}
+
+#[cfg(feature = "budget")]
+impl Default for Budget {
+    fn default() -> Self {
+        Self::new(usize::MAX)
+    }
+}
+
 /// Internal type representing the owned parts of an input - used at the top level by a call to
 /// `parse`.
 pub(crate) struct InputOwn<'a, 's, I: Input<'a>, E: ParserExtra<'a, I>> {
     pub(crate) input: I,
     pub(crate) errors: Errors<I::Offset, E::Error>,
+    #[cfg(feature = "budget")]
+    pub(crate) budget: Budget,
+    /// `None` unless profiling with [`Parser::parse_profiled`].
+    #[cfg(feature = "profile")]
+    pub(crate) profile: Option<ProfileReport>,
     pub(crate) state: MaybeMut<'s, E::State>,
+    #[cfg(feature = "trivia")]
+    pub(crate) trivia: StateLog<'a, E::State>,
+    pub(crate) events: StateLog<'a, E::State>,
     pub(crate) ctx: E::Context,
+    /// Identifies this parse. See [`InputRef::parse_token`].
+    pub(crate) parse_token: Option<RefC<()>>,
     #[cfg(feature = "memoization")]
     pub(crate) memos: HashMap<(I::Offset, usize), Option<Located<I::Offset, E::Error>>>,
 }
@@ -873,8 +1468,16 @@ where
         InputOwn {
             input,
             errors: Errors::default(),
+            #[cfg(feature = "budget")]
+            budget: Budget::default(),
+            #[cfg(feature = "profile")]
+            profile: None,
             state: MaybeMut::Val(E::State::default()),
+            #[cfg(feature = "trivia")]
+            trivia: StateLog::default(),
+            events: StateLog::default(),
             ctx: E::Context::default(),
+            parse_token: None,
             #[cfg(feature = "memoization")]
             memos: HashMap::default(),
         }
@@ -887,8 +1490,16 @@ where
         InputOwn {
             input,
             errors: Errors::default(),
+            #[cfg(feature = "budget")]
+            budget: Budget::default(),
+            #[cfg(feature = "profile")]
+            profile: None,
             state: MaybeMut::Ref(state),
+            #[cfg(feature = "trivia")]
+            trivia: StateLog::default(),
+            events: StateLog::default(),
             ctx: E::Context::default(),
+            parse_token: None,
             #[cfg(feature = "memoization")]
             memos: HashMap::default(),
         }
@@ -899,14 +1510,21 @@ where
             offset: self.input.start(),
             input: &self.input,
             errors: &mut self.errors,
+            #[cfg(feature = "budget")]
+            budget: &mut self.budget,
+            #[cfg(feature = "profile")]
+            profile: &mut self.profile,
             state: &mut self.state,
+            #[cfg(feature = "trivia")]
+            trivia: &mut self.trivia,
+            events: &mut self.events,
             ctx: &self.ctx,
+            parse_token: &mut self.parse_token,
             #[cfg(feature = "memoization")]
             memos: &mut self.memos,
         }
     }
 
-    #[cfg(test)]
     pub(crate) fn as_ref_at<'parse>(
         &'parse mut self,
         offset: I::Offset,
@@ -915,8 +1533,16 @@ where
             offset,
             input: &self.input,
             errors: &mut self.errors,
+            #[cfg(feature = "budget")]
+            budget: &mut self.budget,
+            #[cfg(feature = "profile")]
+            profile: &mut self.profile,
             state: &mut self.state,
+            #[cfg(feature = "trivia")]
+            trivia: &mut self.trivia,
+            events: &mut self.events,
             ctx: &self.ctx,
+            parse_token: &mut self.parse_token,
             #[cfg(feature = "memoization")]
             memos: &mut self.memos,
         }
@@ -936,8 +1562,16 @@ pub struct InputRef<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> {
     pub(crate) offset: I::Offset,
     pub(crate) input: &'parse I,
     pub(crate) errors: &'parse mut Errors<I::Offset, E::Error>,
+    #[cfg(feature = "budget")]
+    pub(crate) budget: &'parse mut Budget,
+    #[cfg(feature = "profile")]
+    pub(crate) profile: &'parse mut Option<ProfileReport>,
     pub(crate) state: &'parse mut E::State,
+    #[cfg(feature = "trivia")]
+    pub(crate) trivia: &'parse mut StateLog<'a, E::State>,
+    pub(crate) events: &'parse mut StateLog<'a, E::State>,
     pub(crate) ctx: &'parse E::Context,
+    pub(crate) parse_token: &'parse mut Option<RefC<()>>,
     #[cfg(feature = "memoization")]
     pub(crate) memos: &'parse mut HashMap<(I::Offset, usize), Option<Located<I::Offset, E::Error>>>,
 }
@@ -957,8 +1591,16 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
             input: self.input,
             offset: self.offset,
             state: self.state,
+            #[cfg(feature = "trivia")]
+            trivia: self.trivia,
+            events: self.events,
             ctx: new_ctx,
+            parse_token: self.parse_token,
             errors: self.errors,
+            #[cfg(feature = "budget")]
+            budget: self.budget,
+            #[cfg(feature = "profile")]
+            profile: self.profile,
             #[cfg(feature = "memoization")]
             memos: self.memos,
         };
@@ -971,7 +1613,7 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     pub(crate) fn with_state<'sub_parse, S, O>(
         &'sub_parse mut self,
         new_state: &'sub_parse mut S,
-        f: impl FnOnce(&mut InputRef<'a, 'sub_parse, I, extra::Full<E::Error, S, E::Context>>) -> O,
+        f: impl FnOnce(&mut InputRef<'a, '_, I, extra::Full<E::Error, S, E::Context>>) -> O,
     ) -> O
     where
         'parse: 'sub_parse,
@@ -981,8 +1623,16 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
             input: self.input,
             offset: self.offset,
             state: new_state,
+            #[cfg(feature = "trivia")]
+            trivia: &mut StateLog::default(),
+            events: &mut StateLog::default(),
             ctx: self.ctx,
+            parse_token: self.parse_token,
             errors: self.errors,
+            #[cfg(feature = "budget")]
+            budget: self.budget,
+            #[cfg(feature = "profile")]
+            profile: self.profile,
             #[cfg(feature = "memoization")]
             memos: self.memos,
         };
@@ -1008,14 +1658,70 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
             offset: new_input.start(),
             input: new_input,
             state: self.state,
+            #[cfg(feature = "trivia")]
+            trivia: self.trivia,
+            events: self.events,
             ctx: self.ctx,
+            parse_token: self.parse_token,
             errors: self.errors,
+            #[cfg(feature = "budget")]
+            budget: self.budget,
+            #[cfg(feature = "profile")]
+            profile: self.profile,
             #[cfg(feature = "memoization")]
             memos,
         };
         f(&mut new_inp)
     }
 
+    /// Continue parsing from the current offset, but through a different view of the same input.
+    #[inline]
+    pub(crate) fn with_view<'sub_parse, J, O>(
+        &'sub_parse mut self,
+        view: &'sub_parse J,
+        f: impl FnOnce(
+            &mut InputRef<'a, 'sub_parse, J, extra::Full<E::Error, E::State, E::Context>>,
+        ) -> O,
+        #[cfg(feature = "memoization")] memos: &'sub_parse mut HashMap<
+            (I::Offset, usize),
+            Option<Located<I::Offset, E::Error>>,
+        >,
+    ) -> O
+    where
+        'parse: 'sub_parse,
+        J: Input<'a, Offset = I::Offset>,
+        E::Error: Error<'a, J>,
+    {
+        let mut new_inp = InputRef {
+            offset: self.offset,
+            input: view,
+            state: self.state,
+            #[cfg(feature = "trivia")]
+            trivia: self.trivia,
+            events: self.events,
+            ctx: self.ctx,
+            parse_token: self.parse_token,
+            errors: self.errors,
+            #[cfg(feature = "budget")]
+            budget: self.budget,
+            #[cfg(feature = "profile")]
+            profile: self.profile,
+            #[cfg(feature = "memoization")]
+            memos,
+        };
+        let res = f(&mut new_inp);
+        self.offset = new_inp.offset;
+        res
+    }
+
+    /// A token that identifies the current parse, for parsers that keep data between invocations that must not be
+    /// reused by later parses. It is only allocated when first requested, and a weak reference to it can be kept to
+    /// recognise the parse later without keeping the token alive.
+    #[inline]
+    pub(crate) fn parse_token(&mut self) -> &RefC<()> {
+        self.parse_token.get_or_insert_with(|| RefC::new(()))
+    }
+
     /// Get the internal offset of the input at this moment in time.
     ///
     /// Can be used for generating spans or slices. See [`InputRef::span`] and [`InputRef::slice`].
@@ -1035,6 +1741,9 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
         Marker {
             offset: self.offset,
             err_count: self.errors.secondary.len(),
+            #[cfg(feature = "trivia")]
+            trivia_len: self.trivia.len,
+            events_len: self.events.len,
             phantom: PhantomData,
         }
     }
@@ -1045,9 +1754,96 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     #[inline(always)]
     pub fn rewind(&mut self, marker: Marker<'a, 'parse, I>) {
         self.errors.secondary.truncate(marker.err_count);
+        #[cfg(feature = "trivia")]
+        if self.trivia.len > marker.trivia_len {
+            self.forget_trivia(marker.trivia_len);
+        }
+        if self.events.len > marker.events_len {
+            self.forget_events(marker.events_len);
+        }
         self.offset = marker.offset;
     }
 
+    #[cfg(feature = "trivia")]
+    #[cold]
+    #[inline(never)]
+    fn forget_trivia(&mut self, len: usize) {
+        self.trivia.forget(self.state, len);
+    }
+
+    /// Record the input consumed since `before` as a piece of trivia in the parser state.
+    ///
+    /// See [`Parser::collect_trivia_into_state`].
+    #[cfg(feature = "trivia")]
+    #[inline]
+    pub(crate) fn record_trivia(&mut self, before: Offset<'a, 'parse, I>)
+    where
+        I: SliceInput<'a>,
+        E::State: TriviaSink<I::Span, I::Slice>,
+        I::Span: Clone,
+        I::Slice: Clone,
+    {
+        let span = self.span_since(before);
+        let text = self.slice_inner(before.offset..self.offset);
+        self.trivia
+            .push(<E::State as TriviaSink<I::Span, I::Slice>>::forget, || {
+                let (span, text) = (span.clone(), text.clone());
+                RefC::new(move |state: &mut E::State| state.trivia(span.clone(), text.clone()))
+            });
+        self.state.trivia(span, text);
+    }
+
+    /// Make the parser state forget the events recorded since there were `len` of them.
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn forget_events(&mut self, len: usize) {
+        self.events.forget(self.state, len);
+    }
+
+    /// Record an event in the parser state.
+    ///
+    /// See [`Parser::with_events`].
+    #[inline]
+    pub(crate) fn record_event<K>(&mut self, event: Event<K, I::Span>)
+    where
+        E::State: EventSink<K, I::Span>,
+        I::Span: Clone,
+        K: Clone + 'a,
+    {
+        self.events
+            .push(<E::State as EventSink<K, I::Span>>::forget, || {
+                let event = event.clone();
+                RefC::new(move |state: &mut E::State| state.event(event.clone()))
+            });
+        self.state.event(event);
+    }
+
+    /// Start keeping everything recorded into the parser state, so that it can be replayed if the output of the
+    /// parser marked with [`Parser::cached_at_position`] that is about to run gets reused.
+    #[inline]
+    pub(crate) fn begin_cached(&mut self) {
+        #[cfg(feature = "trivia")]
+        self.trivia.begin_cached();
+        self.events.begin_cached();
+    }
+
+    /// Stop keeping what was recorded since `before` for the cached parser that started there, remembering it under
+    /// `key` if its output was cached.
+    #[inline]
+    pub(crate) fn end_cached(&mut self, before: Marker<'a, 'parse, I>, key: Option<u64>) {
+        #[cfg(feature = "trivia")]
+        self.trivia.end_cached(before.trivia_len, key);
+        self.events.end_cached(before.events_len, key);
+    }
+
+    /// Record again whatever the cached parser with the given key recorded when its output was cached.
+    #[inline]
+    pub(crate) fn replay_cached(&mut self, key: u64) {
+        #[cfg(feature = "trivia")]
+        self.trivia.replay_cached(self.state, key);
+        self.events.replay_cached(self.state, key);
+    }
+
     /// Get a mutable reference to the state associated with the current parse.
     #[inline(always)]
     pub fn state(&mut self) -> &mut E::State {
@@ -1070,6 +1866,10 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
         I: ValueInput<'a>,
     {
         loop {
+            #[cfg(feature = "budget")]
+            if !self.budget.spend() {
+                break;
+            }
             // SAFETY: offset was generated by previous call to `Input::next`
             let (offset, token) = unsafe { self.input.next(self.offset) };
             if token.filter(&mut f).is_none() {
@@ -1085,6 +1885,10 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     where
         I: ValueInput<'a>,
     {
+        #[cfg(feature = "budget")]
+        if !self.budget.spend() {
+            return (self.offset, None);
+        }
         // SAFETY: offset was generated by previous call to `Input::next`
         let (offset, token) = unsafe { self.input.next(self.offset) };
         self.offset = offset;
@@ -1093,6 +1897,10 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
 
     #[inline(always)]
     pub(crate) fn next_maybe_inner(&mut self) -> (I::Offset, Option<I::TokenMaybe>) {
+        #[cfg(feature = "budget")]
+        if !self.budget.spend() {
+            return (self.offset, None);
+        }
         // SAFETY: offset was generated by previous call to `Input::next`
         let (offset, token) = unsafe { self.input.next_maybe(self.offset) };
         self.offset = offset;
@@ -1104,6 +1912,10 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     where
         I: BorrowInput<'a>,
     {
+        #[cfg(feature = "budget")]
+        if !self.budget.spend() {
+            return (self.offset, None);
+        }
         // SAFETY: offset was generated by previous call to `Input::next`
         let (offset, token) = unsafe { self.input.next_ref(self.offset) };
         self.offset = offset;
@@ -1292,7 +2104,7 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     }
 
     #[inline(always)]
-    #[cfg(any(feature = "regex", feature = "lexical-numbers"))]
+    #[cfg(any(feature = "regex", feature = "lexical-numbers", not(feature = "budget")))]
     pub(crate) fn skip_bytes(&mut self, skip: usize)
     where
         I: SliceInput<'a, Offset = usize>,
@@ -1326,6 +2138,7 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
             },
             None => Located::at(at, Error::expected_found(expected, found, span)),
         });
+        self.errors.update_furthest();
     }
 
     #[inline]
@@ -1339,6 +2152,46 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
             },
             None => Located::at(at, err),
         });
+        self.errors.update_furthest();
+    }
+
+    /// Run `f` with no alternative error pending, returning the alternative error that it produced (if any) alongside
+    /// its result. The previously pending error is restored afterwards, so errors produced by `f` can be discarded
+    /// without affecting those of other branches, or kept with [`InputRef::add_located_alt`].
+    #[inline]
+    pub(crate) fn with_own_alt<R>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> (R, Option<Located<I::Offset, E::Error>>) {
+        let alt = self.errors.alt.take();
+        let res = f(self);
+        (res, core::mem::replace(&mut self.errors.alt, alt))
+    }
+
+    /// Add an alternative error previously returned by [`InputRef::with_own_alt`], prioritising it as usual.
+    #[inline]
+    pub(crate) fn add_located_alt(&mut self, alt: Option<Located<I::Offset, E::Error>>) {
+        if let Some(alt) = alt {
+            self.add_alt_err(alt.pos, alt.err);
+        }
+    }
+
+    /// Create an error reporting that a parser matched without consuming any input since `before`.
+    #[inline]
+    pub(crate) fn no_progress_err(&self, before: Offset<'a, 'parse, I>) -> E::Error {
+        E::Error::message(
+            self.span_since(before),
+            "parser made no progress".to_string(),
+        )
+    }
+
+    /// Report that a repeated parser matched without consuming any input since `before`. This is a bug in the
+    /// grammar, so the error replaces any alternatives tried so far.
+    #[inline]
+    pub(crate) fn add_no_progress_err(&mut self, before: Offset<'a, 'parse, I>) {
+        let err = self.no_progress_err(before);
+        self.errors.alt = None;
+        self.add_alt_err(self.offset, err);
     }
 }
 
@@ -48,14 +48,10 @@ where
 {
     #[inline]
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
-        let old_alt = inp.errors.alt.take();
         let before = inp.save();
-        let res = self.parser.go::<M>(inp);
+        let (res, new_alt) = inp.with_own_alt(|inp| self.parser.go::<M>(inp));
 
         // TODO: Label secondary errors too?
-        let new_alt = inp.errors.alt.take();
-        inp.errors.alt = old_alt;
-
         if let Some(mut new_alt) = new_alt {
             let before_next = before.offset.into() + 1;
             if new_alt.pos.into() == before_next {
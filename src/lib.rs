@@ -1,7 +1,6 @@
 #![cfg_attr(not(any(doc, feature = "std", test)), no_std)]
 #![cfg_attr(docsrs, feature(doc_auto_cfg, doc_cfg), deny(rustdoc::all))]
 #![cfg_attr(feature = "nightly", feature(never_type, rustc_attrs))]
-#![cfg_attr(feature = "nightly", feature(fn_traits, tuple_trait, unboxed_closures))]
 #![doc = include_str!("../README.md")]
 #![deny(missing_docs, clippy::undocumented_unsafe_blocks)]
 #![allow(
@@ -50,6 +49,8 @@ macro_rules! go_cfg_extra {
 mod blanket;
 pub mod combinator;
 pub mod container;
+#[cfg(feature = "rowan")]
+pub mod cst;
 #[cfg(feature = "either")]
 pub mod either;
 pub mod error;
@@ -65,6 +66,8 @@ pub mod label;
 pub mod number;
 pub mod primitive;
 mod private;
+#[cfg(feature = "profile")]
+pub mod profile;
 pub mod recovery;
 pub mod recursive;
 #[cfg(feature = "regex")]
@@ -87,19 +90,31 @@ pub mod prelude {
         error::{Cheap, EmptyErr, Error as _, Rich, Simple},
         extra,
         input::Input,
-        primitive::{any, choice, custom, empty, end, group, just, map_ctx, none_of, one_of, todo},
-        recovery::{nested_delimiters, skip_then_retry_until, skip_until, via_parser},
+        primitive::{
+            any, bracketed_list, build, choice, custom, dedented, dispatch, empty, end, group,
+            head_then_rest, in_range, just, longest_of, map_ctx, never, none_of, one_of, position,
+            todo, with_continuations,
+        },
+        recovery::{
+            nested_delimiters, recover_resume, skip_then_retry_until, skip_until,
+            skip_until_tagged, via_parser,
+        },
         recursive::{recursive, Recursive},
         span::{SimpleSpan, Span as _},
-        text, Boxed, ConfigIterParser, ConfigParser, IterParser, ParseResult, Parser,
+        text, ArcBoxed, Boxed, ConfigIterParser, ConfigParser, IterParser, ParseResult, Parser,
     };
-    pub use crate::{select, select_ref};
+    pub use crate::{alt, select, select_ref};
 }
 
 use crate::input::InputOwn;
-use alloc::{boxed::Box, rc::Rc, string::String, sync::Arc, vec, vec::Vec};
-#[cfg(feature = "nightly")]
-use core::marker::Tuple;
+use alloc::{
+    boxed::Box,
+    rc::Rc,
+    string::{String, ToString},
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
 use core::{
     borrow::Borrow,
     cell::{Cell, RefCell, UnsafeCell},
@@ -108,19 +123,26 @@ use core::{
     hash::Hash,
     marker::PhantomData,
     mem::MaybeUninit,
-    ops::{Range, RangeFrom},
+    ops::{Range, RangeBounds, RangeFrom},
     panic::Location,
     str::FromStr,
+    task::Poll,
 };
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
 
+#[cfg(feature = "budget")]
+use self::error::BudgetExceeded;
+#[cfg(feature = "trivia")]
+use self::extra::TriviaSink;
 #[cfg(feature = "label")]
 use self::label::{LabelError, Labelled};
+#[cfg(feature = "profile")]
+use self::profile::{CoverageReport, ProfileReport};
 use self::{
     combinator::*,
     container::*,
-    error::Error,
-    extra::ParserExtra,
+    error::{Error, FromErrors},
+    extra::{Event, EventSink, NodeIdSource, ParserExtra, PositionCacheSource},
     input::{BorrowInput, Emitter, ExactSizeInput, InputRef, SliceInput, StrInput, ValueInput},
     prelude::*,
     primitive::Any,
@@ -128,10 +150,10 @@ use self::{
         Check, ConfigIterParserSealed, ConfigParserSealed, Emit, IPResult, IterParserSealed,
         Located, MaybeUninitExt, Mode, PResult, ParserSealed, Sealed,
     },
-    recovery::{RecoverWith, Strategy},
+    recovery::{RecoverWith, Strategy, Tolerant},
     span::Span,
     text::*,
-    util::{MaybeMut, MaybeRef},
+    util::{Deferred, MaybeMut, MaybeRef},
 };
 #[cfg(all(feature = "extension", doc))]
 use self::{extension::v1::*, primitive::custom, stream::Stream};
@@ -168,6 +190,20 @@ mod sync {
     pub(crate) type RefW<T> = alloc::sync::Weak<T>;
     pub(crate) type DynParser<'a, 'b, I, O, E> = dyn Parser<'a, I, O, E> + Send + Sync + 'b;
 
+    /// Interior mutability for data that parsers share between their clones.
+    pub(crate) struct RefLock<T>(spin::Mutex<T>);
+
+    impl<T> RefLock<T> {
+        pub(crate) fn new(x: T) -> Self {
+            Self(spin::Mutex::new(x))
+        }
+
+        /// Access the data. `f` must not try to access it again.
+        pub(crate) fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+            f(&mut self.0.lock())
+        }
+    }
+
     /// A trait that requires either nothing or `Send` and `Sync` bounds depending on whether the `sync` feature is
     /// enabled. Used to constrain API usage succinctly and easily.
     pub trait MaybeSync: Send + Sync {}
@@ -182,13 +218,27 @@ mod sync {
     pub(crate) type RefW<T> = alloc::rc::Weak<T>;
     pub(crate) type DynParser<'a, 'b, I, O, E> = dyn Parser<'a, I, O, E> + 'b;
 
+    /// Interior mutability for data that parsers share between their clones.
+    pub(crate) struct RefLock<T>(RefCell<T>);
+
+    impl<T> RefLock<T> {
+        pub(crate) fn new(x: T) -> Self {
+            Self(RefCell::new(x))
+        }
+
+        /// Access the data. `f` must not try to access it again.
+        pub(crate) fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+            f(&mut self.0.borrow_mut())
+        }
+    }
+
     /// A trait that requires either nothing or `Send` and `Sync` bounds depending on whether the `sync` feature is
     /// enabled. Used to constrain API usage succinctly and easily.
     pub trait MaybeSync {}
     impl<T> MaybeSync for T {}
 }
 
-use sync::{DynParser, MaybeSync, RefC, RefW};
+use sync::{DynParser, MaybeSync, RefC, RefLock, RefW};
 
 /// The result of running a [`Parser`]. Can be converted into a [`Result`] via
 /// [`ParseResult::into_result`] for when you only care about success or failure, or into distinct
@@ -197,11 +247,16 @@ use sync::{DynParser, MaybeSync, RefC, RefW};
 pub struct ParseResult<T, E> {
     output: Option<T>,
     errs: Vec<E>,
+    furthest: Option<E>,
 }
 
 impl<T, E> ParseResult<T, E> {
     pub(crate) fn new(output: Option<T>, errs: Vec<E>) -> ParseResult<T, E> {
-        ParseResult { output, errs }
+        ParseResult {
+            output,
+            errs,
+            furthest: None,
+        }
     }
 
     /// Whether this result contains output
@@ -224,6 +279,13 @@ impl<T, E> ParseResult<T, E> {
         self.errs.iter()
     }
 
+    /// Get the furthest error encountered during parsing, including errors from alternatives that were abandoned by
+    /// backtracking. This is only tracked when parsing with [`Parser::parse_with_furthest_error`], and will be `None`
+    /// otherwise.
+    pub fn furthest_error(&self) -> Option<&E> {
+        self.furthest.as_ref()
+    }
+
     /// Convert this `ParseResult` into an option containing the output, if any exists
     pub fn into_output(self) -> Option<T> {
         self.output
@@ -251,6 +313,40 @@ impl<T, E> ParseResult<T, E> {
         }
     }
 
+    /// Replace the errors of this result with a single error summarising all of them, via [`FromErrors`].
+    ///
+    /// This is useful for top-level reporting, where only the fact that errors occurred (and where the first of them
+    /// is) matters. If there are no errors, this result is returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let digits = one_of::<_, _, extra::Err<Rich<char>>>('0'..='9')
+    ///     .recover_with(via_parser(any().to('0')))
+    ///     .repeated()
+    ///     .collect::<String>();
+    ///
+    /// let res = digits.parse("1a2b").summarize_errors();
+    /// assert_eq!(res.output(), Some(&"1020".to_string()));
+    /// assert_eq!(res.errors().map(|e| e.to_string()).collect::<Vec<_>>(), vec!["2 errors, first at 1..2"]);
+    /// ```
+    pub fn summarize_errors(self) -> ParseResult<T, E>
+    where
+        E: FromErrors,
+    {
+        let errs = if self.errs.is_empty() {
+            self.errs
+        } else {
+            vec![E::from_errors(self.errs)]
+        };
+        ParseResult {
+            output: self.output,
+            errs,
+            furthest: self.furthest,
+        }
+    }
+
     /// If the parse succeeded (i.e: no errors were produced), this function returns the output value, `T`.
     ///
     /// If parsing generated errors, this function panics (even if these errors were non-fatal).
@@ -357,6 +453,94 @@ pub trait Parser<'a, I: Input<'a>, O, E: ParserExtra<'a, I> = extra::Default>:
         ParseResult::new(out, errs)
     }
 
+    /// Parse a stream of tokens like [`Parser::parse`], but also keep track of the furthest error encountered along the
+    /// way, even if it belonged to an alternative that was later abandoned and parsing succeeded overall.
+    ///
+    /// The furthest error is available through [`ParseResult::furthest_error`]. This is useful for tools like linters
+    /// that want to offer suggestions based on what the parser almost accepted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let kw = text::ascii::keyword::<_, _, _, extra::Err<Rich<char>>>("let")
+    ///     .then(just(' ').then(text::ascii::ident()))
+    ///     .ignored()
+    ///     .or(text::ascii::ident().ignored());
+    ///
+    /// let res = kw.parse_with_furthest_error("let");
+    /// assert!(!res.has_errors());
+    /// assert_eq!(res.furthest_error().map(|e| e.span().start), Some(3));
+    /// ```
+    fn parse_with_furthest_error(&self, input: I) -> ParseResult<O, E::Error>
+    where
+        Self: Sized,
+        I: Input<'a>,
+        E::Error: Clone,
+        E::State: Default,
+        E::Context: Default,
+    {
+        let mut own = InputOwn::new(input);
+        own.errors.clone_furthest = Some(E::Error::clone);
+        let mut inp = own.as_ref_start();
+        let res = self.then_ignore(end()).go::<Emit>(&mut inp);
+        let alt = inp.errors.alt.take();
+        let furthest = own.errors.furthest.take().map(|furthest| furthest.err);
+        let mut errs = own.into_errs();
+        let out = match res {
+            Ok(out) => Some(out),
+            Err(()) => {
+                errs.push(alt.expect("error but no alt?").err);
+                None
+            }
+        };
+        ParseResult {
+            furthest,
+            ..ParseResult::new(out, errs)
+        }
+    }
+
+    /// Parse a stream of tokens, yielding an output if possible and appending any errors encountered along the way to
+    /// the given [`Vec`].
+    ///
+    /// This is useful when errors from several parses are gathered into a single collection, such as by a diagnostics
+    /// pipeline. Errors already in the [`Vec`] are left untouched.
+    ///
+    /// If `None` is returned (i.e: parsing failed) then there will *always* be at least one error appended.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let num = text::int::<_, _, extra::Err<Simple<char>>>(10);
+    ///
+    /// let mut errs = Vec::new();
+    /// assert_eq!(num.parse_into_errors("42", &mut errs), Some("42"));
+    /// assert_eq!(num.parse_into_errors("x", &mut errs), None);
+    /// assert_eq!(num.parse_into_errors("y", &mut errs), None);
+    /// assert_eq!(errs.len(), 2);
+    /// ```
+    fn parse_into_errors(&self, input: I, errors: &mut Vec<E::Error>) -> Option<O>
+    where
+        Self: Sized,
+        I: Input<'a>,
+        E::State: Default,
+        E::Context: Default,
+    {
+        let mut own = InputOwn::new(input);
+        let mut inp = own.as_ref_start();
+        let res = self.then_ignore(end()).go::<Emit>(&mut inp);
+        let alt = inp.errors.alt.take();
+        errors.extend(own.errors.secondary.into_iter().map(|err| err.err));
+        match res {
+            Ok(out) => Some(out),
+            Err(()) => {
+                errors.push(alt.expect("error but no alt?").err);
+                None
+            }
+        }
+    }
+
     /// Parse a stream of tokens, ignoring any output, and returning any errors encountered along the way.
     ///
     /// If parsing failed, then there will *always* be at least one item in the returned `Vec`.
@@ -402,1207 +586,1396 @@ pub trait Parser<'a, I: Input<'a>, O, E: ParserExtra<'a, I> = extra::Default>:
         ParseResult::new(out, errs)
     }
 
-    /// Map from a slice of the input based on the current parser's span to a value.
+    /// Parse a stream of tokens like [`Parser::parse`], but abort if the parser does more than a bounded amount of
+    /// work.
     ///
-    /// The returned value may borrow data from the input slice, making this function very useful
-    /// for creating zero-copy AST output values
-    fn map_slice<U, F: Fn(I::Slice) -> U>(self, f: F) -> MapSlice<'a, Self, I, O, E, F, U>
+    /// Every token pulled from the input counts as one step, including tokens that are pulled again after the parser
+    /// backtracks. If parsing would require more than `max_steps` steps, it is aborted and [`BudgetExceeded`] is
+    /// returned instead of a [`ParseResult`]. This makes it possible to safely parse untrusted input with grammars that
+    /// might otherwise backtrack catastrophically.
+    ///
+    /// Parsers that skip over input without pulling individual tokens (such as `regex`) spend no steps when doing so.
+    /// Once the budget is exhausted, every further attempt to pull a token behaves as though the end of input had been
+    /// reached so that the parse unwinds quickly; the result of such a parse is discarded.
+    ///
+    /// This requires the `budget` feature. Without it, the budget checks are not compiled into the parser at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let word = text::ascii::ident::<_, _, extra::Err<Simple<char>>>();
+    ///
+    /// assert_eq!(word.parse_with_budget("hello", 100).map(|r| r.into_result()), Ok(Ok("hello")));
+    /// assert!(word.parse_with_budget("hello", 3).is_err());
+    /// ```
+    #[cfg(feature = "budget")]
+    fn parse_with_budget(
+        &self,
+        input: I,
+        max_steps: usize,
+    ) -> Result<ParseResult<O, E::Error>, BudgetExceeded>
     where
         Self: Sized,
-        I: SliceInput<'a>,
+        I: Input<'a>,
+        E::State: Default,
+        E::Context: Default,
     {
-        MapSlice {
-            parser: self,
-            mapper: f,
-            phantom: EmptyPhantom::new(),
+        let mut state = E::State::default();
+        let mut own = InputOwn::new_state(input, &mut state);
+        own.budget = input::Budget::new(max_steps);
+        let mut inp = own.as_ref_start();
+        let res = self.then_ignore(end()).go::<Emit>(&mut inp);
+        let alt = inp.errors.alt.take();
+        if own.budget.exceeded {
+            return Err(BudgetExceeded(()));
         }
+        let mut errs = own.into_errs();
+        let out = match res {
+            Ok(out) => Some(out),
+            Err(()) => {
+                errs.push(alt.expect("error but no alt?").err);
+                None
+            }
+        };
+        Ok(ParseResult::new(out, errs))
     }
 
-    /// Convert the output of this parser into a slice of the input, based on the current parser's
-    /// span.
+    /// Parse a stream of independent records, each followed by `delimiter` (or the end of input), producing an
+    /// iterator over the result of parsing each record along with its span.
     ///
-    /// This is effectively a special case of [`map_slice`](Parser::map_slice)`(|x| x)`
-    fn slice(self) -> Slice<Self, O>
+    /// Each record is parsed separately: if a record fails to parse, the iterator yields its errors and then
+    /// resynchronises by skipping input until the next `delimiter`, so that subsequent records are unaffected. A record
+    /// that parsed successfully but emitted errors (for example, via [`Parser::recover_with`]) is also reported as
+    /// failed. The span of a record does not include its delimiter. If neither a record nor the delimiter after it
+    /// consume any input, the record is reported as failed and extended to cover the next token, so that the iterator
+    /// always makes progress.
+    ///
+    /// This is useful for inputs like log files, which are a long sequence of unrelated entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let pair = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .then_ignore(just('='))
+    ///     .then(text::int(10));
+    ///
+    /// let results = pair
+    ///     .iter_matches("a=1;b=?;c=3", just(';'))
+    ///     .map(|(res, span)| (res.is_ok(), span.into_range()))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(results, vec![(true, 0..3), (false, 4..7), (true, 8..11)]);
+    /// ```
+    fn iter_matches<D, OD>(
+        &self,
+        input: I,
+        delimiter: D,
+    ) -> IterMatches<'a, '_, Self, D, I, O, OD, E>
     where
         Self: Sized,
+        I: Input<'a>,
+        D: Parser<'a, I, OD, E>,
+        E::State: Default,
+        E::Context: Default,
     {
-        Slice {
+        IterMatches {
             parser: self,
+            delimiter,
+            offset: input.start(),
+            own: InputOwn::new(input),
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Filter the output of this parser, accepting only inputs that match the given predicate.
+    /// Parse a stream of tokens, yielding an output if possible along with a [`ProfileReport`] describing how often
+    /// each parser marked with [`Parser::profiled`] ran.
     ///
-    /// The output type of this parser is `I`, the input that was found.
+    /// This is intended for finding hot spots (such as excessive backtracking) in large grammars.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let lowercase = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(char::is_ascii_lowercase)
-    ///     .repeated()
-    ///     .at_least(1)
-    ///     .collect::<String>();
-    ///
-    /// assert_eq!(lowercase.parse("hello").into_result(), Ok("hello".to_string()));
-    /// assert!(lowercase.parse("Hello").has_errors());
+    /// # use chumsky::prelude::*;
+    /// let digit = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(char::is_ascii_digit)
+    ///     .profiled("digit");
+    /// let number = digit.repeated().at_least(1).collect::<String>();
+    ///
+    /// let (res, report) = number.parse_profiled("123");
+    /// assert_eq!(res.into_result(), Ok("123".to_string()));
+    /// // The digit parser runs a fourth time, failing at the end of the input
+    /// assert_eq!(report.get("digit").unwrap().invocations(), 4);
+    /// assert_eq!(report.get("digit").unwrap().successes(), 3);
     /// ```
-    fn filter<F: Fn(&O) -> bool>(self, f: F) -> Filter<Self, F>
+    #[cfg(feature = "profile")]
+    fn parse_profiled(&self, input: I) -> (ParseResult<O, E::Error>, ProfileReport)
     where
         Self: Sized,
+        I: Input<'a>,
+        E::State: Default,
+        E::Context: Default,
     {
-        Filter {
-            parser: self,
-            filter: f,
-        }
+        let mut own = InputOwn::new(input);
+        own.profile = Some(ProfileReport::default());
+        let mut inp = own.as_ref_start();
+        let res = self.then_ignore(end()).go::<Emit>(&mut inp);
+        let alt = inp.errors.alt.take();
+        let report = own.profile.take().unwrap_or_default();
+        let mut errs = own.into_errs();
+        let out = match res {
+            Ok(out) => Some(out),
+            Err(()) => {
+                errs.push(alt.expect("error but no alt?").err);
+                None
+            }
+        };
+        (ParseResult::new(out, errs), report)
     }
 
-    /// Map the output of this parser to another value.
+    /// Parse a stream of tokens like [`Parser::parse`], recording which parsers marked with [`Parser::profiled`]
+    /// matched into the given [`CoverageReport`].
     ///
-    /// The output type of this parser is `U`, the same as the function's output.
+    /// Reusing the same report across a corpus of inputs shows which parts of a grammar the corpus exercises, which
+    /// is useful when writing tests or seeding fuzzers.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// #[derive(Debug, PartialEq)]
-    /// enum Token { Word(String), Num(u64) }
+    /// # use chumsky::{prelude::*, profile::CoverageReport};
+    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10).profiled("int");
+    /// let ident = text::ascii::ident().profiled("ident");
+    /// let atom = int.or(ident);
+    ///
+    /// let mut coverage = CoverageReport::default();
+    /// for input in ["1", "42"] {
+    ///     assert!(!atom.parse_with_coverage(input, &mut coverage).has_errors());
+    /// }
+    /// assert!(coverage.is_covered("int"));
+    /// assert!(!coverage.is_covered("ident"));
+    /// ```
+    #[cfg(feature = "profile")]
+    fn parse_with_coverage(
+        &self,
+        input: I,
+        coverage: &mut CoverageReport,
+    ) -> ParseResult<O, E::Error>
+    where
+        Self: Sized,
+        I: Input<'a>,
+        E::State: Default,
+        E::Context: Default,
+    {
+        let (res, report) = self.parse_profiled(input);
+        coverage.add(&report);
+        res
+    }
+
+    /// Mark this parser with a name so that the number of times it runs, and how much input it pulls, is recorded
+    /// when parsing with [`Parser::parse_profiled`].
     ///
-    /// let word = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(|c: &char| c.is_alphabetic())
-    ///     .repeated().at_least(1)
-    ///     .collect::<String>()
-    ///     .map(Token::Word);
+    /// Marked parsers have no effect when parsing by any other means: no statistics are gathered for them, and they
+    /// behave exactly like the original parser.
     ///
-    /// let num = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(|c: &char| c.is_ascii_digit())
-    ///     .repeated().at_least(1)
-    ///     .collect::<String>()
-    ///     .map(|s| Token::Num(s.parse().unwrap()));
+    /// The output type of this parser is `O`, the same as the original parser.
     ///
-    /// let token = word.or(num);
+    /// # Examples
     ///
-    /// assert_eq!(token.parse("test").into_result(), Ok(Token::Word("test".to_string())));
-    /// assert_eq!(token.parse("42").into_result(), Ok(Token::Num(42)));
     /// ```
-    fn map<U, F: Fn(O) -> U>(self, f: F) -> Map<Self, O, F>
+    /// # use chumsky::prelude::*;
+    /// let word = text::ascii::ident::<_, _, extra::Err<Simple<char>>>().profiled("word");
+    /// let words = word.padded().repeated().collect::<Vec<_>>();
+    ///
+    /// let (res, report) = words.parse_profiled("the quick fox");
+    /// assert_eq!(res.into_result(), Ok(vec!["the", "quick", "fox"]));
+    /// // The word parser also runs a fourth time, failing at the end of the input
+    /// assert_eq!(report.get("word").unwrap().invocations(), 4);
+    /// assert_eq!(report.get("word").unwrap().successes(), 3);
+    ///
+    /// // Parsing normally behaves exactly as it would without the marker
+    /// assert_eq!(words.parse("the quick fox").into_result(), Ok(vec!["the", "quick", "fox"]));
+    /// ```
+    #[cfg(feature = "profile")]
+    fn profiled(self, name: &'static str) -> Profiled<Self>
     where
         Self: Sized,
     {
-        Map {
+        Profiled { parser: self, name }
+    }
+
+    /// Map from a slice of the input based on the current parser's span to a value.
+    ///
+    /// The returned value may borrow data from the input slice, making this function very useful
+    /// for creating zero-copy AST output values
+    fn map_slice<U, F: Fn(I::Slice) -> U>(self, f: F) -> MapSlice<'a, Self, I, O, E, F, U>
+    where
+        Self: Sized,
+        I: SliceInput<'a>,
+    {
+        MapSlice {
             parser: self,
             mapper: f,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Map the output of this parser to another value.
-    /// If the output of this parser isn't a tuple, use [`Parser::map`].
+    /// Convert the output of this parser into a slice of the input, based on the current parser's
+    /// span.
     ///
-    /// The output type of this parser is `U`, the same as the function's output.
+    /// This is effectively a special case of [`map_slice`](Parser::map_slice)`(|x| x)`
+    fn slice(self) -> Slice<Self, O>
+    where
+        Self: Sized,
+    {
+        Slice {
+            parser: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Convert the output of this parser into both a slice of the input and the span it covers.
+    ///
+    /// This is useful for building tokens that need both their source text and their location.
+    ///
+    /// The output type of this parser is `(I::Slice, I::Span)`.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::prelude::*;
-    /// #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-    ///  pub enum Value {
-    ///       One(u8),
-    ///      Two(u8, u8),
-    ///      Three(u8, u8, u8),
-    /// }
-    ///
-    /// fn parser<'a>() -> impl Parser<'a, &'a [u8], Vec<Value>> {
-    ///     choice((
-    ///         just(1).ignore_then(any()).map(Value::One),
-    ///         just(2)
-    ///             .ignore_then(group((any(), any())))
-    ///             .map_group(Value::Two),
-    ///         just(3)
-    ///             .ignore_then(group((any(), any(), any())))
-    ///             .map_group(Value::Three),
-    ///     ))
-    ///     .repeated()
-    ///     .collect()
-    /// }
+    /// let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .slice_and_span()
+    ///     .padded();
     ///
-    /// let bytes = &[3, 1, 2, 3, 1, 127, 2, 21, 69];
-    /// assert_eq!(
-    ///     parser().parse(bytes).into_result(),
-    ///     Ok(vec![
-    ///         Value::Three(1, 2, 3),
-    ///         Value::One(127),
-    ///         Value::Two(21, 69)
-    ///     ])
-    /// );
+    /// assert_eq!(ident.parse(" foo ").into_result(), Ok(("foo", SimpleSpan::new(1, 4))));
     /// ```
-    #[cfg(feature = "nightly")]
-    fn map_group<F: Fn<O>>(self, f: F) -> MapGroup<Self, O, F>
+    fn slice_and_span(self) -> SliceAndSpan<Self, O>
     where
         Self: Sized,
-        O: Tuple,
+        I: SliceInput<'a>,
     {
-        MapGroup {
+        SliceAndSpan {
             parser: self,
-            mapper: f,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Map the output of this parser to another value, making use of the pattern's span when doing so.
+    /// Map from the slice of the input matched by this parser and its span to a value, discarding the parser's
+    /// original output.
     ///
-    /// This is very useful when generating an AST that attaches a span to each AST node.
+    /// This is a convenient way to build tokens that need both their source text and their location. Like
+    /// [`Parser::map_slice`], the original parser is run without producing any output.
     ///
-    /// The output type of this parser is `U`, the same as the function's output.
+    /// The output type of this parser is `U`, the result of the given function.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::prelude::*;
-    ///
-    /// // It's common for AST nodes to use a wrapper type that allows attaching span information to them
     /// #[derive(Debug, PartialEq)]
-    /// pub struct Spanned<T>(T, SimpleSpan<usize>);
+    /// struct Token<'a> { text: &'a str, span: SimpleSpan }
     ///
     /// let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
-    ///     .map_with_span(Spanned) // Equivalent to `.map_with_span(|ident, span| Spanned(ident, span))`
+    ///     .to_token(|text, span| Token { text, span })
     ///     .padded();
     ///
-    /// assert_eq!(ident.parse("hello").into_result(), Ok(Spanned("hello", (0..5).into())));
-    /// assert_eq!(ident.parse("       hello   ").into_result(), Ok(Spanned("hello", (7..12).into())));
+    /// assert_eq!(
+    ///     ident.parse(" foo ").into_result(),
+    ///     Ok(Token { text: "foo", span: SimpleSpan::new(1, 4) }),
+    /// );
     /// ```
-    fn map_with_span<U, F: Fn(O, I::Span) -> U>(self, f: F) -> MapWithSpan<Self, O, F>
+    fn to_token<U, F: Fn(I::Slice, I::Span) -> U>(self, f: F) -> ToToken<Self, O, F>
     where
         Self: Sized,
+        I: SliceInput<'a>,
     {
-        MapWithSpan {
+        ToToken {
             parser: self,
             mapper: f,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Transform the output of this parser to the pattern's span.
+    /// Convert the output of this parser into a [`&str`] slice of a byte input, validating that it is UTF-8.
     ///
-    /// This is commonly used when you know what pattern you've parsed and are only interested in the span of the
-    /// pattern.
+    /// This is useful for parsing binary formats that contain regions of text. If the slice is not valid UTF-8, an
+    /// error is generated with the span of the invalid bytes.
     ///
-    /// The output type of this parser is `I::Span`.
+    /// The output type of this parser is `&'a str`.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::prelude::*;
+    /// let text = any::<&[u8], extra::Err<Simple<u8>>>()
+    ///     .filter(|b| *b != 0)
+    ///     .repeated()
+    ///     .map_str()
+    ///     .then_ignore(just(0));
     ///
-    /// // It's common for AST nodes to use a wrapper type that allows attaching span information to them
-    /// #[derive(Debug, PartialEq)]
-    /// pub enum Expr<'a> {
-    ///     Int(&'a str, SimpleSpan),
-    ///     // The span is that of the operator, '+'
-    ///     Add(Box<Expr<'a>>, SimpleSpan, Box<Expr<'a>>),
-    /// }
-    ///
-    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
-    ///     .slice()
-    ///     .map_with_span(Expr::Int)
-    ///     .padded();
-    ///
-    /// let add_op = just('+').to_span().padded();
-    /// let sum = int.foldl(
-    ///     add_op.then(int).repeated(),
-    ///     |a, (op_span, b)| Expr::Add(Box::new(a), op_span, Box::new(b)),
-    /// );
-    ///
-    /// assert_eq!(sum.parse("42 + 7 + 13").into_result(), Ok(Expr::Add(
-    ///     Box::new(Expr::Add(
-    ///         Box::new(Expr::Int("42", (0..2).into())),
-    ///         (3..4).into(),
-    ///         Box::new(Expr::Int("7", (5..6).into())),
-    ///     )),
-    ///     (7..8).into(),
-    ///     Box::new(Expr::Int("13", (9..11).into())),
-    /// )));
+    /// assert_eq!(text.parse(b"hello\0").into_result(), Ok("hello"));
+    /// // Invalid UTF-8 produces an error, rather than a panic
+    /// assert!(text.parse(b"\xffoops\0").has_errors());
     /// ```
-    fn to_span(self) -> ToSpan<Self, O>
+    fn map_str(self) -> MapStr<Self, O>
     where
         Self: Sized,
+        I: StrInput<'a, u8>,
     {
-        ToSpan {
+        MapStr {
             parser: self,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Map the output of this parser to another value, making use of the parser's state when doing so.
+    /// Parse a pattern, and then parse the slice of input that it matched again with `inner`.
     ///
-    /// This is very useful for parsing non context-free grammars.
+    /// This is useful for two-phase parsing, such as parsing an embedded language inside a string literal. `inner`
+    /// is run over a [`SliceView`](input::SliceView) that covers only the matched slice, and must consume all of it.
+    /// It shares the parser state and context of the outer parser, and its errors are reported as errors of the
+    /// outer parser. Because the view shares the offsets of the original input, spans produced by `inner` (including
+    /// those of its errors) are spans of the original input.
     ///
-    /// The output type of this parser is `U`, the same as the function's output.
+    /// The output type of this parser is `U`, the output of `inner`.
     ///
     /// # Examples
     ///
-    /// ## General
-    ///
     /// ```
     /// # use chumsky::prelude::*;
-    /// use std::ops::Range;
-    /// use lasso::{Rodeo, Spur};
-    ///
-    /// // It's common for AST nodes to use interned versions of identifiers
-    /// // Keys are generally smaller, faster to compare, and can be `Copy`
-    /// #[derive(Copy, Clone)]
-    /// pub struct Ident(Spur);
-    ///
-    /// let ident = text::ascii::ident::<_, _, extra::Full<Simple<char>, Rodeo, ()>>()
-    ///     .map_with_state(|ident, span, state| Ident(state.get_or_intern(ident)))
-    ///     .padded()
+    /// let list = text::int::<_, _, extra::Err<Simple<char>>>(10).separated_by(just(',')).collect::<Vec<_>>();
+    /// let string = none_of::<_, _, extra::Err<Simple<char>>>('"')
     ///     .repeated()
-    ///     .at_least(1)
-    ///     .collect::<Vec<_>>();
-    ///
-    /// // Test out parser
+    ///     .reparse_slice(list)
+    ///     .delimited_by(just('"'), just('"'));
     ///
-    /// let mut interner = Rodeo::new();
-    ///
-    /// match ident.parse_with_state("hello", &mut interner).into_result() {
-    ///     Ok(idents) => {
-    ///         assert_eq!(interner.resolve(&idents[0].0), "hello");
-    ///     }
-    ///     Err(e) => panic!("Parsing Failed: {:?}", e),
-    /// }
-    ///
-    /// match ident.parse_with_state("hello hello", &mut interner).into_result() {
-    ///     Ok(idents) => {
-    ///         assert_eq!(idents[0].0, idents[1].0);
-    ///     }
-    ///     Err(e) => panic!("Parsing Failed: {:?}", e),
-    /// }
+    /// assert_eq!(string.parse("\"1,2,3\"").into_result(), Ok(vec!["1", "2", "3"]));
+    /// assert!(string.parse("\"1,,3\"").has_errors());
     /// ```
-    ///
-    /// See [`Parser::foldl_with_state`] for an example showing arena allocation via parser state.
-    fn map_with_state<U, F: Fn(O, I::Span, &mut E::State) -> U>(
-        self,
-        f: F,
-    ) -> MapWithState<Self, O, F>
+    fn reparse_slice<U, B>(self, inner: B) -> ReparseSlice<Self, B, O>
     where
         Self: Sized,
+        I: SliceInput<'a> + Clone,
+        E::Error: Error<'a, input::SliceView<'a, I>>,
+        B: Parser<'a, input::SliceView<'a, I>, U, extra::Full<E::Error, E::State, E::Context>>,
     {
-        MapWithState {
+        ReparseSlice {
             parser: self,
-            mapper: f,
+            inner,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// After a successful parse, apply a fallible function to the output. If the function produces an error, treat it
-    /// as a parsing error.
+    /// Declare that this parser always consumes exactly `N` units of input (bytes, for [`&[u8]`] and [`&str`]) when
+    /// it succeeds.
     ///
-    /// If you wish parsing of this pattern to continue when an error is generated instead of halting, consider using
-    /// [`Parser::validate`] instead.
+    /// This is intended for parsers of binary formats with fixed layouts, such as headers, and helps to catch layout
+    /// mistakes. In debug builds, a successful parse that consumes any other amount of input causes a panic. In
+    /// release builds, this combinator has no effect.
     ///
-    /// The output type of this parser is `U`, the [`Ok`] return value of the function.
+    /// The output type of this parser is `O`, the same as the original parser.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::prelude::*;
-    /// let byte = text::int::<_, _, extra::Err<Rich<char>>>(10)
-    ///     .try_map(|s: &str, span| s
-    ///         .parse::<u8>()
-    ///         .map_err(|e| Rich::custom(span, e)));
-    ///
-    /// assert!(byte.parse("255").has_output());
-    /// assert!(byte.parse("256").has_errors()); // Out of range
+    /// let u16_le = any::<&[u8], extra::Err<Simple<u8>>>()
+    ///     .then(any())
+    ///     .map(|(lo, hi)| u16::from_le_bytes([lo, hi]));
+    /// let header = just(b"BM")
+    ///     .ignore_then(u16_le)
+    ///     .then(u16_le)
+    ///     .fixed_size::<6>();
+    ///
+    /// assert_eq!(header.parse(b"BM\x01\x00\x02\x00").into_result(), Ok((1, 2)));
     /// ```
-    #[doc(alias = "filter_map")]
-    fn try_map<U, F: Fn(O, I::Span) -> Result<U, E::Error>>(self, f: F) -> TryMap<Self, O, F>
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn fixed_size<const N: usize>(self) -> FixedSize<Self, N>
     where
         Self: Sized,
     {
-        TryMap {
+        FixedSize {
             parser: self,
-            mapper: f,
-            phantom: EmptyPhantom::new(),
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
         }
     }
 
-    /// After a successful parse, apply a fallible function to the output, making use of the parser's state when
-    /// doing so. If the function produces an error, treat it as a parsing error.
+    /// Filter the output of this parser, accepting only inputs that match the given predicate.
     ///
-    /// If you wish parsing of this pattern to continue when an error is generated instead of halting, consider using
-    /// [`Parser::validate`] instead.
+    /// The output type of this parser is `I`, the input that was found.
     ///
-    /// The output type of this parser is `U`, the [`Ok`] return value of the function.
-    fn try_map_with_state<U, F: Fn(O, I::Span, &mut E::State) -> Result<U, E::Error>>(
-        self,
-        f: F,
-    ) -> TryMapWithState<Self, O, F>
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let lowercase = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(char::is_ascii_lowercase)
+    ///     .repeated()
+    ///     .at_least(1)
+    ///     .collect::<String>();
+    ///
+    /// assert_eq!(lowercase.parse("hello").into_result(), Ok("hello".to_string()));
+    /// assert!(lowercase.parse("Hello").has_errors());
+    /// ```
+    fn filter<F: Fn(&O) -> bool>(self, f: F) -> Filter<Self, F>
     where
         Self: Sized,
     {
-        TryMapWithState {
+        Filter {
             parser: self,
-            mapper: f,
-            phantom: EmptyPhantom::new(),
+            filter: f,
         }
     }
 
-    /// Ignore the output of this parser, yielding `()` as an output instead.
+    /// Only accept the output of this parser if the token that follows it satisfies the given predicate.
     ///
-    /// This can be used to reduce the cost of parsing by avoiding unnecessary allocations (most collections containing
-    /// [ZSTs](https://doc.rust-lang.org/nomicon/exotic-sizes.html#zero-sized-types-zsts)
-    /// [do not allocate](https://doc.rust-lang.org/std/vec/struct.Vec.html#guarantees)). For example, it's common to
-    /// want to ignore whitespace in many grammars (see [`text::whitespace`]).
+    /// The following token is only inspected, not consumed. The predicate is given `None` if the parser finished at the
+    /// end of the input. This is useful in lexers, where the meaning of a token sometimes depends on what comes after it.
     ///
-    /// The output type of this parser is `()`.
+    /// The output type of this parser is `O`, the same as the original parser.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// // A parser that parses any number of whitespace characters without allocating
-    /// let whitespace = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(|c: &char| c.is_whitespace())
-    ///     .ignored()
-    ///     .repeated()
-    ///     .collect::<Vec<_>>();
+    /// # use chumsky::prelude::*;
+    /// // `.` is a member access unless it's followed by a digit
+    /// let dot = just::<_, _, extra::Err<Simple<char>>>('.')
+    ///     .followed_by(|c: Option<&char>| !c.map_or(false, char::is_ascii_digit));
     ///
-    /// assert_eq!(whitespace.parse("    ").into_result(), Ok(vec![(); 4]));
-    /// assert!(whitespace.parse("  hello").has_errors());
+    /// assert_eq!(dot.then(text::ascii::ident()).parse(".len").into_result(), Ok(('.', "len")));
+    /// assert!(dot.then(text::digits(10)).parse(".5").has_errors());
     /// ```
-    fn ignored(self) -> Ignored<Self, O>
+    fn followed_by<F: Fn(Option<&I::Token>) -> bool>(self, pred: F) -> FollowedBy<Self, F>
     where
         Self: Sized,
     {
-        Ignored {
-            parser: self,
-            phantom: EmptyPhantom::new(),
-        }
+        FollowedBy { parser: self, pred }
     }
 
-    /// Memoize the parser such that later attempts to parse the same input 'remember' the attempt and exit early.
+    /// Filter the output of this parser, accepting only inputs that match the given predicate, and describing what
+    /// was expected with the given label on failure.
     ///
-    /// If you're finding that certain inputs produce exponential behaviour in your parser, strategically applying
-    /// memoization to a ['garden path'](https://en.wikipedia.org/wiki/Garden-path_sentence) rule is often an effective
-    /// way to solve the problem. At the limit, applying memoization to all combinators will turn any parser into one
-    /// with `O(n)`, albeit with very significant per-element overhead and high memory usage.
+    /// This is a lightweight alternative to `.filter(f).labelled(label)`: errors generated by the filter will say that
+    /// the label was expected, and will point at the pattern that was rejected.
     ///
-    /// Memoization also works with recursion, so this can be used to write parsers using
-    /// [left recursion](https://en.wikipedia.org/wiki/Left_recursion).
-    // TODO: Example
-    #[cfg(feature = "memoization")]
-    fn memoized(self) -> Memoized<Self>
+    /// Labelling errors requires the `label` feature, with which the error type must implement
+    /// `LabelError`. Without it, the label is ignored and errors are otherwise the same.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let vowel = any::<_, extra::Err<Rich<char>>>()
+    ///     .filter_labelled(|c: &char| "aeiou".contains(*c), "vowel");
+    ///
+    /// assert_eq!(vowel.parse("a").into_result(), Ok('a'));
+    /// # #[cfg(feature = "label")]
+    /// assert_eq!(vowel.parse("x").into_errors()[0].to_string(), "found 'x' expected vowel");
+    /// ```
+    fn filter_labelled<F: Fn(&O) -> bool, L>(self, f: F, label: L) -> FilterLabelled<Self, F, L>
     where
         Self: Sized,
     {
-        Memoized { parser: self }
+        FilterLabelled {
+            parser: self,
+            filter: f,
+            label,
+        }
     }
 
-    /// Transform all outputs of this parser to a pretermined value.
+    /// Map the output of this parser to another value.
     ///
-    /// The output type of this parser is `U`, the type of the predetermined value.
+    /// The output type of this parser is `U`, the same as the function's output.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// #[derive(Clone, Debug, PartialEq)]
-    /// enum Op { Add, Sub, Mul, Div }
+    /// #[derive(Debug, PartialEq)]
+    /// enum Token { Word(String), Num(u64) }
     ///
-    /// let op = just::<_, _, extra::Err<Simple<char>>>('+').to(Op::Add)
-    ///     .or(just('-').to(Op::Sub))
-    ///     .or(just('*').to(Op::Mul))
-    ///     .or(just('/').to(Op::Div));
+    /// let word = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(|c: &char| c.is_alphabetic())
+    ///     .repeated().at_least(1)
+    ///     .collect::<String>()
+    ///     .map(Token::Word);
     ///
-    /// assert_eq!(op.parse("+").into_result(), Ok(Op::Add));
-    /// assert_eq!(op.parse("/").into_result(), Ok(Op::Div));
+    /// let num = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(|c: &char| c.is_ascii_digit())
+    ///     .repeated().at_least(1)
+    ///     .collect::<String>()
+    ///     .map(|s| Token::Num(s.parse().unwrap()));
+    ///
+    /// let token = word.or(num);
+    ///
+    /// assert_eq!(token.parse("test").into_result(), Ok(Token::Word("test".to_string())));
+    /// assert_eq!(token.parse("42").into_result(), Ok(Token::Num(42)));
     /// ```
-    fn to<U: Clone>(self, to: U) -> To<Self, O, U>
+    fn map<U, F: Fn(O) -> U>(self, f: F) -> Map<Self, O, F>
     where
         Self: Sized,
     {
-        To {
+        Map {
             parser: self,
-            to,
+            mapper: f,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Label this parser with the given label.
+    /// Map the tuple output of this parser to another value, passing each element of the tuple as a separate argument.
     ///
-    /// Labelling a parser makes all errors generated by the parser refer to the label rather than any sub-elements
-    /// within the parser. For example, labelling a parser for an expression would yield "expected expression" errors
-    /// rather than "expected integer, string, binary op, etc." errors.
-    // TODO: Example
-    #[cfg(feature = "label")]
-    fn labelled<L>(self, label: L) -> Labelled<Self, L>
+    /// This is most useful after [`group`], where it avoids the need to destructure the tuple in the closure's
+    /// parameters. Tuples of up to 12 elements are supported.
+    ///
+    /// The output type of this parser is `U`, the output of the function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let range = group((
+    ///     text::int::<_, _, extra::Err<Simple<char>>>(10).from_str::<u32>().unwrapped(),
+    ///     just(".."),
+    ///     text::int(10).from_str::<u32>().unwrapped(),
+    /// ))
+    /// .map_group(|start, _, end| start..end);
+    ///
+    /// assert_eq!(range.parse("3..7").into_result(), Ok(3..7));
+    /// ```
+    fn map_group<U, F: GroupFn<O, U>>(self, f: F) -> MapGroup<Self, O, F>
     where
         Self: Sized,
-        E::Error: LabelError<'a, I, L>,
     {
-        Labelled {
+        MapGroup {
             parser: self,
-            label,
-            is_context: false,
+            mapper: f,
+            phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse one thing and then another thing, yielding a tuple of the two outputs.
+    /// Map the output of this parser to another value, deferring the call to the function until the value is needed.
     ///
-    /// The output type of this parser is `(O, U)`, a combination of the outputs of both parsers.
+    /// The output is a [`Deferred`] value, which only calls the function when it is forced (see [`Deferred::force`]
+    /// and [`Deferred::into_inner`]). This is useful when the value is expensive to construct but is often thrown
+    /// away, such as when the pattern is a prefix of several alternatives and an enclosing parser may backtrack over
+    /// it.
+    ///
+    /// The output type of this parser is `Deferred<'a, U>`.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let word = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(|c: &char| c.is_alphabetic())
-    ///     .repeated()
-    ///     .at_least(1)
-    ///     .collect::<String>();
-    /// let two_words = word.then_ignore(just(' ')).then(word);
+    /// # use chumsky::prelude::*;
+    /// let word = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .lazy_map(|s: &str| s.to_uppercase());
     ///
-    /// assert_eq!(two_words.parse("dog cat").into_result(), Ok(("dog".to_string(), "cat".to_string())));
-    /// assert!(two_words.parse("hedgehog").has_errors());
+    /// let shout = word.parse("hello").into_output().unwrap();
+    /// assert!(!shout.is_forced());
+    /// assert_eq!(shout.force(), "HELLO");
+    /// assert_eq!(shout.into_inner(), "HELLO");
     /// ```
-    fn then<U, B: Parser<'a, I, U, E>>(self, other: B) -> Then<Self, B, O, U, E>
+    fn lazy_map<U, F: Fn(O) -> U + Clone>(self, f: F) -> LazyMap<Self, O, F>
     where
         Self: Sized,
     {
-        Then {
-            parser_a: self,
-            parser_b: other,
+        LazyMap {
+            parser: self,
+            mapper: f,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse one thing and then another thing, yielding only the output of the latter.
+    /// Map the output of this parser through two functions in turn.
     ///
-    /// The output type of this parser is `U`, the same as the second parser.
+    /// `a.map2(f, g)` is equivalent to `a.map(f).map(g)`.
+    ///
+    /// The output type of this parser is `V`, the output of `g`.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let zeroes = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| *c == '0').ignored().repeated().collect::<Vec<_>>();
-    /// let digits = any().filter(|c: &char| c.is_ascii_digit())
-    ///     .repeated()
-    ///     .collect::<String>();
-    /// let integer = zeroes
-    ///     .ignore_then(digits)
-    ///     .from_str()
-    ///     .unwrapped();
+    /// # use chumsky::prelude::*;
+    /// let doubled = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .map2(|s: &str| s.parse::<u32>().unwrap(), |n| n * 2);
     ///
-    /// assert_eq!(integer.parse("00064").into_result(), Ok(64));
-    /// assert_eq!(integer.parse("32").into_result(), Ok(32));
+    /// assert_eq!(doubled.parse("21").into_result(), Ok(42));
     /// ```
-    fn ignore_then<U, B: Parser<'a, I, U, E>>(self, other: B) -> IgnoreThen<Self, B, O, E>
+    fn map2<U, V, F: Fn(O) -> U, G: Fn(U) -> V>(self, f: F, g: G) -> Map2<Self, O, F, U, G>
     where
         Self: Sized,
     {
-        IgnoreThen {
-            parser_a: self,
-            parser_b: other,
+        Map2 {
+            parser: self,
+            first: f,
+            second: g,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse one thing and then another thing, yielding only the output of the former.
+    /// Map the output of this parser to another value, making use of the pattern's span when doing so.
     ///
-    /// The output type of this parser is `O`, the same as the original parser.
+    /// This is very useful when generating an AST that attaches a span to each AST node.
+    ///
+    /// The output type of this parser is `U`, the same as the function's output.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let word = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(|c: &char| c.is_alphabetic())
-    ///     .repeated()
-    ///     .at_least(1)
-    ///     .collect::<String>();
+    /// # use chumsky::prelude::*;
     ///
-    /// let punctuated = word
-    ///     .then_ignore(just('!').or(just('?')).or_not());
+    /// // It's common for AST nodes to use a wrapper type that allows attaching span information to them
+    /// #[derive(Debug, PartialEq)]
+    /// pub struct Spanned<T>(T, SimpleSpan<usize>);
     ///
-    /// let sentence = punctuated
-    ///     .padded() // Allow for whitespace gaps
-    ///     .repeated()
-    ///     .collect::<Vec<_>>();
+    /// let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .map_with_span(Spanned) // Equivalent to `.map_with_span(|ident, span| Spanned(ident, span))`
+    ///     .padded();
     ///
-    /// assert_eq!(
-    ///     sentence.parse("hello! how are you?").into_result(),
-    ///     Ok(vec![
-    ///         "hello".to_string(),
-    ///         "how".to_string(),
-    ///         "are".to_string(),
-    ///         "you".to_string(),
-    ///     ]),
-    /// );
+    /// assert_eq!(ident.parse("hello").into_result(), Ok(Spanned("hello", (0..5).into())));
+    /// assert_eq!(ident.parse("       hello   ").into_result(), Ok(Spanned("hello", (7..12).into())));
     /// ```
-    fn then_ignore<U, B: Parser<'a, I, U, E>>(self, other: B) -> ThenIgnore<Self, B, U, E>
+    fn map_with_span<U, F: Fn(O, I::Span) -> U>(self, f: F) -> MapWithSpan<Self, O, F>
     where
         Self: Sized,
     {
-        ThenIgnore {
-            parser_a: self,
-            parser_b: other,
+        MapWithSpan {
+            parser: self,
+            mapper: f,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse input as part of a token-tree - using an input generated from within the current
-    /// input. In other words, this parser will attempt to create a *new* input stream from within
-    /// the one it is being run on, and the parser it was called on will be provided this *new* input.
-    /// By default, the original parser is expected to consume up to the end of the new stream. To
-    /// allow only consuming part of the stream, use [`Parser::lazy`] to ignore trailing tokens.
+    /// Pair the output of this parser with the pattern's span, as a [`Range<usize>`].
     ///
-    /// The provided parser `P` is expected to have both an input and output type which match the input
-    /// type of the parser it is called on. As an example, if the original parser takes an input of
-    /// `Stream<Iterator<Item = T>>`, `P` will be run first against that input, and is expected to
-    /// output a new `Stream<Iterator<Item = T>>` which the original parser will be run against.
+    /// This is a convenience for inputs such as [`&str`](str) and [`&[T]`](slice) whose spans are offset ranges, and
+    /// is useful for passing spans to tools that expect plain ranges.
     ///
-    /// The output of this parser is `O`, the output of the parser it is called on.
+    /// The output type of this parser is `(O, Range<usize>)`.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, util::MaybeRef, error::Simple};
-    /// #[derive(Debug, Clone, PartialEq)]
-    /// enum Token<'a> {
-    ///     Struct,
-    ///     Ident(&'a str),
-    ///     Item(&'a str),
-    ///     Group(Vec<Token<'a>>),
-    /// }
-    ///
-    /// let group = select_ref! { Token::Group(g) => g.as_slice() };
-    ///
-    /// let ident = select_ref! { Token::Ident(i) => *i };
-    ///
-    /// let items = select_ref! { Token::Item(i) => *i }
-    ///     .repeated()
-    ///     .collect::<Vec<_>>()
-    ///     .nested_in(group);
-    ///
-    /// let struc = just::<_, _, extra::Err<Simple<_>>>(&Token::Struct)
-    ///     .ignore_then(ident)
-    ///     .then(items);
-    ///
-    /// let tl = struc
-    ///     .repeated()
-    ///     .collect::<Vec<_>>();
-    ///
-    /// let tokens = [
-    ///     Token::Struct,
-    ///     Token::Ident("foo"),
-    ///     Token::Group(vec![
-    ///         Token::Item("a"),
-    ///         Token::Item("b"),
-    ///     ]),
-    /// ];
+    /// # use chumsky::prelude::*;
+    /// let word = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .padded()
+    ///     .spanned_range();
     ///
-    /// assert_eq!(tl.parse(&tokens).into_result(), Ok(vec![("foo", vec!["a", "b"])]));
+    /// assert_eq!(word.parse(" foo").into_result(), Ok(("foo", 0..4)));
     /// ```
-    fn nested_in<B: Parser<'a, I, I, E>>(self, other: B) -> NestedIn<Self, B, O, E>
+    fn spanned_range(self) -> MapWithSpan<Self, O, fn(O, I::Span) -> (O, Range<usize>)>
     where
         Self: Sized,
-        I: 'a,
+        I::Span: Into<Range<usize>>,
     {
-        NestedIn {
-            parser_a: self,
-            parser_b: other,
+        MapWithSpan {
+            parser: self,
+            mapper: (|out, span: I::Span| (out, span.into())) as fn(_, _) -> _,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse one thing and then another thing, creating the second parser from the result of
-    /// the first. If you don't need the context in the output, use [`Parser::then_with_ctx`].
+    /// Transform the output of this parser to the pattern's span.
     ///
-    /// The output of this parser is `U`, the result of the second parser
+    /// This is commonly used when you know what pattern you've parsed and are only interested in the span of the
+    /// pattern.
     ///
-    /// Error recovery for this parser may be sub-optimal, as if the first parser succeeds on
-    /// recovery then the second produces an error, the primary error will point to the location in
-    /// the second parser which failed, ignoring that the first parser may be the root cause. There
-    /// may be other pathological errors cases as well.
+    /// The output type of this parser is `I::Span`.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let successor = just(b'\0').configure(|cfg, ctx: &u8| cfg.seq(*ctx + 1));
+    /// # use chumsky::prelude::*;
     ///
-    /// // A parser that parses a single letter and then its successor
-    /// let successive_letters = one_of::<_, _, extra::Err<Simple<u8>>>(b'a'..=b'z')
-    ///     .ignore_with_ctx(successor);
+    /// // It's common for AST nodes to use a wrapper type that allows attaching span information to them
+    /// #[derive(Debug, PartialEq)]
+    /// pub enum Expr<'a> {
+    ///     Int(&'a str, SimpleSpan),
+    ///     // The span is that of the operator, '+'
+    ///     Add(Box<Expr<'a>>, SimpleSpan, Box<Expr<'a>>),
+    /// }
     ///
-    /// assert_eq!(successive_letters.parse(b"ab").into_result(), Ok(b'b')); // 'b' follows 'a'
-    /// assert!(successive_letters.parse(b"ac").has_errors()); // 'c' does not follow 'a'
+    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .slice()
+    ///     .map_with_span(Expr::Int)
+    ///     .padded();
+    ///
+    /// let add_op = just('+').to_span().padded();
+    /// let sum = int.foldl(
+    ///     add_op.then(int).repeated(),
+    ///     |a, (op_span, b)| Expr::Add(Box::new(a), op_span, Box::new(b)),
+    /// );
+    ///
+    /// assert_eq!(sum.parse("42 + 7 + 13").into_result(), Ok(Expr::Add(
+    ///     Box::new(Expr::Add(
+    ///         Box::new(Expr::Int("42", (0..2).into())),
+    ///         (3..4).into(),
+    ///         Box::new(Expr::Int("7", (5..6).into())),
+    ///     )),
+    ///     (7..8).into(),
+    ///     Box::new(Expr::Int("13", (9..11).into())),
+    /// )));
     /// ```
-    fn ignore_with_ctx<U, P>(
-        self,
-        then: P,
-    ) -> IgnoreWithCtx<Self, P, O, I, extra::Full<E::Error, E::State, O>>
+    fn to_span(self) -> ToSpan<Self, O>
     where
         Self: Sized,
-        O: 'a,
-        P: Parser<'a, I, U, extra::Full<E::Error, E::State, O>>,
     {
-        IgnoreWithCtx {
+        ToSpan {
             parser: self,
-            then,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse one thing and then another thing, creating the second parser from the result of
-    /// the first. If you don't need the context in the output, prefer [`Parser::ignore_with_ctx`].
+    /// Map the output of this parser to another value, making use of the parser's state when doing so.
     ///
-    /// The output of this parser is `(E::Context, O)`,
-    /// a combination of the context and the output of the parser.
+    /// This is very useful for parsing non context-free grammars.
     ///
-    /// Error recovery for this parser may be sub-optimal, as if the first parser succeeds on
-    /// recovery then the second produces an error, the primary error will point to the location in
-    /// the second parser which failed, ignoring that the first parser may be the root cause. There
-    /// may be other pathological errors cases as well.
-    fn then_with_ctx<U, P>(
+    /// The mapping function is only invoked when the parser is generating output. When the parser is only being
+    /// checked (such as via [`Parser::check`], or when used inside a combinator that discards its output), the mapping
+    /// function is skipped and so any side effects it has on the state will not occur. If you need state to be updated
+    /// regardless of the parse mode, consider using [`Parser::try_map_with_state`], which always invokes its function.
+    ///
+    /// The output type of this parser is `U`, the same as the function's output.
+    ///
+    /// # Examples
+    ///
+    /// ## General
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// use std::ops::Range;
+    /// use lasso::{Rodeo, Spur};
+    ///
+    /// // It's common for AST nodes to use interned versions of identifiers
+    /// // Keys are generally smaller, faster to compare, and can be `Copy`
+    /// #[derive(Copy, Clone)]
+    /// pub struct Ident(Spur);
+    ///
+    /// let ident = text::ascii::ident::<_, _, extra::Full<Simple<char>, Rodeo, ()>>()
+    ///     .map_with_state(|ident, span, state| Ident(state.get_or_intern(ident)))
+    ///     .padded()
+    ///     .repeated()
+    ///     .at_least(1)
+    ///     .collect::<Vec<_>>();
+    ///
+    /// // Test out parser
+    ///
+    /// let mut interner = Rodeo::new();
+    ///
+    /// match ident.parse_with_state("hello", &mut interner).into_result() {
+    ///     Ok(idents) => {
+    ///         assert_eq!(interner.resolve(&idents[0].0), "hello");
+    ///     }
+    ///     Err(e) => panic!("Parsing Failed: {:?}", e),
+    /// }
+    ///
+    /// match ident.parse_with_state("hello hello", &mut interner).into_result() {
+    ///     Ok(idents) => {
+    ///         assert_eq!(idents[0].0, idents[1].0);
+    ///     }
+    ///     Err(e) => panic!("Parsing Failed: {:?}", e),
+    /// }
+    /// ```
+    ///
+    /// ## Returning references into state
+    ///
+    /// The state is mutably borrowed for the whole parse, so the `&mut E::State` given to the function only lives for
+    /// the duration of the call: a reference into an owned state cannot be returned, because later calls could
+    /// mutate (or reallocate) the data it points to. If the data only needs to be read, use a shared reference as the
+    /// state instead. Copying that reference out of the `&mut` produces references that live for `'a`.
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// use std::collections::HashSet;
+    ///
+    /// type Table<'a> = &'a HashSet<String>;
+    ///
+    /// let keyword = text::ascii::ident::<_, _, extra::Full<Simple<char>, Table, ()>>()
+    ///     .map_with_state(|name, _, table: &mut Table| {
+    ///         let table: Table = table;
+    ///         table.get(name).map(String::as_str)
+    ///     });
+    ///
+    /// let table = HashSet::from(["if".to_string()]);
+    /// assert_eq!(keyword.parse_with_state("if", &mut &table).into_result(), Ok(Some("if")));
+    /// assert_eq!(keyword.parse_with_state("x", &mut &table).into_result(), Ok(None));
+    /// ```
+    ///
+    /// See [`Parser::foldl_with_state`] for an example showing arena allocation via parser state.
+    fn map_with_state<U, F: Fn(O, I::Span, &mut E::State) -> U>(
         self,
-        then: P,
-    ) -> ThenWithCtx<Self, P, O, I, extra::Full<E::Error, E::State, O>>
+        f: F,
+    ) -> MapWithState<Self, O, F>
     where
         Self: Sized,
-        O: 'a,
-        P: Parser<'a, I, U, extra::Full<E::Error, E::State, O>>,
     {
-        ThenWithCtx {
+        MapWithState {
             parser: self,
-            then,
+            mapper: f,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Run the previous contextual parser with the provided context
+    /// After a successful parse, apply a fallible function to the output. If the function produces an error, treat it
+    /// as a parsing error.
     ///
-    /// ```
-    /// # use chumsky::prelude::*;
-    /// # use chumsky::primitive::JustCfg;
+    /// If you wish parsing of this pattern to continue when an error is generated instead of halting, consider using
+    /// [`Parser::validate`] instead.
     ///
-    /// let generic = just(b'0').configure(|cfg, ctx: &u8| cfg.seq(*ctx));
+    /// The error may use any span, not just the one given to the function. To point at part of the matched input, see
+    /// [`SimpleSpan::sub_span`](span::SimpleSpan::sub_span).
     ///
-    /// let parse_a = just::<_, _, extra::Default>(b'b').ignore_then(generic.with_ctx::<u8>(b'a'));
-    /// let parse_b = just::<_, _, extra::Default>(b'a').ignore_then(generic.with_ctx(b'b'));
+    /// The output type of this parser is `U`, the [`Ok`] return value of the function.
+    ///
+    /// # Examples
     ///
-    /// assert_eq!(parse_a.parse(b"ba" as &[_]).into_result(), Ok::<_, Vec<EmptyErr>>(b'a'));
-    /// assert!(parse_a.parse(b"bb").has_errors());
-    /// assert_eq!(parse_b.parse(b"ab" as &[_]).into_result(), Ok(b'b'));
-    /// assert!(parse_b.parse(b"aa").has_errors());
     /// ```
-    fn with_ctx<Ctx>(self, ctx: Ctx) -> WithCtx<Self, Ctx>
+    /// # use chumsky::prelude::*;
+    /// let byte = text::int::<_, _, extra::Err<Rich<char>>>(10)
+    ///     .try_map(|s: &str, span| s
+    ///         .parse::<u8>()
+    ///         .map_err(|e| Rich::custom(span, e)));
+    ///
+    /// assert!(byte.parse("255").has_output());
+    /// assert!(byte.parse("256").has_errors()); // Out of range
+    /// ```
+    #[doc(alias = "filter_map")]
+    fn try_map<U, F: Fn(O, I::Span) -> Result<U, E::Error>>(self, f: F) -> TryMap<Self, O, F>
     where
         Self: Sized,
-        Ctx: 'a + Clone,
     {
-        WithCtx { parser: self, ctx }
+        TryMap {
+            parser: self,
+            mapper: f,
+            phantom: EmptyPhantom::new(),
+        }
     }
 
-    /// TODO
-    fn with_state<State>(self, state: State) -> WithState<Self, State>
+    /// After a successful parse, apply a fallible function to the output, making use of the parser's state when
+    /// doing so. If the function produces an error, treat it as a parsing error.
+    ///
+    /// If you wish parsing of this pattern to continue when an error is generated instead of halting, consider using
+    /// [`Parser::validate`] instead.
+    ///
+    /// The output type of this parser is `U`, the [`Ok`] return value of the function.
+    fn try_map_with_state<U, F: Fn(O, I::Span, &mut E::State) -> Result<U, E::Error>>(
+        self,
+        f: F,
+    ) -> TryMapWithState<Self, O, F>
     where
         Self: Sized,
-        State: 'a + Clone,
     {
-        WithState {
+        TryMapWithState {
             parser: self,
-            state,
+            mapper: f,
+            phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Applies both parsers to the same position in the input, succeeding
-    /// only if both succeed. The returned value will be that of the first parser,
-    /// and the input will be at the end of the first parser if `and_is` succeeds.
+    /// Mark this parser as a node of the given kind, producing [`Event`]s into the parser state as it is parsed.
     ///
-    /// The second parser is allowed to consume more or less input than the first parser,
-    /// but like its output, how much it consumes won't affect the final result.
+    /// When the parser begins, an [`Event::Enter`] is recorded. If it succeeds, an [`Event::Exit`] containing the span
+    /// of the node is recorded. If it fails, all events produced since it began are discarded. Together, the events
+    /// form a flat, structure-independent stream describing the shape of the parse, much like the event streams used
+    /// to build lossless syntax trees. The state type must implement [`EventSink`], as `Vec<Event<K, I::Span>>` does.
     ///
-    /// The motivating use-case is in combination with [`Parser::not`], allowing a parser
-    /// to consume something only if it isn't also something like an escape sequence or a nested block.
+    /// Events are produced even when the output of the parser is not used (such as in [`Parser::then_ignore`]). Events
+    /// produced by a marked parser that succeeded are also discarded if the input is later rewound past them, such as
+    /// when the first branch of a [`Parser::or`] fails after the marked parser.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    ///
-    /// let escape = just("\\n").to('\n');
-    ///
-    /// // C-style string literal
-    /// let string = none_of::<_, _, extra::Err<Simple<char>>>('"')
-    ///     .and_is(escape.not())
-    ///     .or(escape)
-    ///     .repeated()
-    ///     .collect::<String>()
-    ///     .padded_by(just('"'));
-    ///
-    /// assert_eq!(
-    ///     string.parse("\"wxyz\"").into_result().as_deref(),
-    ///     Ok("wxyz"),
-    /// );
-    /// assert_eq!(
-    ///     string.parse("\"a\nb\"").into_result().as_deref(),
-    ///     Ok("a\nb"),
-    /// );
+    /// # use chumsky::{prelude::*, extra::Event};
+    /// let ident = text::ascii::ident::<_, _, extra::State<Vec<Event<&str, SimpleSpan>>>>()
+    ///     .with_events("ident");
+    /// let call = ident
+    ///     .then(ident.delimited_by(just('('), just(')')))
+    ///     .with_events("call");
+    ///
+    /// let mut events = Vec::new();
+    /// call.parse_with_state("f(x)", &mut events).into_result().unwrap();
+    ///
+    /// assert_eq!(events, vec![
+    ///     Event::Enter("call"),
+    ///     Event::Enter("ident"),
+    ///     Event::Exit("ident", SimpleSpan::new(0, 1)),
+    ///     Event::Enter("ident"),
+    ///     Event::Exit("ident", SimpleSpan::new(2, 3)),
+    ///     Event::Exit("call", SimpleSpan::new(0, 4)),
+    /// ]);
     /// ```
-    fn and_is<U, B>(self, other: B) -> AndIs<Self, B, U>
+    fn with_events<K: Clone + 'a>(self, kind: K) -> WithEvents<Self, K>
     where
         Self: Sized,
-        B: Parser<'a, I, U, E>,
+        E::State: EventSink<K, I::Span>,
+        I::Span: Clone,
     {
-        AndIs {
-            parser_a: self,
-            parser_b: other,
-            phantom: EmptyPhantom::new(),
-        }
+        WithEvents { parser: self, kind }
     }
 
-    /// Parse the pattern surrounded by the given delimiters.
+    /// Mark this parser as a node of the given kind, assigning it a stable [`NodeId`](extra::NodeId).
+    ///
+    /// The id is derived from the kind and start position of this node and of every marked parser that it is nested
+    /// within, so parsing the same input always yields the same ids and nodes unaffected by an edit keep their ids,
+    /// making them useful for AST diffing and incremental tooling. While this parser is running, its id can be
+    /// obtained from [`NodeIds::current`](extra::NodeIds::current), usually via [`Parser::map_with_state`] on the
+    /// inner parser. The state type must implement [`NodeIdSource`], as [`NodeIds`](extra::NodeIds) does.
+    ///
+    /// Two nodes of the same kind that start at the same position and have the same marked ancestors receive the same
+    /// id. Since one of them must consume no input, this is rarely a problem in practice.
     ///
     /// The output type of this parser is `O`, the same as the original parser.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// // A LISP-style S-expression
-    /// #[derive(Debug, PartialEq)]
-    /// enum SExpr {
-    ///     Ident(String),
-    ///     Num(u64),
-    ///     List(Vec<SExpr>),
-    /// }
-    ///
-    /// let ident = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_alphabetic())
-    ///     .repeated()
-    ///     .at_least(1)
-    ///     .collect::<String>();
-    ///
-    /// let num = text::int(10)
-    ///     .from_str()
-    ///     .unwrapped();
-    ///
-    /// let s_expr = recursive(|s_expr| s_expr
-    ///     .padded()
-    ///     .repeated()
-    ///     .collect::<Vec<_>>()
-    ///     .map(SExpr::List)
-    ///     .delimited_by(just('('), just(')'))
-    ///     .or(ident.map(SExpr::Ident))
-    ///     .or(num.map(SExpr::Num)));
+    /// # use chumsky::{prelude::*, extra::{NodeId, NodeIds}};
+    /// let ident = text::ascii::ident::<_, _, extra::State<NodeIds>>()
+    ///     .map_with_state(|name, _, ids: &mut NodeIds| (ids.current().unwrap(), name))
+    ///     .with_node_id("ident");
+    /// let idents = ident.padded().repeated().collect::<Vec<_>>();
+    ///
+    /// let ids = |src| -> Vec<NodeId> {
+    ///     idents
+    ///         .parse_with_state(src, &mut NodeIds::new())
+    ///         .into_output()
+    ///         .unwrap()
+    ///         .into_iter()
+    ///         .map(|(id, _)| id)
+    ///         .collect()
+    /// };
     ///
-    /// // A valid input
-    /// assert_eq!(
-    ///     s_expr.parse("(add (mul 42 3) 15)").into_result(),
-    ///     Ok(SExpr::List(vec![
-    ///         SExpr::Ident("add".to_string()),
-    ///         SExpr::List(vec![
-    ///             SExpr::Ident("mul".to_string()),
-    ///             SExpr::Num(42),
-    ///             SExpr::Num(3),
-    ///         ]),
-    ///         SExpr::Num(15),
-    ///     ])),
-    /// );
+    /// // Ids are deterministic...
+    /// assert_eq!(ids("a b"), ids("a b"));
+    /// // ...and distinguish between different nodes
+    /// let ab = ids("a b");
+    /// assert_ne!(ab[0], ab[1]);
     /// ```
-    fn delimited_by<U, V, B, C>(self, start: B, end: C) -> DelimitedBy<Self, B, C, U, V>
+    fn with_node_id<K: Hash>(self, kind: K) -> WithNodeId<Self>
     where
         Self: Sized,
-        B: Parser<'a, I, U, E>,
-        C: Parser<'a, I, V, E>,
+        E::State: NodeIdSource,
     {
-        DelimitedBy {
+        WithNodeId {
             parser: self,
-            start,
-            end,
-            phantom: EmptyPhantom::new(),
+            kind: extra::Fnv::hash(&kind),
         }
     }
 
-    /// Parse a pattern, but with an instance of another pattern on either end, yielding the output of the inner.
+    /// Mark this parser as a syntax node of the given kind, for building a lossless concrete syntax tree.
+    ///
+    /// This behaves like [`Parser::with_events`], recording the span of the node into the parser state. Once parsing
+    /// is complete, pass the recorded events to [`cst::build_green_tree`] to assemble a
+    /// [`rowan`](https://docs.rs/rowan) green tree that contains every part of the input, including trivia.
     ///
     /// The output type of this parser is `O`, the same as the original parser.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
-    ///     .padded_by(just('!'));
+    /// # use chumsky::{prelude::*, extra::Event, cst::build_green_tree};
+    /// #[derive(Copy, Clone)]
+    /// enum Kind { Root, Trivia, Ident, Call }
     ///
-    /// assert_eq!(ident.parse("!hello!").into_result(), Ok("hello"));
-    /// assert!(ident.parse("hello!").has_errors());
-    /// assert!(ident.parse("!hello").has_errors());
-    /// assert!(ident.parse("hello").has_errors());
+    /// impl From<Kind> for rowan::SyntaxKind {
+    ///     fn from(kind: Kind) -> Self { rowan::SyntaxKind(kind as u16) }
+    /// }
+    ///
+    /// let ident = text::ascii::ident::<_, _, extra::State<Vec<Event<Kind, SimpleSpan>>>>()
+    ///     .node(Kind::Ident)
+    ///     .padded();
+    /// let call = ident
+    ///     .then(ident.delimited_by(just('('), just(')')))
+    ///     .node(Kind::Call);
+    ///
+    /// let src = "f( x )";
+    /// let mut events = Vec::new();
+    /// call.parse_with_state(src, &mut events).into_result().unwrap();
+    ///
+    /// let tree = build_green_tree(src, &events, Kind::Root, Kind::Trivia);
+    /// assert_eq!(tree.to_string(), src);
     /// ```
-    fn padded_by<U, B>(self, padding: B) -> PaddedBy<Self, B, U>
+    #[cfg(feature = "rowan")]
+    fn node<K>(self, kind: K) -> WithEvents<Self, K>
     where
         Self: Sized,
-        B: Parser<'a, I, U, E>,
+        K: Clone + Into<rowan::SyntaxKind> + 'a,
+        E::State: EventSink<K, I::Span>,
+        I::Span: Clone,
     {
-        PaddedBy {
-            parser: self,
-            padding,
-            phantom: EmptyPhantom::new(),
-        }
+        self.with_events(kind)
     }
 
-    /// Parse one thing or, on failure, another thing.
-    ///
-    /// The output of both parsers must be of the same type, because either output can be produced.
-    ///
-    /// If both parser succeed, the output of the first parser is guaranteed to be prioritised over the output of the
-    /// second.
-    ///
-    /// If both parsers produce errors, the combinator will attempt to select from or combine the errors to produce an
-    /// error that is most likely to be useful to a human attempting to understand the problem. The exact algorithm
-    /// used is left unspecified, and is not part of the crate's semver guarantees, although regressions in error
-    /// quality should be reported in the issue tracker of the main repository.
+    /// Ignore the output of this parser, yielding `()` as an output instead.
     ///
-    /// Please note that long chains of [`Parser::or`] combinators have been known to result in poor compilation times.
-    /// If you feel you are experiencing this, consider using [`choice`] instead.
+    /// This can be used to reduce the cost of parsing by avoiding unnecessary allocations (most collections containing
+    /// [ZSTs](https://doc.rust-lang.org/nomicon/exotic-sizes.html#zero-sized-types-zsts)
+    /// [do not allocate](https://doc.rust-lang.org/std/vec/struct.Vec.html#guarantees)). For example, it's common to
+    /// want to ignore whitespace in many grammars (see [`text::whitespace`]).
     ///
-    /// The output type of this parser is `O`, the output of both parsers.
+    /// The output type of this parser is `()`.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let op = just::<_, _, extra::Err<Simple<char>>>('+')
-    ///     .or(just('-'))
-    ///     .or(just('*'))
-    ///     .or(just('/'));
+    /// // A parser that parses any number of whitespace characters without allocating
+    /// let whitespace = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(|c: &char| c.is_whitespace())
+    ///     .ignored()
+    ///     .repeated()
+    ///     .collect::<Vec<_>>();
     ///
-    /// assert_eq!(op.parse("+").into_result(), Ok('+'));
-    /// assert_eq!(op.parse("/").into_result(), Ok('/'));
-    /// assert!(op.parse("!").has_errors());
+    /// assert_eq!(whitespace.parse("    ").into_result(), Ok(vec![(); 4]));
+    /// assert!(whitespace.parse("  hello").has_errors());
     /// ```
-    fn or<B>(self, other: B) -> Or<Self, B>
+    fn ignored(self) -> Ignored<Self, O>
     where
         Self: Sized,
-        B: Parser<'a, I, O, E>,
     {
-        Or {
-            choice: choice((self, other)),
+        Ignored {
+            parser: self,
+            phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Attempt to parse something, but only if it exists.
+    /// Memoize the parser such that later attempts to parse the same input 'remember' the attempt and exit early.
     ///
-    /// If parsing of the pattern is successful, the output is `Some(_)`. Otherwise, the output is `None`.
+    /// If you're finding that certain inputs produce exponential behaviour in your parser, strategically applying
+    /// memoization to a ['garden path'](https://en.wikipedia.org/wiki/Garden-path_sentence) rule is often an effective
+    /// way to solve the problem. At the limit, applying memoization to all combinators will turn any parser into one
+    /// with `O(n)`, albeit with very significant per-element overhead and high memory usage.
     ///
-    /// The output type of this parser is `Option<O>`.
+    /// Memoization also works with recursion, so this can be used to write parsers using
+    /// [left recursion](https://en.wikipedia.org/wiki/Left_recursion).
+    // TODO: Example
+    #[cfg(feature = "memoization")]
+    fn memoized(self) -> Memoized<Self>
+    where
+        Self: Sized,
+    {
+        Memoized { parser: self }
+    }
+
+    /// Transform all outputs of this parser to a pretermined value.
+    ///
+    /// The output type of this parser is `U`, the type of the predetermined value.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let word = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_alphabetic())
-    ///     .repeated()
-    ///     .at_least(1)
-    ///     .collect::<String>();
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// enum Op { Add, Sub, Mul, Div }
     ///
-    /// let word_or_question = word
-    ///     .then(just('?').or_not());
+    /// let op = just::<_, _, extra::Err<Simple<char>>>('+').to(Op::Add)
+    ///     .or(just('-').to(Op::Sub))
+    ///     .or(just('*').to(Op::Mul))
+    ///     .or(just('/').to(Op::Div));
     ///
-    /// assert_eq!(word_or_question.parse("hello?").into_result(), Ok(("hello".to_string(), Some('?'))));
-    /// assert_eq!(word_or_question.parse("wednesday").into_result(), Ok(("wednesday".to_string(), None)));
+    /// assert_eq!(op.parse("+").into_result(), Ok(Op::Add));
+    /// assert_eq!(op.parse("/").into_result(), Ok(Op::Div));
     /// ```
-    fn or_not(self) -> OrNot<Self>
+    fn to<U: Clone>(self, to: U) -> To<Self, O, U>
     where
         Self: Sized,
     {
-        OrNot { parser: self }
+        To {
+            parser: self,
+            to,
+            phantom: EmptyPhantom::new(),
+        }
     }
 
-    /// Invert the result of the contained parser, failing if it succeeds and succeeding if it fails.
-    /// The output of this parser is always `()`, the unit type.
+    /// Label this parser with the given label.
     ///
-    /// The motivating case for this is in combination with [`Parser::and_is`], allowing a parser
-    /// to consume something only if it isn't also something like an escape sequence or a nested block.
+    /// Labelling a parser makes all errors generated by the parser refer to the label rather than any sub-elements
+    /// within the parser. For example, labelling a parser for an expression would yield "expected expression" errors
+    /// rather than "expected integer, string, binary op, etc." errors.
+    // TODO: Example
+    #[cfg(feature = "label")]
+    fn labelled<L>(self, label: L) -> Labelled<Self, L>
+    where
+        Self: Sized,
+        E::Error: LabelError<'a, I, L>,
+    {
+        Labelled {
+            parser: self,
+            label,
+            is_context: false,
+        }
+    }
+
+    /// Parse one thing and then another thing, yielding a tuple of the two outputs.
     ///
-    /// Caveats:
-    /// - The error message produced by `not` by default will likely be fairly unhelpful - it can
-    ///   only tell the span that was wrong.
-    /// - If not careful, it's fairly easy to create non-intuitive behavior due to end-of-input
-    ///   being a valid token for a parser to consume, and as most parsers fail at end of input,
-    ///   `not` will succeed on it.
+    /// The output type of this parser is `(O, U)`, a combination of the outputs of both parsers.
+    ///
+    /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
+    /// let word = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(|c: &char| c.is_alphabetic())
+    ///     .repeated()
+    ///     .at_least(1)
+    ///     .collect::<String>();
+    /// let two_words = word.then_ignore(just(' ')).then(word);
     ///
-    /// #[derive(Debug, PartialEq)]
-    /// enum Tree<'a> {
-    ///     Text(&'a str),
-    ///     Group(Vec<Self>),
-    /// }
+    /// assert_eq!(two_words.parse("dog cat").into_result(), Ok(("dog".to_string(), "cat".to_string())));
+    /// assert!(two_words.parse("hedgehog").has_errors());
+    /// ```
+    fn then<U, B: Parser<'a, I, U, E>>(self, other: B) -> Then<Self, B, O, U, E>
+    where
+        Self: Sized,
+    {
+        Then {
+            parser_a: self,
+            parser_b: other,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Parse one thing and then, optionally, another thing, yielding a tuple of the first output and the second
+    /// output (if it was present).
     ///
-    /// // Arbitrary text, nested in a tree with { ... } delimiters
-    /// let tree = recursive::<_, _, extra::Err<Simple<char>>, _, _>(|tree| {
-    ///     let text = any()
-    ///         .and_is(one_of("{}").not())
-    ///         .repeated()
-    ///         .at_least(1)
-    ///         .map_slice(Tree::Text);
+    /// This is equivalent to `a.then(b.or_not())`. If `b` fails, no input is consumed by it, so the span of the
+    /// combined pattern (as seen by combinators like [`Parser::map_with_span`]) ends where `a` ends. If `b` is present,
+    /// the span ends where `b` ends.
     ///
-    ///     let group = tree
-    ///         .repeated()
-    ///         .collect()
-    ///         .delimited_by(just('{'), just('}'))
-    ///         .map(Tree::Group);
+    /// The output type of this parser is `(O, Option<U>)`.
     ///
-    ///     text.or(group)
-    /// });
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let binding = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .then_optional(just(':').padded().ignore_then(text::ascii::ident()))
+    ///     .map_with_span(|(name, ty), span: SimpleSpan<usize>| (name, ty, span.into_range()));
+    ///
+    /// assert_eq!(binding.parse("x : int").into_result(), Ok(("x", Some("int"), 0..7)));
+    /// assert_eq!(binding.lazy().parse("x = 5").into_result(), Ok(("x", None, 0..1)));
+    /// ```
+    fn then_optional<U, B>(self, other: B) -> Then<Self, OrNot<B>, O, Option<U>, E>
+    where
+        Self: Sized,
+        B: Parser<'a, I, U, E>,
+    {
+        self.then(other.or_not())
+    }
+
+    /// Parse one thing and then another thing, mapping both outputs and the span covering them both to a value.
+    ///
+    /// This is equivalent to `a.then(b).map_with_span(|(a, b), span| f(a, b, span))`, but is performed by a single
+    /// combinator.
+    ///
+    /// The output type of this parser is `V`, the same as the function's output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// #[derive(Debug, PartialEq)]
+    /// struct Assign<'a> {
+    ///     name: &'a str,
+    ///     value: &'a str,
+    ///     span: SimpleSpan<usize>,
+    /// }
+    ///
+    /// let assign = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .then_ignore(just('=').padded())
+    ///     .then_map_with_span(text::int(10), |name, value, span| Assign { name, value, span });
     ///
     /// assert_eq!(
-    ///     tree.parse("{abcd{efg{hijk}lmn{opq}rs}tuvwxyz}").into_result(),
-    ///     Ok(Tree::Group(vec![
-    ///         Tree::Text("abcd"),
-    ///         Tree::Group(vec![
-    ///             Tree::Text("efg"),
-    ///             Tree::Group(vec![
-    ///                 Tree::Text("hijk"),
-    ///             ]),
-    ///             Tree::Text("lmn"),
-    ///             Tree::Group(vec![
-    ///                 Tree::Text("opq"),
-    ///             ]),
-    ///             Tree::Text("rs"),
-    ///         ]),
-    ///         Tree::Text("tuvwxyz"),
-    ///     ])),
+    ///     assign.parse("x = 42").into_result(),
+    ///     Ok(Assign { name: "x", value: "42", span: (0..6).into() }),
     /// );
     /// ```
-    fn not(self) -> Not<Self, O>
+    fn then_map_with_span<U, V, B, F>(self, other: B, f: F) -> ThenMapWithSpan<Self, B, O, U, F, E>
     where
         Self: Sized,
+        B: Parser<'a, I, U, E>,
+        F: Fn(O, U, I::Span) -> V,
     {
-        Not {
-            parser: self,
+        ThenMapWithSpan {
+            parser_a: self,
+            parser_b: other,
+            mapper: f,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse a pattern any number of times (including zero times).
-    ///
-    /// Input is eagerly parsed. Be aware that the parser will accept no occurences of the pattern too. Consider using
-    /// [`Repeated::at_least`] instead if it better suits your use-case.
+    /// Parse one thing and then another thing, yielding only the output of the latter.
     ///
-    /// The output type of this parser can be any [`Container`].
+    /// The output type of this parser is `U`, the same as the second parser.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let num = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(|c: &char| c.is_ascii_digit())
+    /// let zeroes = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| *c == '0').ignored().repeated().collect::<Vec<_>>();
+    /// let digits = any().filter(|c: &char| c.is_ascii_digit())
     ///     .repeated()
-    ///     .at_least(1)
-    ///     .collect::<String>()
+    ///     .collect::<String>();
+    /// let integer = zeroes
+    ///     .ignore_then(digits)
     ///     .from_str()
     ///     .unwrapped();
     ///
-    /// let sum = num.clone()
-    ///     .foldl(just('+').ignore_then(num).repeated(), |a, b| a + b);
-    ///
-    /// assert_eq!(sum.parse("2+13+4+0+5").into_result(), Ok(24));
+    /// assert_eq!(integer.parse("00064").into_result(), Ok(64));
+    /// assert_eq!(integer.parse("32").into_result(), Ok(32));
     /// ```
-    #[cfg_attr(debug_assertions, track_caller)]
-    fn repeated(self) -> Repeated<Self, O, I, E>
+    fn ignore_then<U, B: Parser<'a, I, U, E>>(self, other: B) -> IgnoreThen<Self, B, O, E>
     where
         Self: Sized,
     {
-        Repeated {
-            parser: self,
-            at_least: 0,
-            at_most: !0,
-            #[cfg(debug_assertions)]
-            location: *Location::caller(),
+        IgnoreThen {
+            parser_a: self,
+            parser_b: other,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse a pattern, separated by another, any number of times.
-    ///
-    /// You can use [`SeparatedBy::allow_leading`] or [`SeparatedBy::allow_trailing`] to allow leading or trailing
-    /// separators.
+    /// Parse one thing and then another thing, yielding only the output of the former.
     ///
-    /// The output type of this parser can be any [`Container`].
+    /// The output type of this parser is `O`, the same as the original parser.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let shopping = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
-    ///     .padded()
-    ///     .separated_by(just(','))
+    /// let word = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(|c: &char| c.is_alphabetic())
+    ///     .repeated()
+    ///     .at_least(1)
+    ///     .collect::<String>();
+    ///
+    /// let punctuated = word
+    ///     .then_ignore(just('!').or(just('?')).or_not());
+    ///
+    /// let sentence = punctuated
+    ///     .padded() // Allow for whitespace gaps
+    ///     .repeated()
     ///     .collect::<Vec<_>>();
     ///
-    /// assert_eq!(shopping.parse("eggs").into_result(), Ok(vec!["eggs"]));
-    /// assert_eq!(shopping.parse("eggs, flour, milk").into_result(), Ok(vec!["eggs", "flour", "milk"]));
+    /// assert_eq!(
+    ///     sentence.parse("hello! how are you?").into_result(),
+    ///     Ok(vec![
+    ///         "hello".to_string(),
+    ///         "how".to_string(),
+    ///         "are".to_string(),
+    ///         "you".to_string(),
+    ///     ]),
+    /// );
     /// ```
-    ///
-    /// See [`SeparatedBy::allow_leading`] and [`SeparatedBy::allow_trailing`] for more examples.
-    #[cfg_attr(debug_assertions, track_caller)]
-    fn separated_by<U, B>(self, separator: B) -> SeparatedBy<Self, B, O, U, I, E>
+    fn then_ignore<U, B: Parser<'a, I, U, E>>(self, other: B) -> ThenIgnore<Self, B, U, E>
     where
         Self: Sized,
-        B: Parser<'a, I, U, E>,
     {
-        SeparatedBy {
-            parser: self,
-            separator,
-            at_least: 0,
-            at_most: !0,
-            allow_leading: false,
-            allow_trailing: false,
-            #[cfg(debug_assertions)]
-            location: *Location::caller(),
+        ThenIgnore {
+            parser_a: self,
+            parser_b: other,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Left-fold the output of the parser into a single value.
+    /// Parse one thing, then some trivia (such as whitespace or comments), then another thing, yielding all three
+    /// outputs.
     ///
-    /// The output of the original parser must be of type `(A, impl IntoIterator<Item = B>)`.
+    /// This is useful for lossless or concrete syntax trees, where the trivia between tokens must be retained so that
+    /// the original source can be reconstructed.
     ///
-    /// The output type of this parser is `A`, the left-hand component of the original parser's output.
+    /// The output type of this parser is `(O, G, U)`, a combination of the outputs of all three parsers.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
-    ///     .from_str()
-    ///     .unwrapped();
-    ///
-    /// let sum = int
-    ///     .clone()
-    ///     .foldl(just('+').ignore_then(int).repeated(), |a, b| a + b);
+    /// # use chumsky::prelude::*;
+    /// let word = text::ascii::ident::<_, _, extra::Err<Simple<char>>>();
+    /// let pair = word.then_with_gap(text::whitespace().slice(), word);
     ///
-    /// assert_eq!(sum.parse("1+12+3+9").into_result(), Ok(25));
-    /// assert_eq!(sum.parse("6").into_result(), Ok(6));
+    /// assert_eq!(pair.parse("hello \n world").into_result(), Ok(("hello", " \n ", "world")));
     /// ```
-    #[cfg_attr(debug_assertions, track_caller)]
-    fn foldl<B, F, OB>(self, other: B, f: F) -> Foldl<F, Self, B, OB, E>
+    fn then_with_gap<G, OG, U, B>(self, gap: G, other: B) -> ThenWithGap<Self, G, B, O, OG, U, E>
     where
-        F: Fn(O, OB) -> O,
-        B: IterParser<'a, I, OB, E>,
         Self: Sized,
+        G: Parser<'a, I, OG, E>,
+        B: Parser<'a, I, U, E>,
     {
-        Foldl {
+        ThenWithGap {
             parser_a: self,
+            gap,
             parser_b: other,
-            folder: f,
-            #[cfg(debug_assertions)]
-            location: *Location::caller(),
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Left-fold the output of the parser into a single value, making use of the parser's state when doing so.
+    /// Parse one thing and then another thing, ignoring any amount of whitespace between them, yielding a tuple of
+    /// the two outputs.
     ///
-    /// The output of the original parser must be of type `(A, impl IntoIterator<Item = B>)`.
+    /// This is equivalent to `self.then(other)` where `other` may be preceded by whitespace, and is useful for
+    /// whitespace-insensitive grammars that would otherwise require [`Parser::padded`] on every element.
     ///
-    /// The output type of this parser is `A`, the left-hand component of the original parser's output.
+    /// The output type of this parser is `(O, U)`, a combination of the outputs of both parsers.
     ///
     /// # Examples
     ///
-    /// ## General
-    ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let int = text::int::<_, _, extra::Full<Simple<char>, i32, ()>>(10)
-    ///     .from_str()
-    ///     .unwrapped();
-    ///
-    /// let sum = int
-    ///     .clone()
-    ///     .foldl_with_state(just('+').ignore_then(int).repeated(), |a, b, state| (a + b) * *state);
+    /// # use chumsky::prelude::*;
+    /// let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>();
+    /// let assign = ident.then_padded(just('=')).then_padded(ident);
     ///
-    /// let mut multiplier = 2i32;
-    /// assert_eq!(sum.parse_with_state("1+12+3+9", &mut multiplier).into_result(), Ok(134));
-    /// assert_eq!(sum.parse_with_state("6", &mut multiplier).into_result(), Ok(6));
+    /// assert_eq!(assign.parse("x  =\ty").into_result(), Ok((("x", '='), "y")));
+    /// assert_eq!(assign.parse("x=y").into_result(), Ok((("x", '='), "y")));
     /// ```
+    fn then_padded<U, B: Parser<'a, I, U, E>>(
+        self,
+        other: B,
+    ) -> Then<Self, PaddedBefore<B>, O, U, E>
+    where
+        Self: Sized,
+        I: ValueInput<'a>,
+        I::Token: Char,
+    {
+        self.then(PaddedBefore { parser: other })
+    }
+
+    /// Parse one thing and then another thing, ignoring any amount of whitespace between them, yielding only the
+    /// output of the latter.
     ///
-    /// ## Interning / Arena Allocation
+    /// See [`Parser::then_padded`] and [`Parser::ignore_then`].
     ///
-    /// This example assumes use of the `slotmap` crate for arena allocation.
+    /// The output type of this parser is `U`, the same as the second parser.
+    ///
+    /// # Examples
     ///
     /// ```
     /// # use chumsky::prelude::*;
-    /// use slotmap::{new_key_type, SlotMap};
+    /// let neg = just::<_, _, extra::Err<Simple<char>>>('-')
+    ///     .ignore_then_padded(text::int(10));
     ///
-    /// // Metadata type for node Ids for extra type safety
-    /// new_key_type! {
-    ///    pub struct NodeId;
-    /// }
-    ///
-    /// // AST nodes reference other nodes with `NodeId`s instead of containing boxed/owned values
-    /// #[derive(Copy, Clone, Debug, PartialEq)]
-    /// enum Expr {
-    ///     Int(i32),
-    ///     Add(NodeId, NodeId),
-    /// }
-    ///
-    /// type NodeArena = SlotMap<NodeId, Expr>;
-    ///
-    /// // Now, define our parser
-    /// let int = text::int::<&str, _, extra::Full<Simple<char>, NodeArena, ()>>(10)
-    ///     .padded()
-    ///     .map_with_state(|s, _, state: &mut NodeArena|
-    ///         // Return the ID of the new integer node
-    ///         state.insert(Expr::Int(s.parse().unwrap()))
-    ///     );
-    ///
-    /// let sum = int.foldl_with_state(
-    ///     just('+').padded().ignore_then(int).repeated(),
-    ///     |a: NodeId, b: NodeId, state: &mut NodeArena| {
-    ///         // Inserting an item into the arena returns its ID
-    ///         state.insert(Expr::Add(a, b))
-    ///     }
-    /// );
-    ///
-    /// // Test our parser
-    /// let mut arena = NodeArena::default();
-    /// let four_plus_eight = sum.parse_with_state("4 + 8", &mut arena).unwrap();
-    /// if let Expr::Add(a, b) = arena[four_plus_eight] {
-    ///     assert_eq!(arena[a], Expr::Int(4));
-    ///     assert_eq!(arena[b], Expr::Int(8));
-    /// } else {
-    ///     panic!("Not an Expr::Add");
-    /// }
+    /// assert_eq!(neg.parse("-  42").into_result(), Ok("42"));
     /// ```
-    #[cfg_attr(debug_assertions, track_caller)]
-    fn foldl_with_state<B, F, OB>(self, other: B, f: F) -> FoldlWithState<F, Self, B, OB, E>
+    fn ignore_then_padded<U, B: Parser<'a, I, U, E>>(
+        self,
+        other: B,
+    ) -> IgnoreThen<Self, PaddedBefore<B>, O, E>
     where
-        F: Fn(O, OB, &mut E::State) -> O,
-        B: IterParser<'a, I, OB, E>,
         Self: Sized,
+        I: ValueInput<'a>,
+        I::Token: Char,
     {
-        FoldlWithState {
-            parser_a: self,
-            parser_b: other,
-            folder: f,
-            #[cfg(debug_assertions)]
-            location: *Location::caller(),
-            phantom: EmptyPhantom::new(),
-        }
+        self.ignore_then(PaddedBefore { parser: other })
     }
 
-    /// Parse a pattern. Afterwards, the input stream will be rewound to its original state, as if parsing had not
-    /// occurred.
+    /// Parse one thing and then another thing, ignoring any amount of whitespace between them, yielding only the
+    /// output of the former.
     ///
-    /// This combinator is useful for cases in which you wish to avoid a parser accidentally consuming too much input,
-    /// causing later parsers to fail as a result. A typical use-case of this is that you want to parse something that
-    /// is not followed by something else.
+    /// See [`Parser::then_padded`] and [`Parser::then_ignore`].
     ///
     /// The output type of this parser is `O`, the same as the original parser.
     ///
@@ -1610,1013 +1983,2674 @@ pub trait Parser<'a, I: Input<'a>, O, E: ParserExtra<'a, I> = extra::Default>:
     ///
     /// ```
     /// # use chumsky::prelude::*;
-    /// let just_numbers = text::digits::<_, _, extra::Err<Simple<char>>>(10)
-    ///     .slice()
-    ///     .padded()
-    ///     .then_ignore(none_of("+-*/").rewind())
-    ///     .separated_by(just(','))
-    ///     .collect::<Vec<_>>();
-    /// // 3 is not parsed because it's followed by '+'.
-    /// assert_eq!(just_numbers.lazy().parse("1, 2, 3 + 4").into_result(), Ok(vec!["1", "2"]));
+    /// let stmt = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .then_ignore_padded(just(';'));
+    ///
+    /// assert_eq!(stmt.parse("42 ;").into_result(), Ok("42"));
     /// ```
-    fn rewind(self) -> Rewind<Self>
+    fn then_ignore_padded<U, B: Parser<'a, I, U, E>>(
+        self,
+        other: B,
+    ) -> ThenIgnore<Self, PaddedBefore<B>, U, E>
     where
         Self: Sized,
+        I: ValueInput<'a>,
+        I::Token: Char,
     {
-        Rewind { parser: self }
+        self.then_ignore(PaddedBefore { parser: other })
     }
 
-    /// Make the parser lazy, such that it parses as much as it validly can and then finished successfully, leaving
-    /// trailing input untouched.
+    /// Parse input as part of a token-tree - using an input generated from within the current
+    /// input. In other words, this parser will attempt to create a *new* input stream from within
+    /// the one it is being run on, and the parser it was called on will be provided this *new* input.
+    /// By default, the original parser is expected to consume up to the end of the new stream. To
+    /// allow only consuming part of the stream, use [`Parser::lazy`] to ignore trailing tokens.
     ///
-    /// The output type of this parser is `O`, the same as the original parser.
+    /// The provided parser `P` is expected to have both an input and output type which match the input
+    /// type of the parser it is called on. As an example, if the original parser takes an input of
+    /// `Stream<Iterator<Item = T>>`, `P` will be run first against that input, and is expected to
+    /// output a new `Stream<Iterator<Item = T>>` which the original parser will be run against.
+    ///
+    /// The output of this parser is `O`, the output of the parser it is called on.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::prelude::*;
-    /// let digits = one_of::<_, _, extra::Err<Simple<char>>>('0'..='9')
-    ///     .repeated()
-    ///     .collect::<String>()
-    ///     .lazy();
+    /// # use chumsky::{prelude::*, util::MaybeRef, error::Simple};
+    /// #[derive(Debug, Clone, PartialEq)]
+    /// enum Token<'a> {
+    ///     Struct,
+    ///     Ident(&'a str),
+    ///     Item(&'a str),
+    ///     Group(Vec<Token<'a>>),
+    /// }
     ///
-    /// assert_eq!(digits.parse("12345abcde").into_result().as_deref(), Ok("12345"));
-    /// ```
-    fn lazy(self) -> Lazy<'a, Self, I, E>
-    where
-        Self: Sized,
-        I: ValueInput<'a>,
-    {
-        self.then_ignore(any().repeated())
-    }
-
-    /// Parse a pattern, ignoring any amount of whitespace both before and after the pattern.
+    /// let group = select_ref! { Token::Group(g) => g.as_slice() };
     ///
-    /// The output type of this parser is `O`, the same as the original parser.
+    /// let ident = select_ref! { Token::Ident(i) => *i };
     ///
-    /// # Examples
+    /// let items = select_ref! { Token::Item(i) => *i }
+    ///     .repeated()
+    ///     .collect::<Vec<_>>()
+    ///     .nested_in(group);
     ///
-    /// ```
-    /// # use chumsky::prelude::*;
-    /// let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>().padded();
+    /// let struc = just::<_, _, extra::Err<Simple<_>>>(&Token::Struct)
+    ///     .ignore_then(ident)
+    ///     .then(items);
     ///
-    /// // A pattern with no whitespace surrounding it is accepted
-    /// assert_eq!(ident.parse("hello").into_result(), Ok("hello"));
-    /// // A pattern with arbitrary whitespace surrounding it is also accepted
-    /// assert_eq!(ident.parse(" \t \n  \t   world  \t  ").into_result(), Ok("world"));
+    /// let tl = struc
+    ///     .repeated()
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let tokens = [
+    ///     Token::Struct,
+    ///     Token::Ident("foo"),
+    ///     Token::Group(vec![
+    ///         Token::Item("a"),
+    ///         Token::Item("b"),
+    ///     ]),
+    /// ];
+    ///
+    /// assert_eq!(tl.parse(&tokens).into_result(), Ok(vec![("foo", vec!["a", "b"])]));
     /// ```
-    fn padded(self) -> Padded<Self>
+    fn nested_in<B: Parser<'a, I, I, E>>(self, other: B) -> NestedIn<Self, B, O, E>
     where
         Self: Sized,
-        I: Input<'a>,
-        I::Token: Char,
+        I: 'a,
     {
-        Padded { parser: self }
+        NestedIn {
+            parser_a: self,
+            parser_b: other,
+            phantom: EmptyPhantom::new(),
+        }
     }
 
-    // /// Flatten a nested collection.
-    // ///
-    // /// This use-cases of this method are broadly similar to those of [`Iterator::flatten`].
-    // ///
-    // /// The output type of this parser is `Vec<T>`, where the original parser output was
-    // /// `impl IntoIterator<Item = impl IntoIterator<Item = T>>`.
-    // fn flatten<T, Inner>(self) -> Map<Self, O, fn(O) -> Vec<T>>
-    // where
-    //     Self: Sized,
-    //     O: IntoIterator<Item = Inner>,
-    //     Inner: IntoIterator<Item = T>,
-    // {
-    //     self.map(|xs| xs.into_iter().flat_map(|xs| xs.into_iter()).collect())
-    // }
-
-    /// Apply a fallback recovery strategy to this parser should it fail.
-    ///
-    /// There is no silver bullet for error recovery, so this function allows you to specify one of several different
-    /// strategies at the location of your choice. Prefer an error recovery strategy that more precisely mirrors valid
-    /// syntax where possible to make error recovery more reliable.
+    /// Parse one thing and then another thing, creating the second parser from the result of
+    /// the first. If you don't need the context in the output, use [`Parser::then_with_ctx`].
     ///
-    /// Because chumsky is a [PEG](https://en.m.wikipedia.org/wiki/Parsing_expression_grammar) parser, which always
-    /// take the first successful parsing route through a grammar, recovering from an error may cause the parser to
-    /// erroneously miss alternative valid routes through the grammar that do not generate recoverable errors. If you
-    /// run into cases where valid syntax fails to parse without errors, this might be happening: consider removing
-    /// error recovery or switching to a more specific error recovery strategy.
+    /// The output of this parser is `U`, the result of the second parser
     ///
-    /// The output type of this parser is `O`, the same as the original parser.
+    /// Error recovery for this parser may be sub-optimal, as if the first parser succeeds on
+    /// recovery then the second produces an error, the primary error will point to the location in
+    /// the second parser which failed, ignoring that the first parser may be the root cause. There
+    /// may be other pathological errors cases as well.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// #[derive(Debug, PartialEq)]
-    /// enum Expr<'a> {
-    ///     Error,
-    ///     Int(&'a str),
-    ///     List(Vec<Expr<'a>>),
-    /// }
-    ///
-    /// let recovery = just::<_, _, extra::Err<Simple<char>>>('[')
-    ///         .then(none_of(']').repeated().then(just(']')));
-    ///
-    /// let expr = recursive::<_, _, extra::Err<Simple<char>>, _, _>(|expr| expr
-    ///     .separated_by(just(','))
-    ///     .collect::<Vec<_>>()
-    ///     .delimited_by(just('['), just(']'))
-    ///     .map(Expr::List)
-    ///     // If parsing a list expression fails, recover at the next delimiter, generating an error AST node
-    ///     .recover_with(via_parser(recovery.map(|_| Expr::Error)))
-    ///     .or(text::int(10).map(Expr::Int))
-    ///     .padded());
+    /// let successor = just(b'\0').configure(|cfg, ctx: &u8| cfg.seq(*ctx + 1));
     ///
-    /// assert!(expr.parse("five").has_errors()); // Text is not a valid expression in this language...
-    /// assert_eq!(
-    ///     expr.parse("[1, 2, 3]").into_result(),
-    ///     Ok(Expr::List(vec![Expr::Int("1"), Expr::Int("2"), Expr::Int("3")])),
-    /// ); // ...but lists and numbers are!
+    /// // A parser that parses a single letter and then its successor
+    /// let successive_letters = one_of::<_, _, extra::Err<Simple<u8>>>(b'a'..=b'z')
+    ///     .ignore_with_ctx(successor);
     ///
-    /// // This input has two syntax errors...
-    /// let res = expr.parse("[[1, two], [3, four]]");
-    /// // ...and error recovery allows us to catch both of them!
-    /// assert_eq!(res.errors().len(), 2);
-    /// // Additionally, the AST we get back still has useful information.
-    /// assert_eq!(res.output(), Some(&Expr::List(vec![Expr::Error, Expr::Error])));
+    /// assert_eq!(successive_letters.parse(b"ab").into_result(), Ok(b'b')); // 'b' follows 'a'
+    /// assert!(successive_letters.parse(b"ac").has_errors()); // 'c' does not follow 'a'
     /// ```
-    fn recover_with<S: Strategy<'a, I, O, E>>(self, strategy: S) -> RecoverWith<Self, S>
+    fn ignore_with_ctx<U, P>(
+        self,
+        then: P,
+    ) -> IgnoreWithCtx<Self, P, O, I, extra::Full<E::Error, E::State, O>>
     where
         Self: Sized,
+        O: 'a,
+        P: Parser<'a, I, U, extra::Full<E::Error, E::State, O>>,
     {
-        RecoverWith {
+        IgnoreWithCtx {
             parser: self,
-            strategy,
+            then,
+            phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Map the primary error of this parser to another value.
+    /// Parse one thing and then another thing, creating the second parser from the result of
+    /// the first. If you don't need the context in the output, prefer [`Parser::ignore_with_ctx`].
     ///
-    /// This function is most useful when using a custom error type, allowing you to augment errors according to
-    /// context.
+    /// The output of this parser is `(E::Context, O)`,
+    /// a combination of the context and the output of the parser.
     ///
-    /// The output type of this parser is `O`, the same as the original parser.
-    // TODO: Map E -> D, not E -> E
-    fn map_err<F>(self, f: F) -> MapErr<Self, F>
+    /// Error recovery for this parser may be sub-optimal, as if the first parser succeeds on
+    /// recovery then the second produces an error, the primary error will point to the location in
+    /// the second parser which failed, ignoring that the first parser may be the root cause. There
+    /// may be other pathological errors cases as well.
+    fn then_with_ctx<U, P>(
+        self,
+        then: P,
+    ) -> ThenWithCtx<Self, P, O, I, extra::Full<E::Error, E::State, O>>
     where
         Self: Sized,
-        F: Fn(E::Error) -> E::Error,
+        O: 'a,
+        P: Parser<'a, I, U, extra::Full<E::Error, E::State, O>>,
     {
-        MapErr {
+        ThenWithCtx {
             parser: self,
-            mapper: f,
-        }
-    }
-
-    // /// Map the primary error of this parser to another value, making use of the span from the start of the attempted
-    // /// to the point at which the error was encountered.
-    // ///
-    // /// This function is useful for augmenting errors to allow them to display the span of the initial part of a
-    // /// pattern, for example to add a "while parsing" clause to your error messages.
-    // ///
-    // /// The output type of this parser is `O`, the same as the original parser.
-    // ///
-    // // TODO: Map E -> D, not E -> E
-    // fn map_err_with_span<F>(self, f: F) -> MapErrWithSpan<Self, F>
-    // where
-    //     Self: Sized,
-    //     F: Fn(E::Error, I::Span) -> E::Error,
-    // {
-    //     MapErrWithSpan {
-    //         parser: self,
-    //         mapper: f,
-    //     }
-    // }
-
-    /// Map the primary error of this parser to another value, making use of the parser state.
-    ///
-    /// This function is useful for augmenting errors to allow them to include context in non context-free
-    /// languages, or provide contextual notes on possible causes.
-    ///
-    /// The output type of this parser is `O`, the same as the original parser.
-    ///
-    // TODO: Map E -> D, not E -> E
-    fn map_err_with_state<F>(self, f: F) -> MapErrWithState<Self, F>
-    where
-        Self: Sized,
-        F: Fn(E::Error, I::Span, &mut E::State) -> E::Error,
-    {
-        MapErrWithState {
-            parser: self,
-            mapper: f,
+            then,
+            phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Validate an output, producing non-terminal errors if it does not fulfil certain criteria.
-    /// The errors will not immediately halt parsing on this path, but instead it will continue,
-    /// potentially emitting one or more other errors, only failing after the pattern has otherwise
-    /// successfully, or emitted another terminal error.
+    /// Parse one thing and then another thing, creating the second parser from the output of the first and caching
+    /// it so that it is only created once for each distinct output.
     ///
-    /// This function also permits mapping the output to a value of another type, similar to [`Parser::map`].
+    /// This is useful when the second parser depends on a value with few possible states (such as a small enum) and
+    /// is expensive to build, or is built in a hot loop.
     ///
-    /// If you wish parsing of this pattern to halt when an error is generated instead of continuing, consider using
-    /// [`Parser::try_map`] instead.
+    /// Derived parsers are only reused within a single parse: the cache is emptied whenever the parser is used by a new
+    /// parse, so `then` should depend only on the output it is given (and not, for example, on the input). The cache is
+    /// shared between clones of this parser and holds a limited number of derived parsers, which can be set with
+    /// [`ThenWithCached::capacity`]. With the `sync` feature, the cache is guarded by a lock so that the parser remains
+    /// `Send` and `Sync`, but parsing with clones of it on several threads at once will empty it more often.
     ///
-    /// The output type of this parser is `U`, the result of the validation closure.
+    /// The output type of this parser is `(O, U)`, a combination of the outputs of both parsers.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::prelude::*;
-    /// let large_int = text::int::<_, _, extra::Err<Rich<char>>>(10)
-    ///     .from_str()
-    ///     .unwrapped()
-    ///     .validate(|x: u32, span, emitter| {
-    ///         if x < 256 { emitter.emit(Rich::custom(span, format!("{} must be 256 or higher.", x))) }
-    ///         x
-    ///     });
-    ///
-    /// assert_eq!(large_int.parse("537").into_result(), Ok(537));
-    /// assert!(large_int.parse("243").into_result().is_err());
-    /// ```
-    ///
-    /// To show the difference in behavior from [`Parser::try_map`]:
-    ///
-    /// ```
-    /// # use chumsky::prelude::*;
-    /// # use chumsky::util::MaybeRef;
-    /// # use chumsky::error::Error;
-    /// // start with the same large_int validator
-    /// let large_int_val = text::int::<_, _, extra::Err<Rich<char>>>(10)
-    ///         .from_str()
-    ///         .unwrapped()
-    ///         .validate(|x: u32, span, emitter| {
-    ///             if x < 256 { emitter.emit(Rich::custom(span, format!("{} must be 256 or higher", x))) }
-    ///             x
-    ///         });
-    ///
-    /// // A try_map version of the same parser
-    /// let large_int_tm = text::int::<_, _, extra::Err<Rich<char>>>(10)
-    ///         .from_str()
-    ///         .unwrapped()
-    ///         .try_map(|x: u32, span| {
-    ///             if x < 256 {
-    ///                 Err(Rich::custom(span, format!("{} must be 256 or higher", x)))
-    ///             } else {
-    ///                 Ok(x)
-    ///             }
-    ///         });
-    ///
-    /// // Parser that uses the validation version
-    /// let multi_step_val = large_int_val.then(text::ascii::ident().padded());
-    /// // Parser that uses the try_map version
-    /// let multi_step_tm = large_int_tm.then(text::ascii::ident().padded());
-    ///
-    /// // On success, both parsers are equivalent
-    /// assert_eq!(
-    ///     multi_step_val.parse("512 foo").into_result(),
-    ///     Ok((512, "foo"))
-    /// );
-    ///
-    /// assert_eq!(
-    ///     multi_step_tm.parse("512 foo").into_result(),
-    ///     Ok((512, "foo"))
-    /// );
-    ///
-    /// // However, on failure, they may produce different errors:
-    /// assert_eq!(
-    ///     multi_step_val.parse("100 2").into_result(),
-    ///     Err(vec![
-    ///         Rich::<char>::custom((0..3).into(), "100 must be 256 or higher"),
-    ///         <Rich<char> as Error<&str>>::expected_found([], Some(MaybeRef::Val('2')), (4..5).into()),
-    ///     ])
-    /// );
-    ///
-    /// assert_eq!(
-    ///     multi_step_tm.parse("100 2").into_result(),
-    ///     Err(vec![Rich::<char>::custom((0..3).into(), "100 must be 256 or higher")])
-    /// );
+    /// // Parse a radix prefix, then a number in that radix
+    /// let number = just::<_, _, extra::Err<Simple<char>>>("0x").to(16)
+    ///     .or(just("0b").to(2))
+    ///     .then_with_cached(|radix: &u32| text::int(*radix).slice());
+    ///
+    /// assert_eq!(number.parse("0xff").into_result(), Ok((16, "ff")));
+    /// assert_eq!(number.parse("0b101").into_result(), Ok((2, "101")));
+    /// assert!(number.parse("0b12").has_errors());
     /// ```
-    ///
-    /// As is seen in the above example, validation doesn't prevent the emission of later errors in the
-    /// same parser, but still produces an error in the output.
-    ///
-    fn validate<U, F>(self, f: F) -> Validate<Self, O, F>
+    fn then_with_cached<U, B, F>(self, then: F) -> ThenWithCached<Self, B, O, F>
     where
         Self: Sized,
-        F: Fn(O, I::Span, &mut Emitter<E::Error>) -> U,
+        O: Hash + Eq + Clone,
+        B: Parser<'a, I, U, E>,
+        F: Fn(&O) -> B,
     {
-        Validate {
+        ThenWithCached {
             parser: self,
-            validator: f,
-            phantom: EmptyPhantom::new(),
+            then,
+            capacity: 32,
+            cache: RefC::new(RefLock::new(DerivedParsers::default())),
         }
     }
 
-    // /// Map the primary error of this parser to a result. If the result is [`Ok`], the parser succeeds with that value.
-    // ///
-    // /// Note that, if the closure returns [`Err`], the parser will not consume any input.
-    // ///
-    // /// The output type of this parser is `U`, the [`Ok`] type of the result.
-    // fn or_else<F>(self, f: F) -> OrElse<Self, F>
-    // where
-    //     Self: Sized,
-    //     F: Fn(E::Error) -> Result<O, E::Error>,
-    // {
-    //     OrElse {
-    //         parser: self,
-    //         or_else: f,
-    //     }
-    // }
-
-    /// Attempt to convert the output of this parser into something else using Rust's [`FromStr`] trait.
+    /// Remember the output of the most recent successful parse, reusing it if this parser is invoked again at the same
+    /// position.
     ///
-    /// This is most useful when wanting to convert literal values into their corresponding Rust type, such as when
-    /// parsing integers.
+    /// This is a lightweight alternative to memoization for pure parsers (those whose output depends only on the input
+    /// at the position they are invoked) that are often retried at the same position due to backtracking, such as
+    /// the common prefix of several alternatives. Outputs are kept in the parser state, which must implement
+    /// [`PositionCacheSource`], as [`PositionCache`](extra::PositionCache) does. Only one output is kept per `key`,
+    /// so clones of this parser share it while differently keyed parsers do not. Parses that emitted secondary errors
+    /// are not cached. Any events or trivia that the parser recorded into the state (see [`Parser::with_events`]) are
+    /// recorded again when its output is reused.
     ///
-    /// The output type of this parser is `Result<U, U::Err>`, the result of attempting to parse the output, `O`, into
-    /// the value `U`.
+    /// The cache cannot tell different inputs apart, so use a new state for each parse, and avoid using the same key
+    /// for parsers that run on nested inputs (see [`Parser::nested_in`]).
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::prelude::*;
-    /// let uint64 = text::int::<_, _, extra::Err<Simple<char>>>(10)
-    ///     .from_str::<u64>()
-    ///     .unwrapped();
+    /// # use chumsky::{prelude::*, extra::PositionCache};
+    /// let ident = text::ascii::ident::<_, _, extra::State<PositionCache<usize, &str>>>()
+    ///     .cached_at_position("ident");
     ///
-    /// assert_eq!(uint64.parse("7").into_result(), Ok(7));
-    /// assert_eq!(uint64.parse("42").into_result(), Ok(42));
+    /// // Both alternatives start with an identifier, which is only parsed once
+    /// let item = ident.then_ignore(just('(')).or(ident.then_ignore(just('[')));
+    ///
+    /// assert_eq!(item.parse("foo[").into_result(), Ok("foo"));
     /// ```
-    #[allow(clippy::wrong_self_convention)]
-    fn from_str<U>(self) -> Map<Self, O, fn(O) -> Result<U, U::Err>>
+    fn cached_at_position<K: Hash>(self, key: K) -> CachedAtPosition<Self>
     where
         Self: Sized,
-        U: FromStr,
-        O: AsRef<str>,
+        O: Clone,
+        E::State: PositionCacheSource<I::Offset, O>,
     {
-        self.map(|o| o.as_ref().parse())
+        CachedAtPosition {
+            parser: self,
+            key: extra::Fnv::hash(&key),
+        }
     }
 
-    /// For parsers that produce a [`Result`] as their output, unwrap the result (panicking if an [`Err`] is
-    /// encountered).
-    ///
-    /// In general, this method should be avoided except in cases where all possible that the parser might produce can
-    /// by parsed using [`FromStr`] without producing an error.
-    ///
-    /// This combinator is not named `unwrap` to avoid confusion: it unwraps *during parsing*, not immediately.
-    ///
-    /// The output type of this parser is `U`, the [`Ok`] value of the [`Result`].
-    ///
-    /// # Examples
+    /// Run the previous contextual parser with the provided context
     ///
     /// ```
     /// # use chumsky::prelude::*;
-    /// let boolean = just::<_, _, extra::Err<Simple<char>>>("true")
-    ///     .or(just("false"))
-    ///     .from_str::<bool>()
-    ///     .unwrapped(); // Cannot panic: the only possible outputs generated by the parser are "true" or "false"
+    /// # use chumsky::primitive::JustCfg;
     ///
-    /// assert_eq!(boolean.parse("true").into_result(), Ok(true));
-    /// assert_eq!(boolean.parse("false").into_result(), Ok(false));
-    /// // Does not panic, because the original parser only accepts "true" or "false"
-    /// assert!(boolean.parse("42").has_errors());
+    /// let generic = just(b'0').configure(|cfg, ctx: &u8| cfg.seq(*ctx));
+    ///
+    /// let parse_a = just::<_, _, extra::Default>(b'b').ignore_then(generic.with_ctx::<u8>(b'a'));
+    /// let parse_b = just::<_, _, extra::Default>(b'a').ignore_then(generic.with_ctx(b'b'));
+    ///
+    /// assert_eq!(parse_a.parse(b"ba" as &[_]).into_result(), Ok::<_, Vec<EmptyErr>>(b'a'));
+    /// assert!(parse_a.parse(b"bb").has_errors());
+    /// assert_eq!(parse_b.parse(b"ab" as &[_]).into_result(), Ok(b'b'));
+    /// assert!(parse_b.parse(b"aa").has_errors());
     /// ```
-    #[track_caller]
-    fn unwrapped(self) -> Unwrapped<Self, O>
+    fn with_ctx<Ctx>(self, ctx: Ctx) -> WithCtx<Self, Ctx>
     where
         Self: Sized,
+        Ctx: 'a + Clone,
     {
-        Unwrapped {
-            parser: self,
-            location: *Location::caller(),
-            phantom: EmptyPhantom::new(),
-        }
+        WithCtx { parser: self, ctx }
     }
 
-    /// Turn this [`Parser`] into an [`IterParser`] if its output type implements [`IntoIterator`].
-    ///
-    /// The resulting iterable parser will emit each element of the output type in turn.
-    ///
-    /// This is *broadly* analgous to functions like [`Vec::into_iter`], but operating at the level of parser outputs.
-    // TODO: Example
-    fn into_iter(self) -> IntoIter<Self, O>
+    /// TODO
+    fn with_state<State>(self, state: State) -> WithState<Self, State>
     where
         Self: Sized,
-        O: IntoIterator,
+        State: 'a + Clone,
     {
-        IntoIter {
+        WithState {
             parser: self,
-            phantom: EmptyPhantom::new(),
+            state,
         }
     }
 
-    /// Box the parser, yielding a parser that performs parsing through dynamic dispatch.
-    ///
-    /// Boxing a parser might be useful for:
-    ///
-    /// - Dynamically building up parsers at run-time
-    ///
-    /// - Improving compilation times (Rust can struggle to compile code containing very long types)
-    ///
-    /// - Passing a parser over an FFI boundary
-    ///
-    /// - Getting around compiler implementation problems with long types such as
-    ///   [this](https://github.com/rust-lang/rust/issues/54540).
-    ///
-    /// - Places where you need to name the type of a parser
+    /// Applies both parsers to the same position in the input, succeeding
+    /// only if both succeed. The returned value will be that of the first parser,
+    /// and the input will be at the end of the first parser if `and_is` succeeds.
     ///
-    /// Boxing a parser is broadly equivalent to boxing other combinators via dynamic dispatch, such as [`Iterator`].
+    /// The second parser is allowed to consume more or less input than the first parser,
+    /// but like its output, how much it consumes won't affect the final result.
     ///
-    /// The output type of this parser is `O`, the same as the original parser.
+    /// The motivating use-case is in combination with [`Parser::not`], allowing a parser
+    /// to consume something only if it isn't also something like an escape sequence or a nested block.
     ///
     /// # Examples
     ///
-    /// When not using `boxed`, the following patterns are either impossible or very difficult to express:
-    ///
-    /// ```compile_fail
-    /// # use chumsky::prelude::*;
-    ///
-    /// pub trait Parseable: Sized {
-    ///     type Parser<'a>: Parser<'a, &'a str, Self>;
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
     ///
-    ///     fn parser<'a>() -> Self::Parser<'a>;
-    /// }
+    /// let escape = just("\\n").to('\n');
     ///
-    /// impl Parseable for i32 {
-    ///     // We *can* write this type, but it will be very impractical, and change on any alterations
-    ///     // to the implementation
-    ///     type Parser<'a> = ???;
-    ///
-    ///     fn parser<'a>() -> Self::Parser<'a> {
-    ///         todo()
-    ///     }
-    /// }
-    /// ```
-    ///
-    /// ```compile_fail
-    /// # use chumsky::prelude::*;
-    /// # fn user_input<'a>() -> impl IntoIterator<Item = impl Parser<'a, &'a str, char>> { [just('b')] }
-    ///
-    /// let user_input = user_input();
-    ///
-    /// let mut parser = just('a');
-    /// for i in user_input {
-    ///     // Doesn't work due to type mismatch - since every combinator creates a unique type
-    ///     parser = parser.or(i);
-    /// }
-    ///
-    /// let parser = parser.then(just('z'));
-    /// let _ = parser.parse("b").into_result();
-    /// ```
-    ///
-    /// However, with `boxed`, we can express them by making the parsers all share a common type:
-    ///
-    /// ```
-    /// use chumsky::prelude::*;
-    ///
-    /// pub trait Parseable: Sized {
-    ///     fn parser<'a>() -> Boxed<'a, 'a, &'a str, Self, extra::Default>;
-    /// }
-    ///
-    /// impl Parseable for i32 {
-    ///     fn parser<'a>() -> Boxed<'a, 'a, &'a str, Self, extra::Default> {
-    ///         todo().boxed()
-    ///     }
-    /// }
-    /// ```
+    /// // C-style string literal
+    /// let string = none_of::<_, _, extra::Err<Simple<char>>>('"')
+    ///     .and_is(escape.not())
+    ///     .or(escape)
+    ///     .repeated()
+    ///     .collect::<String>()
+    ///     .padded_by(just('"'));
     ///
+    /// assert_eq!(
+    ///     string.parse("\"wxyz\"").into_result().as_deref(),
+    ///     Ok("wxyz"),
+    /// );
+    /// assert_eq!(
+    ///     string.parse("\"a\nb\"").into_result().as_deref(),
+    ///     Ok("a\nb"),
+    /// );
     /// ```
-    /// # use chumsky::prelude::*;
-    /// # fn user_input<'a>() -> impl IntoIterator<Item = impl Parser<'a, &'a str, char>> { [just('b'), just('c')] }
-    /// let user_input = user_input();
-    /// let mut parser = just('a').boxed();
-    /// for i in user_input {
-    ///     // Doesn't work due to type mismatch - since every combinator creates a unique type
-    ///     parser = parser.or(i).boxed();
-    /// }
-    /// let parser = parser.then(just('z'));
-    /// parser.parse("az").into_result().unwrap();
-    /// ```
-    ///
-    fn boxed<'b>(self) -> Boxed<'a, 'b, I, O, E>
+    fn and_is<U, B>(self, other: B) -> AndIs<Self, B, U>
     where
-        Self: MaybeSync + Sized + 'a + 'b,
+        Self: Sized,
+        B: Parser<'a, I, U, E>,
     {
-        ParserSealed::boxed(self)
-    }
-}
-
-#[cfg(feature = "nightly")]
-impl<'a, I, O, E> ParserSealed<'a, I, O, E> for !
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-{
-    fn go<M: Mode>(&self, _inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
-        *self
+        AndIs {
+            parser_a: self,
+            parser_b: other,
+            phantom: EmptyPhantom::new(),
+        }
     }
 
-    go_extra!(O);
-}
-
-/// A [`Parser`] that can be configured with runtime context. This allows for context-sensitive parsing
-/// of input. Note that chumsky only supports 'left'-sensitive parsing, where the context for a parser
-/// is derived from earlier in the input.
-///
-/// Chumsky distinguishes 'state' from 'context'. State is not able to change what input a parser
-/// accepts, but may be used to change the contents of the type it emits. In this way state is expected
-/// to be idempotent - combinators such as [`Parser::map_with_state`] are allowed to not call the
-/// provided closure at all if they don't emit any output. Context and configuration, on the other hand,
-/// is used to change what kind of input a parser may accept, and thus must always be evaluated. Context
-/// isn't usable in any map combinator however - while it may affect accepted input, it is not expected
-/// to change the final result outside of how it changes what the parser itself returns.
-///
-/// Not all parsers currently support configuration. If you feel like you need a parser to be configurable
-/// and it isn't currently, please open an issue on the issue tracker of the main repository.
-pub trait ConfigParser<'a, I, O, E>: ConfigParserSealed<'a, I, O, E>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-{
-    /// A combinator that allows configuration of the parser from the current context. Context
-    /// is most often derived from [`Parser::ignore_with_ctx`], [`Parser::then_with_ctx`] or [`map_ctx`],
-    /// and is how chumsky supports parsing things such as indentation-sensitive grammars.
+    /// Parse the pattern surrounded by the given delimiters.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::prelude::*;
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// // A LISP-style S-expression
+    /// #[derive(Debug, PartialEq)]
+    /// enum SExpr {
+    ///     Ident(String),
+    ///     Num(u64),
+    ///     List(Vec<SExpr>),
+    /// }
     ///
-    /// let int = text::int::<_, _, extra::Err<Rich<char>>>(10)
+    /// let ident = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_alphabetic())
+    ///     .repeated()
+    ///     .at_least(1)
+    ///     .collect::<String>();
+    ///
+    /// let num = text::int(10)
     ///     .from_str()
     ///     .unwrapped();
     ///
-    /// // By default, accepts any number of items
-    /// let item = text::ascii::ident()
+    /// let s_expr = recursive(|s_expr| s_expr
     ///     .padded()
-    ///     .repeated();
-    ///
-    /// // With configuration, we can declare an exact number of items based on a prefix length
-    /// let len_prefixed_arr = int
-    ///     .ignore_with_ctx(item.configure(|repeat, ctx| repeat.exactly(*ctx)).collect::<Vec<_>>());
-    ///
-    /// assert_eq!(
-    ///     len_prefixed_arr.parse("2 foo bar").into_result(),
-    ///     Ok(vec!["foo", "bar"]),
-    /// );
+    ///     .repeated()
+    ///     .collect::<Vec<_>>()
+    ///     .map(SExpr::List)
+    ///     .delimited_by(just('('), just(')'))
+    ///     .or(ident.map(SExpr::Ident))
+    ///     .or(num.map(SExpr::Num)));
     ///
+    /// // A valid input
     /// assert_eq!(
-    ///     len_prefixed_arr.parse("0").into_result(),
-    ///     Ok(vec![]),
+    ///     s_expr.parse("(add (mul 42 3) 15)").into_result(),
+    ///     Ok(SExpr::List(vec![
+    ///         SExpr::Ident("add".to_string()),
+    ///         SExpr::List(vec![
+    ///             SExpr::Ident("mul".to_string()),
+    ///             SExpr::Num(42),
+    ///             SExpr::Num(3),
+    ///         ]),
+    ///         SExpr::Num(15),
+    ///     ])),
     /// );
-    ///
-    /// len_prefixed_arr.parse("3 foo bar baz bam").into_result().unwrap_err();
-    /// len_prefixed_arr.parse("3 foo bar").into_result().unwrap_err();
     /// ```
-    fn configure<F>(self, cfg: F) -> Configure<Self, F>
+    fn delimited_by<U, V, B, C>(self, start: B, end: C) -> DelimitedBy<Self, B, C, U, V>
     where
         Self: Sized,
-        F: Fn(Self::Config, &E::Context) -> Self::Config,
+        B: Parser<'a, I, U, E>,
+        C: Parser<'a, I, V, E>,
     {
-        Configure { parser: self, cfg }
-    }
-}
-
-/// An iterator that wraps an iterable parser. See [`IterParser::parse_iter`].
-#[cfg(test)]
-pub struct ParserIter<'a, 'iter, P: IterParser<'a, I, O, E>, I: Input<'a>, O, E: ParserExtra<'a, I>>
-{
-    parser: P,
-    offset: I::Offset,
-    own: InputOwn<'a, 'iter, I, E>,
-    iter_state: Option<P::IterState<Emit>>,
-    #[allow(dead_code)]
-    phantom: EmptyPhantom<(&'a (), O)>,
-}
-
-#[cfg(test)]
-impl<'a, 'iter, P, I: Input<'a>, O, E: ParserExtra<'a, I>> Iterator
-    for ParserIter<'a, 'iter, P, I, O, E>
-where
-    P: IterParser<'a, I, O, E>,
-{
-    type Item = O;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let mut inp = self.own.as_ref_at(self.offset);
-        let parser = &self.parser;
-
-        let iter_state = match &mut self.iter_state {
-            Some(state) => state,
-            None => {
-                let state = parser.make_iter::<Emit>(&mut inp).ok()?;
-                self.iter_state = Some(state);
-                self.iter_state.as_mut().unwrap()
-            }
-        };
-
-        let res = parser.next::<Emit>(&mut inp, iter_state);
-        self.offset = inp.offset;
-        res.ok().and_then(|res| res)
+        DelimitedBy {
+            parser: self,
+            start,
+            end,
+            phantom: EmptyPhantom::new(),
+        }
     }
-}
 
-/// An iterable equivalent of [`Parser`], i.e: a parser that generates a sequence of outputs.
-pub trait IterParser<'a, I, O, E = extra::Default>: IterParserSealed<'a, I, O, E>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-{
-    /// Collect this iterable parser into a [`Container`].
-    ///
-    /// This is commonly useful for collecting parsers that output many values into containers of various kinds:
-    /// [`Vec`]s, [`String`]s, or even [`HashMap`]s. This method is analogous to [`Iterator::collect`].
+    /// Parse a pattern, but with an instance of another pattern on either end, yielding the output of the inner.
     ///
-    /// The output type of this iterable parser is `C`, the type being collected into.
+    /// The output type of this parser is `O`, the same as the original parser.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let word = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_alphabetic()) // This parser produces an output of `char`
-    ///     .repeated() // This parser is iterable (i.e: implements `IterParser`)
-    ///     .collect::<String>(); // We collect the `char`s into a `String`
+    /// let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .padded_by(just('!'));
     ///
-    /// assert_eq!(word.parse("hello").into_result(), Ok("hello".to_string()));
+    /// assert_eq!(ident.parse("!hello!").into_result(), Ok("hello"));
+    /// assert!(ident.parse("hello!").has_errors());
+    /// assert!(ident.parse("!hello").has_errors());
+    /// assert!(ident.parse("hello").has_errors());
     /// ```
-    #[cfg_attr(debug_assertions, track_caller)]
-    fn collect<C: Container<O>>(self) -> Collect<Self, O, C>
+    fn padded_by<U, B>(self, padding: B) -> PaddedBy<Self, B, U>
     where
         Self: Sized,
+        B: Parser<'a, I, U, E>,
     {
-        Collect {
+        PaddedBy {
             parser: self,
-            #[cfg(debug_assertions)]
-            location: *Location::caller(),
+            padding,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Collect this iterable parser into a [`ContainerExactly`].
+    /// Record each match of this trivia parser (a comment, for example) into the parser state along with its span.
     ///
-    /// This is useful for situations where the number of items to consume is statically known.
-    /// A common use-case is collecting into an array.
+    /// This is most useful for tools like formatters, which need comments to be retained separately from the AST.
+    /// Use the resulting parser as the padding of [`Parser::padded_by`] (usually via [`Parser::repeated`]) and every
+    /// piece of trivia that gets skipped will be recorded. [`Parser::padded_with_trivia`] does this for you. The state
+    /// type must implement [`TriviaSink`], as `Vec<(I::Span, I::Slice)>` does.
     ///
-    /// The output type of this iterable parser if `C`, the type being collected into.
+    /// Trivia recorded by a parser is forgotten again if the input is rewound to before it (for example, when an
+    /// enclosing [`Parser::or`] backtracks), so the state only ever holds the trivia of the final parse.
     ///
-    /// # Exmaples
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let three_digit = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_numeric())
-    ///     .repeated()
-    ///     .collect_exactly::<[_; 3]>();
+    /// # use chumsky::prelude::*;
+    /// let comment = just::<_, _, extra::State<Vec<(SimpleSpan, &str)>>>("//")
+    ///     .then(any().and_is(just('\n').not()).repeated())
+    ///     .collect_trivia_into_state();
+    /// let ident = text::ascii::ident().padded_by(comment.padded().repeated());
     ///
-    /// assert_eq!(three_digit.parse("123").into_result(), Ok(['1', '2', '3']));
-    /// assert!(three_digit.parse("12").into_result().is_err());
-    /// assert!(three_digit.parse("1234").into_result().is_err());
+    /// let mut trivia = Vec::new();
+    /// let idents = ident.repeated().collect::<Vec<_>>();
+    /// assert_eq!(
+    ///     idents.parse_with_state("foo // first\nbar", &mut trivia).into_result(),
+    ///     Ok(vec!["foo", "bar"]),
+    /// );
+    /// assert_eq!(trivia, vec![(SimpleSpan::new(4, 12), "// first")]);
     /// ```
-    fn collect_exactly<C: ContainerExactly<O>>(self) -> CollectExactly<Self, O, C>
+    #[cfg(feature = "trivia")]
+    fn collect_trivia_into_state(self) -> CollectTrivia<Self>
     where
         Self: Sized,
+        I: SliceInput<'a>,
+        E::State: TriviaSink<I::Span, I::Slice>,
+        I::Span: Clone,
+        I::Slice: Clone,
     {
-        CollectExactly {
+        CollectTrivia { parser: self }
+    }
+
+    /// Parse a pattern, ignoring any amount of whitespace and `trivia` (comments, for example) both before and after the
+    /// pattern, and recording each piece of trivia into the parser state along with its span.
+    ///
+    /// This is [`Parser::padded`] for grammars that need to keep their comments around, such as formatters. Trivia is
+    /// recorded just like [`Parser::collect_trivia_into_state`] records it, including being forgotten when the parser
+    /// backtracks. The state type must implement [`TriviaSink`], as `Vec<(I::Span, I::Slice)>` does.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let comment = just::<_, _, extra::State<Vec<(SimpleSpan, &str)>>>("//")
+    ///     .then(any().and_is(just('\n').not()).repeated());
+    /// let ident = text::ascii::ident().padded_with_trivia(comment);
+    ///
+    /// let mut trivia = Vec::new();
+    /// let idents = ident.repeated().collect::<Vec<_>>();
+    /// assert_eq!(
+    ///     idents.parse_with_state("// a\nfoo // b\n bar", &mut trivia).into_result(),
+    ///     Ok(vec!["foo", "bar"]),
+    /// );
+    /// assert_eq!(trivia, vec![(SimpleSpan::new(0, 4), "// a"), (SimpleSpan::new(9, 13), "// b")]);
+    /// ```
+    #[cfg(feature = "trivia")]
+    fn padded_with_trivia<U, B>(self, trivia: B) -> PaddedWithTrivia<Self, B, U>
+    where
+        Self: Sized,
+        I: ValueInput<'a> + SliceInput<'a>,
+        I::Token: Char,
+        E::State: TriviaSink<I::Span, I::Slice>,
+        I::Span: Clone,
+        I::Slice: Clone,
+        B: Parser<'a, I, U, E>,
+    {
+        PaddedWithTrivia {
             parser: self,
+            trivia,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Collect this iterable parser into a [`usize`], outputting the number of elements that were parsed.
+    /// Parse one thing or, on failure, another thing.
     ///
-    /// This is sugar for [`.collect::<usize>()`](Self::collect).
+    /// The output of both parsers must be of the same type, because either output can be produced.
+    ///
+    /// If both parser succeed, the output of the first parser is guaranteed to be prioritised over the output of the
+    /// second.
+    ///
+    /// If both parsers produce errors, the combinator will attempt to select from or combine the errors to produce an
+    /// error that is most likely to be useful to a human attempting to understand the problem. The exact algorithm
+    /// used is left unspecified, and is not part of the crate's semver guarantees, although regressions in error
+    /// quality should be reported in the issue tracker of the main repository.
+    ///
+    /// Please note that long chains of [`Parser::or`] combinators have been known to result in poor compilation times.
+    /// If you feel you are experiencing this, consider using [`choice`] instead.
+    ///
+    /// The output type of this parser is `O`, the output of both parsers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let op = just::<_, _, extra::Err<Simple<char>>>('+')
+    ///     .or(just('-'))
+    ///     .or(just('*'))
+    ///     .or(just('/'));
+    ///
+    /// assert_eq!(op.parse("+").into_result(), Ok('+'));
+    /// assert_eq!(op.parse("/").into_result(), Ok('/'));
+    /// assert!(op.parse("!").has_errors());
+    /// ```
+    fn or<B>(self, other: B) -> Or<Self, B>
+    where
+        Self: Sized,
+        B: Parser<'a, I, O, E>,
+    {
+        Or {
+            choice: choice((self, other)),
+            union_expected: false,
+        }
+    }
+
+    /// Parse one thing or, on failure, another thing, recording which of the two matched.
+    ///
+    /// Unlike [`Parser::or`], the two parsers may have different output types (although they need not).
+    ///
+    /// The output type of this parser is `Either<O, U>` (from the `either` crate): `Either::Left` if this parser
+    /// matched, and `Either::Right` if `other` did.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::prelude::*;
+    /// use either::Either;
     ///
-    /// // Counts how many chess squares are in the input.
-    /// let squares = one_of::<_, _, extra::Err<Simple<char>>>('a'..='z').then(one_of('1'..='8')).padded().repeated().count();
+    /// let atom = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .or_tagged(text::ascii::ident());
     ///
-    /// assert_eq!(squares.parse("a1 b2 c3").into_result(), Ok(3));
-    /// assert_eq!(squares.parse("e5 e7 c6 c7 f6 d5 e6 d7 e4 c5 d6 c4 b6 f5").into_result(), Ok(14));
-    /// assert_eq!(squares.parse("").into_result(), Ok(0));
+    /// assert_eq!(atom.parse("42").into_result(), Ok(Either::Left("42")));
+    /// assert_eq!(atom.parse("x").into_result(), Ok(Either::Right("x")));
     /// ```
-    fn count(self) -> Collect<Self, O, usize>
+    #[cfg(feature = "either")]
+    fn or_tagged<U, B>(self, other: B) -> OrTagged<Self, B>
     where
         Self: Sized,
+        B: Parser<'a, I, U, E>,
     {
-        self.collect()
+        OrTagged {
+            parser_a: self,
+            parser_b: other,
+        }
     }
 
-    /// Enumerate outputs of this iterable parser.
+    /// Attempt to parse something, but only if it exists.
     ///
-    /// This function behaves in a similar way to [`Iterator::enumerate`].
+    /// If parsing of the pattern is successful, the output is `Some(_)`. Otherwise, the output is `None`.
     ///
-    /// The output type of this iterable parser is `(usize, O)`.
+    /// The output type of this parser is `Option<O>`.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let word = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
-    ///     .padded()
-    ///     .repeated() // This parser is iterable (i.e: implements `IterParser`)
-    ///     .enumerate()
-    ///     .collect::<Vec<(usize, &str)>>();
+    /// let word = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_alphabetic())
+    ///     .repeated()
+    ///     .at_least(1)
+    ///     .collect::<String>();
     ///
-    /// assert_eq!(word.parse("hello world").into_result(), Ok(vec![(0, "hello"), (1, "world")]));
+    /// let word_or_question = word
+    ///     .then(just('?').or_not());
+    ///
+    /// assert_eq!(word_or_question.parse("hello?").into_result(), Ok(("hello".to_string(), Some('?'))));
+    /// assert_eq!(word_or_question.parse("wednesday").into_result(), Ok(("wednesday".to_string(), None)));
     /// ```
-    fn enumerate(self) -> Enumerate<Self, O>
+    fn or_not(self) -> OrNot<Self>
     where
         Self: Sized,
     {
-        Enumerate {
-            parser: self,
-            phantom: EmptyPhantom::new(),
-        }
+        OrNot { parser: self }
     }
 
-    /// Right-fold the output of the parser into a single value.
+    /// Attempt to parse something, falling back to a default value only if the end of the input has been reached.
     ///
-    /// The output of the original parser must be of type `(impl IntoIterator<Item = A>, B)`. Because right-folds work
-    /// backwards, the iterator must implement [`DoubleEndedIterator`] so that it can be reversed.
+    /// If the pattern fails to parse but there is still input remaining, the error is propagated as normal. This is
+    /// useful for optional trailing constructs, where the absence of the construct is only valid when nothing else
+    /// follows.
     ///
-    /// The output type of this iterable parser is `B`, the right-hand component of the original parser's output.
+    /// The output type of this parser is `O`, the same as the original parser.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
-    ///     .from_str()
-    ///     .unwrapped();
+    /// let version = just::<_, _, extra::Err<Simple<char>>>("v=")
+    ///     .ignore_then(text::int(10))
+    ///     .or_at_end("1");
+    ///
+    /// assert_eq!(version.parse("v=2").into_result(), Ok("2"));
+    /// // No more input, so the default is used
+    /// assert_eq!(version.parse("").into_result(), Ok("1"));
+    /// // Unexpected input is an error
+    /// assert!(version.parse("x=2").has_errors());
+    /// ```
+    fn or_at_end(self, default: O) -> OrAtEnd<Self, O>
+    where
+        Self: Sized,
+        O: Clone,
+    {
+        OrAtEnd {
+            parser: self,
+            default,
+        }
+    }
+
+    /// Invert the result of the contained parser, failing if it succeeds and succeeding if it fails.
+    /// The output of this parser is always `()`, the unit type.
     ///
-    /// let signed = just('+').to(1)
-    ///     .or(just('-').to(-1))
-    ///     .repeated()
-    ///     .foldr(int, |a, b| a * b);
+    /// The motivating case for this is in combination with [`Parser::and_is`], allowing a parser
+    /// to consume something only if it isn't also something like an escape sequence or a nested block.
+    ///
+    /// Caveats:
+    /// - The error message produced by `not` by default will likely be fairly unhelpful - it can
+    ///   only tell the span that was wrong.
+    /// - If not careful, it's fairly easy to create non-intuitive behavior due to end-of-input
+    ///   being a valid token for a parser to consume, and as most parsers fail at end of input,
+    ///   `not` will succeed on it.
     ///
-    /// assert_eq!(signed.parse("3").into_result(), Ok(3));
-    /// assert_eq!(signed.parse("-17").into_result(), Ok(-17));
-    /// assert_eq!(signed.parse("--+-+-5").into_result(), Ok(5));
     /// ```
-    #[cfg_attr(debug_assertions, track_caller)]
-    fn foldr<B, F, OA>(self, other: B, f: F) -> Foldr<F, Self, B, O, E>
+    /// # use chumsky::{prelude::*, error::Simple};
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// enum Tree<'a> {
+    ///     Text(&'a str),
+    ///     Group(Vec<Self>),
+    /// }
+    ///
+    /// // Arbitrary text, nested in a tree with { ... } delimiters
+    /// let tree = recursive::<_, _, extra::Err<Simple<char>>, _, _>(|tree| {
+    ///     let text = any()
+    ///         .and_is(one_of("{}").not())
+    ///         .repeated()
+    ///         .at_least(1)
+    ///         .map_slice(Tree::Text);
+    ///
+    ///     let group = tree
+    ///         .repeated()
+    ///         .collect()
+    ///         .delimited_by(just('{'), just('}'))
+    ///         .map(Tree::Group);
+    ///
+    ///     text.or(group)
+    /// });
+    ///
+    /// assert_eq!(
+    ///     tree.parse("{abcd{efg{hijk}lmn{opq}rs}tuvwxyz}").into_result(),
+    ///     Ok(Tree::Group(vec![
+    ///         Tree::Text("abcd"),
+    ///         Tree::Group(vec![
+    ///             Tree::Text("efg"),
+    ///             Tree::Group(vec![
+    ///                 Tree::Text("hijk"),
+    ///             ]),
+    ///             Tree::Text("lmn"),
+    ///             Tree::Group(vec![
+    ///                 Tree::Text("opq"),
+    ///             ]),
+    ///             Tree::Text("rs"),
+    ///         ]),
+    ///         Tree::Text("tuvwxyz"),
+    ///     ])),
+    /// );
+    /// ```
+    fn not(self) -> Not<Self, O>
     where
-        F: Fn(O, OA) -> OA,
-        B: Parser<'a, I, OA, E>,
         Self: Sized,
     {
-        Foldr {
-            parser_a: self,
-            parser_b: other,
-            folder: f,
-            #[cfg(debug_assertions)]
-            location: *Location::caller(),
+        Not {
+            parser: self,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Right-fold the output of the parser into a single value, making use of the parser's state when doing so.
+    /// Parse a pattern any number of times (including zero times).
     ///
-    /// The output of the original parser must be of type `(impl IntoIterator<Item = A>, B)`. Because right-folds work
-    /// backwards, the iterator must implement [`DoubleEndedIterator`] so that it can be reversed.
+    /// Input is eagerly parsed. Be aware that the parser will accept no occurences of the pattern too. Consider using
+    /// [`Repeated::at_least`] instead if it better suits your use-case.
     ///
-    /// The output type of this parser is `B`, the right-hand component of the original parser's output.
+    /// The output type of this parser can be any [`Container`].
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let int = text::int::<_, _, extra::Full<Simple<char>, i32, ()>>(10)
+    /// let num = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(|c: &char| c.is_ascii_digit())
+    ///     .repeated()
+    ///     .at_least(1)
+    ///     .collect::<String>()
     ///     .from_str()
     ///     .unwrapped();
     ///
-    /// let signed = just('+').to(1)
-    ///     .or(just('-').to(-1))
-    ///     .repeated()
-    ///     .foldr_with_state(int, |a, b, state| {
-    ///         (*state) += 1;
-    ///         (a * b)
-    ///     });
+    /// let sum = num.clone()
+    ///     .foldl(just('+').ignore_then(num).repeated(), |a, b| a + b);
     ///
-    /// // Test our parser
-    /// let mut folds = 0i32;
-    /// assert_eq!(signed.parse_with_state("3", &mut folds).into_result(), Ok(3));
-    /// assert_eq!(signed.parse_with_state("-17", &mut folds).into_result(), Ok(-17));
-    /// assert_eq!(signed.parse_with_state("--+-+-5", &mut folds).into_result(), Ok(5));
+    /// assert_eq!(sum.parse("2+13+4+0+5").into_result(), Ok(24));
     /// ```
-    ///
-    ///
     #[cfg_attr(debug_assertions, track_caller)]
-    fn foldr_with_state<B, F, OA>(self, other: B, f: F) -> FoldrWithState<F, Self, B, OA, E>
+    fn repeated(self) -> Repeated<Self, O, I, E>
     where
-        F: Fn(O, OA, &mut E::State) -> OA,
-        B: Parser<'a, I, OA, E>,
         Self: Sized,
     {
-        FoldrWithState {
-            parser_a: self,
-            parser_b: other,
-            folder: f,
+        Repeated {
+            parser: self,
+            at_least: 0,
+            at_most: !0,
+            capacity_hint: 0,
+            require_progress: false,
             #[cfg(debug_assertions)]
             location: *Location::caller(),
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Create an iterator over the outputs generated by an iterable parser.
+    /// Parse a run of identical items: this parser is applied once, and then repeatedly for as long as each subsequent
+    /// output is equal to the first.
     ///
-    /// Warning: Trailing errors will be ignored
-    // TODO: Stabilize once error handling is properly decided on
-    #[cfg(test)]
-    fn parse_iter(self, input: I) -> ParseResult<ParserIter<'a, 'static, Self, I, O, E>, E::Error>
-    where
-        Self: IterParser<'a, I, O, E> + Sized,
-        E::State: Default,
-        E::Context: Default,
-    {
-        ParseResult::new(
-            Some(ParserIter {
-                parser: self,
-                offset: input.start(),
-                own: InputOwn::new(input),
-                iter_state: None,
-                phantom: EmptyPhantom::new(),
-            }),
-            Vec::new(),
-        )
-    }
-
-    /// Create an iterator over the outputs generated by an iterable parser with the given parser state.
+    /// Unlike [`Parser::repeated`], the first non-matching item is not consumed, and it need not be an error: parsing
+    /// simply stops there. The pattern must match at least once. A subsequent item that consumes no input also ends
+    /// the run.
     ///
-    /// Warning: Trailing errors will be ignored
-    // TODO: Stabilize once error handling is properly decided on
-    #[cfg(test)]
-    fn parse_iter_with_state<'parse>(
-        self,
-        input: I,
-        state: &'parse mut E::State,
-    ) -> ParseResult<ParserIter<'a, 'parse, Self, I, O, E>, E::Error>
-    where
-        Self: IterParser<'a, I, O, E> + Sized,
-        E::Context: Default,
-    {
-        ParseResult::new(
-            Some(ParserIter {
-                parser: self,
-                offset: input.start(),
-                own: InputOwn::new_state(input, state),
-                iter_state: None,
-                phantom: EmptyPhantom::new(),
-            }),
-            Vec::new(),
-        )
-    }
-}
-
-/// An iterable equivalent of [`ConfigParser`], i.e: a parser that generates a sequence of outputs and
-/// can be configured at runtime.
-pub trait ConfigIterParser<'a, I, O, E = extra::Default>:
-    ConfigIterParserSealed<'a, I, O, E>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-{
-    /// A combinator that allows configuration of the parser from the current context
-    fn configure<F>(self, cfg: F) -> IterConfigure<Self, F, O>
+    /// The output type of this parser is `(I::Slice, usize)`, the slice of the input covering the run and the number
+    /// of items in it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let run = any::<_, extra::Err<Simple<char>>>().same_repeated();
+    ///
+    /// assert_eq!(run.parse("aaaa").into_result(), Ok(("aaaa", 4)));
+    /// assert_eq!(run.lazy().parse("xxyx").into_result(), Ok(("xx", 2)));
+    /// assert!(run.parse("").has_errors());
+    /// ```
+    fn same_repeated(self) -> SameRepeated<Self, O>
     where
         Self: Sized,
-        F: Fn(Self::Config, &E::Context) -> Self::Config,
+        I: SliceInput<'a>,
+        O: PartialEq,
     {
-        IterConfigure {
+        SameRepeated {
             parser: self,
-            cfg,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// A combinator that allows fallible configuration of the parser from the current context -
-    /// if an error is returned, parsing fails.
-    fn try_configure<F>(self, cfg: F) -> TryIterConfigure<Self, F, O>
+    /// Parse a pattern, separated by another, any number of times.
+    ///
+    /// You can use [`SeparatedBy::allow_leading`] or [`SeparatedBy::allow_trailing`] to allow leading or trailing
+    /// separators.
+    ///
+    /// The output type of this parser can be any [`Container`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let shopping = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .padded()
+    ///     .separated_by(just(','))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(shopping.parse("eggs").into_result(), Ok(vec!["eggs"]));
+    /// assert_eq!(shopping.parse("eggs, flour, milk").into_result(), Ok(vec!["eggs", "flour", "milk"]));
+    /// ```
+    ///
+    /// See [`SeparatedBy::allow_leading`] and [`SeparatedBy::allow_trailing`] for more examples.
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn separated_by<U, B>(self, separator: B) -> SeparatedBy<Self, B, O, U, I, E>
     where
         Self: Sized,
-        F: Fn(Self::Config, &E::Context, I::Span) -> Result<Self::Config, E::Error>,
+        B: Parser<'a, I, U, E>,
     {
-        TryIterConfigure {
+        SeparatedBy {
             parser: self,
-            cfg,
+            separator,
+            at_least: 0,
+            at_most: !0,
+            allow_leading: false,
+            allow_trailing: false,
+            forbid_trailing: false,
+            require_progress: false,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
             phantom: EmptyPhantom::new(),
         }
     }
-}
-
-/// See [`Parser::boxed`].
-///
-/// Due to current implementation details, the inner value is not, in fact, a [`Box`], but is an [`Rc`] to facilitate
-/// efficient cloning. This is likely to change in the future. Unlike [`Box`], [`Rc`] has no size guarantees: although
-/// it is *currently* the same size as a raw pointer.
-// TODO: Don't use an Rc
-pub struct Boxed<'a, 'b, I: Input<'a>, O, E: ParserExtra<'a, I>> {
-    inner: RefC<DynParser<'a, 'b, I, O, E>>,
-}
-
-impl<'a, 'b, I: Input<'a>, O, E: ParserExtra<'a, I>> Clone for Boxed<'a, 'b, I, O, E> {
-    fn clone(&self) -> Self {
-        Self {
-            inner: self.inner.clone(),
-        }
-    }
-}
-
-impl<'a, 'b, I, O, E> ParserSealed<'a, I, O, E> for Boxed<'a, 'b, I, O, E>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-{
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
-        M::invoke(&*self.inner, inp)
-    }
-
-    fn boxed<'c>(self) -> Boxed<'a, 'c, I, O, E>
-    where
-        Self: MaybeSync + Sized + 'a + 'c,
-    {
-        // Never double-box parsers
-        self
-    }
-
-    go_extra!(O);
-}
-
-impl<'a, I, O, E, T> ParserSealed<'a, I, O, E> for ::alloc::boxed::Box<T>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-    T: Parser<'a, I, O, E>,
-{
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
-    where
-        Self: Sized,
-    {
-        T::go::<M>(self, inp)
-    }
-
-    go_extra!(O);
-}
 
-impl<'a, I, O, E, T> ParserSealed<'a, I, O, E> for ::alloc::rc::Rc<T>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-    T: Parser<'a, I, O, E>,
-{
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    /// Left-fold the output of the parser into a single value.
+    ///
+    /// The output of the original parser must be of type `(A, impl IntoIterator<Item = B>)`.
+    ///
+    /// The output type of this parser is `A`, the left-hand component of the original parser's output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .from_str()
+    ///     .unwrapped();
+    ///
+    /// let sum = int
+    ///     .clone()
+    ///     .foldl(just('+').ignore_then(int).repeated(), |a, b| a + b);
+    ///
+    /// assert_eq!(sum.parse("1+12+3+9").into_result(), Ok(25));
+    /// assert_eq!(sum.parse("6").into_result(), Ok(6));
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn foldl<B, F, OB>(self, other: B, f: F) -> Foldl<F, Self, B, OB, E>
     where
+        F: Fn(O, OB) -> O,
+        B: IterParser<'a, I, OB, E>,
         Self: Sized,
     {
-        T::go::<M>(self, inp)
+        Foldl {
+            parser_a: self,
+            parser_b: other,
+            folder: f,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
     }
 
-    go_extra!(O);
-}
-
-impl<'a, I, O, E, T> ParserSealed<'a, I, O, E> for ::alloc::sync::Arc<T>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-    T: Parser<'a, I, O, E>,
-{
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    /// Parse a left-associative chain of the form `self (other)*`, folding each `other` into the value so far.
+    ///
+    /// This is shorthand for `self.foldl(other.repeated(), combine)`, and covers the most common pattern for binary
+    /// operators: `other` is typically an operator followed by a right-hand operand, such as `op.then(rhs)`.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .padded()
+    ///     .map(|s: &str| s.parse::<i64>().unwrap());
+    ///
+    /// let expr = int.chain_left(one_of("+-").then(int), |lhs, (op, rhs)| match op {
+    ///     '+' => lhs + rhs,
+    ///     _ => lhs - rhs,
+    /// });
+    ///
+    /// assert_eq!(expr.parse("10 - 2 + 3").into_result(), Ok(11));
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn chain_left<B, F, OB>(
+        self,
+        other: B,
+        combine: F,
+    ) -> Foldl<F, Self, Repeated<B, OB, I, E>, OB, E>
     where
+        F: Fn(O, OB) -> O,
+        B: Parser<'a, I, OB, E>,
         Self: Sized,
     {
-        T::go::<M>(self, inp)
+        self.foldl(other.repeated(), combine)
     }
 
-    go_extra!(O);
-}
-
-/// Create a parser that selects one or more input patterns and map them to an output value.
-///
-/// This is most useful when turning the tokens of a previous compilation pass (such as lexing) into data that can be
-/// used for parsing, although it can also generally be used to select inputs and map them to outputs. Any unmapped
-/// input patterns will become syntax errors, just as with [`Parser::filter`].
-///
-/// Internally, [`select!`] is very similar to [`Parser::try_map`] and thinking of it as such might make it less
-/// confusing.
-///
-/// `select!` requires that tokens implement [`Clone`].
-///
-/// If you're trying to access tokens referentially (for the sake of nested parsing, or simply because you want to
-/// avoid cloning the token), see [`select_ref!`].
-///
-/// # Examples
-///
-/// `select!` is syntactically similar to a `match` expression and has support for
-/// [pattern guards](https://doc.rust-lang.org/reference/expressions/match-expr.html#match-guards):
-///
-/// ```
-/// # use chumsky::{prelude::*, error::Simple};
-/// #[derive(Clone)]
-/// enum Token<'a> { Ident(&'a str) }
+    /// Left-fold the output of the parser into a single value, making use of the parser's state when doing so.
+    ///
+    /// The output of the original parser must be of type `(A, impl IntoIterator<Item = B>)`.
+    ///
+    /// The output type of this parser is `A`, the left-hand component of the original parser's output.
+    ///
+    /// # Examples
+    ///
+    /// ## General
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let int = text::int::<_, _, extra::Full<Simple<char>, i32, ()>>(10)
+    ///     .from_str()
+    ///     .unwrapped();
+    ///
+    /// let sum = int
+    ///     .clone()
+    ///     .foldl_with_state(just('+').ignore_then(int).repeated(), |a, b, state| (a + b) * *state);
+    ///
+    /// let mut multiplier = 2i32;
+    /// assert_eq!(sum.parse_with_state("1+12+3+9", &mut multiplier).into_result(), Ok(134));
+    /// assert_eq!(sum.parse_with_state("6", &mut multiplier).into_result(), Ok(6));
+    /// ```
+    ///
+    /// ## Interning / Arena Allocation
+    ///
+    /// This example assumes use of the `slotmap` crate for arena allocation.
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// use slotmap::{new_key_type, SlotMap};
+    ///
+    /// // Metadata type for node Ids for extra type safety
+    /// new_key_type! {
+    ///    pub struct NodeId;
+    /// }
+    ///
+    /// // AST nodes reference other nodes with `NodeId`s instead of containing boxed/owned values
+    /// #[derive(Copy, Clone, Debug, PartialEq)]
+    /// enum Expr {
+    ///     Int(i32),
+    ///     Add(NodeId, NodeId),
+    /// }
+    ///
+    /// type NodeArena = SlotMap<NodeId, Expr>;
+    ///
+    /// // Now, define our parser
+    /// let int = text::int::<&str, _, extra::Full<Simple<char>, NodeArena, ()>>(10)
+    ///     .padded()
+    ///     .map_with_state(|s, _, state: &mut NodeArena|
+    ///         // Return the ID of the new integer node
+    ///         state.insert(Expr::Int(s.parse().unwrap()))
+    ///     );
+    ///
+    /// let sum = int.foldl_with_state(
+    ///     just('+').padded().ignore_then(int).repeated(),
+    ///     |a: NodeId, b: NodeId, state: &mut NodeArena| {
+    ///         // Inserting an item into the arena returns its ID
+    ///         state.insert(Expr::Add(a, b))
+    ///     }
+    /// );
+    ///
+    /// // Test our parser
+    /// let mut arena = NodeArena::default();
+    /// let four_plus_eight = sum.parse_with_state("4 + 8", &mut arena).unwrap();
+    /// if let Expr::Add(a, b) = arena[four_plus_eight] {
+    ///     assert_eq!(arena[a], Expr::Int(4));
+    ///     assert_eq!(arena[b], Expr::Int(8));
+    /// } else {
+    ///     panic!("Not an Expr::Add");
+    /// }
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn foldl_with_state<B, F, OB>(self, other: B, f: F) -> FoldlWithState<F, Self, B, OB, E>
+    where
+        F: Fn(O, OB, &mut E::State) -> O,
+        B: IterParser<'a, I, OB, E>,
+        Self: Sized,
+    {
+        FoldlWithState {
+            parser_a: self,
+            parser_b: other,
+            folder: f,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Parse a pattern. Afterwards, the input stream will be rewound to its original state, as if parsing had not
+    /// occurred.
+    ///
+    /// This combinator is useful for cases in which you wish to avoid a parser accidentally consuming too much input,
+    /// causing later parsers to fail as a result. A typical use-case of this is that you want to parse something that
+    /// is not followed by something else.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let just_numbers = text::digits::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .slice()
+    ///     .padded()
+    ///     .then_ignore(none_of("+-*/").rewind())
+    ///     .separated_by(just(','))
+    ///     .collect::<Vec<_>>();
+    /// // 3 is not parsed because it's followed by '+'.
+    /// assert_eq!(just_numbers.lazy().parse("1, 2, 3 + 4").into_result(), Ok(vec!["1", "2"]));
+    /// ```
+    fn rewind(self) -> Rewind<Self>
+    where
+        Self: Sized,
+    {
+        Rewind { parser: self }
+    }
+
+    /// Make the parser lazy, such that it parses as much as it validly can and then finished successfully, leaving
+    /// trailing input untouched.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let digits = one_of::<_, _, extra::Err<Simple<char>>>('0'..='9')
+    ///     .repeated()
+    ///     .collect::<String>()
+    ///     .lazy();
+    ///
+    /// assert_eq!(digits.parse("12345abcde").into_result().as_deref(), Ok("12345"));
+    /// ```
+    fn lazy(self) -> Lazy<'a, Self, I, E>
+    where
+        Self: Sized,
+        I: ValueInput<'a>,
+    {
+        self.then_ignore(any().repeated())
+    }
+
+    /// Parse a pattern, ignoring any amount of whitespace both before and after the pattern.
+    ///
+    /// Skipping whitespace never produces expectations of its own, so if the input ends after trailing whitespace and
+    /// the next parser fails there, the error points at the end of input and names only what that parser expected.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>().padded();
+    ///
+    /// // A pattern with no whitespace surrounding it is accepted
+    /// assert_eq!(ident.parse("hello").into_result(), Ok("hello"));
+    /// // A pattern with arbitrary whitespace surrounding it is also accepted
+    /// assert_eq!(ident.parse(" \t \n  \t   world  \t  ").into_result(), Ok("world"));
+    /// ```
+    fn padded(self) -> Padded<Self>
+    where
+        Self: Sized,
+        I: Input<'a>,
+        I::Token: Char,
+    {
+        Padded { parser: self }
+    }
+
+    // /// Flatten a nested collection.
+    // ///
+    // /// This use-cases of this method are broadly similar to those of [`Iterator::flatten`].
+    // ///
+    // /// The output type of this parser is `Vec<T>`, where the original parser output was
+    // /// `impl IntoIterator<Item = impl IntoIterator<Item = T>>`.
+    // fn flatten<T, Inner>(self) -> Map<Self, O, fn(O) -> Vec<T>>
+    // where
+    //     Self: Sized,
+    //     O: IntoIterator<Item = Inner>,
+    //     Inner: IntoIterator<Item = T>,
+    // {
+    //     self.map(|xs| xs.into_iter().flat_map(|xs| xs.into_iter()).collect())
+    // }
+
+    /// Apply a fallback recovery strategy to this parser should it fail.
+    ///
+    /// There is no silver bullet for error recovery, so this function allows you to specify one of several different
+    /// strategies at the location of your choice. Prefer an error recovery strategy that more precisely mirrors valid
+    /// syntax where possible to make error recovery more reliable.
+    ///
+    /// Because chumsky is a [PEG](https://en.m.wikipedia.org/wiki/Parsing_expression_grammar) parser, which always
+    /// take the first successful parsing route through a grammar, recovering from an error may cause the parser to
+    /// erroneously miss alternative valid routes through the grammar that do not generate recoverable errors. If you
+    /// run into cases where valid syntax fails to parse without errors, this might be happening: consider removing
+    /// error recovery or switching to a more specific error recovery strategy.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// #[derive(Debug, PartialEq)]
+    /// enum Expr<'a> {
+    ///     Error,
+    ///     Int(&'a str),
+    ///     List(Vec<Expr<'a>>),
+    /// }
+    ///
+    /// let recovery = just::<_, _, extra::Err<Simple<char>>>('[')
+    ///         .then(none_of(']').repeated().then(just(']')));
+    ///
+    /// let expr = recursive::<_, _, extra::Err<Simple<char>>, _, _>(|expr| expr
+    ///     .separated_by(just(','))
+    ///     .collect::<Vec<_>>()
+    ///     .delimited_by(just('['), just(']'))
+    ///     .map(Expr::List)
+    ///     // If parsing a list expression fails, recover at the next delimiter, generating an error AST node
+    ///     .recover_with(via_parser(recovery.map(|_| Expr::Error)))
+    ///     .or(text::int(10).map(Expr::Int))
+    ///     .padded());
+    ///
+    /// assert!(expr.parse("five").has_errors()); // Text is not a valid expression in this language...
+    /// assert_eq!(
+    ///     expr.parse("[1, 2, 3]").into_result(),
+    ///     Ok(Expr::List(vec![Expr::Int("1"), Expr::Int("2"), Expr::Int("3")])),
+    /// ); // ...but lists and numbers are!
+    ///
+    /// // This input has two syntax errors...
+    /// let res = expr.parse("[[1, two], [3, four]]");
+    /// // ...and error recovery allows us to catch both of them!
+    /// assert_eq!(res.errors().len(), 2);
+    /// // Additionally, the AST we get back still has useful information.
+    /// assert_eq!(res.output(), Some(&Expr::List(vec![Expr::Error, Expr::Error])));
+    /// ```
+    fn recover_with<S: Strategy<'a, I, O, E>>(self, strategy: S) -> RecoverWith<Self, S>
+    where
+        Self: Sized,
+    {
+        RecoverWith {
+            parser: self,
+            strategy,
+        }
+    }
+
+    /// Never fail: if this parser fails, emit its error as a secondary error and produce a default output instead.
+    ///
+    /// This is useful for running a parser purely for its diagnostics, such as in a "lint but don't fail" pass. Unlike
+    /// most recovery strategies, input that the parser consumed before failing is not rewound: parsing continues from
+    /// the point at which the failure occurred, so a malformed construct is skipped rather than re-parsed.
+    ///
+    /// Because this parser always succeeds, it should not be repeated unless something else in the repetition is
+    /// guaranteed to consume input, such as a separator.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let num = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .then_ignore(just(';'))
+    ///     .tolerant("0");
+    /// let nums = num.separated_by(just(' ')).collect::<Vec<_>>();
+    ///
+    /// let (out, errs) = nums.parse("1; 2: 3;").into_output_errors();
+    /// assert_eq!(out, Some(vec!["1", "0", "3"]));
+    /// assert_eq!(errs.len(), 1);
+    /// ```
+    fn tolerant(self, default: O) -> Tolerant<Self, O>
+    where
+        Self: Sized,
+        O: Clone,
+    {
+        Tolerant {
+            parser: self,
+            default,
+        }
+    }
+
+    /// Map the primary error of this parser to another value.
+    ///
+    /// This function is most useful when using a custom error type, allowing you to augment errors according to
+    /// context.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    // TODO: Map E -> D, not E -> E
+    fn map_err<F>(self, f: F) -> MapErr<Self, F>
+    where
+        Self: Sized,
+        F: Fn(E::Error) -> E::Error,
+    {
+        MapErr {
+            parser: self,
+            mapper: f,
+        }
+    }
+
+    /// Attach a suggestion (a fix-it, such as "did you mean `==`?") to the primary error of this parser.
+    ///
+    /// The given function is called with the error produced when this parser fails and may return a suggestion
+    /// that is attached to the error with [`Error::with_suggestion`]. Error types that cannot store suggestions
+    /// (such as [`Simple`]) silently discard them.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let eq = just::<_, _, extra::Err<Rich<char>>>('=')
+    ///     .then(just('=').suggest(|_| Some("did you mean `==`?".to_string())))
+    ///     .to(());
+    ///
+    /// let errs = eq.parse("= 1").into_errors();
+    /// assert_eq!(errs[0].suggestion(), Some("did you mean `==`?"));
+    /// ```
+    fn suggest<F>(self, f: F) -> Suggest<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&E::Error) -> Option<String>,
+    {
+        Suggest {
+            parser: self,
+            suggester: f,
+        }
+    }
+
+    /// Attach a related span to the primary error of this parser, along with a message explaining how it relates.
+    ///
+    /// This is useful for pointing diagnostics at more than one location, such as the opening delimiter that an
+    /// unclosed delimiter error refers to. The span is attached with [`Error::with_related_span`]; error types that
+    /// cannot store related spans (such as [`Simple`]) silently discard it, without the message ever being built.
+    ///
+    /// Because the related span is only known while parsing, it is provided from the context with
+    /// [`ConfigParser::configure`]. If no span is configured, the error is left unchanged.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let block = just::<_, _, extra::Err<Rich<char>>>('{')
+    ///     .map_with_span(|_, span| span)
+    ///     .ignore_with_ctx(
+    ///         just('}')
+    ///             .with_related_span("the `{` was opened here")
+    ///             .configure(|cfg, open| cfg.span(*open)),
+    ///     );
+    ///
+    /// let errs = block.parse("{").into_errors();
+    /// let related = errs[0].related_spans().collect::<Vec<_>>();
+    /// assert_eq!(related, vec![(&SimpleSpan::new(0, 1), "the `{` was opened here")]);
+    /// ```
+    fn with_related_span<T>(self, message: T) -> WithRelatedSpan<Self, T>
+    where
+        Self: Sized,
+        T: ToString,
+    {
+        WithRelatedSpan {
+            parser: self,
+            message,
+        }
+    }
+
+    // /// Map the primary error of this parser to another value, making use of the span from the start of the attempted
+    // /// to the point at which the error was encountered.
+    // ///
+    // /// This function is useful for augmenting errors to allow them to display the span of the initial part of a
+    // /// pattern, for example to add a "while parsing" clause to your error messages.
+    // ///
+    // /// The output type of this parser is `O`, the same as the original parser.
+    // ///
+    // // TODO: Map E -> D, not E -> E
+    // fn map_err_with_span<F>(self, f: F) -> MapErrWithSpan<Self, F>
+    // where
+    //     Self: Sized,
+    //     F: Fn(E::Error, I::Span) -> E::Error,
+    // {
+    //     MapErrWithSpan {
+    //         parser: self,
+    //         mapper: f,
+    //     }
+    // }
+
+    /// Map the primary error of this parser to another value, making use of the parser state.
+    ///
+    /// This function is useful for augmenting errors to allow them to include context in non context-free
+    /// languages, or provide contextual notes on possible causes.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    // TODO: Map E -> D, not E -> E
+    fn map_err_with_state<F>(self, f: F) -> MapErrWithState<Self, F>
+    where
+        Self: Sized,
+        F: Fn(E::Error, I::Span, &mut E::State) -> E::Error,
+    {
+        MapErrWithState {
+            parser: self,
+            mapper: f,
+        }
+    }
+
+    /// Validate an output, producing non-terminal errors if it does not fulfil certain criteria.
+    /// The errors will not immediately halt parsing on this path, but instead it will continue,
+    /// potentially emitting one or more other errors, only failing after the pattern has otherwise
+    /// successfully, or emitted another terminal error.
+    ///
+    /// This function also permits mapping the output to a value of another type, similar to [`Parser::map`].
+    ///
+    /// If you wish parsing of this pattern to halt when an error is generated instead of continuing, consider using
+    /// [`Parser::try_map`] instead.
+    ///
+    /// The output type of this parser is `U`, the result of the validation closure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let large_int = text::int::<_, _, extra::Err<Rich<char>>>(10)
+    ///     .from_str()
+    ///     .unwrapped()
+    ///     .validate(|x: u32, span, emitter| {
+    ///         if x < 256 { emitter.emit(Rich::custom(span, format!("{} must be 256 or higher.", x))) }
+    ///         x
+    ///     });
+    ///
+    /// assert_eq!(large_int.parse("537").into_result(), Ok(537));
+    /// assert!(large_int.parse("243").into_result().is_err());
+    /// ```
+    ///
+    /// To show the difference in behavior from [`Parser::try_map`]:
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// # use chumsky::util::MaybeRef;
+    /// # use chumsky::error::Error;
+    /// // start with the same large_int validator
+    /// let large_int_val = text::int::<_, _, extra::Err<Rich<char>>>(10)
+    ///         .from_str()
+    ///         .unwrapped()
+    ///         .validate(|x: u32, span, emitter| {
+    ///             if x < 256 { emitter.emit(Rich::custom(span, format!("{} must be 256 or higher", x))) }
+    ///             x
+    ///         });
+    ///
+    /// // A try_map version of the same parser
+    /// let large_int_tm = text::int::<_, _, extra::Err<Rich<char>>>(10)
+    ///         .from_str()
+    ///         .unwrapped()
+    ///         .try_map(|x: u32, span| {
+    ///             if x < 256 {
+    ///                 Err(Rich::custom(span, format!("{} must be 256 or higher", x)))
+    ///             } else {
+    ///                 Ok(x)
+    ///             }
+    ///         });
+    ///
+    /// // Parser that uses the validation version
+    /// let multi_step_val = large_int_val.then(text::ascii::ident().padded());
+    /// // Parser that uses the try_map version
+    /// let multi_step_tm = large_int_tm.then(text::ascii::ident().padded());
+    ///
+    /// // On success, both parsers are equivalent
+    /// assert_eq!(
+    ///     multi_step_val.parse("512 foo").into_result(),
+    ///     Ok((512, "foo"))
+    /// );
+    ///
+    /// assert_eq!(
+    ///     multi_step_tm.parse("512 foo").into_result(),
+    ///     Ok((512, "foo"))
+    /// );
+    ///
+    /// // However, on failure, they may produce different errors:
+    /// assert_eq!(
+    ///     multi_step_val.parse("100 2").into_result(),
+    ///     Err(vec![
+    ///         Rich::<char>::custom((0..3).into(), "100 must be 256 or higher"),
+    ///         <Rich<char> as Error<&str>>::expected_found([], Some(MaybeRef::Val('2')), (4..5).into()),
+    ///     ])
+    /// );
+    ///
+    /// assert_eq!(
+    ///     multi_step_tm.parse("100 2").into_result(),
+    ///     Err(vec![Rich::<char>::custom((0..3).into(), "100 must be 256 or higher")])
+    /// );
+    /// ```
+    ///
+    /// As is seen in the above example, validation doesn't prevent the emission of later errors in the
+    /// same parser, but still produces an error in the output.
+    ///
+    fn validate<U, F>(self, f: F) -> Validate<Self, O, F>
+    where
+        Self: Sized,
+        F: Fn(O, I::Span, &mut Emitter<E::Error>) -> U,
+    {
+        Validate {
+            parser: self,
+            validator: f,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Map the output of this parser to another value, emitting non-terminal errors (such as warnings) along the way.
+    ///
+    /// This is an alias of [`Parser::validate`], which can already change the output type. Unlike
+    /// [`Parser::try_map`], the mapping function always produces an output, so parsing continues even when errors are
+    /// emitted.
+    ///
+    /// The output type of this parser is `U`, the result of the mapping function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let byte = text::int::<_, _, extra::Err<Rich<char>>>(10)
+    ///     .from_str::<u32>()
+    ///     .unwrapped()
+    ///     .try_map_emit(|x, span, emitter| {
+    ///         if x > 255 {
+    ///             emitter.emit(Rich::custom(span, format!("{x} does not fit in a byte, saturating")));
+    ///         }
+    ///         x.min(255) as u8
+    ///     });
+    ///
+    /// assert_eq!(byte.parse("42").into_result(), Ok(42));
+    /// let (out, errs) = byte.parse("300").into_output_errors();
+    /// assert_eq!(out, Some(255));
+    /// assert_eq!(errs.len(), 1);
+    /// ```
+    fn try_map_emit<U, F>(self, f: F) -> Validate<Self, O, F>
+    where
+        Self: Sized,
+        F: Fn(O, I::Span, &mut Emitter<E::Error>) -> U,
+    {
+        self.validate(f)
+    }
+
+    /// Validate an output, producing non-terminal errors if it does not fulfil certain criteria, making use of the
+    /// parser's state when doing so.
+    ///
+    /// This is identical to [`Parser::validate`], except that the validator is also given mutable access to the
+    /// parser state. This is useful for checking outputs against (and updating) a symbol table or similar.
+    ///
+    /// The output type of this parser is `U`, the result of the validation closure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let label = text::ascii::ident::<_, _, extra::Full<Rich<char>, Vec<String>, ()>>()
+    ///     .then_ignore(just(':'))
+    ///     .padded()
+    ///     .validate_with_state(|name: &str, span, labels: &mut Vec<String>, emitter| {
+    ///         if labels.iter().any(|l| l == name) {
+    ///             emitter.emit(Rich::custom(span, format!("duplicate label `{}`", name)));
+    ///         }
+    ///         labels.push(name.to_string());
+    ///     })
+    ///     .repeated();
+    ///
+    /// let mut labels = Vec::new();
+    /// let errs = label.parse_with_state("start: loop: start:", &mut labels).into_errors();
+    ///
+    /// assert_eq!(labels, vec!["start", "loop", "start"]);
+    /// assert_eq!(errs.len(), 1);
+    /// assert_eq!(errs[0].to_string(), "duplicate label `start`");
+    /// ```
+    fn validate_with_state<U, F>(self, f: F) -> ValidateWithState<Self, O, F>
+    where
+        Self: Sized,
+        F: Fn(O, I::Span, &mut E::State, &mut Emitter<E::Error>) -> U,
+    {
+        ValidateWithState {
+            parser: self,
+            validator: f,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    // /// Map the primary error of this parser to a result. If the result is [`Ok`], the parser succeeds with that value.
+    // ///
+    // /// Note that, if the closure returns [`Err`], the parser will not consume any input.
+    // ///
+    // /// The output type of this parser is `U`, the [`Ok`] type of the result.
+    // fn or_else<F>(self, f: F) -> OrElse<Self, F>
+    // where
+    //     Self: Sized,
+    //     F: Fn(E::Error) -> Result<O, E::Error>,
+    // {
+    //     OrElse {
+    //         parser: self,
+    //         or_else: f,
+    //     }
+    // }
+
+    /// Attempt to convert the output of this parser into something else using Rust's [`FromStr`] trait.
+    ///
+    /// This is most useful when wanting to convert literal values into their corresponding Rust type, such as when
+    /// parsing integers.
+    ///
+    /// The output type of this parser is `Result<U, U::Err>`, the result of attempting to parse the output, `O`, into
+    /// the value `U`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let uint64 = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .from_str::<u64>()
+    ///     .unwrapped();
+    ///
+    /// assert_eq!(uint64.parse("7").into_result(), Ok(7));
+    /// assert_eq!(uint64.parse("42").into_result(), Ok(42));
+    /// ```
+    #[allow(clippy::wrong_self_convention)]
+    fn from_str<U>(self) -> Map<Self, O, fn(O) -> Result<U, U::Err>>
+    where
+        Self: Sized,
+        U: FromStr,
+        O: AsRef<str>,
+    {
+        self.map(|o| o.as_ref().parse())
+    }
+
+    /// For parsers that produce a [`Result`] as their output, unwrap the result (panicking if an [`Err`] is
+    /// encountered).
+    ///
+    /// In general, this method should be avoided except in cases where all possible that the parser might produce can
+    /// by parsed using [`FromStr`] without producing an error.
+    ///
+    /// This combinator is not named `unwrap` to avoid confusion: it unwraps *during parsing*, not immediately.
+    ///
+    /// The output type of this parser is `U`, the [`Ok`] value of the [`Result`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let boolean = just::<_, _, extra::Err<Simple<char>>>("true")
+    ///     .or(just("false"))
+    ///     .from_str::<bool>()
+    ///     .unwrapped(); // Cannot panic: the only possible outputs generated by the parser are "true" or "false"
+    ///
+    /// assert_eq!(boolean.parse("true").into_result(), Ok(true));
+    /// assert_eq!(boolean.parse("false").into_result(), Ok(false));
+    /// // Does not panic, because the original parser only accepts "true" or "false"
+    /// assert!(boolean.parse("42").has_errors());
+    /// ```
+    #[track_caller]
+    fn unwrapped(self) -> Unwrapped<Self, O>
+    where
+        Self: Sized,
+    {
+        Unwrapped {
+            parser: self,
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Turn this [`Parser`] into an [`IterParser`] if its output type implements [`IntoIterator`].
+    ///
+    /// The resulting iterable parser will emit each element of the output type in turn.
+    ///
+    /// This is *broadly* analgous to functions like [`Vec::into_iter`], but operating at the level of parser outputs.
+    // TODO: Example
+    fn into_iter(self) -> IntoIter<Self, O>
+    where
+        Self: Sized,
+        O: IntoIterator,
+    {
+        IntoIter {
+            parser: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Box the parser, yielding a parser that performs parsing through dynamic dispatch.
+    ///
+    /// Boxing a parser might be useful for:
+    ///
+    /// - Dynamically building up parsers at run-time
+    ///
+    /// - Improving compilation times (Rust can struggle to compile code containing very long types)
+    ///
+    /// - Passing a parser over an FFI boundary
+    ///
+    /// - Getting around compiler implementation problems with long types such as
+    ///   [this](https://github.com/rust-lang/rust/issues/54540).
+    ///
+    /// - Places where you need to name the type of a parser
+    ///
+    /// Boxing a parser is broadly equivalent to boxing other combinators via dynamic dispatch, such as [`Iterator`].
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// When not using `boxed`, the following patterns are either impossible or very difficult to express:
+    ///
+    /// ```compile_fail
+    /// # use chumsky::prelude::*;
+    ///
+    /// pub trait Parseable: Sized {
+    ///     type Parser<'a>: Parser<'a, &'a str, Self>;
+    ///
+    ///     fn parser<'a>() -> Self::Parser<'a>;
+    /// }
+    ///
+    /// impl Parseable for i32 {
+    ///     // We *can* write this type, but it will be very impractical, and change on any alterations
+    ///     // to the implementation
+    ///     type Parser<'a> = ???;
+    ///
+    ///     fn parser<'a>() -> Self::Parser<'a> {
+    ///         todo()
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// ```compile_fail
+    /// # use chumsky::prelude::*;
+    /// # fn user_input<'a>() -> impl IntoIterator<Item = impl Parser<'a, &'a str, char>> { [just('b')] }
+    ///
+    /// let user_input = user_input();
+    ///
+    /// let mut parser = just('a');
+    /// for i in user_input {
+    ///     // Doesn't work due to type mismatch - since every combinator creates a unique type
+    ///     parser = parser.or(i);
+    /// }
+    ///
+    /// let parser = parser.then(just('z'));
+    /// let _ = parser.parse("b").into_result();
+    /// ```
+    ///
+    /// However, with `boxed`, we can express them by making the parsers all share a common type:
+    ///
+    /// ```
+    /// use chumsky::prelude::*;
+    ///
+    /// pub trait Parseable: Sized {
+    ///     fn parser<'a>() -> Boxed<'a, 'a, &'a str, Self, extra::Default>;
+    /// }
+    ///
+    /// impl Parseable for i32 {
+    ///     fn parser<'a>() -> Boxed<'a, 'a, &'a str, Self, extra::Default> {
+    ///         todo().boxed()
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// # fn user_input<'a>() -> impl IntoIterator<Item = impl Parser<'a, &'a str, char>> { [just('b'), just('c')] }
+    /// let user_input = user_input();
+    /// let mut parser = just('a').boxed();
+    /// for i in user_input {
+    ///     // Doesn't work due to type mismatch - since every combinator creates a unique type
+    ///     parser = parser.or(i).boxed();
+    /// }
+    /// let parser = parser.then(just('z'));
+    /// parser.parse("az").into_result().unwrap();
+    /// ```
+    ///
+    fn boxed<'b>(self) -> Boxed<'a, 'b, I, O, E>
+    where
+        Self: MaybeSync + Sized + 'a + 'b,
+    {
+        ParserSealed::boxed(self)
+    }
+
+    /// Box the parser behind an [`Arc`], yielding a parser that is always [`Send`] and [`Sync`].
+    ///
+    /// This is similar to [`Parser::boxed`], but is available regardless of whether the `sync` feature is enabled. It
+    /// is useful when a grammar is built once and then shared between many threads, each parsing their own inputs.
+    ///
+    /// Because the parser may be used from many threads, the parser (and any closures within it) must be both
+    /// [`Send`] and [`Sync`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let digits = text::int::<_, _, extra::Err<Simple<char>>>(10).arc_boxed();
+    ///
+    /// std::thread::scope(|s| {
+    ///     let a = s.spawn(|| digits.parse("42").into_result());
+    ///     let b = s.spawn(|| digits.parse("17").into_result());
+    ///     assert_eq!(a.join().unwrap(), Ok("42"));
+    ///     assert_eq!(b.join().unwrap(), Ok("17"));
+    /// });
+    /// ```
+    fn arc_boxed<'b>(self) -> ArcBoxed<'a, 'b, I, O, E>
+    where
+        Self: Send + Sync + Sized + 'a + 'b,
+    {
+        ArcBoxed {
+            inner: Arc::new(self),
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<'a, I, O, E> ParserSealed<'a, I, O, E> for !
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    fn go<M: Mode>(&self, _inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        *self
+    }
+
+    go_extra!(O);
+}
+
+/// A [`Parser`] that can be configured with runtime context. This allows for context-sensitive parsing
+/// of input. Note that chumsky only supports 'left'-sensitive parsing, where the context for a parser
+/// is derived from earlier in the input.
+///
+/// Chumsky distinguishes 'state' from 'context'. State is not able to change what input a parser
+/// accepts, but may be used to change the contents of the type it emits. In this way state is expected
+/// to be idempotent - combinators such as [`Parser::map_with_state`] are allowed to not call the
+/// provided closure at all if they don't emit any output. Context and configuration, on the other hand,
+/// is used to change what kind of input a parser may accept, and thus must always be evaluated. Context
+/// isn't usable in any map combinator however - while it may affect accepted input, it is not expected
+/// to change the final result outside of how it changes what the parser itself returns.
+///
+/// Not all parsers currently support configuration. If you feel like you need a parser to be configurable
+/// and it isn't currently, please open an issue on the issue tracker of the main repository.
+pub trait ConfigParser<'a, I, O, E>: ConfigParserSealed<'a, I, O, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    /// A combinator that allows configuration of the parser from the current context. Context
+    /// is most often derived from [`Parser::ignore_with_ctx`], [`Parser::then_with_ctx`] or [`map_ctx`],
+    /// and is how chumsky supports parsing things such as indentation-sensitive grammars.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    ///
+    /// let int = text::int::<_, _, extra::Err<Rich<char>>>(10)
+    ///     .from_str()
+    ///     .unwrapped();
+    ///
+    /// // By default, accepts any number of items
+    /// let item = text::ascii::ident()
+    ///     .padded()
+    ///     .repeated();
+    ///
+    /// // With configuration, we can declare an exact number of items based on a prefix length
+    /// let len_prefixed_arr = int
+    ///     .ignore_with_ctx(item.configure(|repeat, ctx| repeat.exactly(*ctx)).collect::<Vec<_>>());
+    ///
+    /// assert_eq!(
+    ///     len_prefixed_arr.parse("2 foo bar").into_result(),
+    ///     Ok(vec!["foo", "bar"]),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     len_prefixed_arr.parse("0").into_result(),
+    ///     Ok(vec![]),
+    /// );
+    ///
+    /// len_prefixed_arr.parse("3 foo bar baz bam").into_result().unwrap_err();
+    /// len_prefixed_arr.parse("3 foo bar").into_result().unwrap_err();
+    /// ```
+    fn configure<F>(self, cfg: F) -> Configure<Self, F>
+    where
+        Self: Sized,
+        F: Fn(Self::Config, &E::Context) -> Self::Config,
+    {
+        Configure { parser: self, cfg }
+    }
+}
+
+/// An iterator that wraps an iterable parser. See [`IterParser::parse_iter`].
+#[cfg(test)]
+pub struct ParserIter<'a, 'iter, P: IterParser<'a, I, O, E>, I: Input<'a>, O, E: ParserExtra<'a, I>>
+{
+    parser: P,
+    offset: I::Offset,
+    own: InputOwn<'a, 'iter, I, E>,
+    iter_state: Option<P::IterState<Emit>>,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(&'a (), O)>,
+}
+
+#[cfg(test)]
+impl<'a, 'iter, P, I: Input<'a>, O, E: ParserExtra<'a, I>> Iterator
+    for ParserIter<'a, 'iter, P, I, O, E>
+where
+    P: IterParser<'a, I, O, E>,
+{
+    type Item = O;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut inp = self.own.as_ref_at(self.offset);
+        let parser = &self.parser;
+
+        let iter_state = match &mut self.iter_state {
+            Some(state) => state,
+            None => {
+                let state = parser.make_iter::<Emit>(&mut inp).ok()?;
+                self.iter_state = Some(state);
+                self.iter_state.as_mut().unwrap()
+            }
+        };
+
+        let res = parser.next::<Emit>(&mut inp, iter_state);
+        self.offset = inp.offset;
+        res.ok().and_then(|res| res)
+    }
+}
+
+/// An iterator over the records of an input. See [`Parser::iter_matches`].
+pub struct IterMatches<'a, 'p, P, D, I: Input<'a>, O, OD, E: ParserExtra<'a, I>> {
+    parser: &'p P,
+    delimiter: D,
+    offset: I::Offset,
+    own: InputOwn<'a, 'p, I, E>,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(O, OD)>,
+}
+
+impl<'a, 'p, P, D, I, O, OD, E> Iterator for IterMatches<'a, 'p, P, D, I, O, OD, E>
+where
+    P: Parser<'a, I, O, E>,
+    D: Parser<'a, I, OD, E>,
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    type Item = (Result<O, Vec<E::Error>>, I::Span);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut inp = self.own.as_ref_at(self.offset);
+        let start = inp.offset();
+        let before = inp.save();
+        inp.next_maybe_inner().1?;
+        inp.rewind(before);
+
+        let res = self.parser.go::<Emit>(&mut inp).and_then(|out| {
+            let record_end = inp.save();
+            let terminated = self.delimiter.go::<Check>(&mut inp).is_ok() || {
+                inp.rewind(record_end);
+                end().go::<Check>(&mut inp).is_ok()
+            };
+            inp.rewind(record_end);
+            if terminated {
+                Ok(out)
+            } else {
+                Err(())
+            }
+        });
+        if res.is_err() {
+            inp.errors.secondary.extend(inp.errors.alt.take());
+            // Resynchronise by skipping to the end of the record
+            loop {
+                let before = inp.save();
+                if self.delimiter.go::<Check>(&mut inp).is_ok() {
+                    inp.rewind(before);
+                    break;
+                }
+                inp.rewind(before);
+                if inp.next_maybe_inner().1.is_none() {
+                    break;
+                }
+            }
+        }
+        inp.errors.alt = None;
+        let mut span = inp.span_since(start);
+
+        // Skip past the delimiter, if there is one
+        let before = inp.save();
+        if self.delimiter.go::<Check>(&mut inp).is_err() {
+            inp.rewind(before);
+        }
+        inp.errors.alt = None;
+
+        // A record and delimiter that both match without consuming anything would match at the same place on every
+        // call, so skip a token to make sure that the iterator ends
+        if inp.offset() == start {
+            inp.next_maybe_inner();
+            span = inp.span_since(start);
+            if res.is_ok() {
+                let err = inp.no_progress_err(start);
+                inp.errors.secondary.push(Located::at(inp.offset, err));
+            }
+        }
+        self.offset = inp.offset;
+
+        let errs = inp
+            .errors
+            .secondary
+            .drain(..)
+            .map(|err| err.err)
+            .collect::<Vec<_>>();
+        match res {
+            Ok(out) if errs.is_empty() => Some((Ok(out), span)),
+            _ => Some((Err(errs), span)),
+        }
+    }
+}
+
+/// An incremental parse of an input, performed in bounded steps. See [`IterParser::parse_resumable`].
+pub struct Resumable<'a, P: IterParser<'a, I, O, E>, I: Input<'a>, O, E: ParserExtra<'a, I>> {
+    parser: P,
+    offset: I::Offset,
+    own: Option<InputOwn<'a, 'a, I, E>>,
+    iter_state: Option<P::IterState<Emit>>,
+    outputs: Vec<O>,
+}
+
+impl<'a, P, I, O, E> Resumable<'a, P, I, O, E>
+where
+    P: IterParser<'a, I, O, E>,
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    /// Parse at most `max_items` further items of the input.
+    ///
+    /// Returns [`Poll::Pending`] if the budget ran out before the parse finished, in which case `step` should be
+    /// called again later to continue from where it left off. Otherwise, returns [`Poll::Ready`] with the result of
+    /// the parse, which is the same as if the whole input had been parsed in one go.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called again after the parse has finished.
+    pub fn step(&mut self, max_items: usize) -> Poll<ParseResult<Vec<O>, E::Error>> {
+        let own = self
+            .own
+            .as_mut()
+            .expect("resumable parse stepped after it finished");
+        let mut inp = own.as_ref_at(self.offset);
+        let parser = &self.parser;
+
+        let res = match &mut self.iter_state {
+            Some(_) => Ok(()),
+            None => parser
+                .make_iter::<Emit>(&mut inp)
+                .map(|state| self.iter_state = Some(state)),
+        };
+        let res = res.and_then(|()| {
+            let iter_state = self.iter_state.as_mut().unwrap();
+            for _ in 0..max_items {
+                match parser.next::<Emit>(&mut inp, iter_state)? {
+                    Some(out) => self.outputs.push(out),
+                    None => return end().go::<Emit>(&mut inp).map(Some),
+                }
+            }
+            Ok(None)
+        });
+        self.offset = inp.offset;
+
+        let out = match res {
+            Ok(None) => return Poll::Pending,
+            Ok(Some(())) => Some(core::mem::take(&mut self.outputs)),
+            Err(()) => None,
+        };
+        let mut own = self.own.take().unwrap();
+        let alt = own.errors.alt.take();
+        let mut errs = own.into_errs();
+        if out.is_none() {
+            errs.push(alt.expect("error but no alt?").err);
+        }
+        Poll::Ready(ParseResult::new(out, errs))
+    }
+}
+
+/// An iterable equivalent of [`Parser`], i.e: a parser that generates a sequence of outputs.
+pub trait IterParser<'a, I, O, E = extra::Default>: IterParserSealed<'a, I, O, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    /// Collect this iterable parser into a [`Container`].
+    ///
+    /// This is commonly useful for collecting parsers that output many values into containers of various kinds:
+    /// [`Vec`]s, [`String`]s, or even [`HashMap`]s. This method is analogous to [`Iterator::collect`].
+    ///
+    /// The output type of this iterable parser is `C`, the type being collected into.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let word = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_alphabetic()) // This parser produces an output of `char`
+    ///     .repeated() // This parser is iterable (i.e: implements `IterParser`)
+    ///     .collect::<String>(); // We collect the `char`s into a `String`
+    ///
+    /// assert_eq!(word.parse("hello").into_result(), Ok("hello".to_string()));
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn collect<C: Container<O>>(self) -> Collect<Self, O, C>
+    where
+        Self: Sized,
+    {
+        Collect {
+            parser: self,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Collect the outputs of this iterable parser, each of which is itself iterable, into a single [`Container`].
+    ///
+    /// This is analogous to collecting an [`Iterator::flatten`], and is most useful for lexers that produce several
+    /// tokens from a single match (by using [`Parser::map`] to turn each match into a sequence of tokens).
+    ///
+    /// The output type of this iterable parser is `C`, the type being collected into.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let pairs = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .then_ignore(just('='))
+    ///     .then(text::ascii::ident())
+    ///     .map(|(k, v)| [k, v])
+    ///     .separated_by(just(','))
+    ///     .collect_flat::<Vec<_>>();
+    ///
+    /// assert_eq!(pairs.parse("a=b,c=d").into_result(), Ok(vec!["a", "b", "c", "d"]));
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn collect_flat<C>(self) -> CollectFlat<Self, O, C>
+    where
+        Self: Sized,
+        O: IntoIterator,
+        C: Container<O::Item>,
+    {
+        CollectFlat {
+            parser: self,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Collect this iterable parser into an [`Arc<[O]>`](Arc), which can be cheaply shared.
+    ///
+    /// Because items cannot be pushed into an [`Arc<[O]>`](Arc) one by one, they are first collected into a [`Vec`],
+    /// which is then converted.
+    ///
+    /// The output type of this iterable parser is `Arc<[O]>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// # use std::sync::Arc;
+    /// let args = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .separated_by(just(','))
+    ///     .collect_arc();
+    ///
+    /// let args: Arc<[&str]> = args.parse("1,2").into_result().unwrap();
+    /// assert_eq!(&*args, &["1", "2"]);
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn collect_arc(self) -> Map<Collect<Self, O, Vec<O>>, Vec<O>, fn(Vec<O>) -> Arc<[O]>>
+    where
+        Self: Sized,
+    {
+        Map {
+            parser: self.collect(),
+            mapper: Arc::from as fn(_) -> _,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Collect this iterable parser into an [`Rc<[O]>`](Rc), which can be cheaply shared.
+    ///
+    /// This is the single-threaded equivalent of [`IterParser::collect_arc`].
+    ///
+    /// The output type of this iterable parser is `Rc<[O]>`.
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn collect_rc(self) -> Map<Collect<Self, O, Vec<O>>, Vec<O>, fn(Vec<O>) -> Rc<[O]>>
+    where
+        Self: Sized,
+    {
+        Map {
+            parser: self.collect(),
+            mapper: Rc::from as fn(_) -> _,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Collect this iterable parser into a [`ContainerExactly`].
+    ///
+    /// This is useful for situations where the number of items to consume is statically known.
+    /// A common use-case is collecting into an array.
+    ///
+    /// The output type of this iterable parser if `C`, the type being collected into.
+    ///
+    /// # Exmaples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let three_digit = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_numeric())
+    ///     .repeated()
+    ///     .collect_exactly::<[_; 3]>();
+    ///
+    /// assert_eq!(three_digit.parse("123").into_result(), Ok(['1', '2', '3']));
+    /// assert!(three_digit.parse("12").into_result().is_err());
+    /// assert!(three_digit.parse("1234").into_result().is_err());
+    /// ```
+    fn collect_exactly<C: ContainerExactly<O>>(self) -> CollectExactly<Self, O, C>
+    where
+        Self: Sized,
+    {
+        CollectExactly {
+            parser: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Collect this iterable parser into a [`Container`], rejecting items with duplicate keys.
+    ///
+    /// The `key` function is used to derive a key from each item. When an item's key has already been seen, the item
+    /// is not collected and `duplicate` is used to generate a non-fatal error from the key and the span of the item
+    /// (which, for [`Parser::separated_by`], excludes the separator). Parsing continues as normal afterwards.
+    ///
+    /// This is useful for things like struct fields or map keys, where duplicates are invalid but parsing should
+    /// continue so that further errors can be reported.
+    ///
+    /// The output type of this iterable parser is `C`, the type being collected into.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let fields = text::ascii::ident::<_, _, extra::Err<Rich<char>>>()
+    ///     .separated_by(just(','))
+    ///     .collect_unique::<Vec<_>, _, _, _>(
+    ///         |field: &&str| *field,
+    ///         |field, span| Rich::custom(span, format!("duplicate field `{}`", field)),
+    ///     );
+    ///
+    /// assert_eq!(fields.parse("x,y,z").into_result(), Ok(vec!["x", "y", "z"]));
+    ///
+    /// let (output, errs) = fields.parse("x,y,x").into_output_errors();
+    /// assert_eq!(output, Some(vec!["x", "y"]));
+    /// assert_eq!(errs[0].to_string(), "duplicate field `x`");
+    /// ```
+    fn collect_unique<C, K, F, G>(self, key: F, duplicate: G) -> CollectUnique<Self, F, G, O, C>
+    where
+        Self: Sized,
+        C: Container<O>,
+        K: Hash + Eq,
+        F: Fn(&O) -> K,
+        G: Fn(K, I::Span) -> E::Error,
+    {
+        CollectUnique {
+            parser: self,
+            key,
+            duplicate,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Collect this iterable parser into a [`Container`], checking that each item is in order relative to the one
+    /// before it.
+    ///
+    /// `in_order` is called with each pair of adjacent items and should return whether they are correctly ordered.
+    /// When they are not, `out_of_order` is used to generate a non-fatal error from the later item and its span (which,
+    /// for [`Parser::separated_by`], excludes the separator). Every item is still collected and parsing continues as
+    /// normal afterwards.
+    ///
+    /// The output type of this iterable parser is `C`, the type being collected into.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let keys = text::ascii::ident::<_, _, extra::Err<Rich<char>>>()
+    ///     .separated_by(just(','))
+    ///     .collect_sorted::<Vec<_>, _, _>(
+    ///         |a, b| a < b,
+    ///         |key, span| Rich::custom(span, format!("`{}` is out of order", key)),
+    ///     );
+    ///
+    /// assert_eq!(keys.parse("a,b,c").into_result(), Ok(vec!["a", "b", "c"]));
+    ///
+    /// let (output, errs) = keys.parse("a,c,b").into_output_errors();
+    /// assert_eq!(output, Some(vec!["a", "c", "b"]));
+    /// assert_eq!(errs[0].to_string(), "`b` is out of order");
+    /// ```
+    fn collect_sorted<C, F, G>(
+        self,
+        in_order: F,
+        out_of_order: G,
+    ) -> CollectSorted<Self, F, G, O, C>
+    where
+        Self: Sized,
+        C: Container<O>,
+        O: Clone,
+        F: Fn(&O, &O) -> bool,
+        G: Fn(&O, I::Span) -> E::Error,
+    {
+        CollectSorted {
+            parser: self,
+            in_order,
+            out_of_order,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Collect this iterable parser into a [`usize`], outputting the number of elements that were parsed.
+    ///
+    /// This is sugar for [`.collect::<usize>()`](Self::collect).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    ///
+    /// // Counts how many chess squares are in the input.
+    /// let squares = one_of::<_, _, extra::Err<Simple<char>>>('a'..='z').then(one_of('1'..='8')).padded().repeated().count();
+    ///
+    /// assert_eq!(squares.parse("a1 b2 c3").into_result(), Ok(3));
+    /// assert_eq!(squares.parse("e5 e7 c6 c7 f6 d5 e6 d7 e4 c5 d6 c4 b6 f5").into_result(), Ok(14));
+    /// assert_eq!(squares.parse("").into_result(), Ok(0));
+    /// ```
+    fn count(self) -> Collect<Self, O, usize>
+    where
+        Self: Sized,
+    {
+        self.collect()
+    }
+
+    /// Fold the outputs of this iterable parser into a single value, starting from `init`.
+    ///
+    /// This behaves in a similar way to [`Iterator::fold`] and avoids collecting outputs into an intermediate
+    /// container. The fold function only runs when the output of the parser is actually needed.
+    ///
+    /// The output type of this parser is `T`, the type of the accumulator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .from_str::<u64>()
+    ///     .unwrapped();
+    ///
+    /// let sum = int
+    ///     .separated_by(just('+'))
+    ///     .fold(0, |acc, x| acc + x);
+    ///
+    /// assert_eq!(sum.parse("1+2+3").into_result(), Ok(6));
+    /// assert_eq!(sum.parse("").into_result(), Ok(0));
+    /// ```
+    fn fold<T, F>(self, init: T, f: F) -> Fold<Self, O, T, F>
+    where
+        Self: Sized,
+        T: Clone,
+        F: Fn(T, O) -> T,
+    {
+        Fold {
+            parser: self,
+            init,
+            folder: f,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Enumerate outputs of this iterable parser.
+    ///
+    /// This function behaves in a similar way to [`Iterator::enumerate`].
+    ///
+    /// The output type of this iterable parser is `(usize, O)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let word = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .padded()
+    ///     .repeated() // This parser is iterable (i.e: implements `IterParser`)
+    ///     .enumerate()
+    ///     .collect::<Vec<(usize, &str)>>();
+    ///
+    /// assert_eq!(word.parse("hello world").into_result(), Ok(vec![(0, "hello"), (1, "world")]));
+    /// ```
+    fn enumerate(self) -> Enumerate<Self, O>
+    where
+        Self: Sized,
+    {
+        Enumerate {
+            parser: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Stop this iterable parser, successfully, as soon as `marker` matches before an item.
+    ///
+    /// When the marker is found, it is consumed and parsing continues after it with whatever follows this parser. This
+    /// is useful for formats that end with a sentinel after which anything may appear, such as `__END__` in Perl: the
+    /// rest of the input can then be skipped with [`Parser::lazy`], or captured by the next parser.
+    ///
+    /// The marker only ends the parser once it has produced as many items as it requires (such as with
+    /// [`Repeated::at_least`]). Before then the marker is treated like any other input, so finding it where an item
+    /// is still required is an error.
+    ///
+    /// The output type of this iterable parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let lines = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .padded()
+    ///     .repeated()
+    ///     .stop_on(just("__END__"))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(lines.lazy().parse("a b\n__END__ ?!%").into_result(), Ok(vec!["a", "b"]));
+    /// // The input after the marker is left for the next parser
+    /// let data = lines.then(any().repeated().slice());
+    /// assert_eq!(data.parse("a\n__END__ ?!%").into_result(), Ok((vec!["a"], " ?!%")));
+    /// ```
+    fn stop_on<B, OB>(self, marker: B) -> StopOn<Self, B, OB>
+    where
+        Self: Sized,
+        B: Parser<'a, I, OB, E>,
+    {
+        StopOn {
+            parser: self,
+            marker,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Right-fold the output of the parser into a single value.
+    ///
+    /// The output of the original parser must be of type `(impl IntoIterator<Item = A>, B)`. Because right-folds work
+    /// backwards, the iterator must implement [`DoubleEndedIterator`] so that it can be reversed.
+    ///
+    /// The output type of this iterable parser is `B`, the right-hand component of the original parser's output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .from_str()
+    ///     .unwrapped();
+    ///
+    /// let signed = just('+').to(1)
+    ///     .or(just('-').to(-1))
+    ///     .repeated()
+    ///     .foldr(int, |a, b| a * b);
+    ///
+    /// assert_eq!(signed.parse("3").into_result(), Ok(3));
+    /// assert_eq!(signed.parse("-17").into_result(), Ok(-17));
+    /// assert_eq!(signed.parse("--+-+-5").into_result(), Ok(5));
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn foldr<B, F, OA>(self, other: B, f: F) -> Foldr<F, Self, B, O, E>
+    where
+        F: Fn(O, OA) -> OA,
+        B: Parser<'a, I, OA, E>,
+        Self: Sized,
+    {
+        Foldr {
+            parser_a: self,
+            parser_b: other,
+            folder: f,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Right-fold the output of the parser into a single value, making use of the parser's state when doing so.
+    ///
+    /// The output of the original parser must be of type `(impl IntoIterator<Item = A>, B)`. Because right-folds work
+    /// backwards, the iterator must implement [`DoubleEndedIterator`] so that it can be reversed.
+    ///
+    /// The output type of this parser is `B`, the right-hand component of the original parser's output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let int = text::int::<_, _, extra::Full<Simple<char>, i32, ()>>(10)
+    ///     .from_str()
+    ///     .unwrapped();
+    ///
+    /// let signed = just('+').to(1)
+    ///     .or(just('-').to(-1))
+    ///     .repeated()
+    ///     .foldr_with_state(int, |a, b, state| {
+    ///         (*state) += 1;
+    ///         (a * b)
+    ///     });
+    ///
+    /// // Test our parser
+    /// let mut folds = 0i32;
+    /// assert_eq!(signed.parse_with_state("3", &mut folds).into_result(), Ok(3));
+    /// assert_eq!(signed.parse_with_state("-17", &mut folds).into_result(), Ok(-17));
+    /// assert_eq!(signed.parse_with_state("--+-+-5", &mut folds).into_result(), Ok(5));
+    /// ```
+    ///
+    ///
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn foldr_with_state<B, F, OA>(self, other: B, f: F) -> FoldrWithState<F, Self, B, OA, E>
+    where
+        F: Fn(O, OA, &mut E::State) -> OA,
+        B: Parser<'a, I, OA, E>,
+        Self: Sized,
+    {
+        FoldrWithState {
+            parser_a: self,
+            parser_b: other,
+            folder: f,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Begin parsing the given input incrementally, collecting the outputs into a [`Vec`].
+    ///
+    /// No parsing happens until [`Resumable::step`] is called. Each call parses a bounded number of items before
+    /// returning, keeping the position and any errors generated so far so that the next call can continue where the
+    /// last left off. This is useful for parsing very large inputs without blocking, such as in a UI that must keep
+    /// responding between steps.
+    ///
+    /// As with [`Parser::parse`], the entire input must be consumed for the parse to succeed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// # use core::task::Poll;
+    /// let nums = text::int::<_, _, extra::Err<Simple<char>>>(10).padded().repeated();
+    ///
+    /// let mut parse = nums.parse_resumable("1 2 3 4 5");
+    /// assert!(parse.step(2).is_pending());
+    /// assert!(parse.step(2).is_pending());
+    /// match parse.step(2) {
+    ///     Poll::Ready(res) => assert_eq!(res.into_result(), Ok(vec!["1", "2", "3", "4", "5"])),
+    ///     Poll::Pending => panic!("parse should have finished"),
+    /// }
+    /// ```
+    fn parse_resumable(self, input: I) -> Resumable<'a, Self, I, O, E>
+    where
+        Self: Sized,
+        E::State: Default,
+        E::Context: Default,
+    {
+        Resumable {
+            parser: self,
+            offset: input.start(),
+            own: Some(InputOwn::new(input)),
+            iter_state: None,
+            outputs: Vec::new(),
+        }
+    }
+
+    /// Create an iterator over the outputs generated by an iterable parser.
+    ///
+    /// Warning: Trailing errors will be ignored
+    // TODO: Stabilize once error handling is properly decided on
+    #[cfg(test)]
+    fn parse_iter(self, input: I) -> ParseResult<ParserIter<'a, 'static, Self, I, O, E>, E::Error>
+    where
+        Self: IterParser<'a, I, O, E> + Sized,
+        E::State: Default,
+        E::Context: Default,
+    {
+        ParseResult::new(
+            Some(ParserIter {
+                parser: self,
+                offset: input.start(),
+                own: InputOwn::new(input),
+                iter_state: None,
+                phantom: EmptyPhantom::new(),
+            }),
+            Vec::new(),
+        )
+    }
+
+    /// Create an iterator over the outputs generated by an iterable parser with the given parser state.
+    ///
+    /// Warning: Trailing errors will be ignored
+    // TODO: Stabilize once error handling is properly decided on
+    #[cfg(test)]
+    fn parse_iter_with_state<'parse>(
+        self,
+        input: I,
+        state: &'parse mut E::State,
+    ) -> ParseResult<ParserIter<'a, 'parse, Self, I, O, E>, E::Error>
+    where
+        Self: IterParser<'a, I, O, E> + Sized,
+        E::Context: Default,
+    {
+        ParseResult::new(
+            Some(ParserIter {
+                parser: self,
+                offset: input.start(),
+                own: InputOwn::new_state(input, state),
+                iter_state: None,
+                phantom: EmptyPhantom::new(),
+            }),
+            Vec::new(),
+        )
+    }
+}
+
+/// An iterable equivalent of [`ConfigParser`], i.e: a parser that generates a sequence of outputs and
+/// can be configured at runtime.
+pub trait ConfigIterParser<'a, I, O, E = extra::Default>:
+    ConfigIterParserSealed<'a, I, O, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    /// A combinator that allows configuration of the parser from the current context
+    fn configure<F>(self, cfg: F) -> IterConfigure<Self, F, O>
+    where
+        Self: Sized,
+        F: Fn(Self::Config, &E::Context) -> Self::Config,
+    {
+        IterConfigure {
+            parser: self,
+            cfg,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// A combinator that allows fallible configuration of the parser from the current context -
+    /// if an error is returned, parsing fails.
+    fn try_configure<F>(self, cfg: F) -> TryIterConfigure<Self, F, O>
+    where
+        Self: Sized,
+        F: Fn(Self::Config, &E::Context, I::Span) -> Result<Self::Config, E::Error>,
+    {
+        TryIterConfigure {
+            parser: self,
+            cfg,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+/// See [`Parser::boxed`].
+///
+/// Due to current implementation details, the inner value is not, in fact, a [`Box`], but is an [`Rc`] to facilitate
+/// efficient cloning. This is likely to change in the future. Unlike [`Box`], [`Rc`] has no size guarantees: although
+/// it is *currently* the same size as a raw pointer.
+// TODO: Don't use an Rc
+pub struct Boxed<'a, 'b, I: Input<'a>, O, E: ParserExtra<'a, I>> {
+    inner: RefC<DynParser<'a, 'b, I, O, E>>,
+}
+
+impl<'a, 'b, I: Input<'a>, O, E: ParserExtra<'a, I>> Clone for Boxed<'a, 'b, I, O, E> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, 'b, I, O, E> ParserSealed<'a, I, O, E> for Boxed<'a, 'b, I, O, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        M::invoke(&*self.inner, inp)
+    }
+
+    fn boxed<'c>(self) -> Boxed<'a, 'c, I, O, E>
+    where
+        Self: MaybeSync + Sized + 'a + 'c,
+    {
+        // Never double-box parsers
+        self
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::arc_boxed`].
+///
+/// Unlike [`Boxed`], this type always uses an [`Arc`] internally and is always [`Send`] and [`Sync`], regardless of
+/// whether the `sync` feature is enabled.
+pub struct ArcBoxed<'a, 'b, I: Input<'a>, O, E: ParserExtra<'a, I>> {
+    inner: Arc<dyn Parser<'a, I, O, E> + Send + Sync + 'b>,
+}
+
+impl<'a, 'b, I: Input<'a>, O, E: ParserExtra<'a, I>> Clone for ArcBoxed<'a, 'b, I, O, E> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, 'b, I, O, E> ParserSealed<'a, I, O, E> for ArcBoxed<'a, 'b, I, O, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        M::invoke(&*self.inner, inp)
+    }
+
+    go_extra!(O);
+}
+
+impl<'a, I, O, E, T> ParserSealed<'a, I, O, E> for ::alloc::boxed::Box<T>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    T: Parser<'a, I, O, E>,
+{
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    where
+        Self: Sized,
+    {
+        T::go::<M>(self, inp)
+    }
+
+    go_extra!(O);
+}
+
+impl<'a, I, O, E, T> ParserSealed<'a, I, O, E> for ::alloc::rc::Rc<T>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    T: Parser<'a, I, O, E>,
+{
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    where
+        Self: Sized,
+    {
+        T::go::<M>(self, inp)
+    }
+
+    go_extra!(O);
+}
+
+impl<'a, I, O, E, T> ParserSealed<'a, I, O, E> for ::alloc::sync::Arc<T>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    T: Parser<'a, I, O, E>,
+{
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    where
+        Self: Sized,
+    {
+        T::go::<M>(self, inp)
+    }
+
+    go_extra!(O);
+}
+
+/// Create a parser that selects one or more input patterns and map them to an output value.
+///
+/// This is most useful when turning the tokens of a previous compilation pass (such as lexing) into data that can be
+/// used for parsing, although it can also generally be used to select inputs and map them to outputs. Any unmapped
+/// input patterns will become syntax errors, just as with [`Parser::filter`].
+///
+/// Internally, [`select!`] is very similar to [`Parser::try_map`] and thinking of it as such might make it less
+/// confusing.
+///
+/// `select!` requires that tokens implement [`Clone`].
+///
+/// If you're trying to access tokens referentially (for the sake of nested parsing, or simply because you want to
+/// avoid cloning the token), see [`select_ref!`].
+///
+/// # Examples
+///
+/// `select!` is syntactically similar to a `match` expression and has support for
+/// [pattern guards](https://doc.rust-lang.org/reference/expressions/match-expr.html#match-guards):
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Simple};
+/// #[derive(Clone)]
+/// enum Token<'a> { Ident(&'a str) }
 ///
 /// enum Expr<'a> { Local(&'a str), Null, True, False }
 ///
@@ -2711,148 +4745,562 @@ macro_rules! select {
     });
 }
 
-/// A version of [`select!`] that selects on token by reference instead of by value.
-///
-/// Useful if you want to extract elements from a token in a zero-copy manner.
-///
-/// `select_ref` requires that the parser input implements [`BorrowInput`].
-#[macro_export]
-macro_rules! select_ref {
-    ($($p:pat $(= $span:ident)? $(if $guard:expr)? $(=> $out:expr)?),+ $(,)?) => ({
-        $crate::primitive::select_ref(
-            move |x, span| match x {
-                $($p $(if $guard)? => ::core::option::Option::Some({ $(let $span = span;)? () $(;$out)? })),+,
-                _ => ::core::option::Option::None,
-            }
-        )
-    });
-}
+/// A version of [`select!`] that selects on token by reference instead of by value.
+///
+/// Useful if you want to extract elements from a token in a zero-copy manner.
+///
+/// `select_ref` requires that the parser input implements [`BorrowInput`].
+#[macro_export]
+macro_rules! select_ref {
+    ($($p:pat $(= $span:ident)? $(if $guard:expr)? $(=> $out:expr)?),+ $(,)?) => ({
+        $crate::primitive::select_ref(
+            move |x, span| match x {
+                $($p $(if $guard)? => ::core::option::Option::Some({ $(let $span = span;)? () $(;$out)? })),+,
+                _ => ::core::option::Option::None,
+            }
+        )
+    });
+}
+
+/// Parse one of several alternatives, in order. Shorthand for [`choice`] applied to a tuple of parsers.
+///
+/// `alt![a, b, c]` expands to `choice((a, b, c))` and so has exactly the same semantics: each parser is tried in turn
+/// and the output of the first to succeed is produced. This is equivalent to chaining the parsers together with
+/// [`Parser::or`], but reads more naturally when there are many alternatives.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let op = alt![
+///     just::<_, _, extra::Err<Simple<char>>>("==").to("eq"),
+///     just("!=").to("ne"),
+///     just("=").to("assign"),
+/// ];
+///
+/// assert_eq!(op.parse("!=").into_result(), Ok("ne"));
+/// assert_eq!(op.parse("=").into_result(), Ok("assign"));
+/// assert!(op.parse("<").has_errors());
+/// ```
+#[macro_export]
+macro_rules! alt {
+    ($($p:expr),+ $(,)?) => ({
+        $crate::primitive::choice(($($p,)+))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_copy() {
+        use self::input::WithContext;
+        use self::prelude::*;
+
+        #[derive(PartialEq, Debug)]
+        enum Token<'a> {
+            Ident(&'a str),
+            String(&'a str),
+        }
+
+        type FileId = u32;
+        type Span = SimpleSpan<usize, FileId>;
+
+        fn parser<'a>() -> impl Parser<'a, WithContext<Span, &'a str>, [(Span, Token<'a>); 6]> {
+            let ident = any()
+                .filter(|c: &char| c.is_alphanumeric())
+                .repeated()
+                .at_least(1)
+                .map_slice(Token::Ident);
+
+            let string = just('"')
+                .then(any().filter(|c: &char| *c != '"').repeated())
+                .then(just('"'))
+                .map_slice(Token::String);
+
+            ident
+                .or(string)
+                .map_with_span(|token, span| (span, token))
+                .padded()
+                .repeated()
+                .collect_exactly()
+        }
+
+        assert_eq!(
+            parser()
+                .parse(r#"hello "world" these are "test" tokens"#.with_context(42))
+                .into_result(),
+            Ok([
+                (Span::new(42, 0..5), Token::Ident("hello")),
+                (Span::new(42, 6..13), Token::String("\"world\"")),
+                (Span::new(42, 14..19), Token::Ident("these")),
+                (Span::new(42, 20..23), Token::Ident("are")),
+                (Span::new(42, 24..30), Token::String("\"test\"")),
+                (Span::new(42, 31..37), Token::Ident("tokens")),
+            ]),
+        );
+    }
+
+    #[test]
+    fn zero_copy_map_span() {
+        use self::input::MappedSpan;
+        use self::prelude::*;
+
+        #[derive(PartialEq, Debug)]
+        enum Token<'a> {
+            Ident(&'a str),
+            String(&'a str),
+        }
+
+        type FileId<'a> = &'a str;
+        type Span<'a> = SimpleSpan<usize, FileId<'a>>;
+
+        fn parser<'a, F: Fn(SimpleSpan) -> Span<'a> + 'a>(
+        ) -> impl Parser<'a, MappedSpan<Span<'a>, &'a str, F>, [(Span<'a>, Token<'a>); 6]> {
+            let ident = any()
+                .filter(|c: &char| c.is_alphanumeric())
+                .repeated()
+                .at_least(1)
+                .map_slice(Token::Ident);
+
+            let string = just('"')
+                .then(any().filter(|c: &char| *c != '"').repeated())
+                .then(just('"'))
+                .map_slice(Token::String);
+
+            ident
+                .or(string)
+                .map_with_span(|token, span| (span, token))
+                .padded()
+                .repeated()
+                .collect_exactly()
+        }
+
+        let filename = "file.txt".to_string();
+        let fstr = filename.as_str();
+
+        assert_eq!(
+            parser()
+                .parse(
+                    r#"hello "world" these are "test" tokens"#
+                        .map_span(|span| Span::new(fstr, span.start()..span.end()))
+                )
+                .into_result(),
+            Ok([
+                (Span::new("file.txt", 0..5), Token::Ident("hello")),
+                (Span::new("file.txt", 6..13), Token::String("\"world\"")),
+                (Span::new("file.txt", 14..19), Token::Ident("these")),
+                (Span::new("file.txt", 20..23), Token::Ident("are")),
+                (Span::new("file.txt", 24..30), Token::String("\"test\"")),
+                (Span::new("file.txt", 31..37), Token::Ident("tokens")),
+            ]),
+        );
+    }
+
+    #[test]
+    fn zero_copy_repetition() {
+        use self::prelude::*;
+
+        fn parser<'a>() -> impl Parser<'a, &'a str, Vec<u64>> {
+            any()
+                .filter(|c: &char| c.is_ascii_digit())
+                .repeated()
+                .at_least(1)
+                .at_most(3)
+                .map_slice(|b: &str| b.parse::<u64>().unwrap())
+                .padded()
+                .separated_by(just(',').padded())
+                .allow_trailing()
+                .collect()
+                .delimited_by(just('['), just(']'))
+        }
+
+        assert_eq!(
+            parser().parse("[122 , 23,43,    4, ]").into_result(),
+            Ok(vec![122, 23, 43, 4]),
+        );
+        assert_eq!(
+            parser().parse("[0, 3, 6, 900,120]").into_result(),
+            Ok(vec![0, 3, 6, 900, 120]),
+        );
+        assert_eq!(
+            parser().parse("[200,400,50  ,0,0, ]").into_result(),
+            Ok(vec![200, 400, 50, 0, 0]),
+        );
+
+        assert!(parser().parse("[1234,123,12,1]").has_errors());
+        assert!(parser().parse("[,0, 1, 456]").has_errors());
+        assert!(parser().parse("[3, 4, 5, 67 89,]").has_errors());
+    }
+
+    #[test]
+    fn zero_copy_group() {
+        use self::prelude::*;
+
+        fn parser<'a>() -> impl Parser<'a, &'a str, (&'a str, u64, char)> {
+            group((
+                any()
+                    .filter(|c: &char| c.is_ascii_alphabetic())
+                    .repeated()
+                    .at_least(1)
+                    .slice()
+                    .padded(),
+                any()
+                    .filter(|c: &char| c.is_ascii_digit())
+                    .repeated()
+                    .at_least(1)
+                    .map_slice(|s: &str| s.parse::<u64>().unwrap())
+                    .padded(),
+                any().filter(|c: &char| !c.is_whitespace()).padded(),
+            ))
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        assert_eq!(
+            parser().parse("abc 123 [").into_result(),
+            Ok(("abc", 123, '[')),
+        );
+        assert_eq!(
+            parser().parse("among3d").into_result(),
+            Ok(("among", 3, 'd')),
+        );
+        assert_eq!(
+            parser().parse("cba321,").into_result(),
+            Ok(("cba", 321, ',')),
+        );
+
+        assert!(parser().parse("abc 123  ").has_errors());
+        assert!(parser().parse("123abc ]").has_errors());
+        assert!(parser().parse("and one &").has_errors());
+    }
 
     #[test]
-    fn zero_copy() {
-        use self::input::WithContext;
+    fn zero_copy_group_array() {
+        use self::prelude::*;
+
+        fn parser<'a>() -> impl Parser<'a, &'a str, [char; 3]> {
+            group([just('a'), just('b'), just('c')])
+        }
+
+        assert_eq!(parser().parse("abc").into_result(), Ok(['a', 'b', 'c']));
+        assert!(parser().parse("abd").has_errors());
+    }
+
+    #[test]
+    fn unicode_str() {
+        let input = "🄯🄚🹠🴎🄐🝋🰏🄂🬯🈦g🸵🍩🕔🈳2🬙🨞🅢🭳🎅h🵚🧿🏩🰬k🠡🀔🈆🝹🤟🉗🴟📵🰄🤿🝜🙘🹄5🠻🡉🱖🠓";
+        let mut own = InputOwn::<_, extra::Default>::new(input);
+        let mut inp = own.as_ref_start();
+
+        while let Some(_c) = inp.next() {}
+    }
+
+    #[test]
+    fn iter() {
+        use self::prelude::*;
+
+        fn parser<'a>() -> impl IterParser<'a, &'a str, char> {
+            any().repeated()
+        }
+
+        let mut chars = String::new();
+        for c in parser().parse_iter(&"abcdefg").into_result().unwrap() {
+            chars.push(c);
+        }
+
+        assert_eq!(&chars, "abcdefg");
+    }
+
+    #[test]
+    #[cfg(feature = "memoization")]
+    fn exponential() {
+        use self::prelude::*;
+
+        fn parser<'a>() -> impl Parser<'a, &'a str, String> {
+            recursive(|expr| {
+                let atom = any()
+                    .filter(|c: &char| c.is_alphabetic())
+                    .repeated()
+                    .at_least(1)
+                    .collect()
+                    .or(expr.delimited_by(just('('), just(')')));
+
+                atom.clone()
+                    .then_ignore(just('+'))
+                    .then(atom.clone())
+                    .map(|(a, b)| format!("{}{}", a, b))
+                    .memoized()
+                    .or(atom)
+            })
+            .then_ignore(end())
+        }
+
+        parser()
+            .parse("((((((((((((((((((((((((((((((a+b))))))))))))))))))))))))))))))")
+            .into_result()
+            .unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "memoization")]
+    fn left_recursive() {
         use self::prelude::*;
 
-        #[derive(PartialEq, Debug)]
-        enum Token<'a> {
-            Ident(&'a str),
-            String(&'a str),
+        fn parser<'a>() -> impl Parser<'a, &'a str, String> {
+            recursive(|expr| {
+                let atom = any()
+                    .filter(|c: &char| c.is_alphabetic())
+                    .repeated()
+                    .at_least(1)
+                    .collect();
+
+                let sum = expr
+                    .clone()
+                    .then_ignore(just('+'))
+                    .then(expr)
+                    .map(|(a, b)| format!("{}{}", a, b))
+                    .memoized();
+
+                sum.or(atom)
+            })
+            .then_ignore(end())
+        }
+
+        assert_eq!(parser().parse("a+b+c").into_result().unwrap(), "abc");
+    }
+
+    #[cfg(debug_assertions)]
+    mod debug_asserts {
+        use super::prelude::*;
+
+        // TODO panic when left recursive parser is detected
+        // #[test]
+        // #[should_panic]
+        // fn debug_assert_left_recursive() {
+        //     recursive(|expr| {
+        //         let atom = any::<&str, extra::Default>()
+        //             .filter(|c: &char| c.is_alphabetic())
+        //             .repeated()
+        //             .at_least(1)
+        //             .collect();
+
+        //         let sum = expr
+        //             .clone()
+        //             .then_ignore(just('+'))
+        //             .then(expr)
+        //             .map(|(a, b)| format!("{}{}", a, b));
+
+        //         sum.or(atom)
+        //     })
+        //     .then_ignore(end())
+        //     .parse("a+b+c");
+        // }
+
+        #[test]
+        #[should_panic]
+        #[cfg(debug_assertions)]
+        fn debug_assert_collect() {
+            empty::<&str, extra::Default>()
+                .to(())
+                .repeated()
+                .collect::<()>()
+                .parse("a+b+c")
+                .unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        #[cfg(debug_assertions)]
+        fn debug_assert_separated_by() {
+            empty::<&str, extra::Default>()
+                .to(())
+                .separated_by(empty())
+                .collect::<()>()
+                .parse("a+b+c");
+        }
+
+        #[test]
+        fn debug_assert_separated_by2() {
+            assert_eq!(
+                empty::<&str, extra::Default>()
+                    .to(())
+                    .separated_by(just(','))
+                    .count()
+                    .parse(",")
+                    .unwrap(),
+                2
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        #[cfg(debug_assertions)]
+        fn debug_assert_foldl() {
+            assert_eq!(
+                empty::<&str, extra::Default>()
+                    .to(1)
+                    .foldl(empty().repeated(), |n, ()| n + 1)
+                    .parse("a+b+c")
+                    .unwrap(),
+                3
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        #[cfg(debug_assertions)]
+        fn debug_assert_foldl_with_state() {
+            let mut state = 100;
+            empty::<&str, extra::Full<EmptyErr, i32, ()>>()
+                .foldl_with_state(empty().to(()).repeated(), |_, _, _| ())
+                .parse_with_state("a+b+c", &mut state);
+        }
+
+        #[test]
+        #[should_panic]
+        #[cfg(debug_assertions)]
+        fn debug_assert_foldr() {
+            empty::<&str, extra::Default>()
+                .to(())
+                .repeated()
+                .foldr(empty(), |_, _| ())
+                .parse("a+b+c");
+        }
+
+        #[test]
+        #[should_panic]
+        #[cfg(debug_assertions)]
+        fn debug_assert_foldr_with_state() {
+            empty::<&str, extra::Default>()
+                .to(())
+                .repeated()
+                .foldr_with_state(empty(), |_, _, _| ())
+                .parse_with_state("a+b+c", &mut ());
+        }
+
+        #[test]
+        #[should_panic]
+        #[cfg(debug_assertions)]
+        fn debug_assert_repeated() {
+            empty::<&str, extra::Default>()
+                .to(())
+                .repeated()
+                .parse("a+b+c");
         }
 
-        type FileId = u32;
-        type Span = SimpleSpan<usize, FileId>;
+        // TODO what about IterConfigure and TryIterConfigure?
+    }
 
-        fn parser<'a>() -> impl Parser<'a, WithContext<Span, &'a str>, [(Span, Token<'a>); 6]> {
-            let ident = any()
-                .filter(|c: &char| c.is_alphanumeric())
+    #[test]
+    #[should_panic]
+    fn recursive_define_twice() {
+        let mut expr = Recursive::declare();
+        expr.define({
+            let atom = any::<&str, extra::Default>()
+                .filter(|c: &char| c.is_alphabetic())
                 .repeated()
                 .at_least(1)
-                .map_slice(Token::Ident);
-
-            let string = just('"')
-                .then(any().filter(|c: &char| *c != '"').repeated())
-                .then(just('"'))
-                .map_slice(Token::String);
+                .collect();
+            let sum = expr
+                .clone()
+                .then_ignore(just('+'))
+                .then(expr.clone())
+                .map(|(a, b)| format!("{}{}", a, b));
 
-            ident
-                .or(string)
-                .map_with_span(|token, span| (span, token))
-                .padded()
-                .repeated()
-                .collect_exactly()
-        }
+            sum.or(atom)
+        });
+        expr.define(expr.clone());
 
-        assert_eq!(
-            parser()
-                .parse(r#"hello "world" these are "test" tokens"#.with_context(42))
-                .into_result(),
-            Ok([
-                (Span::new(42, 0..5), Token::Ident("hello")),
-                (Span::new(42, 6..13), Token::String("\"world\"")),
-                (Span::new(42, 14..19), Token::Ident("these")),
-                (Span::new(42, 20..23), Token::Ident("are")),
-                (Span::new(42, 24..30), Token::String("\"test\"")),
-                (Span::new(42, 31..37), Token::Ident("tokens")),
-            ]),
-        );
+        expr.then_ignore(end()).parse("a+b+c");
     }
 
     #[test]
-    fn zero_copy_map_span() {
-        use self::input::MappedSpan;
-        use self::prelude::*;
+    #[should_panic]
+    fn todo_err() {
+        let expr = todo::<&str, String, extra::Default>();
+        expr.then_ignore(end()).parse("a+b+c");
+    }
 
-        #[derive(PartialEq, Debug)]
-        enum Token<'a> {
-            Ident(&'a str),
-            String(&'a str),
+    #[test]
+    fn arc_impl() {
+        fn parser<'a>() -> impl Parser<'a, &'a str, Vec<u64>> {
+            Arc::new(
+                any()
+                    .filter(|c: &char| c.is_ascii_digit())
+                    .repeated()
+                    .at_least(1)
+                    .at_most(3)
+                    .map_slice(|b: &str| b.parse::<u64>().unwrap())
+                    .padded()
+                    .separated_by(just(',').padded())
+                    .allow_trailing()
+                    .collect()
+                    .delimited_by(just('['), just(']')),
+            )
         }
 
-        type FileId<'a> = &'a str;
-        type Span<'a> = SimpleSpan<usize, FileId<'a>>;
-
-        fn parser<'a, F: Fn(SimpleSpan) -> Span<'a> + 'a>(
-        ) -> impl Parser<'a, MappedSpan<Span<'a>, &'a str, F>, [(Span<'a>, Token<'a>); 6]> {
-            let ident = any()
-                .filter(|c: &char| c.is_alphanumeric())
-                .repeated()
-                .at_least(1)
-                .map_slice(Token::Ident);
-
-            let string = just('"')
-                .then(any().filter(|c: &char| *c != '"').repeated())
-                .then(just('"'))
-                .map_slice(Token::String);
+        assert_eq!(
+            parser().parse("[122 , 23,43,    4, ]").into_result(),
+            Ok(vec![122, 23, 43, 4]),
+        );
+        assert_eq!(
+            parser().parse("[0, 3, 6, 900,120]").into_result(),
+            Ok(vec![0, 3, 6, 900, 120]),
+        );
+        assert_eq!(
+            parser().parse("[200,400,50  ,0,0, ]").into_result(),
+            Ok(vec![200, 400, 50, 0, 0]),
+        );
+    }
 
-            ident
-                .or(string)
-                .map_with_span(|token, span| (span, token))
-                .padded()
-                .repeated()
-                .collect_exactly()
+    #[test]
+    fn box_impl() {
+        fn parser<'a>() -> impl Parser<'a, &'a str, Vec<u64>> {
+            Box::new(
+                any()
+                    .filter(|c: &char| c.is_ascii_digit())
+                    .repeated()
+                    .at_least(1)
+                    .at_most(3)
+                    .map_slice(|b: &str| b.parse::<u64>().unwrap())
+                    .padded()
+                    .separated_by(just(',').padded())
+                    .allow_trailing()
+                    .collect()
+                    .delimited_by(just('['), just(']')),
+            )
         }
 
-        let filename = "file.txt".to_string();
-        let fstr = filename.as_str();
-
         assert_eq!(
-            parser()
-                .parse(
-                    r#"hello "world" these are "test" tokens"#
-                        .map_span(|span| Span::new(fstr, span.start()..span.end()))
-                )
-                .into_result(),
-            Ok([
-                (Span::new("file.txt", 0..5), Token::Ident("hello")),
-                (Span::new("file.txt", 6..13), Token::String("\"world\"")),
-                (Span::new("file.txt", 14..19), Token::Ident("these")),
-                (Span::new("file.txt", 20..23), Token::Ident("are")),
-                (Span::new("file.txt", 24..30), Token::String("\"test\"")),
-                (Span::new("file.txt", 31..37), Token::Ident("tokens")),
-            ]),
+            parser().parse("[122 , 23,43,    4, ]").into_result(),
+            Ok(vec![122, 23, 43, 4]),
+        );
+        assert_eq!(
+            parser().parse("[0, 3, 6, 900,120]").into_result(),
+            Ok(vec![0, 3, 6, 900, 120]),
+        );
+        assert_eq!(
+            parser().parse("[200,400,50  ,0,0, ]").into_result(),
+            Ok(vec![200, 400, 50, 0, 0]),
         );
     }
 
     #[test]
-    fn zero_copy_repetition() {
-        use self::prelude::*;
-
+    fn rc_impl() {
         fn parser<'a>() -> impl Parser<'a, &'a str, Vec<u64>> {
-            any()
-                .filter(|c: &char| c.is_ascii_digit())
-                .repeated()
-                .at_least(1)
-                .at_most(3)
-                .map_slice(|b: &str| b.parse::<u64>().unwrap())
-                .padded()
-                .separated_by(just(',').padded())
-                .allow_trailing()
-                .collect()
-                .delimited_by(just('['), just(']'))
+            Rc::new(
+                any()
+                    .filter(|c: &char| c.is_ascii_digit())
+                    .repeated()
+                    .at_least(1)
+                    .at_most(3)
+                    .map_slice(|b: &str| b.parse::<u64>().unwrap())
+                    .padded()
+                    .separated_by(just(',').padded())
+                    .allow_trailing()
+                    .collect()
+                    .delimited_by(just('['), just(']')),
+            )
         }
 
         assert_eq!(
@@ -2864,395 +5312,1418 @@ mod tests {
             Ok(vec![0, 3, 6, 900, 120]),
         );
         assert_eq!(
-            parser().parse("[200,400,50  ,0,0, ]").into_result(),
-            Ok(vec![200, 400, 50, 0, 0]),
+            parser().parse("[200,400,50  ,0,0, ]").into_result(),
+            Ok(vec![200, 400, 50, 0, 0]),
+        );
+    }
+
+    #[test]
+    fn position_does_not_consume() {
+        let parser = any::<_, extra::Default>()
+            .repeated()
+            .exactly(3)
+            .ignore_then(position())
+            .then(any().repeated().collect::<String>());
+
+        assert_eq!(
+            parser.parse("abcde").into_result(),
+            Ok((SimpleSpan::new(3, 3), "de".to_string())),
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn arc_boxed_threads() {
+        let parser = any::<_, extra::Err<Simple<char>>>()
+            .filter(|c: &char| c.is_ascii_digit())
+            .repeated()
+            .at_least(1)
+            .map_slice(|s: &str| s.parse::<u64>().unwrap())
+            .separated_by(just(','))
+            .collect::<Vec<_>>()
+            .arc_boxed();
+
+        std::thread::scope(|s| {
+            let a = s.spawn(|| parser.parse("1,2,3").into_result());
+            let b = s.spawn(|| parser.parse("40,50").into_result());
+            assert_eq!(a.join().unwrap(), Ok(vec![1, 2, 3]));
+            assert_eq!(b.join().unwrap(), Ok(vec![40, 50]));
+        });
+    }
+
+    #[test]
+    fn recover_with_inside_then() {
+        let semicolon = just::<_, _, extra::Err<Rich<char>>>(';');
+        let insert_semicolon = via_parser(empty().to(';'));
+        let parser = text::int(10).then(semicolon.recover_with(insert_semicolon));
+
+        assert_eq!(parser.parse("42;").into_result(), Ok(("42", ';')));
+
+        let (output, errs) = parser.parse("42").into_output_errors();
+        assert_eq!(output, Some(("42", ';')));
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(2, 2));
+    }
+
+    #[test]
+    #[cfg(feature = "budget")]
+    fn parse_with_budget() {
+        // Each `or` branch re-parses the entire input before failing on the final token, and each level of nesting
+        // doubles the number of attempts, so this grammar backtracks exponentially on long inputs.
+        let input = "a".repeat(32);
+        let expr = recursive::<_, _, extra::Default, _, _>(|expr| {
+            just('a')
+                .then(expr.clone())
+                .then(just('b'))
+                .ignored()
+                .or(just('a').then(expr).then(just('c')).ignored())
+                .or(just('a').ignored())
+        });
+
+        assert!(!expr.parse_with_budget("aab", 1_000).unwrap().has_errors());
+        assert!(expr.parse_with_budget(&input, 10_000).is_err());
+    }
+
+    #[test]
+    fn alt_macro() {
+        fn parser<'a>() -> impl Parser<'a, &'a str, &'a str, extra::Err<Rich<'a, char>>> {
+            alt![
+                text::ascii::keyword("let"),
+                text::ascii::ident(),
+                text::int(10),
+            ]
+        }
+
+        fn equivalent<'a>() -> impl Parser<'a, &'a str, &'a str, extra::Err<Rich<'a, char>>> {
+            choice((
+                text::ascii::keyword("let"),
+                text::ascii::ident(),
+                text::int(10),
+            ))
+        }
+
+        for input in ["let", "foo", "42", "", "?", "4a"] {
+            assert_eq!(
+                parser().parse(input).into_output_errors(),
+                equivalent().parse(input).into_output_errors(),
+            );
+        }
+
+        // A single alternative is permitted
+        let single = alt![just::<_, _, extra::Default>('a')];
+        assert_eq!(single.parse("a").into_result(), Ok('a'));
+    }
+
+    #[test]
+    fn recover_with_ctx() {
+        use crate::recovery::via_parser_with_ctx;
+
+        fn closer(open: char) -> char {
+            match open {
+                '[' => ']',
+                '(' => ')',
+                _ => '}',
+            }
+        }
+
+        let items = text::int(10)
+            .padded()
+            .separated_by(just(','))
+            .collect::<Vec<_>>()
+            .then_ignore(just('\0').configure(|cfg, open: &char| cfg.seq(closer(*open))))
+            .recover_with(via_parser_with_ctx(|open: &char| {
+                let close = closer(*open);
+                none_of(close).repeated().then(just(close)).to(vec!["?"])
+            }));
+        let block = one_of::<_, _, extra::Err<Rich<char>>>("[({").ignore_with_ctx(items);
+        let blocks = block.padded().repeated().collect::<Vec<_>>();
+
+        // The opening `[` steers recovery past the `)` and `}` to the `]`
+        let (output, errs) = blocks.parse("[1, x) } 2] (3)").into_output_errors();
+        assert_eq!(output, Some(vec![vec!["?"], vec!["3"]]));
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(4, 5));
+    }
+
+    #[test]
+    #[cfg(feature = "rowan")]
+    fn node_cst_round_trip() {
+        use crate::{cst::build_green_tree, extra::Event};
+        use alloc::string::ToString;
+
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        #[repr(u16)]
+        enum Kind {
+            Root,
+            Trivia,
+            Num,
+            List,
+        }
+
+        impl From<Kind> for rowan::SyntaxKind {
+            fn from(kind: Kind) -> Self {
+                rowan::SyntaxKind(kind as u16)
+            }
+        }
+
+        type Extra = extra::State<Vec<Event<Kind, SimpleSpan>>>;
+
+        let list = recursive::<_, _, Extra, _, _>(|list| {
+            let num = text::int(10).node(Kind::Num).ignored();
+            num.or(list)
+                .padded()
+                .separated_by(just(','))
+                .allow_trailing()
+                .collect::<()>()
+                .delimited_by(just('['), just(']'))
+                .node(Kind::List)
+        })
+        .padded();
+
+        let src = " [1, [ 2,3 ],\n 4,] ";
+        let mut events = Vec::new();
+        list.parse_with_state(src, &mut events)
+            .into_result()
+            .unwrap();
+
+        let tree = build_green_tree(src, &events, Kind::Root, Kind::Trivia);
+        assert_eq!(tree.to_string(), src);
+        assert_eq!(tree.kind(), Kind::Root.into());
+
+        let outer = tree.children().find_map(|c| c.into_node()).unwrap();
+        assert_eq!(outer.kind(), Kind::List.into());
+        assert_eq!(outer.to_string(), "[1, [ 2,3 ],\n 4,]");
+        let kinds = outer
+            .children()
+            .filter_map(|c| c.into_node().map(|n| n.kind()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![Kind::Num.into(), Kind::List.into(), Kind::Num.into()]
         );
-
-        assert!(parser().parse("[1234,123,12,1]").has_errors());
-        assert!(parser().parse("[,0, 1, 456]").has_errors());
-        assert!(parser().parse("[3, 4, 5, 67 89,]").has_errors());
     }
 
     #[test]
-    fn zero_copy_group() {
-        use self::prelude::*;
+    #[cfg(feature = "rowan")]
+    fn node_cst_forgets_backtracked_nodes() {
+        use crate::{cst::build_green_tree, extra::Event};
+        use alloc::string::ToString;
+
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        #[repr(u16)]
+        enum Kind {
+            Root,
+            Trivia,
+            Num,
+        }
 
-        fn parser<'a>() -> impl Parser<'a, &'a str, (&'a str, u64, char)> {
-            group((
-                any()
-                    .filter(|c: &char| c.is_ascii_alphabetic())
-                    .repeated()
-                    .at_least(1)
-                    .slice()
-                    .padded(),
-                any()
-                    .filter(|c: &char| c.is_ascii_digit())
-                    .repeated()
-                    .at_least(1)
-                    .map_slice(|s: &str| s.parse::<u64>().unwrap())
-                    .padded(),
-                any().filter(|c: &char| !c.is_whitespace()).padded(),
-            ))
+        impl From<Kind> for rowan::SyntaxKind {
+            fn from(kind: Kind) -> Self {
+                rowan::SyntaxKind(kind as u16)
+            }
         }
 
-        assert_eq!(
-            parser().parse("abc 123 [").into_result(),
-            Ok(("abc", 123, '[')),
-        );
-        assert_eq!(
-            parser().parse("among3d").into_result(),
-            Ok(("among", 3, 'd')),
-        );
-        assert_eq!(
-            parser().parse("cba321,").into_result(),
-            Ok(("cba", 321, ',')),
-        );
+        type Extra = extra::State<Vec<Event<Kind, SimpleSpan>>>;
 
-        assert!(parser().parse("abc 123  ").has_errors());
-        assert!(parser().parse("123abc ]").has_errors());
-        assert!(parser().parse("and one &").has_errors());
+        // The first branch finishes a node before failing, so that node must not end up in the tree
+        let num = text::int::<_, _, Extra>(10).node(Kind::Num);
+        let expr = num
+            .then_ignore(just('!'))
+            .or(text::int(10).then_ignore(just('?')));
+
+        let src = "12?";
+        let mut events = Vec::new();
+        expr.parse_with_state(src, &mut events)
+            .into_result()
+            .unwrap();
+        assert_eq!(events, Vec::new());
+
+        let tree = build_green_tree(src, &events, Kind::Root, Kind::Trivia);
+        assert_eq!(tree.to_string(), src);
+        assert!(tree.children().all(|c| c.into_node().is_none()));
     }
 
     #[test]
-    fn zero_copy_group_array() {
-        use self::prelude::*;
-
-        fn parser<'a>() -> impl Parser<'a, &'a str, [char; 3]> {
-            group([just('a'), just('b'), just('c')])
+    fn shared_prefix_parses_prefix_once() {
+        use crate::primitive::shared_prefix;
+        use core::cell::Cell;
+
+        #[derive(Clone, Debug, PartialEq)]
+        enum If {
+            Stmt,
+            Expr,
         }
 
-        assert_eq!(parser().parse("abc").into_result(), Ok(['a', 'b', 'c']));
-        assert!(parser().parse("abd").has_errors());
+        let prefix_parses = Cell::new(0);
+        let if_kw = text::ascii::keyword::<_, _, _, extra::Err<Simple<char>>>("if")
+            .padded()
+            .map(|_| prefix_parses.set(prefix_parses.get() + 1));
+
+        let factored = shared_prefix(
+            if_kw.clone(),
+            choice((
+                text::ascii::keyword("let").to(If::Stmt),
+                just('(').to(If::Expr),
+            )),
+        )
+        .map(|(_, kind)| kind);
+
+        assert_eq!(factored.parse("if (").into_result(), Ok(If::Expr));
+        assert_eq!(prefix_parses.get(), 1);
+
+        // Without factoring, the prefix is parsed again by the second branch
+        prefix_parses.set(0);
+        let unfactored = choice((
+            if_kw.clone().then(text::ascii::keyword("let").to(If::Stmt)),
+            if_kw.then(just('(').to(If::Expr)),
+        ))
+        .map(|(_, kind)| kind);
+        assert_eq!(unfactored.parse("if (").into_result(), Ok(If::Expr));
+        assert_eq!(prefix_parses.get(), 2);
     }
 
     #[test]
-    fn unicode_str() {
-        let input = "🄯🄚🹠🴎🄐🝋🰏🄂🬯🈦g🸵🍩🕔🈳2🬙🨞🅢🭳🎅h🵚🧿🏩🰬k🠡🀔🈆🝹🤟🉗🴟📵🰄🤿🝜🙘🹄5🠻🡉🱖🠓";
-        let mut own = InputOwn::<_, extra::Default>::new(input);
-        let mut inp = own.as_ref_start();
+    fn expect_exact_magic_header() {
+        use crate::primitive::expect_exact;
 
-        while let Some(_c) = inp.next() {}
+        const PNG_MAGIC: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+        let png = expect_exact::<_, _, _, extra::Err<Rich<u8>>>(PNG_MAGIC)
+            .then(any().repeated().collect::<Vec<_>>());
+
+        let mut file = PNG_MAGIC.to_vec();
+        file.extend_from_slice(b"IHDR");
+        assert_eq!(
+            png.parse(&file[..]).into_result(),
+            Ok((PNG_MAGIC, b"IHDR".to_vec())),
+        );
+
+        // The error points at the first byte that differs
+        let errs = png.parse(b"\x89PNG\r\n\x1a\r" as &[u8]).into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(7, 8));
+        assert_eq!(errs[0].found(), Some(&b'\r'));
+
+        // Multi-byte characters are compared correctly, and mismatches are reported at character boundaries
+        let greeting = expect_exact::<_, _, _, extra::Err<Rich<char>>>("héllo");
+        assert_eq!(greeting.parse("héllo").into_result(), Ok("héllo"));
+        let errs = greeting.parse("hèllo").into_errors();
+        assert_eq!(errs[0].span(), &SimpleSpan::new(1, 3));
+        assert_eq!(errs[0].found(), Some(&'è'));
+
+        // Every matched token is charged to the budget
+        #[cfg(feature = "budget")]
+        {
+            assert!(greeting.parse_with_budget("héllo", 6).is_ok());
+            assert!(greeting.parse_with_budget("héllo", 3).is_err());
+        }
     }
 
     #[test]
-    fn iter() {
-        use self::prelude::*;
+    fn postfix_index() {
+        use crate::primitive::postfix;
 
-        fn parser<'a>() -> impl IterParser<'a, &'a str, char> {
-            any().repeated()
+        #[derive(Debug, PartialEq)]
+        enum Expr<'a> {
+            Var(&'a str),
+            Index(Box<Self>, u64),
         }
 
-        let mut chars = String::new();
-        for c in parser().parse_iter(&"abcdefg").into_result().unwrap() {
-            chars.push(c);
+        let index = text::int::<_, _, extra::Err<Simple<char>>>(10)
+            .from_str()
+            .unwrapped()
+            .delimited_by(just('['), just(']'));
+        let expr = postfix(text::ascii::ident().map(Expr::Var), index, |e, i| {
+            Expr::Index(Box::new(e), i)
+        });
+
+        assert_eq!(
+            expr.parse("a[0][1]").into_result(),
+            Ok(Expr::Index(
+                Box::new(Expr::Index(Box::new(Expr::Var("a")), 0)),
+                1
+            )),
+        );
+        assert_eq!(expr.parse("a").into_result(), Ok(Expr::Var("a")));
+    }
+
+    #[test]
+    fn prefix_neg() {
+        use crate::primitive::prefix;
+
+        #[derive(Debug, PartialEq)]
+        enum Expr {
+            Num(u64),
+            Neg(Box<Self>),
         }
 
-        assert_eq!(&chars, "abcdefg");
+        let num = text::int::<_, _, extra::Err<Simple<char>>>(10)
+            .from_str()
+            .unwrapped()
+            .map(Expr::Num);
+        let expr = prefix(just('-'), num, |_, e| Expr::Neg(Box::new(e)));
+
+        assert_eq!(
+            expr.parse("--5").into_result(),
+            Ok(Expr::Neg(Box::new(Expr::Neg(Box::new(Expr::Num(5)))))),
+        );
+        assert_eq!(expr.parse("5").into_result(), Ok(Expr::Num(5)));
     }
 
     #[test]
-    #[cfg(feature = "memoization")]
-    fn exponential() {
-        use self::prelude::*;
+    fn just_seq_matches_chained() {
+        use crate::primitive::just_seq;
 
-        fn parser<'a>() -> impl Parser<'a, &'a str, String> {
-            recursive(|expr| {
-                let atom = any()
-                    .filter(|c: &char| c.is_alphabetic())
-                    .repeated()
-                    .at_least(1)
-                    .collect()
-                    .or(expr.delimited_by(just('('), just(')')));
+        let seq = just_seq::<_, _, _, extra::Err<Rich<char>>>(&["<<", "="][..]);
+        let chained = just::<_, _, extra::Err<Rich<char>>>("<<").then(just("="));
 
-                atom.clone()
-                    .then_ignore(just('+'))
-                    .then(atom.clone())
-                    .map(|(a, b)| format!("{}{}", a, b))
-                    .memoized()
-                    .or(atom)
-            })
-            .then_ignore(end())
+        assert_eq!(seq.parse("<<=").into_result(), Ok(&["<<", "="][..]));
+        assert_eq!(chained.parse("<<=").into_result(), Ok(("<<", "=")));
+
+        // Errors are identical to those of the chained version
+        for input in ["<=", "<<", "<<>", ""] {
+            assert_eq!(
+                seq.parse(input).into_errors(),
+                chained.parse(input).into_errors(),
+                "input: {input:?}",
+            );
         }
+    }
 
-        parser()
-            .parse("((((((((((((((((((((((((((((((a+b))))))))))))))))))))))))))))))")
-            .into_result()
-            .unwrap();
+    #[test]
+    #[cfg(feature = "profile")]
+    fn parse_profiled() {
+        let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+            .profiled("ident")
+            .padded();
+        // Both alternatives start with an identifier, so it gets parsed twice for every assignment
+        let stmt = ident
+            .then_ignore(just('('))
+            .then_ignore(just(')'))
+            .ignored()
+            .profiled("call")
+            .or(ident
+                .then_ignore(just('='))
+                .then(ident)
+                .ignored()
+                .profiled("assign"))
+            .then_ignore(just(';').padded());
+
+        let (res, report) = stmt.repeated().parse_profiled("a = b; f(); c = d;");
+        assert!(!res.has_errors());
+
+        // 7 successful parses, plus one failure per alternative when `repeated` tries for a fourth statement
+        let ident = report.get("ident").unwrap();
+        assert_eq!(ident.invocations(), 9);
+        assert_eq!(ident.successes(), 7);
+        assert_eq!(report.get("call").unwrap().invocations(), 4);
+        assert_eq!(report.get("call").unwrap().successes(), 1);
+        assert_eq!(report.get("assign").unwrap().successes(), 2);
+        assert!(report.get("unused").is_none());
     }
 
     #[test]
-    #[cfg(feature = "memoization")]
-    fn left_recursive() {
-        use self::prelude::*;
+    #[cfg(feature = "profile")]
+    fn parse_with_coverage_over_corpus() {
+        use self::profile::CoverageReport;
+
+        let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
+            .ignored()
+            .profiled("int");
+        let string = just('"')
+            .then(none_of('"').repeated())
+            .then(just('"'))
+            .ignored()
+            .profiled("string");
+        let list = int
+            .or(string)
+            .padded()
+            .separated_by(just(','))
+            .collect::<Vec<_>>()
+            .delimited_by(just('['), just(']'))
+            .ignored()
+            .profiled("list");
+        let value = int.or(string).or(list);
+
+        let mut coverage = CoverageReport::default();
+        for input in ["1", "[1, 2]", "[]"] {
+            assert!(!value.parse_with_coverage(input, &mut coverage).has_errors());
+        }
 
-        fn parser<'a>() -> impl Parser<'a, &'a str, String> {
-            recursive(|expr| {
-                let atom = any()
-                    .filter(|c: &char| c.is_alphabetic())
-                    .repeated()
-                    .at_least(1)
-                    .collect();
+        assert!(coverage.is_covered("int"));
+        assert!(coverage.is_covered("list"));
+        // Strings are tried, but the corpus never contains one
+        assert!(!coverage.is_covered("string"));
+        assert_eq!(coverage.uncovered().collect::<Vec<_>>(), vec!["string"]);
+        assert_eq!(coverage.get("list").unwrap().successes(), 2);
+
+        assert!(!value
+            .parse_with_coverage("\"a\"", &mut coverage)
+            .has_errors());
+        assert!(coverage.is_covered("string"));
+        assert_eq!(coverage.uncovered().count(), 0);
+    }
 
-                let sum = expr
-                    .clone()
-                    .then_ignore(just('+'))
-                    .then(expr)
-                    .map(|(a, b)| format!("{}{}", a, b))
-                    .memoized();
+    #[test]
+    fn recover_with_tagged_error_node() {
+        #[derive(Debug, PartialEq)]
+        enum Stmt<'a> {
+            Let(&'a str, Expr),
+        }
 
-                sum.or(atom)
-            })
-            .then_ignore(end())
+        #[derive(Debug, PartialEq)]
+        enum Expr {
+            Num(u64),
+            Error(&'static str, SimpleSpan<usize>),
         }
 
-        assert_eq!(parser().parse("a+b+c").into_result().unwrap(), "abc");
+        let expr = text::int::<_, _, extra::Err<Rich<char>>>(10)
+            .from_str()
+            .unwrapped()
+            .map(Expr::Num)
+            .then_ignore(just(';'))
+            .recover_with(skip_until_tagged(
+                any().ignored(),
+                just(';').ignored(),
+                "expected expression",
+                Expr::Error,
+            ));
+        let stmt = text::ascii::keyword("let")
+            .ignore_then(text::ascii::ident().padded())
+            .then_ignore(just('=').padded())
+            .then(expr)
+            .map(|(name, expr)| Stmt::Let(name, expr))
+            .padded();
+
+        assert_eq!(
+            stmt.parse("let x = 5;").into_result(),
+            Ok(Stmt::Let("x", Expr::Num(5))),
+        );
+
+        let (out, errs) = stmt.parse("let x = +*;").into_output_errors();
+        assert_eq!(
+            out,
+            Some(Stmt::Let(
+                "x",
+                Expr::Error("expected expression", SimpleSpan::new(8, 11)),
+            )),
+        );
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(8, 9));
     }
 
-    #[cfg(debug_assertions)]
-    mod debug_asserts {
-        use super::prelude::*;
+    #[test]
+    fn never_in_choice() {
+        use core::convert::Infallible;
+
+        #[derive(Debug, PartialEq)]
+        enum Literal {
+            Int(u64),
+            Bool(bool),
+            // Not yet supported by the language
+            #[allow(dead_code)]
+            Float(Infallible),
+        }
 
-        // TODO panic when left recursive parser is detected
-        // #[test]
-        // #[should_panic]
-        // fn debug_assert_left_recursive() {
-        //     recursive(|expr| {
-        //         let atom = any::<&str, extra::Default>()
-        //             .filter(|c: &char| c.is_alphabetic())
-        //             .repeated()
-        //             .at_least(1)
-        //             .collect();
+        let literal = choice((
+            text::int::<_, _, extra::Err<Rich<char>>>(10)
+                .from_str()
+                .unwrapped()
+                .map(Literal::Int),
+            never().map(Literal::Float),
+            text::ascii::keyword("true")
+                .to(true)
+                .or(text::ascii::keyword("false").to(false))
+                .map(Literal::Bool),
+        ));
+
+        assert_eq!(literal.parse("42").into_result(), Ok(Literal::Int(42)));
+        assert_eq!(literal.parse("true").into_result(), Ok(Literal::Bool(true)));
+        assert_eq!(
+            literal.parse("false").into_result(),
+            Ok(Literal::Bool(false))
+        );
 
-        //         let sum = expr
-        //             .clone()
-        //             .then_ignore(just('+'))
-        //             .then(expr)
-        //             .map(|(a, b)| format!("{}{}", a, b));
+        // `never` contributes no expected inputs to errors
+        let errs = literal.parse("?").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(0, 1));
+        assert_eq!(
+            errs[0].expected().count(),
+            choice((
+                text::int::<_, _, extra::Err<Rich<char>>>(10),
+                text::ascii::keyword("true"),
+                text::ascii::keyword("false"),
+            ))
+            .parse("?")
+            .into_errors()[0]
+                .expected()
+                .count(),
+        );
 
-        //         sum.or(atom)
-        //     })
-        //     .then_ignore(end())
-        //     .parse("a+b+c");
-        // }
+        assert!(never::<_, (), extra::Default>().parse("").has_errors());
+    }
 
-        #[test]
-        #[should_panic]
-        #[cfg(debug_assertions)]
-        fn debug_assert_collect() {
-            empty::<&str, extra::Default>()
-                .to(())
-                .repeated()
-                .collect::<()>()
-                .parse("a+b+c")
-                .unwrap();
+    #[test]
+    fn iter_matches_log() {
+        #[derive(Clone, Debug, PartialEq)]
+        enum Level {
+            Info,
+            Warn,
         }
 
-        #[test]
-        #[should_panic]
-        #[cfg(debug_assertions)]
-        fn debug_assert_separated_by() {
-            empty::<&str, extra::Default>()
-                .to(())
-                .separated_by(empty())
-                .collect::<()>()
-                .parse("a+b+c");
-        }
+        let level = choice((
+            text::ascii::keyword("INFO").to(Level::Info),
+            text::ascii::keyword("WARN").to(Level::Warn),
+        ));
+        let record = text::int::<_, _, extra::Err<Rich<char>>>(10)
+            .from_str::<u32>()
+            .unwrapped()
+            .then_ignore(just(' '))
+            .then(level)
+            .then_ignore(just(' '))
+            .then(none_of('\n').repeated().slice());
+
+        let log = "1 INFO starting\n2 WARN disk low\nthree INFO oops\n4 INFO done";
+        let results = record.iter_matches(log, just('\n')).collect::<Vec<_>>();
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(
+            results[0],
+            (Ok(((1, Level::Info), "starting")), SimpleSpan::new(0, 15)),
+        );
+        assert_eq!(
+            results[1],
+            (Ok(((2, Level::Warn), "disk low")), SimpleSpan::new(16, 31)),
+        );
+        let (res, span) = &results[2];
+        assert_eq!(span, &SimpleSpan::new(32, 47));
+        let errs = res.as_ref().unwrap_err();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(32, 33));
+        assert_eq!(
+            results[3],
+            (Ok(((4, Level::Info), "done")), SimpleSpan::new(48, 59)),
+        );
 
-        #[test]
-        fn debug_assert_separated_by2() {
-            assert_eq!(
-                empty::<&str, extra::Default>()
-                    .to(())
-                    .separated_by(just(','))
-                    .count()
-                    .parse(",")
-                    .unwrap(),
-                2
-            );
-        }
+        assert_eq!(record.iter_matches("", just('\n')).count(), 0);
+    }
 
-        #[test]
-        #[should_panic]
-        #[cfg(debug_assertions)]
-        fn debug_assert_foldl() {
-            assert_eq!(
-                empty::<&str, extra::Default>()
-                    .to(1)
-                    .foldl(empty().repeated(), |n, ()| n + 1)
-                    .parse("a+b+c")
-                    .unwrap(),
-                3
-            );
-        }
+    #[test]
+    fn iter_matches_zero_width_delimiter() {
+        // The delimiter always matches, so on its own it would never move the iterator forward
+        let digit = any::<_, extra::Err<Rich<char>>>().filter(char::is_ascii_digit);
+        let results = digit
+            .iter_matches("1x2", empty())
+            .map(|(res, span)| (res.is_ok(), span.into_range()))
+            .collect::<Vec<_>>();
+        assert_eq!(results, vec![(true, 0..1), (false, 1..2), (true, 2..3)]);
+
+        // Neither the record nor the delimiter consume anything
+        let results = text::whitespace::<_, _, extra::Err<Rich<char>>>()
+            .iter_matches("ab", text::whitespace())
+            .collect::<Vec<_>>();
+        assert_eq!(results.len(), 2);
+        let (res, span) = &results[0];
+        assert_eq!(span, &SimpleSpan::new(0, 1));
+        assert_eq!(
+            res.as_ref().unwrap_err()[0].to_string(),
+            "parser made no progress"
+        );
+    }
 
-        #[test]
-        #[should_panic]
-        #[cfg(debug_assertions)]
-        fn debug_assert_foldl_with_state() {
-            let mut state = 100;
-            empty::<&str, extra::Full<EmptyErr, i32, ()>>()
-                .foldl_with_state(empty().to(()).repeated(), |_, _, _| ())
-                .parse_with_state("a+b+c", &mut state);
+    #[test]
+    fn summarize_errors() {
+        let item = text::int::<_, _, extra::Err<Rich<char>>>(10)
+            .from_str::<u32>()
+            .unwrapped()
+            .recover_with(via_parser(none_of(",").repeated().at_least(1).to(0)));
+        let list = item.separated_by(just(',')).collect::<Vec<_>>();
+
+        let res = list.parse("1,x,2,yy,3,z").summarize_errors();
+        assert_eq!(res.output(), Some(&vec![1, 0, 2, 0, 3, 0]));
+        let errs = res.into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].to_string(), "3 errors, first at 2..3");
+        assert_eq!(errs[0].span(), &SimpleSpan::new(2, 3));
+
+        // Successful parses are unaffected
+        let res = list.parse("1,2").summarize_errors();
+        assert_eq!(res.into_result(), Ok(vec![1, 2]));
+
+        // Error types that cannot store a count keep the first error
+        let item = text::int::<_, _, extra::Err<Simple<char>>>(10)
+            .recover_with(via_parser(none_of(",").repeated().at_least(1).to("0")));
+        let list = item.separated_by(just(',')).collect::<Vec<_>>();
+        let errs = list.parse("1,x,2,yy").summarize_errors().into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(
+            errs[0],
+            <Simple<char> as Error<&str>>::expected_found(
+                [],
+                Some(MaybeRef::Val('x')),
+                (2..3).into(),
+            ),
+        );
+    }
+
+    #[test]
+    fn dispatch_runs_only_matching_branch() {
+        use core::cell::RefCell;
+
+        let ran = RefCell::new(Vec::new());
+        let branch = |kw: &'static str| {
+            let ran = &ran;
+            text::ascii::keyword::<_, _, _, extra::Err<Rich<char>>>(kw).map(move |kw: &str| {
+                ran.borrow_mut().push(kw);
+                kw
+            })
+        };
+        let stmt = dispatch([
+            ('l', branch("let")),
+            ('f', branch("fn")),
+            ('i', branch("if")),
+            ('w', branch("while")),
+            ('r', branch("return")),
+        ]);
+
+        for kw in ["let", "fn", "if", "while", "return"] {
+            ran.borrow_mut().clear();
+            assert_eq!(stmt.parse(kw).into_result(), Ok(kw));
+            assert_eq!(*ran.borrow_mut(), vec![kw]);
         }
 
-        #[test]
-        #[should_panic]
-        #[cfg(debug_assertions)]
-        fn debug_assert_foldr() {
-            empty::<&str, extra::Default>()
-                .to(())
-                .repeated()
-                .foldr(empty(), |_, _| ())
-                .parse("a+b+c");
+        // The branch is chosen by the first token alone
+        ran.borrow_mut().clear();
+        assert!(stmt.parse("loop").has_errors());
+        assert!(ran.borrow_mut().is_empty());
+
+        // Unknown first tokens fall through to an error expecting any of the branches
+        let errs = stmt.parse("match").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(0, 1));
+        assert_eq!(errs[0].found(), Some(&'m'));
+        assert_eq!(errs[0].expected().count(), 5);
+        assert!(ran.borrow_mut().is_empty());
+
+        // Only the last branch for a first token is kept, and shadowed branches aren't expected
+        let stmt = dispatch([('l', branch("let")), ('l', branch("loop"))]);
+        assert_eq!(stmt.parse("loop").into_result(), Ok("loop"));
+        assert!(stmt.parse("let").has_errors());
+        let errs = stmt.parse("match").into_errors();
+        assert_eq!(errs[0].expected().count(), 1);
+    }
+
+    #[test]
+    fn try_map_error_at_sub_span() {
+        let two_digits = || {
+            text::digits::<_, _, extra::Err<Rich<char>>>(10)
+                .exactly(2)
+                .slice()
+        };
+        let date = two_digits()
+            .then_ignore(just('/'))
+            .then(two_digits())
+            .then_ignore(just('/'))
+            .then(two_digits())
+            .slice()
+            .try_map(|s: &str, span: SimpleSpan| {
+                let month = s[3..5].parse::<u8>().unwrap();
+                if (1..=12).contains(&month) {
+                    Ok(s)
+                } else {
+                    Err(Rich::custom(span.sub_span(3..5), "month out of range"))
+                }
+            });
+
+        assert_eq!(date.parse("05/12/23").into_result(), Ok("05/12/23"));
+
+        let errs = date.parse("05/13/23").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(3, 5));
+    }
+
+    #[test]
+    fn str_spans_are_byte_offsets() {
+        use crate::span::Span;
+
+        let src = "é + ident";
+        let ident = just::<_, _, extra::Err<Simple<char>>>('é')
+            .padded()
+            .ignore_then(just('+'))
+            .padded()
+            .ignore_then(text::ascii::ident().map_with_span(|_, span: SimpleSpan| span));
+
+        let span = ident.parse(src).into_result().unwrap();
+        assert_eq!(span, SimpleSpan::new(5, 10));
+        assert_eq!(&src[span.into_range()], "ident");
+        assert_eq!(span.to_utf16(src), 4..9);
+    }
+
+    #[test]
+    fn in_range_bounds() {
+        fn check<'a, P: Parser<'a, &'a str, char, extra::Err<Simple<'a, char>>>>(
+            p: P,
+            input: &'a str,
+        ) -> bool {
+            p.parse(input).has_output()
         }
 
-        #[test]
-        #[should_panic]
-        #[cfg(debug_assertions)]
-        fn debug_assert_foldr_with_state() {
-            empty::<&str, extra::Default>()
-                .to(())
-                .repeated()
-                .foldr_with_state(empty(), |_, _, _| ())
-                .parse_with_state("a+b+c", &mut ());
+        assert!(check(in_range('a'..='z'), "z"));
+        assert!(!check(in_range('a'..='z'), "A"));
+        assert!(check(in_range('a'..'m'), "l"));
+        assert!(!check(in_range('a'..'m'), "m"));
+        assert!(check(in_range('x'..), "\u{10FFFF}"));
+        assert!(!check(in_range('x'..), "w"));
+        assert!(check(in_range(..'x'), "w"));
+        assert!(!check(in_range(..'x'), "x"));
+        assert!(!check(in_range('a'..='z'), ""));
+
+        let bytes = in_range::<_, &[u8], extra::Err<Simple<'_, u8>>>(0x80..)
+            .repeated()
+            .collect::<Vec<_>>()
+            .then(in_range(..=0x7F));
+        assert_eq!(
+            bytes.parse(&[0x80, 0xFF, 0x7F][..]).into_result(),
+            Ok((vec![0x80, 0xFF], 0x7F)),
+        );
+        assert!(bytes.parse(&[0x80, 0x80][..]).has_errors());
+    }
+
+    #[test]
+    fn recover_resume_skips_parsed_prefix() {
+        #[derive(Debug, PartialEq)]
+        enum Stmt<'a> {
+            Let(&'a str, u64),
+            Error,
         }
 
-        #[test]
-        #[should_panic]
-        #[cfg(debug_assertions)]
-        fn debug_assert_repeated() {
-            empty::<&str, extra::Default>()
-                .to(())
-                .repeated()
-                .parse("a+b+c");
+        let stmt = text::ascii::keyword::<_, _, _, extra::Err<Rich<char>>>("let")
+            .ignore_then(text::ascii::ident().padded())
+            .then_ignore(just('=').padded())
+            .then(text::int(10).from_str().unwrapped())
+            .then_ignore(just(';'))
+            .map(|(name, val)| Stmt::Let(name, val))
+            .recover_with(recover_resume(|| Stmt::Error))
+            .padded();
+
+        let (out, errs) = stmt
+            .repeated()
+            .collect::<Vec<_>>()
+            .parse("let x = ; let y = 2;")
+            .into_output_errors();
+        assert_eq!(out, Some(vec![Stmt::Error, Stmt::Let("y", 2)]));
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(8, 9));
+    }
+
+    #[test]
+    fn parse_resumable_in_steps() {
+        use core::task::Poll;
+
+        let list = text::int::<_, _, extra::Err<Rich<char>>>(10)
+            .from_str::<u32>()
+            .unwrapped()
+            .then_ignore(just(',').or_not())
+            .padded()
+            .repeated();
+        let input = (0..1000).map(|i| format!("{i}, ")).collect::<String>();
+
+        let mut parse = list.parse_resumable(input.as_str());
+        let mut steps = 0;
+        let res = loop {
+            steps += 1;
+            if let Poll::Ready(res) = parse.step(64) {
+                break res;
+            }
+        };
+        assert_eq!(steps, 16);
+        assert_eq!(
+            res.into_result(),
+            list.collect::<Vec<_>>().parse(input.as_str()).into_result(),
+        );
+
+        let mut parse = list.parse_resumable("1, 2, x");
+        assert!(parse.step(1).is_pending());
+        match parse.step(10) {
+            Poll::Ready(res) => {
+                let (out, errs) = res.into_output_errors();
+                assert_eq!(out, None);
+                assert_eq!(errs.len(), 1);
+                assert_eq!(errs[0].span(), &SimpleSpan::new(6, 7));
+            }
+            Poll::Pending => panic!("parse should have finished"),
         }
+    }
 
-        // TODO what about IterConfigure and TryIterConfigure?
+    #[test]
+    fn then_missing_second_at_eof_names_second() {
+        use crate::error::RichPattern;
+
+        let assign = text::ascii::ident::<_, _, extra::Err<Rich<char>>>()
+            .then_ignore(just('=').padded())
+            .then(just('0'));
+
+        let errs = assign.parse("x =").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].found(), None);
+        assert_eq!(
+            errs[0].expected().collect::<Vec<_>>(),
+            vec![&RichPattern::Token('0'.into())],
+        );
+        assert_eq!(errs[0].span(), &SimpleSpan::new(3, 3));
+        assert_eq!(errs[0].to_string(), "found end of input expected '0'");
     }
 
     #[test]
-    #[should_panic]
-    fn recursive_define_twice() {
-        let mut expr = Recursive::declare();
-        expr.define({
-            let atom = any::<&str, extra::Default>()
-                .filter(|c: &char| c.is_alphabetic())
-                .repeated()
-                .at_least(1)
-                .collect();
-            let sum = expr
-                .clone()
-                .then_ignore(just('+'))
-                .then(expr.clone())
-                .map(|(a, b)| format!("{}{}", a, b));
+    fn collect_shared_slices() {
+        let items = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+            .padded()
+            .separated_by(just(','));
 
-            sum.or(atom)
-        });
-        expr.define(expr.clone());
+        let arc: Arc<[&str]> = items.collect_arc().parse("a, b, c").into_result().unwrap();
+        assert_eq!(&*arc, &["a", "b", "c"]);
 
-        expr.then_ignore(end()).parse("a+b+c");
+        let rc: Rc<[&str]> = items.collect_rc().parse("a, b, c").into_result().unwrap();
+        assert_eq!(&*rc, &["a", "b", "c"]);
     }
 
     #[test]
-    #[should_panic]
-    fn todo_err() {
-        let expr = todo::<&str, String, extra::Default>();
-        expr.then_ignore(end()).parse("a+b+c");
+    fn or_just_literals_expected_set() {
+        use crate::error::RichPattern;
+
+        let boolean = just::<_, _, extra::Err<Rich<char>>>("true").or(just("false"));
+        let errs = boolean.parse("maybe").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].found(), Some(&'m'));
+        assert_eq!(errs[0].span(), &SimpleSpan::new(0, 1));
+        assert_eq!(
+            errs[0].expected().collect::<Vec<_>>(),
+            vec![
+                &RichPattern::Token('t'.into()),
+                &RichPattern::Token('f'.into())
+            ],
+        );
+
+        // Literals with a common prefix expect the prefix's token only once
+        let t = just::<_, _, extra::Err<Rich<char>>>("true").or(just("try"));
+        let errs = t.parse("x").into_errors();
+        assert_eq!(
+            errs[0].expected().collect::<Vec<_>>(),
+            vec![&RichPattern::Token('t'.into())],
+        );
+        // Where they diverge, each expects its next token
+        let errs = t.parse("trx").into_errors();
+        assert_eq!(errs[0].span(), &SimpleSpan::new(2, 3));
+        assert_eq!(
+            errs[0].expected().collect::<Vec<_>>(),
+            vec![
+                &RichPattern::Token('u'.into()),
+                &RichPattern::Token('y'.into())
+            ],
+        );
     }
 
     #[test]
-    fn arc_impl() {
-        fn parser<'a>() -> impl Parser<'a, &'a str, Vec<u64>> {
-            Arc::new(
-                any()
-                    .filter(|c: &char| c.is_ascii_digit())
-                    .repeated()
-                    .at_least(1)
-                    .at_most(3)
-                    .map_slice(|b: &str| b.parse::<u64>().unwrap())
-                    .padded()
-                    .separated_by(just(',').padded())
-                    .allow_trailing()
-                    .collect()
-                    .delimited_by(just('['), just(']')),
-            )
-        }
+    fn spanned_range_byte_offsets() {
+        let idents = text::unicode::ident::<_, _, extra::Err<Simple<char>>>()
+            .spanned_range()
+            .padded()
+            .repeated()
+            .collect::<Vec<_>>();
 
         assert_eq!(
-            parser().parse("[122 , 23,43,    4, ]").into_result(),
-            Ok(vec![122, 23, 43, 4]),
+            idents.parse("größe x").into_result(),
+            Ok(vec![("größe", 0..7), ("x", 8..9)]),
+        );
+    }
+
+    #[test]
+    fn head_then_rest_signature() {
+        let param = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+            .then_ignore(just(':').padded())
+            .then(text::ascii::ident())
+            .delimited_by(just('('), just(')'))
+            .padded();
+        let sig = head_then_rest(text::ascii::ident().padded(), param);
+
+        assert_eq!(
+            sig.parse("add (x: int) (y: int)").into_result(),
+            Ok(("add", vec![("x", "int"), ("y", "int")])),
         );
+        assert_eq!(sig.parse("main").into_result(), Ok(("main", vec![])));
+        assert!(sig.parse("(x: int)").has_errors());
+    }
+
+    #[test]
+    fn end_expects_end_of_input() {
+        use crate::error::RichPattern;
+
+        let num = text::int::<_, _, extra::Err<Rich<char>>>(10);
+        let errs = num.parse("12ab").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(2, 3));
+        assert_eq!(errs[0].found(), Some(&'a'));
+        assert!(errs[0].expected().any(|e| e == &RichPattern::EndOfInput));
+
+        let errs = end::<_, extra::Err<Rich<char>>>().parse("x").into_errors();
+        assert_eq!(errs[0].to_string(), "found 'x' expected end of input");
+    }
+
+    #[test]
+    fn parse_into_errors_accumulates() {
+        let list = text::int::<_, _, extra::Err<Rich<char>>>(10)
+            .separated_by(just(','))
+            .recover_item_with(via_parser(text::ascii::ident().to("?")))
+            .collect::<Vec<_>>();
+
+        let mut errs = vec![Rich::custom(SimpleSpan::new(0, 0), "earlier diagnostic")];
         assert_eq!(
-            parser().parse("[0, 3, 6, 900,120]").into_result(),
-            Ok(vec![0, 3, 6, 900, 120]),
+            list.parse_into_errors("1,a,3", &mut errs),
+            Some(vec!["1", "?", "3"]),
         );
+        assert_eq!(list.parse_into_errors("b,2,!", &mut errs), None);
+
+        let spans = errs.iter().map(|e| *e.span()).collect::<Vec<_>>();
         assert_eq!(
-            parser().parse("[200,400,50  ,0,0, ]").into_result(),
-            Ok(vec![200, 400, 50, 0, 0]),
+            spans,
+            vec![
+                SimpleSpan::new(0, 0),
+                SimpleSpan::new(2, 3),
+                SimpleSpan::new(0, 1),
+                SimpleSpan::new(4, 5),
+            ],
         );
     }
 
     #[test]
-    fn box_impl() {
-        fn parser<'a>() -> impl Parser<'a, &'a str, Vec<u64>> {
-            Box::new(
-                any()
-                    .filter(|c: &char| c.is_ascii_digit())
-                    .repeated()
-                    .at_least(1)
-                    .at_most(3)
-                    .map_slice(|b: &str| b.parse::<u64>().unwrap())
-                    .padded()
-                    .separated_by(just(',').padded())
-                    .allow_trailing()
-                    .collect()
-                    .delimited_by(just('['), just(']')),
+    fn tolerant_continues_after_malformed_construct() {
+        #[derive(Clone, Debug, PartialEq)]
+        enum Call<'a> {
+            Call(&'a str, Vec<&'a str>),
+            Invalid,
+        }
+
+        let call = text::ascii::ident::<_, _, extra::Err<Rich<char>>>()
+            .then(
+                text::int(10)
+                    .separated_by(just(','))
+                    .collect::<Vec<_>>()
+                    .delimited_by(just('('), just(')')),
             )
+            .map(|(name, args)| Call::Call(name, args))
+            .tolerant(Call::Invalid);
+        let calls = call.separated_by(just(';')).collect::<Vec<_>>();
+
+        let (out, errs) = calls.parse("f(1);g(2,3];h()").into_output_errors();
+        assert_eq!(
+            out,
+            Some(vec![
+                Call::Call("f", vec!["1"]),
+                Call::Invalid,
+                Call::Call("h", vec![]),
+            ]),
+        );
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(10, 11));
+    }
+
+    #[test]
+    fn bracketed_list_recovers_missing_close() {
+        let list = bracketed_list(
+            just::<_, _, extra::Err<Rich<char>>>('['),
+            text::int(10).padded(),
+            just(','),
+            just(']'),
+        );
+        let stmt = list.then_ignore(just(';'));
+
+        assert_eq!(
+            stmt.parse("[1, 2, 3];").into_result(),
+            Ok(vec!["1", "2", "3"])
+        );
+        assert_eq!(stmt.parse("[];").into_result(), Ok(vec![]));
+
+        let (out, errs) = stmt.parse("[1, 2, 3;").into_output_errors();
+        assert_eq!(out, Some(vec!["1", "2", "3"]));
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(8, 9));
+    }
+
+    #[test]
+    fn with_context_distinguishes_files() {
+        type FileId = u32;
+        type Span = SimpleSpan<usize, FileId>;
+
+        fn parser<'a>() -> impl Parser<'a, input::WithContext<Span, &'a str>, Vec<(&'a str, Span)>>
+        {
+            text::ascii::ident()
+                .map_with_span(|name, span| (name, span))
+                .padded()
+                .repeated()
+                .collect()
         }
 
+        let a = parser().parse("foo bar".with_context(1)).into_result();
+        let b = parser().parse("baz".with_context(2)).into_result();
+
         assert_eq!(
-            parser().parse("[122 , 23,43,    4, ]").into_result(),
-            Ok(vec![122, 23, 43, 4]),
+            a,
+            Ok(vec![
+                ("foo", Span::new(1, 0..3)),
+                ("bar", Span::new(1, 4..7))
+            ]),
+        );
+        assert_eq!(b, Ok(vec![("baz", Span::new(2, 0..3))]));
+        assert_ne!(a.unwrap()[0].1.context(), b.unwrap()[0].1.context());
+    }
+
+    #[test]
+    fn parse_with_furthest_error_reports_abandoned_branch() {
+        // `1.` looks like the start of a float, but the integer branch is used instead
+        let num = text::int::<_, _, extra::Err<Rich<char>>>(10)
+            .then(just('.').then(text::digits(10)))
+            .slice()
+            .or(text::int(10))
+            .then_ignore(just('.').or_not());
+
+        let res = num.parse_with_furthest_error("1.");
+        assert_eq!(res.output(), Some(&"1"));
+        assert!(!res.has_errors());
+
+        let furthest = res.furthest_error().unwrap();
+        assert_eq!(furthest.span(), &SimpleSpan::new(2, 2));
+        assert_eq!(furthest.found(), None);
+
+        // Without opting in, the furthest error isn't tracked
+        assert!(num.parse("1.").furthest_error().is_none());
+    }
+
+    #[test]
+    fn parse_with_furthest_error_sees_mapped_errors() {
+        let ab = just::<_, _, extra::Err<Rich<char>>>('a')
+            .then(just('b').map_err(|e: Rich<char>| Rich::custom(*e.span(), "missing b")))
+            .to('b')
+            .or(just('a'));
+
+        let res = ab.parse_with_furthest_error("a");
+        assert_eq!(res.output(), Some(&'a'));
+        assert_eq!(
+            res.furthest_error().map(ToString::to_string),
+            Some("missing b".to_string()),
         );
+    }
+
+    #[test]
+    fn dedented_block() {
+        // Each item is a name followed by an indented block of lines, which is parsed as if it wasn't indented
+        let line = any::<_, extra::Err<Simple<char>>>()
+            .filter(|c: &char| *c != '\n')
+            .repeated()
+            .at_least(1)
+            .map_with_span(|_, span: SimpleSpan| span)
+            .then_ignore(just('\n'));
+        let item = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+            .then_ignore(just(":\n"))
+            .then(dedented(2, line.repeated().collect::<Vec<_>>()));
+        let items = item.repeated().collect::<Vec<_>>();
+
+        let src = "a:\n  x\n  yy\nb:\n  z\n";
+        let out = items.parse(src).into_result().unwrap();
+        let slices = out
+            .iter()
+            .map(|(name, lines)| (*name, lines.iter().map(|s| &src[s.into_range()]).collect()))
+            .collect::<Vec<(_, Vec<_>)>>();
+        assert_eq!(slices, vec![("a", vec!["x", "yy"]), ("b", vec!["z"])]);
+
+        // Deeper indentation is kept relative to the block
+        let end = just::<_, _, extra::Err<Simple<char>>>("end");
+        let block = dedented(2, any().repeated().collect::<String>()).then_ignore(end);
         assert_eq!(
-            parser().parse("[0, 3, 6, 900,120]").into_result(),
-            Ok(vec![0, 3, 6, 900, 120]),
+            block.parse("  a\n    b\n\n  c\nend").into_result(),
+            Ok("a\n  b\n\nc\n".to_string()),
         );
+    }
+
+    #[test]
+    fn dedented_nested() {
+        let rest = any::<_, extra::Err<Rich<char>>>()
+            .repeated()
+            .collect::<String>();
+        let inner = just("b:\n").ignore_then(dedented(2, rest));
+        let outer =
+            dedented(2, inner.then(rest)).then_ignore(just::<_, _, extra::Err<Rich<char>>>("end"));
+
+        // The inner block strips the indentation of both blocks, and ends where the outer block's indentation resumes
         assert_eq!(
-            parser().parse("[200,400,50  ,0,0, ]").into_result(),
-            Ok(vec![200, 400, 50, 0, 0]),
+            outer.parse("  b:\n    x\n      y\n  c\nend").into_result(),
+            Ok(("x\n  y\n".to_string(), "c\n".to_string())),
         );
+
+        // Spans within the inner block are those of the original input
+        let inner = just("b:\n").ignore_then(dedented(2, just("x\nz")));
+        let outer = just::<_, _, extra::Err<Rich<char>>>("a:\n").ignore_then(dedented(2, inner));
+        let errs = outer.parse("a:\n  b:\n    x\n    y\n").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(18, 19));
+        assert_eq!(errs[0].found(), Some(&'y'));
     }
 
     #[test]
-    fn rc_impl() {
-        fn parser<'a>() -> impl Parser<'a, &'a str, Vec<u64>> {
-            Rc::new(
+    fn dedented_reparse_slice() {
+        let rest = any::<_, extra::Err<Rich<char>>>()
+            .repeated()
+            .collect::<String>();
+        let block = dedented(2, rest);
+        let literal = none_of::<_, _, extra::Err<Rich<char>>>('"')
+            .repeated()
+            .reparse_slice(block)
+            .delimited_by(just('"'), just('"'));
+        assert_eq!(
+            literal.parse("\"\n  a\n    b\n\"").into_result(),
+            Ok("\na\n  b\n".to_string()),
+        );
+
+        // Spans within the block are those of the original input
+        let block = dedented(2, just::<_, _, extra::Err<Rich<char>>>("\na\nc"));
+        let literal = none_of::<_, _, extra::Err<Rich<char>>>('"')
+            .repeated()
+            .reparse_slice(block)
+            .delimited_by(just('"'), just('"'));
+        let errs = literal.parse("\"\n  a\n  b\n\"").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(8, 9));
+        assert_eq!(errs[0].found(), Some(&'b'));
+    }
+
+    #[test]
+    fn longest_of_prefers_longer_literal() {
+        fn op<'a>(
+            ops: [&'a str; 2],
+        ) -> impl Parser<'a, &'a str, Vec<&'a str>, extra::Err<Simple<'a, char>>> {
+            longest_of(ops).padded().repeated().collect()
+        }
+
+        for ops in [["<", "<<"], ["<<", "<"]] {
+            assert_eq!(op(ops).parse("<<").into_result(), Ok(vec!["<<"]));
+            assert_eq!(op(ops).parse("<").into_result(), Ok(vec!["<"]));
+            assert_eq!(
+                op(ops).parse("<<< <").into_result(),
+                Ok(vec!["<<", "<", "<"])
+            );
+        }
+    }
+
+    #[test]
+    fn recovered_child_span_within_parent() {
+        #[derive(Debug, PartialEq)]
+        enum Expr {
+            Num(u64, SimpleSpan),
+            Error(SimpleSpan),
+        }
+
+        let num = text::int::<_, _, extra::Err<Rich<char>>>(10)
+            .map_with_span(|s: &str, span| Expr::Num(s.parse().unwrap(), span));
+        // On failure, skip everything up to (but not including) the `;`
+        let value = num.recover_with(skip_until(
+            none_of(';').ignored(),
+            just(';').rewind().ignored(),
+            || Expr::Error(SimpleSpan::new(0, 0)),
+        ));
+        let child = value.map_with_span(|expr, span| match expr {
+            Expr::Error(_) => Expr::Error(span),
+            expr => expr,
+        });
+        let stmt = text::ascii::ident()
+            .then_ignore(just('=').padded())
+            .then(child)
+            .then_ignore(just(';'))
+            .map_with_span(|(name, child), span: SimpleSpan| (name, child, span));
+
+        assert_eq!(
+            stmt.parse("x = 42;").into_result(),
+            Ok((
+                "x",
+                Expr::Num(42, SimpleSpan::new(4, 6)),
+                SimpleSpan::new(0, 7)
+            )),
+        );
+
+        let (out, errs) = stmt.parse("x = ?!;").into_output_errors();
+        assert_eq!(errs.len(), 1);
+        let (name, child, span) = out.unwrap();
+        assert_eq!(name, "x");
+        // The recovered child covers the skipped region, and the parent covers both the prefix and the child
+        assert_eq!(child, Expr::Error(SimpleSpan::new(4, 6)));
+        assert_eq!(span, SimpleSpan::new(0, 7));
+        // A zero-width recovered child still yields a well-formed parent span
+        let (out, errs) = stmt.parse("x = ;").into_output_errors();
+        assert_eq!(errs.len(), 1);
+        let (_, child, span) = out.unwrap();
+        assert_eq!(child, Expr::Error(SimpleSpan::new(4, 4)));
+        assert_eq!(span, SimpleSpan::new(0, 5));
+    }
+
+    #[test]
+    fn wrapping_input_record_across_boundary() {
+        use crate::input::WrappingInput;
+
+        // A length-prefixed record, written into a ring buffer so that it straddles the end of the buffer
+        let mut buffer = [0u8; 8];
+        for (i, b) in [4, b'a', b'b', b'c', b'd'].into_iter().enumerate() {
+            buffer[(6 + i) % buffer.len()] = b;
+        }
+
+        let record = any::<_, extra::Err<Simple<u8>>>()
+            .ignore_with_ctx(
                 any()
-                    .filter(|c: &char| c.is_ascii_digit())
                     .repeated()
-                    .at_least(1)
-                    .at_most(3)
-                    .map_slice(|b: &str| b.parse::<u64>().unwrap())
-                    .padded()
-                    .separated_by(just(',').padded())
-                    .allow_trailing()
-                    .collect()
-                    .delimited_by(just('['), just(']')),
+                    .configure(|cfg, len: &u8| cfg.exactly(*len as usize))
+                    .map_slice(|(head, tail): (&[u8], &[u8])| [head, tail].concat())
+                    .map_with_span(|body, span: SimpleSpan| (body, span)),
             )
-        }
+            .then_ignore(end());
 
         assert_eq!(
-            parser().parse("[122 , 23,43,    4, ]").into_result(),
-            Ok(vec![122, 23, 43, 4]),
+            record
+                .parse(WrappingInput::new(&buffer, 6, 5))
+                .into_result(),
+            Ok((b"abcd".to_vec(), SimpleSpan::new(1, 5))),
         );
+        // The same record, unwrapped, parses identically
         assert_eq!(
-            parser().parse("[0, 3, 6, 900,120]").into_result(),
-            Ok(vec![0, 3, 6, 900, 120]),
+            record
+                .parse(WrappingInput::new(&[4, b'a', b'b', b'c', b'd'], 0, 5))
+                .into_result(),
+            Ok((b"abcd".to_vec(), SimpleSpan::new(1, 5))),
+        );
+    }
+
+    #[test]
+    fn build_config_fields() {
+        #[derive(Default, Debug, PartialEq)]
+        struct Config {
+            name: String,
+            port: u16,
+            debug: bool,
+            tags: Vec<String>,
+        }
+
+        fn entry<'a, O>(
+            key: &'a str,
+            value: impl Parser<'a, &'a str, O, extra::Err<Simple<'a, char>>>,
+        ) -> impl Parser<'a, &'a str, O, extra::Err<Simple<'a, char>>> {
+            just(key)
+                .then_ignore(just('=').padded())
+                .ignore_then(value)
+                .then_ignore(just(';').padded())
+        }
+
+        let ident = text::ascii::ident().map(ToString::to_string);
+        let config = build(Config::default)
+            .field(entry("name", ident), |c, name| c.name = name)
+            .field(
+                entry("port", text::int(10).from_str().unwrapped()),
+                |c, port| c.port = port,
+            )
+            .field(
+                entry("debug", just("true").to(true).or(just("false").to(false))),
+                |c, debug| c.debug = debug,
+            )
+            .field(
+                entry("tags", ident.separated_by(just(',')).collect()),
+                |c, tags| c.tags = tags,
+            );
+
+        assert_eq!(
+            config
+                .parse("name = server; port = 8080; debug = true; tags = a,b;")
+                .into_result(),
+            Ok(Config {
+                name: "server".to_string(),
+                port: 8080,
+                debug: true,
+                tags: vec!["a".to_string(), "b".to_string()],
+            }),
         );
+        assert!(config.parse("name = server; debug = true;").has_errors());
+    }
+
+    #[test]
+    fn map_with_state_returns_state_ref() {
+        use std::collections::HashSet;
+
+        fn known_ident<'a>(
+        ) -> impl Parser<'a, &'a str, &'a str, extra::Full<Rich<'a, char>, &'a HashSet<String>, ()>>
+        {
+            text::ascii::ident().try_map_with_state(
+                |name, span, table: &mut &'a HashSet<String>| {
+                    // Copy the shared reference out of the state so that the result borrows from the table itself
+                    let table: &'a HashSet<String> = table;
+                    table
+                        .get(name)
+                        .map(String::as_str)
+                        .ok_or_else(|| Rich::custom(span, "unknown identifier"))
+                },
+            )
+        }
+
+        let table = ["foo".to_string(), "bar".to_string()]
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let src = "bar".to_string();
+        let name = known_ident()
+            .parse_with_state(&src, &mut &table)
+            .into_result()
+            .unwrap();
+
+        assert_eq!(name, "bar");
+        assert!(core::ptr::eq(name, table.get("bar").unwrap().as_str()));
+        assert!(known_ident()
+            .parse_with_state("baz", &mut &table)
+            .has_errors());
+    }
+
+    #[test]
+    fn choice_and_or_prefer_furthest_error() {
+        fn check<'a>(parser: impl Parser<'a, &'a str, &'a str, extra::Err<Rich<'a, char>>>) {
+            let errs = parser.parse("abx").into_errors();
+            assert_eq!(errs.len(), 1);
+            // The branch that got furthest wins, and errors at the same position are merged
+            assert_eq!(errs[0].span(), &SimpleSpan::new(2, 3));
+            assert_eq!(errs[0].found(), Some(&'x'));
+            let mut expected = errs[0]
+                .expected()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>();
+            expected.sort();
+            assert_eq!(expected, vec!["'c'", "'d'"]);
+        }
+
+        let a = || just("a1");
+        let b = || just("abc");
+        let c = || just("ax");
+        let d = || just("abd");
+
+        check(a().or(b()).or(c()).or(d()));
+        check(d().or(c().or(b())).or(a()));
+        check(choice((a(), b(), c(), d())));
+        check(choice((c(), d(), a(), b())));
+        check(choice([a(), b(), c(), d()]));
+    }
+
+    #[test]
+    fn with_continuations_separator_only_between() {
+        let item = text::ascii::ident::<_, _, extra::Err<Simple<char>>>();
+        let items = with_continuations(item, just(';').padded(), item);
+
         assert_eq!(
-            parser().parse("[200,400,50  ,0,0, ]").into_result(),
-            Ok(vec![200, 400, 50, 0, 0]),
+            items.parse("a; b; c").into_result(),
+            Ok(("a", vec!["b", "c"]))
         );
+        assert_eq!(items.parse("a").into_result(), Ok(("a", vec![])));
+        // The first item has no leading separator, and there's no trailing one
+        assert!(items.parse("; a; b").has_errors());
+        assert!(items.parse("a; b;").has_errors());
     }
 }
@@ -13,6 +13,7 @@
 //! - [`any`]: parses any single input
 //! - [`one_of`]: parses any one of a sequence of inputs
 //! - [`none_of`]: parses any input that does not appear in a sequence of inputs
+//! - [`in_range`]: parses any input that falls within a range of inputs
 //! - [`end`]: parses the end of input (i.e: if there any more inputs, this parse fails)
 
 use super::*;
@@ -45,7 +46,7 @@ where
         match inp.next_maybe_inner() {
             (_, None) => Ok(M::bind(|| ())),
             (at, Some(tok)) => {
-                inp.add_alt(at, None, Some(tok.into()), inp.span_since(before));
+                inp.add_alt(at, Some(None), Some(tok.into()), inp.span_since(before));
                 Err(())
             }
         }
@@ -84,6 +85,50 @@ where
     go_extra!(());
 }
 
+/// See [`position`].
+pub struct Position<I, E>(EmptyPhantom<(E, I)>);
+
+/// A parser that parses no inputs, producing a zero-width span at the current position.
+///
+/// This is useful for marking locations in the input that don't correspond to any tokens, such as the place where an
+/// implicit token would be inserted.
+///
+/// The output type of this parser is `I::Span`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let implicit_semicolon = just::<_, _, extra::Err<Simple<char>>>("let x")
+///     .ignore_then(position());
+///
+/// assert_eq!(implicit_semicolon.parse("let x").into_result(), Ok(SimpleSpan::new(5, 5)));
+/// ```
+pub const fn position<'a, I: Input<'a>, E: ParserExtra<'a, I>>() -> Position<I, E> {
+    Position(EmptyPhantom::new())
+}
+
+impl<I, E> Copy for Position<I, E> {}
+impl<I, E> Clone for Position<I, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, I, E> ParserSealed<'a, I, I::Span, E> for Position<I, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, I::Span> {
+        let here = inp.offset();
+        Ok(M::bind(|| inp.span_since(here)))
+    }
+
+    go_extra!(I::Span);
+}
+
 // impl<'b, T, C: Container<T>> Container<T> for &'b C {
 //     type Iter<'a> = C::Iter<'a>;
 //     fn iter(&self) -> Self::Iter<'_> { (*self).iter() }
@@ -212,6 +257,189 @@ where
     go_cfg_extra!(T);
 }
 
+/// See [`just_seq`].
+pub struct JustSeq<S, T, I, E = EmptyErr> {
+    seqs: S,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(T, E, I)>,
+}
+
+impl<S: Copy, T, I, E> Copy for JustSeq<S, T, I, E> {}
+impl<S: Clone, T, I, E> Clone for JustSeq<S, T, I, E> {
+    fn clone(&self) -> Self {
+        Self {
+            seqs: self.seqs.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+/// A parser that accepts a series of inputs or sequences, one after the other.
+///
+/// This is equivalent to chaining [`just`] parsers together with [`Parser::then`] (for example,
+/// `just("<<").then(just("="))`), but produces the sequences themselves as its output rather than a nested tuple.
+///
+/// Like [`just`], this works with any input and so compares the input token by token. When the input is text or
+/// bytes and the sequences are known up front, [`expect_exact`] can match them (concatenated) with a single slice
+/// comparison instead.
+///
+/// The output type of this parser is `S`, the series of sequences that was provided.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, primitive::just_seq};
+/// let shl_assign = just_seq::<_, _, _, extra::Err<Simple<char>>>(["<<", "="]);
+///
+/// assert_eq!(shl_assign.parse("<<=").into_result(), Ok(["<<", "="]));
+/// assert!(shl_assign.parse("<=").has_errors());
+/// ```
+pub const fn just_seq<'a, S, T, I, E>(seqs: S) -> JustSeq<S, T, I, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: PartialEq,
+    S: AsRef<[T]> + Clone,
+    T: OrderedSeq<'a, I::Token>,
+{
+    JustSeq {
+        seqs,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+impl<'a, I, E, S, T> ParserSealed<'a, I, S, E> for JustSeq<S, T, I, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: PartialEq,
+    S: AsRef<[T]> + Clone,
+    T: OrderedSeq<'a, I::Token>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, S> {
+        for seq in self.seqs.as_ref() {
+            for next in seq.seq_iter() {
+                let before = inp.offset();
+                match inp.next_maybe_inner() {
+                    (_, Some(tok)) if next.borrow() == tok.borrow() => {}
+                    (at, found) => {
+                        inp.add_alt(
+                            at,
+                            Some(Some(T::to_maybe_ref(next))),
+                            found.map(|f| f.into()),
+                            inp.span_since(before),
+                        );
+                        return Err(());
+                    }
+                }
+            }
+        }
+        Ok(M::bind(|| self.seqs.clone()))
+    }
+
+    go_extra!(S);
+}
+
+/// See [`expect_exact`].
+pub struct ExpectExact<T, C, I, E> {
+    expected: T,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(C, E, I)>,
+}
+
+impl<T: Copy, C, I, E> Copy for ExpectExact<T, C, I, E> {}
+impl<T: Clone, C, I, E> Clone for ExpectExact<T, C, I, E> {
+    fn clone(&self) -> Self {
+        Self {
+            expected: self.expected.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+/// A parser that accepts only the given slice of text or bytes, comparing it against the upcoming input in a single
+/// operation.
+///
+/// This behaves like [`just`] with a sequence, but rather than comparing token by token, the upcoming input is compared
+/// against `expected` byte-wise in one go. This makes it well suited to large, fixed chunks of input like file headers
+/// and magic numbers. If the comparison fails, the error points at the first token that differs. With the `budget`
+/// feature, the input is instead compared token by token, so that each token is charged to the budget.
+///
+/// The output type of this parser is `&C::Str`, the slice of the input that was matched.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, primitive::expect_exact};
+/// let header = expect_exact::<_, _, _, extra::Err<Simple<u8>>>(b"%PDF-" as &[u8]);
+///
+/// assert_eq!(header.parse(b"%PDF-" as &[u8]).into_result(), Ok(b"%PDF-" as &[u8]));
+/// assert!(header.parse(b"%PNG-" as &[u8]).has_errors());
+/// ```
+pub const fn expect_exact<'a, T, C, I, E>(expected: T) -> ExpectExact<T, C, I, E>
+where
+    C: Char,
+    C::Str: AsRef<[u8]>,
+    I: StrInput<'a, C>,
+    E: ParserExtra<'a, I>,
+    T: AsRef<C::Str>,
+{
+    ExpectExact {
+        expected,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+impl<'a, T, C, I, E> ParserSealed<'a, I, &'a C::Str, E> for ExpectExact<T, C, I, E>
+where
+    C: Char,
+    C::Str: AsRef<[u8]>,
+    I: StrInput<'a, C>,
+    E: ParserExtra<'a, I>,
+    T: AsRef<C::Str>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, &'a C::Str> {
+        let before = inp.offset();
+        let expected = self.expected.as_ref();
+
+        // Without a budget to charge for each token, a match can be found with a single comparison
+        #[cfg(not(feature = "budget"))]
+        {
+            let expected_bytes: &[u8] = expected.as_ref();
+            let trailing: &[u8] = inp.slice_trailing_inner().as_ref();
+            if trailing.starts_with(expected_bytes) {
+                inp.skip_bytes(expected_bytes.len());
+                let after = inp.offset;
+                return Ok(M::bind(|| inp.slice_inner(before.offset..after)));
+            }
+        }
+
+        // Otherwise, walk the input token by token, which also finds the first differing token so that it can be
+        // reported
+        for next in C::str_to_chars(expected) {
+            let before = inp.offset();
+            match inp.next_inner() {
+                (_, Some(tok)) if tok == next => {}
+                (at, found) => {
+                    inp.add_alt(
+                        at,
+                        Some(Some(MaybeRef::Val(next))),
+                        found.map(MaybeRef::Val),
+                        inp.span_since(before),
+                    );
+                    return Err(());
+                }
+            }
+        }
+        let after = inp.offset;
+        Ok(M::bind(|| inp.slice_inner(before.offset..after)))
+    }
+
+    go_extra!(&'a C::Str);
+}
+
 /// See [`one_of`].
 pub struct OneOf<T, I, E> {
     seq: T,
@@ -357,6 +585,79 @@ where
     go_extra!(I::Token);
 }
 
+/// See [`in_range`].
+pub struct InRange<R, I, E> {
+    range: R,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(E, I)>,
+}
+
+impl<R: Copy, I, E> Copy for InRange<R, I, E> {}
+impl<R: Clone, I, E> Clone for InRange<R, I, E> {
+    fn clone(&self) -> Self {
+        Self {
+            range: self.range.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+/// A parser that accepts any input that falls within a range of ordered inputs.
+///
+/// Unlike [`one_of`], the range does not need to be enumerable, and any kind of range (inclusive, exclusive,
+/// half-open, or unbounded) may be used.
+///
+/// The output type of this parser is `I`, the input that was found.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Simple};
+/// let hex = in_range::<_, _, extra::Err<Simple<char>>>('0'..='9')
+///     .or(in_range('a'..'g'))
+///     .repeated()
+///     .at_least(1)
+///     .collect::<String>();
+///
+/// assert_eq!(hex.parse("c0ffee").into_result(), Ok("c0ffee".to_string()));
+/// assert!(hex.parse("c0ffeg").has_errors());
+/// ```
+pub const fn in_range<'a, R, I, E>(range: R) -> InRange<R, I, E>
+where
+    I: ValueInput<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: PartialOrd,
+    R: RangeBounds<I::Token>,
+{
+    InRange {
+        range,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+impl<'a, I, E, R> ParserSealed<'a, I, I::Token, E> for InRange<R, I, E>
+where
+    I: ValueInput<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: PartialOrd,
+    R: RangeBounds<I::Token>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, I::Token> {
+        let before = inp.offset();
+        match inp.next_inner() {
+            (_, Some(tok)) if self.range.contains(&tok) => Ok(M::bind(|| tok)),
+            (at, found) => {
+                let err_span = inp.span_since(before);
+                inp.add_alt(at, None, found.map(|f| f.into()), err_span);
+                Err(())
+            }
+        }
+    }
+
+    go_extra!(I::Token);
+}
+
 /// See [`custom`].
 pub struct Custom<F, I, O, E> {
     f: F,
@@ -663,6 +964,123 @@ where
     MapCtx { parser, mapper }
 }
 
+/// Parse a prefix shared by several alternatives once, then choose between the parsers that follow it.
+///
+/// This is manual left-factoring made ergonomic. Writing `choice((prefix.then(a), prefix.then(b)))` parses `prefix`
+/// again for every alternative that fails after it, whereas `shared_prefix(prefix, choice((a, b)))` parses it exactly
+/// once. Note that `suffixes` is usually a [`choice`] between the diverging parts of each alternative.
+///
+/// The output type of this parser is `(OP, OS)`, a combination of the outputs of the prefix and the chosen suffix.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// # use chumsky::primitive::shared_prefix;
+/// #[derive(Clone, Debug, PartialEq)]
+/// enum If { Stmt, Expr }
+///
+/// let if_ = shared_prefix(
+///     text::ascii::keyword::<_, _, _, extra::Err<Simple<char>>>("if").padded(),
+///     choice((
+///         text::ascii::keyword("let").to(If::Stmt),
+///         just('(').to(If::Expr),
+///     )),
+/// )
+///     .map(|(_, kind)| kind);
+///
+/// assert_eq!(if_.parse("if let").into_result(), Ok(If::Stmt));
+/// assert_eq!(if_.parse("if (").into_result(), Ok(If::Expr));
+/// ```
+pub const fn shared_prefix<'a, P, S, OP, OS, I, E>(prefix: P, suffixes: S) -> Then<P, S, OP, OS, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    P: Parser<'a, I, OP, E>,
+    S: Parser<'a, I, OS, E>,
+{
+    Then {
+        parser_a: prefix,
+        parser_b: suffixes,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// Parse a base followed by any number of postfix operators, applying each operator to the value so far from left to
+/// right.
+///
+/// This is shorthand for `base.foldl(suffix.repeated(), apply)` and covers postfix operators like indexing
+/// (`a[0][1]`), field access (`a.b.c`), and function calls (`f(x)(y)`).
+///
+/// The output type of this parser is `O`, the output of the base parser.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// # use chumsky::primitive::postfix;
+/// let call = postfix(
+///     text::ascii::ident::<_, _, extra::Err<Simple<char>>>().map(ToString::to_string),
+///     text::ascii::ident().delimited_by(just('('), just(')')),
+///     |f, arg| format!("{f}({arg})"),
+/// );
+///
+/// assert_eq!(call.parse("f(x)(y)").into_result(), Ok("f(x)(y)".to_string()));
+/// ```
+#[cfg_attr(debug_assertions, track_caller)]
+pub fn postfix<'a, A, S, F, O, OS, I, E>(
+    base: A,
+    suffix: S,
+    apply: F,
+) -> Foldl<F, A, Repeated<S, OS, I, E>, OS, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+    S: Parser<'a, I, OS, E>,
+    F: Fn(O, OS) -> O,
+{
+    base.foldl(suffix.repeated(), apply)
+}
+
+/// Parse any number of prefix operators followed by a base, applying each operator to the value so far from the
+/// innermost (closest to the base) outwards.
+///
+/// This is shorthand for `op.repeated().foldr(base, apply)` and covers prefix operators like negation (`--x`) and
+/// logical not (`!!b`). The outermost operator is applied last.
+///
+/// The output type of this parser is `O`, the output of the base parser.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// # use chumsky::primitive::prefix;
+/// let not = prefix(
+///     just::<_, _, extra::Err<Simple<char>>>('!'),
+///     text::ascii::keyword("true").to(true).or(text::ascii::keyword("false").to(false)),
+///     |_, b| !b,
+/// );
+///
+/// assert_eq!(not.parse("!!!true").into_result(), Ok(false));
+/// assert_eq!(not.parse("false").into_result(), Ok(false));
+/// ```
+#[cfg_attr(debug_assertions, track_caller)]
+pub fn prefix<'a, P, A, F, OP, O, I, E>(
+    op: P,
+    base: A,
+    apply: F,
+) -> Foldr<F, Repeated<P, OP, I, E>, A, OP, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    P: Parser<'a, I, OP, E>,
+    A: Parser<'a, I, O, E>,
+    F: Fn(OP, O) -> O,
+{
+    op.repeated().foldr(base, apply)
+}
+
 /// See [`fn@todo`].
 pub struct Todo<I, O, E> {
     location: Location<'static>,
@@ -726,10 +1144,65 @@ where
     go_extra!(O);
 }
 
+/// See [`never`].
+pub struct Never<I, O, E> {
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(O, E, I)>,
+}
+
+impl<I, O, E> Copy for Never<I, O, E> {}
+impl<I, O, E> Clone for Never<I, O, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+/// A parser that always fails without expecting anything, producing an error at the current position.
+///
+/// Unlike [`fn@todo`], this parser never panics, making it usable as a placeholder branch of a [`choice`] or as a
+/// parser for types (such as [`core::convert::Infallible`]) that can never be constructed.
+///
+/// The output type of this parser is whatever you want it to be: it'll never produce output!
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let int = choice((
+///     just::<_, _, extra::Err<Simple<char>>>("0x").ignore_then(never()),
+///     text::int(10),
+/// ));
+///
+/// assert_eq!(int.parse("12").into_result(), Ok("12"));
+/// // Hexadecimal numbers are not supported yet, but they produce an error rather than a panic
+/// assert!(int.parse("0xd4").has_errors());
+/// ```
+pub const fn never<'a, I: Input<'a>, O, E: ParserExtra<'a, I>>() -> Never<I, O, E> {
+    Never {
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+impl<'a, I, O, E> ParserSealed<'a, I, O, E> for Never<I, O, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let before = inp.offset();
+        let (at, found) = inp.next_maybe_inner();
+        inp.add_alt(at, None, found.map(|f| f.into()), inp.span_since(before));
+        Err(())
+    }
+
+    go_extra!(O);
+}
+
 /// See [`choice`].
 #[derive(Copy, Clone)]
 pub struct Choice<T> {
-    parsers: T,
+    pub(crate) parsers: T,
 }
 
 /// Parse using a tuple of many parsers, producing the output of the first to successfully parse.
@@ -871,6 +1344,138 @@ where
     go_extra!(O);
 }
 
+/// Parse the longest of several literal sequences that matches the input.
+///
+/// This is useful in lexers, where some literals are prefixes of others (such as `<` and `<<`): the literals are tried
+/// from longest to shortest, so the longer literal always wins when it is present, regardless of the order in which the
+/// literals were given. Literals of equal length are tried in the order given.
+///
+/// The output type of this parser is `T`, the literal that was matched.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let op = longest_of::<_, _, extra::Err<Simple<char>>, 4>(["<", "<<", "<=", "<<="]);
+///
+/// assert_eq!(op.parse("<").into_result(), Ok("<"));
+/// assert_eq!(op.parse("<<").into_result(), Ok("<<"));
+/// assert_eq!(op.parse("<<=").into_result(), Ok("<<="));
+/// ```
+pub fn longest_of<'a, T, I, E, const N: usize>(mut seqs: [T; N]) -> Choice<[Just<T, I, E>; N]>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: PartialEq,
+    T: OrderedSeq<'a, I::Token> + Clone,
+{
+    seqs.sort_by_key(|seq| core::cmp::Reverse(seq.seq_iter().count()));
+    choice(seqs.map(just))
+}
+
+/// See [`dispatch`].
+pub struct Dispatch<T, P, I, E> {
+    index: HashMap<T, usize>,
+    branches: Vec<(T, P)>,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(E, I)>,
+}
+
+impl<T: Clone, P: Clone, I, E> Clone for Dispatch<T, P, I, E> {
+    fn clone(&self) -> Self {
+        Self {
+            index: self.index.clone(),
+            branches: self.branches.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+/// Parse using one of several branches, selected by peeking at the first token of the input.
+///
+/// Unlike [`choice`], which tries each branch in turn, this looks up the branch associated with the next token in a
+/// [`HashMap`], so selecting a branch takes constant time regardless of how many branches there are. This is useful
+/// for large grammars in which every alternative starts with a distinct token, such as keyword-led statements. The
+/// selected branch is run from the start of the input, so it should parse the first token itself.
+///
+/// If several branches share a first token, only the last of them is used. If no branch matches the next token, an
+/// error is generated that expects any of the first tokens.
+///
+/// The output type of this parser is `O`, the output of the branches.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let stmt = dispatch::<_, _, _, _, extra::Err<Simple<char>>>([
+///     ('l', just("let").to("let").boxed()),
+///     ('f', just("fn").to("fn").boxed()),
+///     ('r', just("return").to("return").boxed()),
+/// ]);
+///
+/// assert_eq!(stmt.parse("fn").into_result(), Ok("fn"));
+/// assert_eq!(stmt.parse("return").into_result(), Ok("return"));
+/// assert!(stmt.parse("while").has_errors());
+/// ```
+pub fn dispatch<'a, P, B, O, I, E>(branches: B) -> Dispatch<I::Token, P, I, E>
+where
+    I: ValueInput<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: Hash + Eq + Clone,
+    P: Parser<'a, I, O, E>,
+    B: IntoIterator<Item = (I::Token, P)>,
+{
+    let mut index = HashMap::<_, usize>::new();
+    let mut kept = Vec::<(I::Token, P)>::new();
+    for (tok, parser) in branches {
+        // A later branch replaces an earlier one with the same first token, which would otherwise never run
+        match index.get(&tok) {
+            Some(&i) => kept[i].1 = parser,
+            None => {
+                index.insert(tok.clone(), kept.len());
+                kept.push((tok, parser));
+            }
+        }
+    }
+    Dispatch {
+        index,
+        branches: kept,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+impl<'a, P, I, O, E> ParserSealed<'a, I, O, E> for Dispatch<I::Token, P, I, E>
+where
+    I: ValueInput<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: Hash + Eq + Clone,
+    P: Parser<'a, I, O, E>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let before = inp.save();
+        let start = inp.offset();
+        let (at, found) = inp.next_inner();
+        if let Some(&i) = found.as_ref().and_then(|tok| self.index.get(tok)) {
+            inp.rewind(before);
+            self.branches[i].1.go::<M>(inp)
+        } else {
+            let err_span = inp.span_since(start);
+            inp.add_alt(
+                at,
+                self.branches
+                    .iter()
+                    .map(|(tok, _)| Some(MaybeRef::Val(tok.clone()))),
+                found.map(|f| f.into()),
+                err_span,
+            );
+            Err(())
+        }
+    }
+
+    go_extra!(O);
+}
+
 /// See [`group`].
 #[derive(Copy, Clone)]
 pub struct Group<T> {
@@ -996,3 +1601,340 @@ impl_group_for_tuple! {
     Y_ OY
     Z_ OZ
 }
+
+/// Parse a mandatory head, followed by zero or more items of a different shape, producing the head's output along with
+/// a [`Vec`] of the rest.
+///
+/// This is equivalent to `head.then(rest.repeated().collect())`, but more clearly expresses the intent of constructs
+/// such as a declaration followed by any number of continuations.
+///
+/// The output type of this parser is `(OA, Vec<OB>)`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let path = head_then_rest(
+///     text::ascii::ident::<_, _, extra::Err<Simple<char>>>(),
+///     just("::").ignore_then(text::ascii::ident()),
+/// );
+///
+/// assert_eq!(path.parse("std").into_result(), Ok(("std", vec![])));
+/// assert_eq!(path.parse("std::vec::Vec").into_result(), Ok(("std", vec!["vec", "Vec"])));
+/// ```
+#[cfg_attr(debug_assertions, track_caller)]
+pub fn head_then_rest<'a, I, E, A, B, OA, OB>(
+    head: A,
+    rest: B,
+) -> Then<A, Collect<Repeated<B, OB, I, E>, OB, Vec<OB>>, OA, Vec<OB>, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+    B: Parser<'a, I, OB, E>,
+{
+    head.then(rest.repeated().collect())
+}
+
+/// Parse a mandatory first item, followed by zero or more continuations that are each preceded by a separator,
+/// producing the first item's output along with a [`Vec`] of the continuations.
+///
+/// This is equivalent to `first.then(sep.ignore_then(next).repeated().collect())`. Unlike
+/// [`Parser::separated_by`], the first item and the continuations may be parsed differently.
+///
+/// The output type of this parser is `(OA, Vec<OB>)`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let chain = with_continuations(
+///     text::ascii::ident::<_, _, extra::Err<Simple<char>>>(),
+///     just('.'),
+///     text::int(10),
+/// );
+///
+/// assert_eq!(chain.parse("x").into_result(), Ok(("x", vec![])));
+/// assert_eq!(chain.parse("x.0.1").into_result(), Ok(("x", vec!["0", "1"])));
+/// ```
+#[allow(clippy::type_complexity)]
+#[cfg_attr(debug_assertions, track_caller)]
+pub fn with_continuations<'a, I, E, A, S, B, OA, OS, OB>(
+    first: A,
+    sep: S,
+    next: B,
+) -> Then<A, Collect<Repeated<IgnoreThen<S, B, OS, E>, OB, I, E>, OB, Vec<OB>>, OA, Vec<OB>, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+    S: Parser<'a, I, OS, E>,
+    B: Parser<'a, I, OB, E>,
+{
+    first.then(sep.ignore_then(next).repeated().collect())
+}
+
+/// Parse a delimited list of items separated by a separator, such as `[1, 2, 3]`, producing a [`Vec`] of the items.
+///
+/// This is equivalent to `item.separated_by(sep).allow_trailing().collect().delimited_by(open, close)`, except that a
+/// missing closing delimiter is recovered from: an error is emitted, and the list is produced as if the delimiter had
+/// been present.
+///
+/// The output type of this parser is `Vec<OP>`, where `OP` is the output of the item parser.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let list = bracketed_list(
+///     just::<_, _, extra::Err<Simple<char>>>('['),
+///     text::int(10).padded(),
+///     just(','),
+///     just(']'),
+/// );
+///
+/// assert_eq!(list.parse("[1, 2, 3]").into_result(), Ok(vec!["1", "2", "3"]));
+/// assert_eq!(list.parse("[1, 2,]").into_result(), Ok(vec!["1", "2"]));
+///
+/// let (out, errs) = list.parse("[1, 2").into_output_errors();
+/// assert_eq!(out, Some(vec!["1", "2"]));
+/// assert_eq!(errs.len(), 1);
+/// ```
+#[allow(clippy::type_complexity)]
+#[cfg_attr(debug_assertions, track_caller)]
+pub fn bracketed_list<'a, I, E, A, P, S, C, OA, OP, OS, OC>(
+    open: A,
+    item: P,
+    sep: S,
+    close: C,
+) -> DelimitedBy<
+    Collect<SeparatedBy<P, S, OP, OS, I, E>, OP, Vec<OP>>,
+    A,
+    RecoverWith<Ignored<C, OC>, recovery::ViaParser<Empty<I, E>>>,
+    OA,
+    (),
+>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+    P: Parser<'a, I, OP, E>,
+    S: Parser<'a, I, OS, E>,
+    C: Parser<'a, I, OC, E>,
+{
+    item.separated_by(sep)
+        .allow_trailing()
+        .collect()
+        .delimited_by(
+            open,
+            close.ignored().recover_with(recovery::via_parser(empty())),
+        )
+}
+
+/// See [`dedented`].
+pub struct Dedented<A, O> {
+    parser: A,
+    indent: usize,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<O>,
+}
+
+impl<A: Copy, O> Copy for Dedented<A, O> {}
+impl<A: Clone, O> Clone for Dedented<A, O> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            indent: self.indent,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+/// Parse an indented block of lines with `indent` leading spaces stripped from each line.
+///
+/// The inner parser is run over a [`DedentedStr`](input::DedentedStr), a view of the input that starts at the current
+/// position and covers every following line indented by at least `indent` spaces (blank lines included). Within the
+/// view, the first `indent` spaces of each line are skipped, so the inner parser sees the block as if it were not
+/// indented at all: deeper indentation is preserved relative to the block. Once the inner parser finishes, parsing
+/// continues from wherever it stopped.
+///
+/// Spans produced by the inner parser are spans of the original input, even when `dedented` is itself used within
+/// another `dedented` block (in which case the indentation of both is stripped) or within [`Parser::reparse_slice`].
+/// This makes it useful for formats with indentation-sensitive blocks, such as YAML-like block scalars, Markdown code
+/// blocks, or here-docs.
+///
+/// The output type of this parser is `O`, the output of the inner parser.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let block = just::<_, _, extra::Err<Simple<char>>>("code:\n")
+///     .ignore_then(dedented(4, any().repeated().collect::<String>()))
+///     .then_ignore(just("end"));
+///
+/// assert_eq!(
+///     block.parse("code:\n    if x:\n        y\n\n    z\nend").into_result(),
+///     Ok("if x:\n    y\n\nz\n".to_string()),
+/// );
+/// ```
+pub const fn dedented<A, O>(indent: usize, parser: A) -> Dedented<A, O> {
+    Dedented {
+        parser,
+        indent,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+impl<'a, A, O, I, E> ParserSealed<'a, I, O, E> for Dedented<A, O>
+where
+    I: StrInput<'a, char>,
+    E: ParserExtra<'a, I>,
+    E::Error: Error<'a, input::DedentedStr<'a>>,
+    A: Parser<'a, input::DedentedStr<'a>, O, extra::Full<E::Error, E::State, E::Context>>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        // The view shares the offsets of the input, and strips its indentation on top of any that the input strips
+        let (src, base, stripped) = inp.input.indented_source();
+        let view = input::DedentedStr {
+            src,
+            base,
+            indent: stripped + self.indent,
+            stripped,
+        };
+        inp.offset = view.skip_indent(inp.offset);
+
+        #[cfg(feature = "memoization")]
+        let mut memos = HashMap::default();
+        inp.with_view(
+            &view,
+            |inp| self.parser.go::<M>(inp),
+            #[cfg(feature = "memoization")]
+            &mut memos,
+        )
+    }
+
+    go_extra!(O);
+}
+
+/// See [`build`].
+#[derive(Copy, Clone)]
+pub struct Build<F> {
+    init: F,
+}
+
+/// Build a value field-by-field, starting from the value produced by `init` and assigning into it the outputs of the
+/// parsers given to [`Build::field`], in order.
+///
+/// This is a more readable alternative to mapping a large tuple into a struct, because each parser sits next to the
+/// field that its output is assigned to.
+///
+/// The output type of this parser is `T`, the value produced by `init`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// #[derive(Default, Debug, PartialEq)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let int = text::int::<_, _, extra::Err<Simple<char>>>(10).from_str().unwrapped();
+///
+/// let point = build(Point::default)
+///     .field(int.then_ignore(just(',')), |p, x| p.x = x)
+///     .field(int, |p, y| p.y = y);
+///
+/// assert_eq!(point.parse("3,4").into_result(), Ok(Point { x: 3, y: 4 }));
+/// ```
+pub const fn build<F>(init: F) -> Build<F> {
+    Build { init }
+}
+
+impl<F> Build<F> {
+    /// Parse a field with the given parser, then assign its output into the value being built with `set`.
+    pub fn field<A, OA, S, T>(self, parser: A, set: S) -> BuildField<Self, A, S, OA, T>
+    where
+        F: Fn() -> T,
+        S: Fn(&mut T, OA),
+    {
+        BuildField {
+            builder: self,
+            parser,
+            set,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, E, F, T> ParserSealed<'a, I, T, E> for Build<F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    F: Fn() -> T,
+{
+    #[inline]
+    fn go<M: Mode>(&self, _: &mut InputRef<'a, '_, I, E>) -> PResult<M, T> {
+        Ok(M::bind(|| (self.init)()))
+    }
+
+    go_extra!(T);
+}
+
+/// See [`Build::field`].
+pub struct BuildField<B, A, S, OA, T> {
+    builder: B,
+    parser: A,
+    set: S,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(OA, T)>,
+}
+
+impl<B: Copy, A: Copy, S: Copy, OA, T> Copy for BuildField<B, A, S, OA, T> {}
+impl<B: Clone, A: Clone, S: Clone, OA, T> Clone for BuildField<B, A, S, OA, T> {
+    fn clone(&self) -> Self {
+        Self {
+            builder: self.builder.clone(),
+            parser: self.parser.clone(),
+            set: self.set.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<B, A, S, OA, T> BuildField<B, A, S, OA, T> {
+    /// Parse a field with the given parser, then assign its output into the value being built with `set`.
+    pub fn field<A2, OA2, S2>(self, parser: A2, set: S2) -> BuildField<Self, A2, S2, OA2, T>
+    where
+        S2: Fn(&mut T, OA2),
+    {
+        BuildField {
+            builder: self,
+            parser,
+            set,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, E, B, A, S, OA, T> ParserSealed<'a, I, T, E> for BuildField<B, A, S, OA, T>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    B: Parser<'a, I, T, E>,
+    A: Parser<'a, I, OA, E>,
+    S: Fn(&mut T, OA),
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, T> {
+        let mut out = self.builder.go::<M>(inp)?;
+        let field = self.parser.go::<M>(inp)?;
+        M::combine_mut(&mut out, field, |out, field| (self.set)(out, field));
+        Ok(out)
+    }
+
+    go_extra!(T);
+}
@@ -250,6 +250,33 @@ where
         inp: &mut InputRef<'a, '_, I, E>,
         state: &mut Self::IterState<M>,
     ) -> IPResult<M, O>;
+
+    /// Like [`IterParserSealed::next`], but also produces the offset at which the item itself began, excluding
+    /// anything (such as a separator) that was parsed before it.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn next_with_start<'parse, M: Mode>(
+        &self,
+        inp: &mut InputRef<'a, 'parse, I, E>,
+        state: &mut Self::IterState<M>,
+    ) -> Result<Option<(input::Offset<'a, 'parse, I>, M::Output<O>)>, ()> {
+        let before = inp.offset();
+        Ok(self.next::<M>(inp, state)?.map(|out| (before, out)))
+    }
+
+    /// The number of items that collecting this parser's output is expected to need space for.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn capacity_hint(&self) -> usize {
+        0
+    }
+
+    /// Whether the iterable parser may end here, having already produced as many items as it requires.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn may_stop<M: Mode>(&self, _state: &Self::IterState<M>) -> bool {
+        true
+    }
 }
 
 pub trait ConfigIterParserSealed<'a, I, O, E>: IterParserSealed<'a, I, O, E>
@@ -266,6 +293,13 @@ where
         state: &mut Self::IterState<M>,
         cfg: &Self::Config,
     ) -> IPResult<M, O>;
+
+    /// Like [`IterParserSealed::may_stop`], but with the given configuration.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn may_stop_cfg<M: Mode>(&self, state: &Self::IterState<M>, _cfg: &Self::Config) -> bool {
+        self.may_stop(state)
+    }
 }
 
 // TODO: Remove this when MaybeUninit transforms to/from arrays stabilize in any form
@@ -0,0 +1,92 @@
+//! Utilities for profiling parsers.
+//!
+//! Mark the parsers that you are interested in with [`Parser::profiled`], then parse with
+//! [`Parser::parse_profiled`] to find out how often each of them ran and how much input they pulled.
+//! To find out which of them are exercised by a corpus of inputs, parse each input with
+//! [`Parser::parse_with_coverage`] instead.
+
+use super::*;
+
+/// Statistics gathered for a single parser marked with [`Parser::profiled`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProfileEntry {
+    pub(crate) invocations: usize,
+    pub(crate) successes: usize,
+    pub(crate) tokens: usize,
+}
+
+impl ProfileEntry {
+    /// The number of times the parser was run.
+    pub fn invocations(&self) -> usize {
+        self.invocations
+    }
+
+    /// The number of times the parser succeeded.
+    pub fn successes(&self) -> usize {
+        self.successes
+    }
+
+    /// The total number of tokens pulled from the input while the parser was running, including by its children and
+    /// by attempts that were later backtracked.
+    pub fn tokens(&self) -> usize {
+        self.tokens
+    }
+}
+
+/// A report of how often each parser marked with [`Parser::profiled`] ran. See [`Parser::parse_profiled`].
+#[derive(Clone, Debug, Default)]
+pub struct ProfileReport {
+    pub(crate) entries: HashMap<&'static str, ProfileEntry>,
+}
+
+impl ProfileReport {
+    /// Get the statistics for the parser with the given name, if it ran at all.
+    pub fn get(&self, name: &str) -> Option<&ProfileEntry> {
+        self.entries.get(name)
+    }
+
+    /// Iterate over the statistics of every parser that ran, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &ProfileEntry)> + '_ {
+        self.entries.iter().map(|(name, entry)| (*name, entry))
+    }
+}
+
+/// Which parsers marked with [`Parser::profiled`] matched at least once over a series of parses, such as a test
+/// corpus. See [`Parser::parse_with_coverage`].
+///
+/// Only parsers that ran during one of the parses are known to the report: a parser that never ran at all is
+/// neither covered nor listed by [`CoverageReport::uncovered`].
+#[derive(Clone, Debug, Default)]
+pub struct CoverageReport {
+    pub(crate) entries: HashMap<&'static str, ProfileEntry>,
+}
+
+impl CoverageReport {
+    /// Accumulate the statistics of a single parse into this report.
+    pub fn add(&mut self, report: &ProfileReport) {
+        for (name, entry) in report.iter() {
+            let total = self.entries.entry(name).or_default();
+            total.invocations += entry.invocations;
+            total.successes += entry.successes;
+            total.tokens += entry.tokens;
+        }
+    }
+
+    /// Whether the parser with the given name matched at least once.
+    pub fn is_covered(&self, name: &str) -> bool {
+        matches!(self.entries.get(name), Some(entry) if entry.successes > 0)
+    }
+
+    /// Iterate over the names of parsers that ran but never matched, in no particular order.
+    pub fn uncovered(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.entries
+            .iter()
+            .filter(|(_, entry)| entry.successes == 0)
+            .map(|(name, _)| *name)
+    }
+
+    /// Get the statistics for the parser with the given name, totalled over every parse, if it ran at all.
+    pub fn get(&self, name: &str) -> Option<&ProfileEntry> {
+        self.entries.get(name)
+    }
+}
@@ -52,6 +52,62 @@ where
     }
 }
 
+/// See [`via_parser_with_ctx`].
+#[derive(Copy, Clone)]
+pub struct ViaParserWithCtx<F>(F);
+
+/// Recover via a recovery parser created from the current context.
+///
+/// This allows recovery to depend on a value produced earlier in the parse, such as the opening delimiter of a block
+/// (so that recovery can skip to the matching closing delimiter). See [`Parser::then_with_ctx`] and
+/// [`Parser::ignore_with_ctx`] for how to provide context to a parser.
+///
+/// The recovery parser is only created when recovery is attempted.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// # use chumsky::recovery::via_parser_with_ctx;
+/// let closer = |open: char| if open == '[' { ']' } else { ')' };
+///
+/// let list = text::int(10)
+///     .separated_by(just(','))
+///     .collect::<Vec<_>>()
+///     .then_ignore(just('\0').configure(move |cfg, open: &char| cfg.seq(closer(*open))))
+///     // Skip to whichever delimiter closes the list
+///     .recover_with(via_parser_with_ctx(move |open: &char| {
+///         let close = closer(*open);
+///         none_of(close).repeated().then(just(close)).to(Vec::new())
+///     }));
+/// let block = one_of::<_, _, extra::Err<Simple<char>>>("[(").ignore_with_ctx(list);
+///
+/// assert_eq!(block.parse("[1,2]").into_result(), Ok(vec!["1", "2"]));
+/// let (output, errs) = block.parse("(1,?]2)").into_output_errors();
+/// assert_eq!(output, Some(Vec::new()));
+/// assert_eq!(errs.len(), 1);
+/// ```
+pub fn via_parser_with_ctx<F>(f: F) -> ViaParserWithCtx<F> {
+    ViaParserWithCtx(f)
+}
+
+impl<F> Sealed for ViaParserWithCtx<F> {}
+impl<'a, I, O, E, F, A> Strategy<'a, I, O, E> for ViaParserWithCtx<F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    F: Fn(&E::Context) -> A,
+    A: Parser<'a, I, O, E>,
+{
+    fn recover<M: Mode, P: Parser<'a, I, O, E>>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+        parser: &P,
+    ) -> PResult<M, O> {
+        ViaParser((self.0)(inp.ctx())).recover::<M, P>(inp, parser)
+    }
+}
+
 /// See [`Parser::recover_with`].
 #[derive(Copy, Clone)]
 pub struct RecoverWith<A, S> {
@@ -87,6 +143,42 @@ where
     go_extra!(O);
 }
 
+/// See [`Parser::tolerant`].
+#[derive(Copy, Clone)]
+pub struct Tolerant<A, O> {
+    pub(crate) parser: A,
+    pub(crate) default: O,
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for Tolerant<A, O>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+    O: Clone,
+{
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let before = inp.offset;
+        match self.parser.go::<M>(inp) {
+            Ok(out) => Ok(out),
+            Err(()) => {
+                // Errors emitted before the failure are kept, so only the offset is reset
+                inp.offset = before;
+                recover_resume(|| self.default.clone())
+                    .recover::<M, _>(inp, &self.parser)
+                    .or_else(|()| {
+                        // The parser failed without getting past where it started, so there's nothing to skip
+                        let alt = inp.errors.alt.take().expect("error but no alt?");
+                        inp.emit(inp.offset, alt.err);
+                        Ok(M::bind(|| self.default.clone()))
+                    })
+            }
+        }
+    }
+
+    go_extra!(O);
+}
+
 /// See [`skip_then_retry_until`].
 #[must_use]
 #[derive(Copy, Clone)]
@@ -168,19 +260,35 @@ where
         inp: &mut InputRef<'a, '_, I, E>,
         _parser: &P,
     ) -> PResult<M, O> {
-        let alt = inp.errors.alt.take().expect("error but no alt?");
-        loop {
-            let before = inp.save();
-            if let Ok(()) = self.until.go::<Check>(inp) {
-                inp.emit(inp.offset, alt.err);
-                break Ok(M::bind(|| (self.fallback)()));
-            }
-            inp.rewind(before);
+        skip_until_inner(&self.skip, &self.until, inp)?;
+        Ok(M::bind(|| (self.fallback)()))
+    }
+}
 
-            if let Err(()) = self.skip.go::<Check>(inp) {
-                inp.errors.alt = Some(alt);
-                break Err(());
-            }
+// Skip input until `until` matches, emitting the pending alternative error if it does.
+fn skip_until_inner<'a, I, E, S, U>(
+    skip: &S,
+    until: &U,
+    inp: &mut InputRef<'a, '_, I, E>,
+) -> Result<(), ()>
+where
+    I: ValueInput<'a>,
+    S: Parser<'a, I, (), E>,
+    U: Parser<'a, I, (), E>,
+    E: ParserExtra<'a, I>,
+{
+    let alt = inp.errors.alt.take().expect("error but no alt?");
+    loop {
+        let before = inp.save();
+        if let Ok(()) = until.go::<Check>(inp) {
+            inp.emit(inp.offset, alt.err);
+            break Ok(());
+        }
+        inp.rewind(before);
+
+        if let Err(()) = skip.go::<Check>(inp) {
+            inp.errors.alt = Some(alt);
+            break Err(());
         }
     }
 }
@@ -197,6 +305,142 @@ pub fn skip_until<S, U, F>(skip: S, until: U, fallback: F) -> SkipUntil<S, U, F>
     }
 }
 
+/// See [`skip_until_tagged`].
+#[must_use]
+#[derive(Copy, Clone)]
+pub struct SkipUntilTagged<S, U, R, F> {
+    skip: S,
+    until: U,
+    reason: R,
+    fallback: F,
+}
+
+impl<S, U, R, F> Sealed for SkipUntilTagged<S, U, R, F> {}
+impl<'a, I, O, E, S, U, R, F> Strategy<'a, I, O, E> for SkipUntilTagged<S, U, R, F>
+where
+    I: ValueInput<'a>,
+    S: Parser<'a, I, (), E>,
+    U: Parser<'a, I, (), E>,
+    R: Clone,
+    F: Fn(R, I::Span) -> O,
+    E: ParserExtra<'a, I>,
+{
+    fn recover<M: Mode, P: Parser<'a, I, O, E>>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+        _parser: &P,
+    ) -> PResult<M, O> {
+        let start = inp.offset();
+        skip_until_inner(&self.skip, &self.until, inp)?;
+        let span = inp.span_since(start);
+        Ok(M::bind(|| (self.fallback)(self.reason.clone(), span)))
+    }
+}
+
+/// Like [`skip_until`], but the fallback output is built from a `reason` and the span of the recovered input.
+///
+/// This is useful for producing placeholder nodes that record what was expected, such as
+/// `Expr::Error(ExpectedExpr)`. The span covers everything consumed during recovery, including the input matched by
+/// `until`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// #[derive(Debug, PartialEq)]
+/// enum Expr {
+///     Num(u64),
+///     Error(&'static str, SimpleSpan<usize>),
+/// }
+///
+/// let expr = text::int::<_, _, extra::Err<Simple<char>>>(10)
+///     .from_str()
+///     .unwrapped()
+///     .map(Expr::Num)
+///     .recover_with(skip_until_tagged(
+///         any().ignored(),
+///         just(';').ignored(),
+///         "expected expression",
+///         Expr::Error,
+///     ));
+///
+/// let (out, errs) = expr.parse("+-;").into_output_errors();
+/// assert_eq!(out, Some(Expr::Error("expected expression", (0..3).into())));
+/// assert_eq!(errs.len(), 1);
+/// ```
+pub fn skip_until_tagged<S, U, R, F>(
+    skip: S,
+    until: U,
+    reason: R,
+    fallback: F,
+) -> SkipUntilTagged<S, U, R, F> {
+    SkipUntilTagged {
+        skip,
+        until,
+        reason,
+        fallback,
+    }
+}
+
+/// See [`recover_resume`].
+#[must_use]
+#[derive(Copy, Clone)]
+pub struct RecoverResume<F> {
+    fallback: F,
+}
+
+impl<F> Sealed for RecoverResume<F> {}
+impl<'a, I, O, E, F> Strategy<'a, I, O, E> for RecoverResume<F>
+where
+    I: Input<'a>,
+    F: Fn() -> O,
+    E: ParserExtra<'a, I>,
+{
+    fn recover<M: Mode, P: Parser<'a, I, O, E>>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+        _parser: &P,
+    ) -> PResult<M, O> {
+        let alt = inp.errors.alt.take().expect("error but no alt?");
+        // Recovering without making progress would allow repetitions to loop forever
+        if alt.pos.into() <= inp.offset.into() {
+            inp.errors.alt = Some(alt);
+            return Err(());
+        }
+        inp.offset = alt.pos;
+        inp.emit(inp.offset, alt.err);
+        Ok(M::bind(|| (self.fallback)()))
+    }
+}
+
+/// A recovery strategy that resumes parsing from the point at which the pattern failed, rather than from where it
+/// started.
+///
+/// The input that the pattern consumed before failing, along with the input that caused the failure, is skipped and
+/// the fallback output is produced in its place. This is useful for half-parsed constructs where re-parsing the
+/// prefix with another strategy would be pointless or would produce cascading errors.
+///
+/// If the pattern failed without getting past its starting point, recovery fails.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let call = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+///     .then_ignore(just("()"))
+///     .map(Some)
+///     .recover_with(recover_resume(|| None));
+/// let calls = call.padded().repeated().collect::<Vec<_>>();
+///
+/// let (out, errs) = calls.parse("foo() bar( baz()").into_output_errors();
+/// // `bar(` is skipped along with the unexpected space, and parsing continues with `baz()`
+/// assert_eq!(out, Some(vec![Some("foo"), None, Some("baz")]));
+/// assert_eq!(errs.len(), 1);
+/// ```
+pub fn recover_resume<F>(fallback: F) -> RecoverResume<F> {
+    RecoverResume { fallback }
+}
+
 /// A recovery parser that searches for a start and end delimiter, respecting nesting.
 ///
 /// It is possible to specify additional delimiter pairs that are valid in the pattern's context for better errors. For
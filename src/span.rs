@@ -43,6 +43,31 @@ pub trait Span {
 
     /// Return the end offset of the span.
     fn end(&self) -> Self::Offset;
+
+    /// Convert the UTF-8 byte offsets of this span into UTF-16 code unit offsets within `source`.
+    ///
+    /// Spans produced when parsing [`&str`](str) input are UTF-8 byte offsets, suitable for indexing the original
+    /// string. Some tools, such as editors speaking the Language Server Protocol, instead expect offsets in UTF-16 code
+    /// units.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either offset is out of bounds of `source` or does not lie on a character boundary.
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, span::Span};
+    /// let src = "😀 = x";
+    /// let span = SimpleSpan::new(7, 8); // `x`
+    /// assert_eq!(&src[span.into_range()], "x");
+    /// assert_eq!(span.to_utf16(src), 5..6);
+    /// ```
+    fn to_utf16(&self, source: &str) -> Range<usize>
+    where
+        Self::Offset: Into<usize>,
+    {
+        let utf16_len = |offset: usize| source[..offset].encode_utf16().count();
+        utf16_len(self.start().into())..utf16_len(self.end().into())
+    }
 }
 
 /// The most basic implementor of `Span` - akin to `Range`, but `Copy` since it's not also
@@ -80,6 +105,37 @@ impl<T> SimpleSpan<T> {
     }
 }
 
+impl<C: Clone> SimpleSpan<usize, C> {
+    /// Create a span covering part of this span, given a range of offsets relative to its start.
+    ///
+    /// This is useful for pointing errors produced by [`Parser::try_map`] at a specific part
+    /// of the matched input rather than the whole of it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is reversed or extends past the end of this span.
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let span = SimpleSpan::new(10, 20);
+    /// assert_eq!(span.sub_span(2..4), SimpleSpan::new(12, 14));
+    /// ```
+    pub fn sub_span(&self, range: Range<usize>) -> Self {
+        assert!(
+            range.start <= range.end && self.start + range.end <= self.end,
+            "sub-span {:?} out of bounds of span {}..{}",
+            range,
+            self.start,
+            self.end,
+        );
+        SimpleSpan {
+            start: self.start + range.start,
+            end: self.start + range.end,
+            context: self.context.clone(),
+        }
+    }
+}
+
 impl<T> From<Range<T>> for SimpleSpan<T> {
     fn from(range: Range<T>) -> Self {
         SimpleSpan {
@@ -168,6 +168,49 @@ impl Char for u8 {
     }
 }
 
+/// A trait implemented by primitive integer types that can be produced by [`int_value`].
+///
+/// This trait is currently sealed to minimise the impact of breaking changes.
+pub trait Integer: Sized + Copy + Sealed {
+    /// The value `0` for this type.
+    #[doc(hidden)]
+    const ZERO: Self;
+
+    /// Compute `self * radix + digit`, returning `None` if the result would overflow.
+    #[doc(hidden)]
+    fn checked_push_digit(self, radix: u32, digit: u32) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($($T:ty),*) => {
+        $(
+            impl Integer for $T {
+                const ZERO: Self = 0;
+
+                #[inline]
+                fn checked_push_digit(self, radix: u32, digit: u32) -> Option<Self> {
+                    self.checked_mul(<$T>::try_from(radix).ok()?)?
+                        .checked_add(<$T>::try_from(digit).ok()?)
+                }
+            }
+        )*
+    };
+}
+
+impl Sealed for u16 {}
+impl Sealed for u32 {}
+impl Sealed for u64 {}
+impl Sealed for u128 {}
+impl Sealed for usize {}
+impl Sealed for i8 {}
+impl Sealed for i16 {}
+impl Sealed for i32 {}
+impl Sealed for i64 {}
+impl Sealed for i128 {}
+impl Sealed for isize {}
+
+impl_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
 /// A parser that accepts (and ignores) any number of whitespace characters before or after another pattern.
 #[derive(Copy, Clone)]
 pub struct Padded<A> {
@@ -191,6 +234,104 @@ where
     go_extra!(O);
 }
 
+/// A parser that accepts any number of whitespace characters and trivia before or after another pattern, recording
+/// the trivia into the parser state.
+///
+/// See [`Parser::padded_with_trivia`].
+#[cfg(feature = "trivia")]
+pub struct PaddedWithTrivia<A, B, OB> {
+    pub(crate) parser: A,
+    pub(crate) trivia: B,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<OB>,
+}
+
+#[cfg(feature = "trivia")]
+impl<A: Copy, B: Copy, OB> Copy for PaddedWithTrivia<A, B, OB> {}
+#[cfg(feature = "trivia")]
+impl<A: Clone, B: Clone, OB> Clone for PaddedWithTrivia<A, B, OB> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            trivia: self.trivia.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+#[cfg(feature = "trivia")]
+impl<A, B, OB> PaddedWithTrivia<A, B, OB> {
+    fn skip_padding<'a, I, E>(&self, inp: &mut InputRef<'a, '_, I, E>)
+    where
+        I: ValueInput<'a> + SliceInput<'a>,
+        E: ParserExtra<'a, I>,
+        E::State: TriviaSink<I::Span, I::Slice>,
+        I::Span: Clone,
+        I::Slice: Clone,
+        I::Token: Char,
+        B: Parser<'a, I, OB, E>,
+    {
+        loop {
+            inp.skip_while(|c| c.is_whitespace());
+            let before = inp.save();
+            match self.trivia.go::<Check>(inp) {
+                // Trivia that matches no input would be recorded forever
+                Ok(()) if inp.offset != before.offset => inp.record_trivia(before.offset()),
+                _ => {
+                    inp.rewind(before);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "trivia")]
+impl<'a, I, O, E, A, B, OB> ParserSealed<'a, I, O, E> for PaddedWithTrivia<A, B, OB>
+where
+    I: ValueInput<'a> + SliceInput<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: TriviaSink<I::Span, I::Slice>,
+    I::Span: Clone,
+    I::Slice: Clone,
+    I::Token: Char,
+    A: Parser<'a, I, O, E>,
+    B: Parser<'a, I, OB, E>,
+{
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        self.skip_padding(inp);
+        let out = self.parser.go::<M>(inp)?;
+        self.skip_padding(inp);
+        Ok(out)
+    }
+
+    go_extra!(O);
+}
+
+/// A parser that accepts (and ignores) any number of whitespace characters before another pattern.
+///
+/// See [`Parser::then_padded`].
+#[derive(Copy, Clone)]
+pub struct PaddedBefore<A> {
+    pub(crate) parser: A,
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for PaddedBefore<A>
+where
+    I: ValueInput<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: Char,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        inp.skip_while(|c| c.is_whitespace());
+        self.parser.go::<M>(inp)
+    }
+
+    go_extra!(O);
+}
+
 /// A parser that accepts (and ignores) any number of whitespace characters.
 ///
 /// This parser is a `Parser::Repeated` and so methods such as `at_least()` can be called on it.
@@ -249,6 +390,46 @@ where
         .repeated()
 }
 
+/// A parser that accepts any amount of inline whitespace (such as the indentation at the start of a line) and
+/// produces the visual column that it ends at.
+///
+/// Tabs advance the column to the next multiple of `tab_width` (a `tab_width` of `0` is treated as `1`), while every
+/// other inline whitespace character advances it by one. This makes it possible to compare the indentation of lines
+/// that mix tabs and spaces.
+///
+/// The output type of this parser is `usize`, the column after the whitespace, starting from `0`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let indent = text::indent::<_, _, extra::Err<Simple<char>>>(4);
+///
+/// assert_eq!(indent.parse("").into_result(), Ok(0));
+/// assert_eq!(indent.parse("   ").into_result(), Ok(3));
+/// assert_eq!(indent.parse("\t\t").into_result(), Ok(8));
+/// // Spaces before a tab are absorbed by it
+/// assert_eq!(indent.parse("  \t ").into_result(), Ok(5));
+/// ```
+pub fn indent<'a, C: Char, I: ValueInput<'a> + StrInput<'a, C>, E: ParserExtra<'a, I>>(
+    tab_width: usize,
+) -> impl Parser<'a, I, usize, E> + Copy
+where
+    I::Token: Char,
+{
+    let tab_width = tab_width.max(1);
+    any()
+        .filter(|c: &I::Token| c.is_inline_whitespace())
+        .repeated()
+        .fold(0, move |column, c: I::Token| {
+            if c.to_char() == '\t' {
+                (column / tab_width + 1) * tab_width
+            } else {
+                column + 1
+            }
+        })
+}
+
 /// A parser that accepts (and ignores) any newline characters or character sequences.
 ///
 /// The output type of this parser is `()`.
@@ -393,6 +574,271 @@ pub fn int<'a, I: ValueInput<'a> + StrInput<'a, C>, C: Char, E: ParserExtra<'a,
         .slice()
 }
 
+/// A parser that accepts one or more digits and produces their value as an integer.
+///
+/// Unlike [`digits`] followed by [`Parser::from_str`], this parser respects the given radix. If the value does not fit
+/// within `T`, an error spanning all of the digits is generated.
+///
+/// The output type of this parser is `T`, the integer type being parsed.
+///
+/// The `radix` parameter functions identically to [`char::is_digit`]. If in doubt, choose `10`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let dec = text::int_value::<u32, _, _, extra::Err<Simple<char>>>(10);
+///
+/// assert_eq!(dec.parse("1452").into_result(), Ok(1452));
+/// assert_eq!(dec.parse("007").into_result(), Ok(7));
+/// assert!(dec.parse("").has_errors());
+///
+/// let byte = text::int_value::<u8, _, _, extra::Err<Simple<char>>>(16);
+///
+/// assert_eq!(byte.parse("7f").into_result(), Ok(127));
+/// // Overflow produces an error
+/// assert!(byte.parse("100").has_errors());
+/// ```
+#[must_use]
+pub fn int_value<'a, T, I, C, E>(radix: u32) -> impl Parser<'a, I, T, E> + Copy
+where
+    T: Integer,
+    I: ValueInput<'a> + StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    custom(move |inp| {
+        let before = inp.offset();
+        // Keep the errors of `digits` apart from those of other branches, so that only they are discarded on overflow
+        let alt = inp.errors.alt.take();
+        let s: Result<&C::Str, _> = inp.parse(digits(radix).slice());
+        let digits_alt = core::mem::replace(&mut inp.errors.alt, alt);
+        let n = C::str_to_chars(s?).try_fold(T::ZERO, |n, c| {
+            // Digits have already been validated by `digits`
+            let digit = c.to_char().to_digit(radix).unwrap_or_default();
+            n.checked_push_digit(radix, digit)
+        });
+        match n {
+            Some(n) => {
+                if let Some(digits_alt) = digits_alt {
+                    inp.add_alt_err(digits_alt.pos, digits_alt.err);
+                }
+                Ok(n)
+            }
+            // The overflow is more relevant than whatever `digits` expected after the last digit
+            None => Err(Error::message(
+                inp.span_since(before),
+                "integer overflow".to_string(),
+            )),
+        }
+    })
+}
+
+/// A parser that accepts one or more ASCII digit bytes and produces their value as an integer.
+///
+/// This is [`int_value`] specialised to byte inputs such as [`&[u8]`]: digits are converted directly from bytes, so
+/// there is no need to check that the input is valid UTF-8 (as `str::from_utf8(bytes)?.parse()` would). If the value
+/// does not fit within `T`, an error spanning all of the digits is generated.
+///
+/// The output type of this parser is `T`, the integer type being parsed.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let dec = text::bytes_to_int::<u64, &[u8], extra::Err<Simple<u8>>>(10);
+///
+/// assert_eq!(dec.parse(b"1234").into_result(), Ok(1234));
+/// assert!(dec.parse(b"12a4").has_errors());
+/// assert!(dec.parse(b"99999999999999999999").has_errors());
+/// ```
+#[must_use]
+pub fn bytes_to_int<'a, T, I, E>(radix: u32) -> impl Parser<'a, I, T, E> + Copy
+where
+    T: Integer,
+    I: ValueInput<'a> + StrInput<'a, u8>,
+    E: ParserExtra<'a, I>,
+{
+    int_value::<T, I, u8, E>(radix)
+}
+
+/// A parser that accepts a quoted string, decoding escape sequences within it.
+///
+/// The string must begin and end with `quote`. Within it, a backslash followed by the first character of any pair in
+/// `escapes` is decoded into the second character of the pair. If `unicode` is `true`, escapes of the form `\uXXXX`
+/// (where `XXXX` are exactly four hexadecimal digits) are also decoded into the corresponding unicode scalar value.
+/// Any other escape sequence is an error.
+///
+/// The output type of this parser is [`String`], the decoded contents of the string without its quotes.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let escapes = [('\\', '\\'), ('"', '"'), ('n', '\n'), ('t', '\t')];
+/// let string = text::escaped_string::<_, extra::Err<Simple<char>>>('"', &escapes, true);
+///
+/// assert_eq!(string.parse(r#""hello""#).into_result(), Ok("hello".to_string()));
+/// assert_eq!(string.parse(r#""say \"hi\"\t\u263A""#).into_result(), Ok("say \"hi\"\t\u{263A}".to_string()));
+/// // Unknown escapes are not permitted
+/// assert!(string.parse(r#""\q""#).has_errors());
+/// // Neither are unterminated strings
+/// assert!(string.parse(r#""hello"#).has_errors());
+/// ```
+#[must_use]
+pub fn escaped_string<'a, I, E>(
+    quote: char,
+    escapes: &'a [(char, char)],
+    unicode: bool,
+) -> impl Parser<'a, I, String, E> + Copy
+where
+    I: StrInput<'a, char>,
+    E: ParserExtra<'a, I>,
+{
+    let unicode_escape = just('u')
+        .filter(move |_| unicode)
+        .ignore_then(
+            any()
+                .filter(char::is_ascii_hexdigit)
+                .repeated()
+                .exactly(4)
+                .slice(),
+        )
+        .try_map(|digits: &str, span| {
+            u32::from_str_radix(digits, 16)
+                .ok()
+                .and_then(char::from_u32)
+                // Surrogates are not valid unicode scalar values
+                .ok_or_else(|| {
+                    Error::message(span, "unicode escape is not a valid character".to_string())
+                })
+        });
+
+    let escape = just('\\').ignore_then(unicode_escape.or(any().try_map(move |c: char, span| {
+        escapes
+            .iter()
+            .find(|(from, _)| *from == c)
+            .map(|(_, to)| *to)
+            .ok_or_else(|| {
+                Error::expected_found(
+                    escapes.iter().map(|(from, _)| Some(MaybeRef::Val(*from))),
+                    Some(MaybeRef::Val(c)),
+                    span,
+                )
+            })
+    })));
+
+    none_of(['\\', quote])
+        .or(escape)
+        .repeated()
+        .collect::<String>()
+        .delimited_by(just(quote), just(quote))
+}
+
+/// Decode the escape sequences in a byte buffer in place, returning the decoded prefix of the buffer.
+///
+/// Parsers cannot modify their input, so this is intended to be used after parsing: parse the (still escaped)
+/// region with a parser, find its location with something like [`Parser::map_with_span`], and then decode it within a
+/// buffer that you own. Because escape sequences are never shorter than the bytes they decode to, no allocation is
+/// needed.
+///
+/// A backslash followed by the first byte of any pair in `escapes` is decoded into the second byte of the pair. If
+/// any other escape sequence is found (including a trailing backslash), the index of its backslash is returned as an
+/// error. The contents of the buffer are unspecified when an error is returned.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let escapes = [(b'\\', b'\\'), (b'"', b'"'), (b'n', b'\n')];
+/// let string = just::<_, &[u8], extra::Err<Simple<u8>>>(b'"')
+///     .ignore_then(none_of(b"\\\"").or(just(b'\\').then(any()).to(0)).repeated())
+///     .then_ignore(just(b'"'))
+///     .map_with_span(|_, span: SimpleSpan<usize>| span);
+///
+/// let mut buf = br#""say \"hi\"\n""#.to_vec();
+/// let span = string.parse(&buf).into_result().unwrap();
+/// // Decode the contents of the string, without its quotes
+/// let contents = text::unescape_in_place(&mut buf[span.start + 1..span.end - 1], &escapes);
+/// assert_eq!(contents.as_deref(), Ok(&b"say \"hi\"\n"[..]));
+/// ```
+pub fn unescape_in_place<'b>(
+    buf: &'b mut [u8],
+    escapes: &[(u8, u8)],
+) -> Result<&'b mut [u8], usize> {
+    let (mut read, mut write) = (0, 0);
+    while read < buf.len() {
+        let b = buf[read];
+        if b == b'\\' {
+            let escaped = buf.get(read + 1).ok_or(read)?;
+            buf[write] = escapes
+                .iter()
+                .find(|(from, _)| from == escaped)
+                .map(|(_, to)| *to)
+                .ok_or(read)?;
+            read += 2;
+        } else {
+            buf[write] = b;
+            read += 1;
+        }
+        write += 1;
+    }
+    Ok(&mut buf[..write])
+}
+
+/// A parser that accepts a single character from UTF-16 encoded input, decoding surrogate pairs.
+///
+/// UTF-16 text (such as that produced by Windows APIs or JavaScript engines) can be parsed directly as a `&[u16]`.
+/// Characters outside of the Basic Multilingual Plane are encoded as a pair of surrogate code units, which this parser
+/// decodes into a single [`char`]. An unpaired surrogate is an error.
+///
+/// The output type of this parser is [`char`].
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let ident = text::utf16_char::<&[u16], extra::Err<Simple<u16>>>()
+///     .filter(|c| c.is_alphanumeric())
+///     .repeated()
+///     .at_least(1)
+///     .collect::<String>();
+///
+/// let src = "x\u{1D4B3}y".encode_utf16().collect::<Vec<_>>();
+/// assert_eq!(src.len(), 4);
+/// assert_eq!(ident.parse(&src).into_result(), Ok("x\u{1D4B3}y".to_string()));
+///
+/// // An unpaired surrogate cannot be decoded
+/// assert!(ident.parse(&[0x78, 0xD835]).has_errors());
+/// ```
+#[must_use]
+pub fn utf16_char<'a, I, E>() -> impl Parser<'a, I, char, E> + Copy
+where
+    I: ValueInput<'a, Token = u16>,
+    E: ParserExtra<'a, I>,
+{
+    custom(|inp| {
+        let before = inp.offset();
+        let high = match inp.next() {
+            Some(unit) => unit,
+            None => return Err(Error::expected_found(None, None, inp.span_since(before))),
+        };
+        let c = match high {
+            0xD800..=0xDBFF => match inp.peek() {
+                Some(low @ 0xDC00..=0xDFFF) => {
+                    inp.skip();
+                    char::decode_utf16([high, low]).next().and_then(Result::ok)
+                }
+                _ => None,
+            },
+            _ => char::from_u32(high as u32),
+        };
+        c.ok_or_else(|| {
+            Error::expected_found(None, Some(MaybeRef::Val(high)), inp.span_since(before))
+        })
+    })
+}
+
 /// Parsers and utilities for working with ASCII inputs.
 pub mod ascii {
     use super::*;
@@ -629,4 +1075,179 @@ mod tests {
     fn keyword_unicode_in_ascii() {
         make_ascii_kw_parser::<char, &str>("שלום");
     }
+
+    #[test]
+    fn int_value() {
+        let dec = text::int_value::<u8, _, _, extra::Err<Rich<char>>>(10);
+        assert_eq!(dec.parse("255").into_result(), Ok(255));
+
+        let hex = text::int_value::<u8, _, _, extra::Err<Rich<char>>>(16);
+        assert_eq!(hex.parse("ff").into_result(), Ok(255));
+
+        let errs = dec.parse("256").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(0, 3));
+        assert_eq!(errs[0].to_string(), "integer overflow");
+
+        // Error types without a message of their own, like `Simple`, fall back to `Error::message`'s default
+        let errs = text::int_value::<u8, _, _, extra::Err<Simple<char>>>(10)
+            .parse("256")
+            .into_errors();
+        assert_eq!(
+            errs,
+            vec![<Simple<char> as crate::error::Error<&str>>::expected_found(
+                [],
+                None,
+                (0..3).into(),
+            )],
+        );
+
+        // Errors from other branches that got further are not discarded by the overflow
+        let expr = just("300+x")
+            .ignored()
+            .or(dec.then_ignore(just('+')).ignored());
+        let errs = expr.parse("300+y").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(4, 5));
+    }
+
+    #[test]
+    fn escaped_string() {
+        let escapes = [('\\', '\\'), ('"', '"'), ('n', '\n')];
+        let string = text::escaped_string::<_, extra::Err<Rich<char>>>('"', &escapes, true);
+
+        assert_eq!(
+            string.parse(r#""a\nb\u0041""#).into_result(),
+            Ok("a\nbA".to_string()),
+        );
+
+        let errs = string.parse(r#""abc"#).into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(4, 4));
+        assert_eq!(errs[0].found(), None);
+        assert!(errs[0]
+            .expected()
+            .any(|e| e == &crate::error::RichPattern::Token(crate::util::MaybeRef::Val('"'))));
+
+        let errs = string.parse(r#""\uD800""#).into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(2, 7));
+        assert_eq!(
+            errs[0].to_string(),
+            "unicode escape is not a valid character"
+        );
+
+        let no_unicode = text::escaped_string::<_, extra::Err<Rich<char>>>('"', &escapes, false);
+        assert!(no_unicode.parse(r#""\u0041""#).has_errors());
+    }
+
+    #[test]
+    fn utf16_char() {
+        let ident = text::utf16_char::<&[u16], extra::Err<Rich<u16>>>()
+            .filter(|c| c.is_alphanumeric() || *c == '_')
+            .repeated()
+            .at_least(1)
+            .collect::<String>();
+
+        // U+1D4B3 MATHEMATICAL SCRIPT CAPITAL X is encoded as a surrogate pair
+        let src = "_x\u{1D4B3}9".encode_utf16().collect::<Vec<_>>();
+        assert_eq!(src.len(), 5);
+        assert_eq!(
+            ident.parse(&src).into_result(),
+            Ok("_x\u{1D4B3}9".to_string())
+        );
+
+        // A lone low surrogate
+        let errs = ident.parse(&[0x61, 0xDC00]).into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(1, 2));
+        assert_eq!(errs[0].found(), Some(&0xDC00));
+    }
+
+    #[test]
+    fn bytes_to_int() {
+        let dec = text::bytes_to_int::<u64, &[u8], extra::Err<Rich<u8>>>(10);
+
+        assert_eq!(dec.parse(b"1234").into_result(), Ok(1234));
+        assert_eq!(
+            dec.parse(b"18446744073709551615").into_result(),
+            Ok(u64::MAX)
+        );
+
+        let errs = dec.parse(b"18446744073709551616").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(0, 20));
+
+        assert!(dec.parse(b"").has_errors());
+        assert!(dec.parse(b"12 ").has_errors());
+
+        let hex = text::bytes_to_int::<u8, &[u8], extra::Err<Rich<u8>>>(16);
+        assert_eq!(hex.parse(b"fF").into_result(), Ok(255));
+        assert!(hex.parse(b"100").has_errors());
+    }
+
+    #[test]
+    fn padded_trailing_whitespace_at_eof() {
+        use crate::error::RichPattern;
+
+        let sum = text::int::<_, _, extra::Err<Rich<char>>>(10)
+            .padded()
+            .then(just('+'));
+
+        // The trailing whitespace is consumed by `padded`, but the error still describes what was expected after it
+        let errs = sum.parse("42   ").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].found(), None);
+        assert_eq!(
+            errs[0].expected().collect::<Vec<_>>(),
+            vec![&RichPattern::Token('+'.into())],
+        );
+        assert_eq!(errs[0].span(), &SimpleSpan::new(5, 5));
+        assert_eq!(errs[0].to_string(), "found end of input expected '+'");
+    }
+
+    #[test]
+    fn unescape_in_place() {
+        let escapes = [(b'\\', b'\\'), (b'n', b'\n'), (b't', b'\t')];
+
+        let mut buf = *b"a\\nb";
+        let ptr = buf.as_ptr();
+        let out = text::unescape_in_place(&mut buf, &escapes).unwrap();
+        assert_eq!(out, b"a\nb");
+        // The output is a sub-slice of the original buffer, not a new allocation
+        assert_eq!(out.as_ptr(), ptr);
+
+        let mut buf = *b"\\\\\\t";
+        assert_eq!(
+            text::unescape_in_place(&mut buf, &escapes).as_deref(),
+            Ok(&b"\\\t"[..]),
+        );
+
+        let mut buf = *b"ok\\q";
+        assert_eq!(text::unescape_in_place(&mut buf, &escapes), Err(2));
+        let mut buf = *b"ok\\";
+        assert_eq!(text::unescape_in_place(&mut buf, &escapes), Err(2));
+    }
+
+    #[test]
+    fn indent_tab_expansion() {
+        let indent = text::indent::<_, _, extra::Err<Simple<char>>>(4);
+
+        assert_eq!(indent.parse("\t  ").into_result(), Ok(6));
+        assert_eq!(indent.parse(" \t").into_result(), Ok(4));
+        assert_eq!(indent.parse("    \t").into_result(), Ok(8));
+
+        // The column of each line's leading whitespace
+        let lines = indent
+            .then_ignore(none_of('\n').repeated())
+            .separated_by(just('\n'))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines.parse("a\n\tb\n\t  c\n  \td").into_result(),
+            Ok(vec![0, 4, 6, 4]),
+        );
+
+        let bytes = text::indent::<_, &[u8], extra::Err<Simple<u8>>>(8);
+        assert_eq!(bytes.parse(b"\t  ").into_result(), Ok(10));
+    }
 }
@@ -137,3 +137,100 @@ impl<'a, T> From<&'a mut T> for Maybe<T, &'a mut T> {
         Self::Ref(x)
     }
 }
+
+enum DeferredState<'a, T> {
+    Unforced(Box<dyn FnOnce() -> T + 'a>),
+    Forcing,
+    Forced(T),
+    /// The function panicked while constructing the value, so there is no value to return.
+    Poisoned,
+}
+
+/// Marks a [`Deferred`] as poisoned if it is dropped while the value is still being constructed, which only happens
+/// when the constructing function panics.
+struct PoisonOnUnwind<'b, 'a, T>(&'b UnsafeCell<DeferredState<'a, T>>);
+
+impl<'b, 'a, T> Drop for PoisonOnUnwind<'b, 'a, T> {
+    fn drop(&mut self) {
+        // SAFETY: See `Deferred::force`. The guard only exists while the state is being constructed, when no
+        // references into it can exist.
+        unsafe {
+            if let DeferredState::Forcing = &*self.0.get() {
+                *self.0.get() = DeferredState::Poisoned;
+            }
+        }
+    }
+}
+
+/// A value whose construction is deferred until it is first needed. See [`Parser::lazy_map`].
+///
+/// If the function constructing the value panics, the value is poisoned: any later attempt to force it or take it
+/// panics too.
+pub struct Deferred<'a, T> {
+    state: UnsafeCell<DeferredState<'a, T>>,
+}
+
+impl<'a, T> Deferred<'a, T> {
+    /// Create a new deferred value that will be constructed by the given function.
+    pub fn new(f: impl FnOnce() -> T + 'a) -> Self {
+        Self {
+            state: UnsafeCell::new(DeferredState::Unforced(Box::new(f))),
+        }
+    }
+
+    /// Construct the value if it has not yet been constructed, and return a reference to it.
+    pub fn force(&self) -> &T {
+        // SAFETY: `Deferred` is not `Sync`, so nothing else can access the state concurrently. The state is only
+        // mutated while it is unforced, when no references into it can exist, and is never mutated again once forced.
+        // Constructing the value may re-enter this function, but then finds the state `Forcing` and panics without
+        // touching it.
+        unsafe {
+            if let DeferredState::Unforced(_) = &*self.state.get() {
+                let f = match core::mem::replace(&mut *self.state.get(), DeferredState::Forcing) {
+                    DeferredState::Unforced(f) => f,
+                    _ => unreachable!(),
+                };
+                let guard = PoisonOnUnwind(&self.state);
+                let value = f();
+                *self.state.get() = DeferredState::Forced(value);
+                drop(guard);
+            }
+            match &*self.state.get() {
+                DeferredState::Forced(value) => value,
+                DeferredState::Poisoned => {
+                    panic!("deferred value was poisoned by a panic during its construction")
+                }
+                _ => panic!("deferred value was forced during its own construction"),
+            }
+        }
+    }
+
+    /// Returns `true` if the value has been constructed.
+    pub fn is_forced(&self) -> bool {
+        // SAFETY: See `Deferred::force`. The reference does not outlive this function.
+        matches!(unsafe { &*self.state.get() }, DeferredState::Forced(_))
+    }
+
+    /// Take the value, constructing it first if it has not yet been constructed.
+    pub fn into_inner(self) -> T {
+        match self.state.into_inner() {
+            DeferredState::Unforced(f) => f(),
+            DeferredState::Forced(value) => value,
+            DeferredState::Poisoned => {
+                panic!("deferred value was poisoned by a panic during its construction")
+            }
+            DeferredState::Forcing => unreachable!(),
+        }
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for Deferred<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // SAFETY: See `Deferred::force`. The reference does not outlive this function.
+        match unsafe { &*self.state.get() } {
+            DeferredState::Forced(value) => f.debug_tuple("Deferred").field(value).finish(),
+            DeferredState::Poisoned => f.write_str("Deferred(<poisoned>)"),
+            _ => f.write_str("Deferred(<unforced>)"),
+        }
+    }
+}